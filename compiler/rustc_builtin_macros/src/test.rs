@@ -204,6 +204,14 @@ pub fn expand_test_or_bench(
                 ), // )
             ],
         )
+    } else if !fn_.sig.decl.inputs.is_empty() {
+        // A parameterized test, which adds its cases to a `test::TestCases`:
+        // test::StaticCasesFn(super::$test_fn)
+        cx.expr_call(
+            sp,
+            cx.expr_path(test_path("StaticCasesFn")),
+            thin_vec![cx.expr_path(cx.path(sp, vec![item.ident]))],
+        )
     } else {
         cx.expr_call(
             sp,
@@ -545,8 +553,21 @@ fn has_test_signature(cx: &ExtCtxt<'_>, i: &ast::Item) -> bool {
                 _ => true,
             };
 
-            if !sig.decl.inputs.is_empty() {
-                sd.span_err(i.span, "functions used as tests can not have any arguments");
+            // Parameterized tests take the `&mut test::TestCases` their cases are added to.
+            let is_parameterized = match &sig.decl.inputs[..] {
+                [] => false,
+                [param] if is_test_cases_ref(&param.ty) => true,
+                _ => {
+                    sd.span_err(i.span, "functions used as tests can not have any arguments");
+                    return false;
+                }
+            };
+            if is_parameterized && has_output {
+                sd.span_err(
+                    i.span,
+                    "functions used as parameterized tests must have signature \
+                    `fn(&mut TestCases)`",
+                );
                 return false;
             }
 
@@ -577,6 +598,20 @@ fn has_test_signature(cx: &ExtCtxt<'_>, i: &ast::Item) -> bool {
     }
 }
 
+/// Whether `ty` is `&mut TestCases`, the argument of parameterized tests. Like the other
+/// checks here this is purely syntactic, as it runs before name resolution.
+fn is_test_cases_ref(ty: &ast::Ty) -> bool {
+    match &ty.kind {
+        ast::TyKind::Ref(_, ast::MutTy { ty, mutbl: ast::Mutability::Mut }) => match &ty.kind {
+            ast::TyKind::Path(None, path) => {
+                path.segments.last().is_some_and(|segment| segment.ident.name == sym::TestCases)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 fn has_bench_signature(cx: &ExtCtxt<'_>, i: &ast::Item) -> bool {
     let has_sig = match &i.kind {
         // N.B., inadequate check, but we're running
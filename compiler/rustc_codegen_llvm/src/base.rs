@@ -28,7 +28,7 @@
 use rustc_data_structures::small_c_str::SmallCStr;
 use rustc_middle::dep_graph;
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrs;
-use rustc_middle::mir::mono::{Linkage, Visibility};
+use rustc_middle::mir::mono::{Linkage, MonoItem, Visibility};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::DebugInfo;
 use rustc_span::symbol::Symbol;
@@ -57,6 +57,23 @@ pub fn iter_globals(llmod: &llvm::Module) -> ValueIter<'_> {
     unsafe { ValueIter { cur: llvm::LLVMGetFirstGlobal(llmod), step: llvm::LLVMGetNextGlobal } }
 }
 
+/// Counts the instructions in the body of the function `llfn`.
+fn count_instructions(llfn: &Value) -> usize {
+    unsafe {
+        if llvm::LLVMCountBasicBlocks(llfn) == 0 {
+            return 0;
+        }
+        let mut count = 0;
+        let mut block = Some(llvm::LLVMGetFirstBasicBlock(llfn));
+        while let Some(bb) = block {
+            let first = llvm::LLVMGetFirstInstruction(bb);
+            count += ValueIter { cur: first, step: llvm::LLVMGetNextInstruction }.count();
+            block = llvm::LLVMGetNextBasicBlock(bb);
+        }
+        count
+    }
+}
+
 pub fn compile_codegen_unit(tcx: TyCtxt<'_>, cgu_name: Symbol) -> (ModuleCodegen<ModuleLlvm>, u64) {
     let start_time = Instant::now();
 
@@ -95,6 +112,19 @@ fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleLlvm
                 mono_item.define::<Builder<'_, '_, '_>>(&cx);
             }
 
+            // Record the size of each function for `-Z dump-mono-stats`, before it is optimized.
+            if cx.sess().opts.unstable_opts.dump_mono_stats.enabled() {
+                for &(mono_item, _) in &mono_items {
+                    if let MonoItem::Fn(instance) = mono_item
+                        && mono_item.is_user_defined()
+                        && let Some(&llfn) = cx.instances.borrow().get(&instance)
+                    {
+                        let instructions = count_instructions(llfn);
+                        tcx.sess.code_stats.record_ir_instructions(instance.def_id(), instructions);
+                    }
+                }
+            }
+
             // If this codegen unit contains the main function, also create the
             // wrapper here
             if let Some(entry) = maybe_create_entry_wrapper::<Builder<'_, '_, '_>>(&cx) {
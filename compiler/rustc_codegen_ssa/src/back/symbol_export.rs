@@ -15,7 +15,7 @@
 use rustc_middle::ty::subst::{GenericArgKind, SubstsRef};
 use rustc_middle::ty::Instance;
 use rustc_middle::ty::{self, SymbolName, TyCtxt};
-use rustc_session::config::{CrateType, OomStrategy};
+use rustc_session::config::{CrateType, OomStrategy, ShareGenerics};
 use rustc_target::spec::SanitizerSet;
 
 pub fn threshold(tcx: TyCtxt<'_>) -> SymbolExportLevel {
@@ -315,6 +315,11 @@ fn exported_symbols_provider_local(
         // external linkage is enough for monomorphization to be linked to.
         let need_visibility = tcx.sess.target.dynamic_linking && !tcx.sess.target.only_cdylib;
 
+        // With `-Zshare-generics=matching-opt-level`, only instantiations of upstream items are
+        // shared, see `mono_item_visibility`.
+        let only_upstream_items =
+            tcx.sess.opts.share_generics_mode() == ShareGenerics::MatchingOptLevel;
+
         let (_, cgus) = tcx.collect_and_partition_mono_items(());
 
         for (mono_item, &(linkage, visibility)) in cgus.iter().flat_map(|cgu| cgu.items().iter()) {
@@ -332,7 +337,9 @@ fn exported_symbols_provider_local(
 
             match *mono_item {
                 MonoItem::Fn(Instance { def: InstanceDef::Item(def), substs }) => {
-                    if substs.non_erasable_generics().next().is_some() {
+                    if substs.non_erasable_generics().next().is_some()
+                        && !(only_upstream_items && def.is_local())
+                    {
                         let symbol = ExportedSymbol::Generic(def, substs);
                         symbols.push((
                             symbol,
@@ -382,7 +389,16 @@ fn upstream_monomorphizations_provider(
 
     let drop_in_place_fn_def_id = tcx.lang_items().drop_in_place_fn();
 
+    let require_matching_opt_level =
+        tcx.sess.opts.share_generics_mode() == ShareGenerics::MatchingOptLevel;
+
     for &cnum in cnums.iter() {
+        if require_matching_opt_level && tcx.crate_opt_level(cnum) != tcx.sess.opts.optimize {
+            // Linking to this crate's monomorphizations would mean calling code that was
+            // optimized differently from ours, so we instantiate our own copies instead.
+            continue;
+        }
+
         for (exported_symbol, _) in tcx.exported_symbols(cnum).iter() {
             let (def_id, substs) = match *exported_symbol {
                 ExportedSymbol::Generic(def_id, substs) => (def_id, substs),
@@ -0,0 +1,379 @@
+//! Applying machine-applicable suggestions to the source files (`-Z fix`).
+//!
+//! The crate is checked repeatedly with diagnostics muted. After each check, the
+//! machine-applicable suggestions of all diagnostics are applied to an in-memory copy of the
+//! source files that the next check reads instead of the files on disk. Suggestions that overlap
+//! a suggestion applied in the same round are left for the next round, which will suggest them
+//! again if they still apply. Once a round applies nothing, the fixed files are written back (or
+//! printed as a diff), and the actual compilation runs on them as usual.
+
+use crate::Callbacks;
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_errors::CodeSuggestion;
+use rustc_interface::interface;
+use rustc_session::config::{FixMode, Input};
+use rustc_session::getopts::Matches;
+use rustc_session::{early_error, early_warn};
+use rustc_span::def_id::LOCAL_CRATE;
+use rustc_span::source_map::{FileLoader, RealFileLoader, SourceMap};
+use rustc_span::FileName;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+#[cfg(test)]
+mod tests;
+
+// This import blocks the use of panicking `print` and `println` in all the code
+// below. Please use `safe_print` and `safe_println` to avoid ICE when
+// encountering an I/O error during print.
+#[allow(unused_imports)]
+use std::{compile_error as print, compile_error as println};
+
+/// How often the crate is checked at most. Applying a suggestion can lead to new ones, e.g.
+/// removing an unused import can make another import unused, so this is usually more than one.
+const MAX_ROUNDS: usize = 10;
+
+/// Applies the machine-applicable suggestions for the crate described by `config` until no more
+/// of them apply, and makes `config` compile the result.
+pub(crate) fn apply_suggestions(
+    matches: &Matches,
+    config: &mut interface::Config,
+    callbacks: &mut (dyn Callbacks + Send),
+    mode: FixMode,
+) {
+    let error_format = config.opts.error_format;
+    let Input::File(path) = &config.input else {
+        early_error(error_format, "`-Z fix` requires the input to be a file");
+    };
+
+    let loader = FixedFileLoader::new(config.file_loader.take());
+    let mut fixed_point = false;
+    for _ in 0..MAX_ROUNDS {
+        let mut round = interface::Config {
+            opts: config.opts.clone(),
+            crate_cfg: interface::parse_cfgspecs(matches.opt_strs("cfg")),
+            crate_check_cfg: interface::parse_check_cfg(matches.opt_strs("check-cfg")),
+            input: Input::File(path.clone()),
+            output_file: config.output_file.clone(),
+            output_dir: config.output_dir.clone(),
+            file_loader: Some(Box::new(loader.clone())),
+            locale_resources: config.locale_resources,
+            lint_caps: Default::default(),
+            parse_sess_created: None,
+            register_lints: None,
+            override_queries: None,
+            make_codegen_backend: None,
+            registry: config.registry.clone(),
+        };
+        // The rounds only check the crate; they must not touch the incremental state.
+        round.opts.incremental = None;
+        callbacks.config(&mut round);
+
+        let suggestions = crate::catch_fatal_errors(|| {
+            interface::run_compiler(round, |compiler| {
+                let sess = compiler.session();
+                sess.diagnostic().collect_machine_applicable_suggestions();
+                let _ = compiler.enter(|queries| -> interface::Result<()> {
+                    queries.global_ctxt()?.enter(|tcx| tcx.analysis(()))
+                });
+                let suggestions = sess.diagnostic().take_machine_applicable_suggestions();
+                suggestions
+                    .iter()
+                    .filter_map(|suggestion| resolve(sess.source_map(), suggestion))
+                    .collect::<Vec<_>>()
+            })
+        });
+        // A fatal error stops the fixing; the compilation below reports it.
+        let Ok(suggestions) = suggestions else {
+            fixed_point = true;
+            break;
+        };
+        if !loader.apply(suggestions) {
+            fixed_point = true;
+            break;
+        }
+    }
+    if !fixed_point {
+        early_warn(
+            error_format,
+            &format!(
+                "`-Z fix` stopped after checking the crate {MAX_ROUNDS} times, \
+                 some suggestions may not have been applied"
+            ),
+        );
+    }
+
+    let mut files = loader.files.lock().unwrap();
+    for (path, file) in files.iter().filter(|(_, file)| file.fixed != file.original) {
+        match mode {
+            FixMode::InPlace => {
+                if let Err(err) = fs::write(path, &file.fixed) {
+                    early_error(
+                        error_format,
+                        &format!("failed to write `{}`: {err}", path.display()),
+                    );
+                }
+            }
+            FixMode::Diff => safe_print!("{}", unified_diff(path, &file.original, &file.fixed)),
+        }
+    }
+    // A diff leaves the files on disk alone, so that is what gets compiled.
+    if mode == FixMode::Diff {
+        files.clear();
+    }
+    drop(files);
+    config.file_loader = Some(Box::new(loader));
+}
+
+/// A part of a suggestion, resolved to a byte range in the source text of a local file.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Replacement {
+    path: PathBuf,
+    start: usize,
+    end: usize,
+    snippet: String,
+}
+
+impl Replacement {
+    /// Whether the order in which `self` and `other` are applied matters.
+    fn conflicts_with(&self, other: &Replacement) -> bool {
+        self.path == other.path
+            && (self.start == other.start || self.start < other.end && other.start < self.end)
+    }
+}
+
+/// Resolves the parts of `suggestion` to byte ranges in the source text (before normalization of
+/// line endings) of the local crate's files. Returns `None` if any part is outside of them.
+fn resolve(source_map: &SourceMap, suggestion: &CodeSuggestion) -> Option<Vec<Replacement>> {
+    // Like `rustfix`, we always pick the first of several alternatives.
+    let substitution = suggestion.substitutions.first()?;
+    substitution
+        .parts
+        .iter()
+        .map(|part| {
+            let span = part.span.data();
+            let file = source_map.lookup_source_file(span.lo);
+            if file.cnum != LOCAL_CRATE || !file.contains(span.hi) {
+                return None;
+            }
+            let FileName::Real(name) = &file.name else { return None };
+            Some(Replacement {
+                path: name.local_path()?.to_path_buf(),
+                start: file.original_relative_byte_pos(span.lo).0 as usize,
+                end: file.original_relative_byte_pos(span.hi).0 as usize,
+                snippet: part.snippet.clone(),
+            })
+        })
+        .collect()
+}
+
+struct FixedFile {
+    original: String,
+    fixed: String,
+}
+
+/// A [`FileLoader`] that reads the fixed text of the files that suggestions were applied to, and
+/// defers to the underlying loader for all others.
+#[derive(Clone)]
+struct FixedFileLoader {
+    inner: Arc<dyn FileLoader + Send + Sync>,
+    files: Arc<Mutex<FxIndexMap<PathBuf, FixedFile>>>,
+}
+
+impl FixedFileLoader {
+    fn new(inner: Option<Box<dyn FileLoader + Send + Sync>>) -> FixedFileLoader {
+        FixedFileLoader {
+            inner: inner.map_or_else(|| Arc::new(RealFileLoader) as Arc<_>, Arc::from),
+            files: Default::default(),
+        }
+    }
+
+    /// Applies all suggestions that do not conflict with an earlier one. Returns whether any
+    /// file changed.
+    fn apply(&self, suggestions: Vec<Vec<Replacement>>) -> bool {
+        let mut files = self.files.lock().unwrap();
+        for part in suggestions.iter().flatten() {
+            if !files.contains_key(&part.path) {
+                if let Ok(text) = self.inner.read_file(&part.path) {
+                    files.insert(
+                        part.path.clone(),
+                        FixedFile { original: text.clone(), fixed: text },
+                    );
+                }
+            }
+        }
+
+        let mut seen = FxHashSet::default();
+        let mut accepted: Vec<Replacement> = Vec::new();
+        for mut parts in suggestions {
+            // The same diagnostic is often emitted more than once, e.g. for each expansion of a
+            // macro.
+            if !seen.insert(parts.clone()) {
+                continue;
+            }
+            let in_bounds = parts.iter().all(|part| {
+                files.get(&part.path).is_some_and(|file| {
+                    part.start <= part.end
+                        && part.end <= file.fixed.len()
+                        && file.fixed.is_char_boundary(part.start)
+                        && file.fixed.is_char_boundary(part.end)
+                })
+            });
+            if !in_bounds {
+                continue;
+            }
+            parts.retain(|part| files[&part.path].fixed[part.start..part.end] != part.snippet);
+            let conflict = parts.iter().enumerate().any(|(i, part)| {
+                parts[..i].iter().chain(&accepted).any(|other| part.conflicts_with(other))
+            });
+            if !conflict {
+                accepted.extend(parts);
+            }
+        }
+
+        // Apply the replacements back to front, so that earlier offsets stay valid.
+        accepted.sort_by_key(|part| std::cmp::Reverse(part.start));
+        for part in &accepted {
+            files[&part.path].fixed.replace_range(part.start..part.end, &part.snippet);
+        }
+        !accepted.is_empty()
+    }
+}
+
+impl FileLoader for FixedFileLoader {
+    fn file_exists(&self, path: &Path) -> bool {
+        self.inner.file_exists(path)
+    }
+
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.files.lock().unwrap().get(path) {
+            Some(file) => Ok(file.fixed.clone()),
+            None => self.inner.read_file(path),
+        }
+    }
+
+    fn read_binary_file(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.files.lock().unwrap().get(path) {
+            Some(file) => Ok(file.fixed.clone().into_bytes()),
+            None => self.inner.read_binary_file(path),
+        }
+    }
+}
+
+/// The number of unchanged lines around each hunk of a diff.
+const CONTEXT: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Line {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+/// Returns the changes from `old` to `new` in the unified diff format.
+fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.split_inclusive('\n').collect();
+    let new: Vec<&str> = new.split_inclusive('\n').collect();
+    // Each line of the diff, with the indices of the next old and new line at that point.
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for kind in diff_lines(&old, &new) {
+        lines.push((kind, i, j));
+        match kind {
+            Line::Unchanged => (i, j) = (i + 1, j + 1),
+            Line::Removed => i += 1,
+            Line::Added => j += 1,
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", path.display(), path.display());
+    let mut changes = (0..lines.len()).filter(|&k| lines[k].0 != Line::Unchanged).peekable();
+    while let Some(first) = changes.next() {
+        // Extend the hunk for as long as the context of the next change would overlap or touch
+        // it, i.e. there are at most `2 * CONTEXT` unchanged lines in between.
+        let mut last = first;
+        while let Some(next) = changes.next_if(|&next| next - last - 1 <= 2 * CONTEXT) {
+            last = next;
+        }
+        let hunk = &lines[first.saturating_sub(CONTEXT)..lines.len().min(last + CONTEXT + 1)];
+        let old_len = hunk.iter().filter(|(kind, ..)| *kind != Line::Added).count();
+        let new_len = hunk.iter().filter(|(kind, ..)| *kind != Line::Removed).count();
+        // An empty range starts at the line before it.
+        let old_start = hunk[0].1 + usize::from(old_len > 0);
+        let new_start = hunk[0].2 + usize::from(new_len > 0);
+        writeln!(out, "@@ -{old_start},{old_len} +{new_start},{new_len} @@").unwrap();
+        for &(kind, i, j) in hunk {
+            let (marker, line) = match kind {
+                Line::Unchanged => (' ', old[i]),
+                Line::Removed => ('-', old[i]),
+                Line::Added => ('+', new[j]),
+            };
+            out.push(marker);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    out
+}
+
+/// Computes a shortest edit script from `old` to `new` with Myers' algorithm.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Line> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let offset = n + m + 1;
+    // For each diagonal `k`, the furthest `x` reached on it with the current number of edits.
+    let mut v = vec![0; 2 * offset as usize + 1];
+    // For each number of edits `d`, `v` on the diagonals `-d..=d` after `d` edits.
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=n + m {
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d
+                || (k != d && v[(offset + k - 1) as usize] < v[(offset + k + 1) as usize])
+            {
+                v[(offset + k + 1) as usize]
+            } else {
+                v[(offset + k - 1) as usize] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                (x, y) = (x + 1, y + 1);
+            }
+            v[(offset + k) as usize] = x;
+            if x >= n && y >= m {
+                trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+                break 'search;
+            }
+        }
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+    }
+
+    // Walk back from the end along the path that reached it.
+    let mut script = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        let k = x - y;
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let prev = &trace[d as usize - 1];
+            let at = |k: isize| prev[(k + d - 1) as usize];
+            let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) { k + 1 } else { k - 1 };
+            (at(prev_k), at(prev_k) - prev_k)
+        };
+        while x > prev_x && y > prev_y {
+            script.push(Line::Unchanged);
+            (x, y) = (x - 1, y - 1);
+        }
+        if d > 0 {
+            script.push(if x == prev_x { Line::Added } else { Line::Removed });
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    script.reverse();
+    script
+}
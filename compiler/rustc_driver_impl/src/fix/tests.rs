@@ -0,0 +1,74 @@
+use super::*;
+
+use Line::*;
+
+fn lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// The lines `1` to `n`, with the lines in `changed` replaced.
+fn numbered(n: usize, changed: &[usize]) -> String {
+    (1..=n)
+        .map(|i| if changed.contains(&i) { format!("x{i}\n") } else { format!("{i}\n") })
+        .collect()
+}
+
+#[test]
+fn diff_empty() {
+    assert_eq!(diff_lines(&[], &[]), []);
+    assert_eq!(diff_lines(&[], &lines("a\nb\n")), [Added, Added]);
+    assert_eq!(diff_lines(&lines("a\nb\n"), &[]), [Removed, Removed]);
+    assert_eq!(unified_diff(Path::new("a.rs"), "", ""), "--- a.rs\n+++ a.rs\n");
+    assert_eq!(
+        unified_diff(Path::new("a.rs"), "", "a\n"),
+        "--- a.rs\n+++ a.rs\n@@ -0,0 +1,1 @@\n+a\n"
+    );
+    assert_eq!(
+        unified_diff(Path::new("a.rs"), "a\n", ""),
+        "--- a.rs\n+++ a.rs\n@@ -1,1 +0,0 @@\n-a\n"
+    );
+}
+
+#[test]
+fn diff_insertions() {
+    let (old, new) = (lines("a\nb\nc\n"), lines("a\nx\nb\nc\ny\n"));
+    assert_eq!(diff_lines(&old, &new), [Unchanged, Added, Unchanged, Unchanged, Added]);
+    assert_eq!(
+        unified_diff(Path::new("a.rs"), "a\nb\nc\n", "a\nx\nb\nc\ny\n"),
+        "--- a.rs\n+++ a.rs\n@@ -1,3 +1,5 @@\n a\n+x\n b\n c\n+y\n"
+    );
+}
+
+#[test]
+fn diff_deletions() {
+    let (old, new) = (lines("a\nx\nb\nc\ny\n"), lines("a\nb\nc\n"));
+    assert_eq!(diff_lines(&old, &new), [Unchanged, Removed, Unchanged, Unchanged, Removed]);
+    assert_eq!(
+        unified_diff(Path::new("a.rs"), "a\nx\nb\nc\ny\n", "a\nb\nc\n"),
+        "--- a.rs\n+++ a.rs\n@@ -1,5 +1,3 @@\n a\n-x\n b\n c\n-y\n"
+    );
+}
+
+#[test]
+fn diff_missing_newline() {
+    assert_eq!(
+        unified_diff(Path::new("a.rs"), "a\nb", "a\nc"),
+        "--- a.rs\n+++ a.rs\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n\\ No newline at end of file\n"
+    );
+}
+
+#[test]
+fn hunks_share_context() {
+    // Six unchanged lines between two changes are shared context, so they form one hunk.
+    let diff = unified_diff(Path::new("a.rs"), &numbered(20, &[]), &numbered(20, &[5, 12]));
+    assert_eq!(
+        diff,
+        "--- a.rs\n+++ a.rs\n@@ -2,14 +2,14 @@\n 2\n 3\n 4\n-5\n+x5\n 6\n 7\n 8\n 9\n 10\n 11\n-12\n+x12\n 13\n 14\n 15\n"
+    );
+    // With seven unchanged lines in between, the changes get separate hunks.
+    let diff = unified_diff(Path::new("a.rs"), &numbered(20, &[]), &numbered(20, &[5, 13]));
+    assert_eq!(
+        diff,
+        "--- a.rs\n+++ a.rs\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+x5\n 6\n 7\n 8\n@@ -10,7 +10,7 @@\n 10\n 11\n 12\n-13\n+x13\n 14\n 15\n 16\n"
+    );
+}
@@ -33,6 +33,7 @@
 use rustc_interface::{interface, Queries};
 use rustc_lint::LintStore;
 use rustc_metadata::locator;
+use rustc_session::config::PrintTypeSizesFormat;
 use rustc_session::config::{nightly_options, CG_OPTIONS, Z_OPTIONS};
 use rustc_session::config::{ErrorOutputType, Input, OutputType, PrintRequest, TrimmedDefPaths};
 use rustc_session::cstore::MetadataLoader;
@@ -65,6 +66,7 @@
 use std::{compile_error as print, compile_error as println};
 
 pub mod args;
+mod fix;
 pub mod pretty;
 #[macro_use]
 mod print;
@@ -286,6 +288,9 @@ fn run_compiler(
         Ok(Some(input)) => {
             config.input = input;
 
+            if let Some(mode) = config.opts.unstable_opts.fix {
+                fix::apply_suggestions(&matches, &mut config, callbacks, mode);
+            }
             callbacks.config(&mut config);
         }
         Ok(None) => match matches.free.len() {
@@ -405,7 +410,10 @@ fn run_compiler(
             queries.ongoing_codegen()?;
 
             if sess.opts.unstable_opts.print_type_sizes {
-                sess.code_stats.print_type_sizes();
+                match sess.opts.unstable_opts.print_type_sizes_format {
+                    PrintTypeSizesFormat::Text => sess.code_stats.print_type_sizes(),
+                    PrintTypeSizesFormat::Json => sess.code_stats.print_type_sizes_json(),
+                }
             }
 
             let linker = queries.linker()?;
@@ -40,6 +40,7 @@
 };
 use rustc_fluent_macro::fluent_messages;
 pub use rustc_lint_defs::{pluralize, Applicability};
+use rustc_span::hygiene::ExpnKind;
 use rustc_span::source_map::SourceMap;
 pub use rustc_span::ErrorGuaranteed;
 use rustc_span::{Loc, Span};
@@ -63,6 +64,7 @@
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 #[cfg(test)]
@@ -462,6 +464,24 @@ struct HandlerInner {
     ///
     /// [RFC-2383]: https://rust-lang.github.io/rfcs/2383-lint-reasons.html
     fulfilled_expectations: FxHashSet<LintExpectationId>,
+
+    /// If `Some`, diagnostics are not emitted, and the machine-applicable suggestions of the
+    /// diagnostics that would have been emitted are collected here instead. This is used by
+    /// `-Zfix`, see [`Handler::collect_machine_applicable_suggestions`].
+    collected_suggestions: Option<Vec<CodeSuggestion>>,
+
+    /// The errors and warnings from macro expansions, grouped by their level, code, message and
+    /// the location of their primary span in the macro (see [`HandlerFlags::group_diagnostics`]).
+    /// A group is emitted as a single diagnostic when the compilation ends.
+    grouped_diagnostics: FxIndexMap<Hash128, DiagnosticGroup>,
+}
+
+/// Identical diagnostics from different expansions of the same macro.
+struct DiagnosticGroup {
+    /// The first diagnostic of the group, which is the one emitted.
+    diagnostic: Diagnostic,
+    /// The outermost call site of the expansion each diagnostic of the group came from.
+    expansion_sites: Vec<Span>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -506,6 +526,10 @@ pub struct HandlerFlags {
     pub macro_backtrace: bool,
     /// If true, identical diagnostics are reported only once.
     pub deduplicate_diagnostics: bool,
+    /// If true, identical diagnostics from different expansions of the same macro are reported
+    /// only once, with a list of the expansion sites.
+    /// (rustc: see `-Z group-diagnostics`)
+    pub group_diagnostics: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`.
     pub track_diagnostics: bool,
 }
@@ -513,6 +537,7 @@ pub struct HandlerFlags {
 impl Drop for HandlerInner {
     fn drop(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_grouped_diagnostics();
 
         if !self.has_errors() {
             let bugs = std::mem::replace(&mut self.delayed_span_bugs, Vec::new());
@@ -617,6 +642,8 @@ pub fn with_emitter_and_flags(
                 check_unstable_expect_diagnostics: false,
                 unstable_expect_diagnostics: Vec::new(),
                 fulfilled_expectations: Default::default(),
+                collected_suggestions: None,
+                grouped_diagnostics: Default::default(),
             }),
         }
     }
@@ -1268,6 +1295,25 @@ pub fn steal_fulfilled_expectation_ids(&self) -> FxHashSet<LintExpectationId> {
         std::mem::take(&mut self.inner.borrow_mut().fulfilled_expectations)
     }
 
+    /// Stops emitting diagnostics. Instead, the machine-applicable suggestions of all
+    /// diagnostics that would have been emitted from now on are collected, to be retrieved with
+    /// [`Handler::take_machine_applicable_suggestions`]. Errors are still counted, and bugs are
+    /// still emitted.
+    pub fn collect_machine_applicable_suggestions(&self) {
+        self.inner.borrow_mut().collected_suggestions.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the suggestions collected since the last call, see
+    /// [`Handler::collect_machine_applicable_suggestions`].
+    pub fn take_machine_applicable_suggestions(&self) -> Vec<CodeSuggestion> {
+        self.inner
+            .borrow_mut()
+            .collected_suggestions
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     pub fn flush_delayed(&self) {
         let mut inner = self.inner.lock();
         let bugs = std::mem::replace(&mut inner.delayed_span_bugs, Vec::new());
@@ -1401,11 +1447,33 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
 
                 diagnostic.children.drain_filter(already_emitted_sub).for_each(|_| {});
 
-                self.emitter.emit_diagnostic(diagnostic);
-                if diagnostic.is_error() {
-                    self.deduplicated_err_count += 1;
-                } else if let Warning(_) = diagnostic.level {
-                    self.deduplicated_warn_count += 1;
+                // Errors have to be visible right away if they are turned into bugs.
+                let grouped = if self.flags.group_diagnostics
+                    && self.flags.treat_err_as_bug.is_none()
+                    && self.collected_suggestions.is_none()
+                {
+                    self.group_diagnostic(diagnostic)
+                } else {
+                    None
+                };
+                match &mut self.collected_suggestions {
+                    _ if grouped.is_some() => {}
+                    Some(collected) if diagnostic.level != Level::Bug => {
+                        if let Ok(suggestions) = &diagnostic.suggestions {
+                            collected.extend(suggestions.iter().cloned().filter(|suggestion| {
+                                suggestion.applicability == Applicability::MachineApplicable
+                            }));
+                        }
+                    }
+                    _ => self.emitter.emit_diagnostic(diagnostic),
+                }
+                // A diagnostic that joins an existing group doesn't show up separately.
+                if grouped != Some(false) {
+                    if diagnostic.is_error() {
+                        self.deduplicated_err_count += 1;
+                    } else if let Warning(_) = diagnostic.level {
+                        self.deduplicated_warn_count += 1;
+                    }
                 }
             }
             if diagnostic.is_error() {
@@ -1427,6 +1495,68 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
         guaranteed
     }
 
+    /// Adds `diagnostic` to its group if it is an error or warning from a macro expansion, see
+    /// [`HandlerFlags::group_diagnostics`]. Returns `None` if it isn't, and otherwise whether it
+    /// started a new group.
+    fn group_diagnostic(&mut self, diagnostic: &Diagnostic) -> Option<bool> {
+        if !matches!(diagnostic.level, Level::Error { .. } | Level::Warning(_)) {
+            return None;
+        }
+        let span = diagnostic.span.primary_span()?;
+        if !matches!(span.ctxt().outer_expn_data().kind, ExpnKind::Macro(..)) {
+            return None;
+        }
+
+        // Expansions of the same macro produce spans with the same location in the macro, but
+        // different syntax contexts. Fluent messages are compared by their identifier, so
+        // diagnostics that only differ in their arguments are grouped as well.
+        let mut hasher = StableHasher::new();
+        diagnostic.level.hash(&mut hasher);
+        diagnostic.code.hash(&mut hasher);
+        for (message, _) in &diagnostic.message {
+            message.hash(&mut hasher);
+        }
+        span.lo().hash(&mut hasher);
+        span.hi().hash(&mut hasher);
+        let key = hasher.finish();
+
+        let site = span.source_callsite();
+        if let Some(group) = self.grouped_diagnostics.get_mut(&key) {
+            group.expansion_sites.push(site);
+            return Some(false);
+        }
+        let group = DiagnosticGroup { diagnostic: diagnostic.clone(), expansion_sites: vec![site] };
+        self.grouped_diagnostics.insert(key, group);
+        Some(true)
+    }
+
+    /// Emits the groups collected by [`HandlerInner::group_diagnostic`], each as its first
+    /// diagnostic, with a note listing all expansion sites if there is more than one.
+    fn emit_grouped_diagnostics(&mut self) {
+        for (_, group) in std::mem::take(&mut self.grouped_diagnostics) {
+            let DiagnosticGroup { mut diagnostic, expansion_sites } = group;
+            if expansion_sites.len() > 1 {
+                let source_map = self.emitter.source_map();
+                let sites: String = expansion_sites
+                    .iter()
+                    .map(|&site| match source_map {
+                        Some(source_map) => {
+                            let loc = source_map.lookup_char_pos(site.lo());
+                            let file = source_map.filename_for_diagnostics(&loc.file.name);
+                            format!("\n- {file}:{}:{}", loc.line, loc.col.0 + 1)
+                        }
+                        None => format!("\n- {site:?}"),
+                    })
+                    .collect();
+                diagnostic.note(format!(
+                    "this diagnostic was reported {} times, for the expansions at:{sites}",
+                    expansion_sites.len()
+                ));
+            }
+            self.emitter.emit_diagnostic(&diagnostic);
+        }
+    }
+
     fn emit_artifact_notification(&mut self, path: &Path, artifact_type: &str) {
         self.emitter.emit_artifact_notification(path, artifact_type);
     }
@@ -1447,6 +1577,11 @@ fn delayed_bug_count(&self) -> usize {
 
     fn print_error_count(&mut self, registry: &Registry) {
         self.emit_stashed_diagnostics();
+        self.emit_grouped_diagnostics();
+
+        if self.collected_suggestions.is_some() {
+            return;
+        }
 
         let warnings = match self.deduplicated_warn_count {
             0 => String::new(),
@@ -0,0 +1,465 @@
+//! A SARIF 2.1.0 emitter for errors.
+//!
+//! SARIF (the Static Analysis Results Interchange Format) is what code scanning and code review
+//! platforms ingest. Unlike the JSON emitter, which prints one object per diagnostic, a SARIF log
+//! is a single document, so diagnostics are collected and the log is written when the emitter is
+//! dropped at the end of the session.
+//!
+//! Error codes and lints become rules, diagnostics become results, spans become regions and
+//! suggestions become fixes. Notes and help messages without a span are appended to the message
+//! of their result.
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::{to_fluent_args, Translate};
+use crate::{CodeSuggestion, DiagnosticId, FluentBundle, LazyFallbackBundle, Level, SpanLabel};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_error_messages::FluentArgs;
+use rustc_lint_defs::Applicability;
+use rustc_span::{FileName, Span};
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+#[cfg(test)]
+mod tests;
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    fluent_bundle: Option<Lrc<FluentBundle>>,
+    fallback_bundle: LazyFallbackBundle,
+    /// The rules referenced by `results` so far, by their id.
+    rules: FxIndexMap<String, Rule>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            registry,
+            source_map,
+            fluent_bundle,
+            fallback_bundle,
+        )
+    }
+
+    pub fn basic(
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(
+            None,
+            Lrc::new(SourceMap::new(file_path_mapping)),
+            fluent_bundle,
+            fallback_bundle,
+        )
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            rules: FxIndexMap::default(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the rule for `code`, adding it if it is new.
+    fn rule_index(&mut self, code: &DiagnosticId) -> usize {
+        let id = match code {
+            DiagnosticId::Error(code) => code,
+            DiagnosticId::Lint { name, .. } => name,
+        };
+        if let Some(index) = self.rules.get_index_of(id) {
+            return index;
+        }
+        let help_uri = match code {
+            DiagnosticId::Error(code) => {
+                Some(format!("https://doc.rust-lang.org/error_codes/{code}.html"))
+            }
+            DiagnosticId::Lint { name, .. } => name.strip_prefix("clippy::").map(|name| {
+                format!("https://rust-lang.github.io/rust-clippy/master/index.html#{name}")
+            }),
+        };
+        let explanation =
+            self.registry.as_ref().and_then(|registry| registry.try_find_description(id).ok()).map(
+                |markdown| Message {
+                    text: markdown.to_owned(),
+                    markdown: Some(markdown.to_owned()),
+                },
+            );
+        let rule = Rule { id: id.clone(), help_uri, full_description: explanation };
+        self.rules.insert_full(id.clone(), rule).0
+    }
+
+    fn region(&self, span: Span) -> Option<(String, Region)> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        let region = Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset: byte_start,
+            byte_length: byte_end - byte_start,
+        };
+        Some((file_uri(&self.sm, &start.file.name), region))
+    }
+
+    fn location(&self, span: Span, message: Option<String>) -> Option<Location> {
+        let (uri, region) = self.region(span)?;
+        Some(Location {
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation { uri },
+                region,
+            },
+            message: message.map(Message::text),
+        })
+    }
+
+    fn label_location(&self, label: SpanLabel, args: &FluentArgs<'_>) -> Option<Location> {
+        let message = label.label.map(|m| self.translate_message(&m, args).unwrap().to_string());
+        self.location(label.span, message)
+    }
+
+    fn fix(&self, suggestion: &CodeSuggestion, args: &FluentArgs<'_>) -> Vec<Fix> {
+        let description = self.translate_message(&suggestion.msg, args).unwrap().to_string();
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                let mut changes: FxIndexMap<String, Vec<Replacement>> = FxIndexMap::default();
+                for part in &substitution.parts {
+                    let Some((uri, deleted_region)) = self.region(part.span) else { continue };
+                    changes.entry(uri).or_default().push(Replacement {
+                        deleted_region,
+                        inserted_content: Content { text: part.snippet.clone() },
+                    });
+                }
+                Fix {
+                    description: Message::text(description.clone()),
+                    artifact_changes: changes
+                        .into_iter()
+                        .map(|(uri, replacements)| ArtifactChange {
+                            artifact_location: ArtifactLocation { uri },
+                            replacements,
+                        })
+                        .collect(),
+                    properties: FixProperties { applicability: suggestion.applicability },
+                }
+            })
+            .collect()
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = Log {
+            schema: "https://json.schemastore.org/sarif-2.1.0.json",
+            version: "2.1.0",
+            runs: [Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "rustc",
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: self.rules.values().collect(),
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        writeln!(&mut self.dst, "{}", serde_json::to_string_pretty(&log).unwrap())?;
+        self.dst.flush()
+    }
+}
+
+impl Translate for SarifEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.fluent_bundle.as_ref()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &self.fallback_bundle
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        // "aborting due to previous error" and the like are not results.
+        if diag.level.is_failure_note() {
+            return;
+        }
+        let args = to_fluent_args(diag.args());
+
+        let mut span = diag.span.clone();
+        let mut children = diag.children.clone();
+        self.fix_multispans_in_extern_macros(&mut span, &mut children);
+
+        let mut text = self.translate_messages(&diag.message, &args).to_string();
+        let mut locations = Vec::new();
+        let mut related_locations = Vec::new();
+        for label in span.span_labels() {
+            let is_primary = label.is_primary;
+            let Some(location) = self.label_location(label, &args) else { continue };
+            if is_primary {
+                locations.push(location);
+            } else {
+                related_locations.push(location);
+            }
+        }
+        for child in &children {
+            let message = self.translate_messages(&child.message, &args).to_string();
+            let span = child.render_span.as_ref().unwrap_or(&child.span);
+            let Some(primary) = span.primary_span() else {
+                text.push_str(&format!("\n{}: {message}", child.level.to_str()));
+                continue;
+            };
+            related_locations.extend(self.location(primary, Some(message)));
+            related_locations.extend(
+                span.span_labels()
+                    .into_iter()
+                    .filter(|label| !label.is_primary)
+                    .filter_map(|label| self.label_location(label, &args)),
+            );
+        }
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flatten()
+            .flat_map(|suggestion| self.fix(suggestion, &args))
+            .collect();
+
+        let rule_index = diag.code.as_ref().map(|code| self.rule_index(code));
+        let result = SarifResult {
+            rule_id: rule_index.map(|index| self.rules[index].id.clone()),
+            rule_index,
+            level: sarif_level(diag.level),
+            message: Message::text(text),
+            locations,
+            related_locations,
+            fixes,
+        };
+        self.results.push(result);
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        // The explanations are part of the rules.
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if let Err(e) = self.write_log() {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+/// The SARIF level of a diagnostic; lints report the level they were emitted at.
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error { .. } => "error",
+        Level::Warning(_) => "warning",
+        Level::Note | Level::OnceNote | Level::Help | Level::FailureNote => "note",
+        Level::Allow | Level::Expect(_) => "none",
+    }
+}
+
+/// SARIF locations are URIs: relative paths stay relative to the working directory, absolute
+/// paths become `file` URIs.
+fn file_uri(sm: &SourceMap, name: &FileName) -> String {
+    let path = sm.filename_for_diagnostics(name).to_string().replace('\\', "/");
+    let mut uri = String::with_capacity(path.len());
+    if path.starts_with('/') {
+        uri.push_str("file://");
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        // A Windows path with a drive letter.
+        uri.push_str("file:///");
+    }
+    for c in path.chars() {
+        match c {
+            ' ' => uri.push_str("%20"),
+            '#' => uri.push_str("%23"),
+            '%' => uri.push_str("%25"),
+            '?' => uri.push_str("%3F"),
+            c => uri.push(c),
+        }
+    }
+    uri
+}
+
+// The following data types are provided just for serialisation. They only cover the parts of
+// the SARIF 2.1.0 schema that we use.
+
+#[derive(Serialize)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run<'a>; 1],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    /// How columns are counted; rustc counts characters.
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: Driver<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<&'a Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    /// The error code or lint name.
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    /// The explanation of an error code, as shown by `rustc --explain`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning", "note" or "none".
+    level: &'static str,
+    message: Message,
+    /// The primary spans.
+    locations: Vec<Location>,
+    /// Secondary spans, and the spans of notes and help messages.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    markdown: Option<String>,
+}
+
+impl Message {
+    fn text(text: String) -> Message {
+        Message { text, markdown: None }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    /// The label of the span.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    /// 1-based, exclusive.
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    /// Whether the fix can be applied automatically, as with `rustfix`.
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: Content,
+}
+
+#[derive(Serialize)]
+struct Content {
+    text: String,
+}
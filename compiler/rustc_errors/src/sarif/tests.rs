@@ -0,0 +1,130 @@
+use super::*;
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::{Applicability, DiagnosticId, Handler};
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+/// Emits the diagnostics created by `emit` for `code` and returns the SARIF log.
+fn test_log(code: &str, emit: impl FnOnce(&Handler)) -> Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("src/test file.rs").to_owned().into(), code.to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(vec![crate::DEFAULT_LOCALE_RESOURCE], false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let emitter = SarifEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            None,
+            sm,
+            None,
+            fallback_bundle,
+        );
+        let handler = Handler::with_emitter(true, None, Box::new(emitter));
+        emit(&handler);
+        // The log is written when the emitter is dropped.
+        drop(handler);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+    })
+}
+
+fn span(lo: u32, hi: u32) -> Span {
+    Span::with_root_ctxt(BytePos(lo), BytePos(hi))
+}
+
+#[test]
+fn error_with_fix() {
+    let log = test_log("fn main() {\n    let x = 1;\n}\n", |handler| {
+        handler
+            .struct_span_err_with_code(
+                span(20, 21),
+                "bad name",
+                DiagnosticId::Error("E0999".into()),
+            )
+            .span_suggestion(span(20, 21), "rename it", "y", Applicability::MachineApplicable)
+            .note("names matter")
+            .emit();
+    });
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["columnKind"], "unicodeCodePoints");
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "E0999");
+    assert_eq!(
+        run["tool"]["driver"]["rules"][0]["helpUri"],
+        "https://doc.rust-lang.org/error_codes/E0999.html"
+    );
+
+    let result = &run["results"][0];
+    assert_eq!(run["results"].as_array().unwrap().len(), 1);
+    assert_eq!(result["ruleId"], "E0999");
+    assert_eq!(result["ruleIndex"], 0);
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "bad name\nnote: names matter");
+
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "src/test%20file.rs");
+    let region = &location["region"];
+    assert_eq!(region["startLine"], 2);
+    assert_eq!(region["startColumn"], 9);
+    assert_eq!(region["endLine"], 2);
+    assert_eq!(region["endColumn"], 10);
+    assert_eq!(region["byteOffset"], 20);
+    assert_eq!(region["byteLength"], 1);
+
+    let fix = &result["fixes"][0];
+    assert_eq!(fix["description"]["text"], "rename it");
+    assert_eq!(fix["properties"]["applicability"], "MachineApplicable");
+    let replacement = &fix["artifactChanges"][0]["replacements"][0];
+    assert_eq!(replacement["deletedRegion"]["byteOffset"], 20);
+    assert_eq!(replacement["insertedContent"]["text"], "y");
+}
+
+#[test]
+fn lints_share_rules() {
+    let lint = || DiagnosticId::Lint {
+        name: "clippy::some_lint".into(),
+        has_future_breakage: false,
+        is_force_warn: false,
+    };
+    let log = test_log("fn a() {}\nfn b() {}\n", |handler| {
+        handler.struct_span_warn(span(3, 4), "first").code(lint()).emit();
+        handler.struct_span_warn(span(13, 14), "second").code(lint()).emit();
+    });
+    let run = &log["runs"][0];
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(
+        rules[0]["helpUri"],
+        "https://rust-lang.github.io/rust-clippy/master/index.html#some_lint"
+    );
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    for result in results {
+        assert_eq!(result["level"], "warning");
+        assert_eq!(result["ruleIndex"], 0);
+    }
+    assert_eq!(results[1]["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+}
@@ -861,6 +861,11 @@ pub fn start_codegen<'tcx>(
 
     info!("Post-codegen\n{:?}", tcx.debug_stats());
 
+    // This includes the size of the code emitted by the backend, so it has to wait until now.
+    if !tcx.sess.opts.unstable_opts.no_codegen && tcx.sess.opts.output_types.should_codegen() {
+        rustc_monomorphize::dump_mono_items_stats(tcx);
+    }
+
     if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
         if let Err(error) = rustc_mir_transform::dump_mir::emit_mir(tcx) {
             tcx.sess.emit_err(errors::CantEmitMIR { error });
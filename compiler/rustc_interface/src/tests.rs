@@ -8,6 +8,7 @@
 use rustc_session::config::DebugInfo;
 use rustc_session::config::Input;
 use rustc_session::config::InstrumentXRay;
+use rustc_session::config::ShareGenerics;
 use rustc_session::config::TraitSolver;
 use rustc_session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc_session::config::{
@@ -15,7 +16,7 @@
     ProcMacroExecutionStrategy, SymbolManglingVersion, WasiExecModel,
 };
 use rustc_session::config::{CFGuard, ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
-use rustc_session::config::{DumpMonoStatsFormat, MirSpanview};
+use rustc_session::config::{DumpMonoStatsFormat, FixMode, MirSpanview, PrintTypeSizesFormat};
 use rustc_session::config::{ErrorOutputType, ExternLocation, LocationDetail, Options, Strip};
 use rustc_session::config::{InstrumentCoverage, Passes};
 use rustc_session::lint::Level;
@@ -666,7 +667,9 @@ macro_rules! untracked {
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
+    untracked!(fix, Some(FixMode::Diff));
     untracked!(future_incompat_test, true);
+    untracked!(group_diagnostics, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
     untracked!(incremental_info, true);
@@ -690,6 +693,7 @@ macro_rules! untracked {
     untracked!(print_llvm_passes, true);
     untracked!(print_mono_items, Some(String::from("abc")));
     untracked!(print_type_sizes, true);
+    untracked!(print_type_sizes_format, PrintTypeSizesFormat::Json);
     untracked!(proc_macro_backtrace, true);
     untracked!(proc_macro_execution_strategy, ProcMacroExecutionStrategy::CrossThread);
     untracked!(profile_closures, true);
@@ -752,6 +756,7 @@ macro_rules! tracked {
     tracked!(force_unstable_if_unmarked, true);
     tracked!(fuel, Some(("abc".to_string(), 99)));
     tracked!(function_sections, Some(false));
+    tracked!(future_size_limit, Some(16384));
     tracked!(human_readable_cgu_names, true);
     tracked!(incremental_ignore_spans, true);
     tracked!(inline_in_all_cgus, Some(true));
@@ -802,7 +807,7 @@ macro_rules! tracked {
     tracked!(sanitizer_memory_track_origins, 2);
     tracked!(sanitizer_recover, SanitizerSet::ADDRESS);
     tracked!(saturating_float_casts, Some(true));
-    tracked!(share_generics, Some(true));
+    tracked!(share_generics, Some(ShareGenerics::MatchingOptLevel));
     tracked!(show_span, Some(String::from("abc")));
     tracked!(simulate_remapped_rust_src_base, Some(PathBuf::from("/rustc/abc")));
     tracked!(split_lto_unit, Some(true));
@@ -3057,6 +3057,62 @@
     "detects large moves or copies",
 }
 
+declare_lint! {
+    /// The `large_futures` lint detects futures and generators whose state
+    /// machine is larger than the limit given with `-Z future-size-limit`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z future-size-limit=10000)
+    /// async fn wait() {}
+    ///
+    /// pub async fn test(arg: [u8; 8192]) {
+    ///     wait().await;
+    ///     drop(arg);
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: this future is 16386 bytes, which is larger than the limit of 10000 bytes
+    ///  --> src/lib.rs:3:36
+    ///   |
+    /// 3 |   pub async fn test(arg: [u8; 8192]) {
+    ///   |  ___________________---______________^
+    ///   | |                   |
+    ///   | |                   `arg` (8192 bytes) is held across the suspension point
+    /// 4 | |     wait().await;
+    ///   | |            ----- the largest suspension point
+    /// 5 | |     drop(arg);
+    /// 6 | | }
+    ///   | |_^
+    ///   |
+    ///   = note: the limit is set with `-Z future-size-limit`
+    ///   = help: consider boxing the largest values held across the suspension point, or the future itself with `Box::pin`
+    ///   = note: `#[warn(large_futures)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// A future holds every local that is alive across an `.await` in its
+    /// state. Large futures are expensive to move and can overflow the stack
+    /// when they are awaited or spawned. The lint reports the values held
+    /// across the suspension point that takes up the most space, which can
+    /// be boxed to shrink the future.
+    ///
+    /// The lint only fires if a limit is set with `-Z future-size-limit`;
+    /// there is no crate-level attribute for it. The size of a future is only
+    /// known once its generic parameters are, so the lint runs while
+    /// collecting the items to generate code for. As a result, it never fires
+    /// under `cargo check`, it never fires for a generic `async fn` that the
+    /// crate does not instantiate, and it fires once for every instantiation
+    /// that is too large.
+    pub LARGE_FUTURES,
+    Warn,
+    "detects futures and generators with a large state",
+}
+
 declare_lint! {
     /// The `deprecated_cfg_attr_crate_type_name` lint detects uses of the
     /// `#![cfg_attr(..., crate_type = "...")]` and
@@ -3317,6 +3373,7 @@
         INVALID_TYPE_PARAM_DEFAULT,
         IRREFUTABLE_LET_PATTERNS,
         LARGE_ASSIGNMENTS,
+        LARGE_FUTURES,
         LATE_BOUND_LIFETIME_ARGUMENTS,
         LEGACY_DERIVE_HELPERS,
         LOSSY_PROVENANCE_CASTS,
@@ -1374,6 +1374,10 @@
         desc { "getting a crate's configured panic-in-drop strategy" }
         separate_provide_extern
     }
+    query crate_opt_level(_: CrateNum) -> OptLevel {
+        desc { "getting the optimization level a crate was compiled with" }
+        separate_provide_extern
+    }
     query is_no_builtins(_: CrateNum) -> bool {
         fatal_cycle
         desc { "getting whether a crate has `#![no_builtins]`" }
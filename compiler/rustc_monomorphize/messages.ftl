@@ -22,6 +22,18 @@ monomorphize_large_assignments =
     .label = value moved from here
     .note = The current maximum size is {$limit}, but it can be customized with the move_size_limit attribute: `#![move_size_limit = "..."]`
 
+monomorphize_large_future =
+    this {$descr} is {$size} bytes, which is larger than the limit of {$limit} bytes
+    .suspension_point = the largest suspension point
+    .note = the limit is set with `-Z future-size-limit`
+    .help = consider boxing the largest values held across the suspension point, or the {$descr} itself with `Box::pin`
+
+monomorphize_large_future_held_local =
+    `{$name}` ({$size} byte{$s}) is held across the suspension point
+
+monomorphize_large_future_held_temporary =
+    a temporary value ({$size} byte{$s}) is held across the suspension point
+
 monomorphize_couldnt_dump_mono_stats =
     unexpected error occurred while dumping monomorphization stats: {$error}
 
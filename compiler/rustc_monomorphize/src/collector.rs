@@ -175,6 +175,7 @@
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::{par_for_each_in, MTLock, MTLockRef};
+use rustc_errors::pluralize;
 use rustc_hir as hir;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{DefId, DefIdMap, LocalDefId};
@@ -195,7 +196,7 @@
 };
 use rustc_middle::{middle::codegen_fn_attrs::CodegenFnAttrFlags, mir::visit::TyContext};
 use rustc_session::config::EntryFnType;
-use rustc_session::lint::builtin::LARGE_ASSIGNMENTS;
+use rustc_session::lint::builtin::{LARGE_ASSIGNMENTS, LARGE_FUTURES};
 use rustc_session::Limit;
 use rustc_span::source_map::{dummy_spanned, respan, Span, Spanned, DUMMY_SP};
 use rustc_target::abi::Size;
@@ -203,7 +204,8 @@
 use std::path::PathBuf;
 
 use crate::errors::{
-    EncounteredErrorWhileInstantiating, LargeAssignmentsLint, RecursionLimit, TypeLengthLimit,
+    EncounteredErrorWhileInstantiating, HeldAcrossSuspension, LargeAssignmentsLint,
+    LargeFutureLint, RecursionLimit, TypeLengthLimit,
 };
 
 #[derive(PartialEq)]
@@ -680,6 +682,97 @@ pub fn monomorphize<T>(&self, value: T) -> T
             ty::EarlyBinder(value),
         )
     }
+
+    /// Emits the `large_futures` lint if the state of the generator `ty`, created at
+    /// `location`, is larger than `-Z future-size-limit`.
+    fn check_generator_size(
+        &self,
+        def_id: DefId,
+        substs: ty::SubstsRef<'tcx>,
+        ty: Ty<'tcx>,
+        location: Location,
+    ) {
+        let tcx = self.tcx;
+        let Some(limit) = tcx.sess.opts.unstable_opts.future_size_limit else {
+            return;
+        };
+        let param_env = ty::ParamEnv::reveal_all();
+        let Ok(layout) = tcx.layout_of(param_env.and(ty)) else {
+            return;
+        };
+        if layout.size.bytes() <= limit as u64 {
+            return;
+        }
+        let source_info = self.body.source_info(location);
+        let Some(lint_root) = source_info.scope.lint_root(&self.body.source_scopes) else {
+            // Like for `large_assignments`, generators created in functions of other crates
+            // have no `HirId` to report the lint on.
+            return;
+        };
+
+        // Find the suspension point that keeps the most data alive, and the largest values
+        // held across it.
+        let (generator_layout, local_names) = tcx.generator_layout_and_saved_local_names(def_id);
+        let local_size = |local: mir::GeneratorSavedLocal| {
+            let ty = ty::EarlyBinder(generator_layout.field_tys[local].ty).subst(tcx, substs);
+            let ty = tcx.normalize_erasing_regions(param_env, ty);
+            tcx.layout_of(param_env.and(ty)).map_or(0, |layout| layout.size.bytes())
+        };
+        let largest_suspension_point = generator_layout
+            .variant_fields
+            .iter_enumerated()
+            .skip(ty::GeneratorSubsts::POISONED + 1)
+            .map(|(variant, locals)| {
+                let locals: Vec<_> =
+                    locals.iter().map(|&local| (local, local_size(local))).collect();
+                let total: u64 = locals.iter().map(|&(_, size)| size).sum();
+                (total, variant, locals)
+            })
+            .max_by_key(|&(total, ..)| total)
+            .filter(|&(total, ..)| total > 0);
+
+        let mut suspension_point = None;
+        let mut held = vec![];
+        if let Some((_, variant, mut locals)) = largest_suspension_point {
+            suspension_point = Some(generator_layout.variant_source_info[variant].span);
+            locals.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+            held = locals
+                .into_iter()
+                .take_while(|&(_, size)| size > 0)
+                // Locals introduced by desugarings, like the awaited future of an `.await`,
+                // aren't something the user can box.
+                .filter(|&(local, _)| {
+                    generator_layout.field_tys[local].source_info.span.desugaring_kind().is_none()
+                })
+                .take(3)
+                .map(|(local, size)| {
+                    let span = generator_layout.field_tys[local].source_info.span;
+                    let s = pluralize!(size);
+                    match local_names[local] {
+                        Some(name) => HeldAcrossSuspension::Local { span, name, size, s },
+                        None => HeldAcrossSuspension::Temporary { span, size, s },
+                    }
+                })
+                .collect();
+        }
+
+        let descr = match tcx.generator_kind(def_id) {
+            Some(hir::GeneratorKind::Async(_)) => "future",
+            _ => "generator",
+        };
+        tcx.emit_spanned_lint(
+            LARGE_FUTURES,
+            lint_root,
+            tcx.def_span(def_id),
+            LargeFutureLint {
+                descr,
+                size: layout.size.bytes(),
+                limit: limit as u64,
+                suspension_point,
+                held,
+            },
+        );
+    }
 }
 
 impl<'a, 'tcx> MirVisitor<'tcx> for MirNeighborCollector<'a, 'tcx> {
@@ -750,6 +843,13 @@ fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
                     _ => bug!(),
                 }
             }
+            mir::Rvalue::Aggregate(ref kind, _) => {
+                if let mir::AggregateKind::Generator(def_id, substs, _) = **kind {
+                    let substs = self.monomorphize(substs);
+                    let ty = self.monomorphize(rvalue.ty(self.body, self.tcx));
+                    self.check_generator_size(def_id, substs, ty, location);
+                }
+            }
             mir::Rvalue::ThreadLocalRef(def_id) => {
                 assert!(self.tcx.is_thread_local_static(def_id));
                 let instance = Instance::mono(self.tcx, def_id);
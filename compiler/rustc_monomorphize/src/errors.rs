@@ -3,8 +3,8 @@
 use crate::fluent_generated as fluent;
 use rustc_errors::ErrorGuaranteed;
 use rustc_errors::IntoDiagnostic;
-use rustc_macros::{Diagnostic, LintDiagnostic};
-use rustc_span::Span;
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
+use rustc_span::{Span, Symbol};
 
 #[derive(Diagnostic)]
 #[diag(monomorphize_recursion_limit)]
@@ -67,6 +67,39 @@ pub struct LargeAssignmentsLint {
     pub limit: u64,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(monomorphize_large_future)]
+#[note]
+#[help]
+pub struct LargeFutureLint {
+    pub descr: &'static str,
+    pub size: u64,
+    pub limit: u64,
+    #[label(monomorphize_suspension_point)]
+    pub suspension_point: Option<Span>,
+    #[subdiagnostic]
+    pub held: Vec<HeldAcrossSuspension>,
+}
+
+#[derive(Subdiagnostic)]
+pub enum HeldAcrossSuspension {
+    #[label(monomorphize_large_future_held_local)]
+    Local {
+        #[primary_span]
+        span: Span,
+        name: Symbol,
+        size: u64,
+        s: &'static str,
+    },
+    #[label(monomorphize_large_future_held_temporary)]
+    Temporary {
+        #[primary_span]
+        span: Span,
+        size: u64,
+        s: &'static str,
+    },
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_unknown_partition_strategy)]
 pub struct UnknownPartitionStrategy;
@@ -23,6 +23,8 @@
 mod polymorphize;
 mod util;
 
+pub use partitioning::dump_mono_items_stats;
+
 fluent_messages! { "../messages.ftl" }
 
 fn custom_coerce_unsize_info<'tcx>(
@@ -10,6 +10,7 @@
 use rustc_middle::mir::mono::{InstantiationMode, MonoItem};
 use rustc_middle::ty::print::characteristic_def_id_of_type;
 use rustc_middle::ty::{self, visit::TypeVisitableExt, InstanceDef, TyCtxt};
+use rustc_session::config::ShareGenerics;
 use rustc_span::symbol::Symbol;
 
 use super::PartitioningCx;
@@ -480,6 +481,11 @@ fn mono_item_visibility<'tcx>(
             if tcx.is_unreachable_local_definition(def_id) {
                 // This instance cannot be used from another crate.
                 Visibility::Hidden
+            } else if tcx.sess.opts.share_generics_mode() == ShareGenerics::MatchingOptLevel {
+                // With `-Zshare-generics=matching-opt-level` we only share instantiations of
+                // upstream items like `Vec<u8>`, which downstream crates are likely to need as
+                // well. Our own instantiations stay candidates for internalization.
+                Visibility::Hidden
             } else {
                 // This instance might be useful in a downstream crate.
                 *can_be_internalized = false;
@@ -479,15 +479,6 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
         })
         .collect();
 
-    // Output monomorphization stats per def_id
-    if let SwitchWithOptPath::Enabled(ref path) = tcx.sess.opts.unstable_opts.dump_mono_stats {
-        if let Err(err) =
-            dump_mono_items_stats(tcx, &codegen_units, path, tcx.crate_name(LOCAL_CRATE))
-        {
-            tcx.sess.emit_fatal(CouldntDumpMonoStats { error: err.to_string() });
-        }
-    }
-
     if tcx.sess.opts.unstable_opts.print_mono_items.is_some() {
         let mut item_to_cgus: FxHashMap<_, Vec<_>> = Default::default();
 
@@ -542,9 +533,21 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
-/// Outputs stats about instantiation counts and estimated size, per `MonoItem`'s
-/// def, to a file in the given output directory.
-fn dump_mono_items_stats<'tcx>(
+/// Writes the monomorphization stats for `-Z dump-mono-stats`: for each generic definition, the
+/// number of instantiations, their estimated size, and the number of instructions the codegen
+/// backend emitted for them. Must be called after codegen, for the latter to be available.
+pub fn dump_mono_items_stats(tcx: TyCtxt<'_>) {
+    let SwitchWithOptPath::Enabled(ref path) = tcx.sess.opts.unstable_opts.dump_mono_stats else {
+        return;
+    };
+    let (_, codegen_units) = tcx.collect_and_partition_mono_items(());
+    if let Err(err) = write_mono_items_stats(tcx, codegen_units, path, tcx.crate_name(LOCAL_CRATE))
+    {
+        tcx.sess.emit_fatal(CouldntDumpMonoStats { error: err.to_string() });
+    }
+}
+
+fn write_mono_items_stats<'tcx>(
     tcx: TyCtxt<'tcx>,
     codegen_units: &[CodegenUnit<'tcx>],
     output_directory: &Option<PathBuf>,
@@ -564,16 +567,19 @@ fn dump_mono_items_stats<'tcx>(
     let file = File::create(&output_path)?;
     let mut file = BufWriter::new(file);
 
-    // Gather instantiated mono items grouped by def_id
-    let mut items_per_def_id: FxHashMap<_, Vec<_>> = Default::default();
+    // Gather instantiated mono items grouped by def_id. An item can be placed in several codegen
+    // units, but it is only one instantiation.
+    let mut items_per_def_id: FxHashMap<_, FxHashSet<_>> = Default::default();
     for cgu in codegen_units {
         for (&mono_item, _) in cgu.items() {
             // Avoid variable-sized compiler-generated shims
             if mono_item.is_user_defined() {
-                items_per_def_id.entry(mono_item.def_id()).or_default().push(mono_item);
+                items_per_def_id.entry(mono_item.def_id()).or_default().insert(mono_item);
             }
         }
     }
+    // This is empty if the backend doesn't record the instructions it emits.
+    let ir_instructions = tcx.sess.code_stats.ir_instructions();
 
     #[derive(serde::Serialize)]
     struct MonoItem {
@@ -581,20 +587,39 @@ struct MonoItem {
         instantiation_count: usize,
         size_estimate: usize,
         total_estimate: usize,
+        /// The number of copies emitted, counting an instantiation once per codegen unit.
+        ir_copies: Option<usize>,
+        ir_instructions: Option<usize>,
     }
 
-    // Output stats sorted by total instantiated size, from heaviest to lightest
+    // Output stats sorted by the emitted instructions, or by the total instantiated size if
+    // there are none, from heaviest to lightest
     let mut stats: Vec<_> = items_per_def_id
         .into_iter()
         .map(|(def_id, items)| {
             let name = with_no_trimmed_paths!(tcx.def_path_str(def_id));
             let instantiation_count = items.len();
-            let size_estimate = items[0].size_estimate(tcx);
-            let total_estimate = instantiation_count * size_estimate;
-            MonoItem { name, instantiation_count, size_estimate, total_estimate }
+            let total_estimate: usize = items.iter().map(|item| item.size_estimate(tcx)).sum();
+            let size_estimate = total_estimate / instantiation_count;
+            let ir = ir_instructions.get(&def_id);
+            MonoItem {
+                name,
+                instantiation_count,
+                size_estimate,
+                total_estimate,
+                ir_copies: ir.map(|ir| ir.copies),
+                ir_instructions: ir.map(|ir| ir.instructions),
+            }
         })
         .collect();
-    stats.sort_unstable_by_key(|item| cmp::Reverse(item.total_estimate));
+    stats.sort_unstable_by(|a, b| {
+        (b.ir_instructions, b.total_estimate, b.instantiation_count, &a.name).cmp(&(
+            a.ir_instructions,
+            a.total_estimate,
+            a.instantiation_count,
+            &b.name,
+        ))
+    });
 
     if !stats.is_empty() {
         match format {
@@ -602,14 +627,25 @@ struct MonoItem {
             DumpMonoStatsFormat::Markdown => {
                 writeln!(
                     file,
-                    "| Item | Instantiation count | Estimated Cost Per Instantiation | Total Estimated Cost |"
+                    "| Item | Instantiation count | Estimated Cost Per Instantiation | Total Estimated Cost | Emitted Copies | Emitted IR Instructions |"
                 )?;
-                writeln!(file, "| --- | ---: | ---: | ---: |")?;
-
-                for MonoItem { name, instantiation_count, size_estimate, total_estimate } in stats {
+                writeln!(file, "| --- | ---: | ---: | ---: | ---: | ---: |")?;
+
+                for MonoItem {
+                    name,
+                    instantiation_count,
+                    size_estimate,
+                    total_estimate,
+                    ir_copies,
+                    ir_instructions,
+                } in stats
+                {
+                    let ir_copies = ir_copies.map_or("-".to_string(), |n| n.to_string());
+                    let ir_instructions =
+                        ir_instructions.map_or("-".to_string(), |n| n.to_string());
                     writeln!(
                         file,
-                        "| `{name}` | {instantiation_count} | {size_estimate} | {total_estimate} |"
+                        "| `{name}` | {instantiation_count} | {size_estimate} | {total_estimate} | {ir_copies} | {ir_instructions} |"
                     )?;
                 }
             }
@@ -1,5 +1,6 @@
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lock;
+use rustc_span::def_id::DefId;
 use rustc_span::Symbol;
 use rustc_target::abi::{Align, Size};
 use std::cmp;
@@ -45,6 +46,16 @@ pub struct FieldInfo {
     pub align: u64,
 }
 
+/// The largest niche of a type, i.e. the invalid values of one of its scalars that can be used
+/// to encode the discriminant of an enum containing it.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NicheInfo {
+    pub offset: u64,
+    pub size: u64,
+    /// The number of invalid values.
+    pub available: u128,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum DataTypeKind {
     Struct,
@@ -62,15 +73,43 @@ pub struct TypeSizeInfo {
     pub overall_size: u64,
     pub packed: bool,
     pub opt_discr_size: Option<u64>,
+    pub largest_niche: Option<NicheInfo>,
     pub variants: Vec<VariantInfo>,
 }
 
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    /// For `-Z dump-mono-stats`: the number of IR instructions the codegen backend emitted for
+    /// all instantiations of each function, and the number of emitted copies.
+    ir_instructions: Lock<FxHashMap<DefId, IrInstructions>>,
+}
+
+/// The code emitted for the instantiations of one function, see [`CodeStats::ir_instructions`].
+#[derive(Clone, Copy, Default, Debug)]
+pub struct IrInstructions {
+    /// The number of functions emitted, which is larger than the number of instantiations if an
+    /// instantiation is emitted in several codegen units.
+    pub copies: usize,
+    pub instructions: usize,
 }
 
 impl CodeStats {
+    /// Records that the codegen backend emitted `instructions` instructions for one copy of an
+    /// instantiation of the function `def_id`.
+    pub fn record_ir_instructions(&self, def_id: DefId, instructions: usize) {
+        let mut ir_instructions = self.ir_instructions.borrow_mut();
+        let entry = ir_instructions.entry(def_id).or_default();
+        entry.copies += 1;
+        entry.instructions += instructions;
+    }
+
+    /// The instructions recorded with [`CodeStats::record_ir_instructions`] for each function.
+    /// Functions in codegen units that were reused from the incremental cache are missing.
+    pub fn ir_instructions(&self) -> FxHashMap<DefId, IrInstructions> {
+        self.ir_instructions.borrow().clone()
+    }
+
     pub fn record_type_size<S: ToString>(
         &self,
         kind: DataTypeKind,
@@ -79,6 +118,7 @@ pub fn record_type_size<S: ToString>(
         overall_size: Size,
         packed: bool,
         opt_discr_size: Option<Size>,
+        largest_niche: Option<NicheInfo>,
         mut variants: Vec<VariantInfo>,
     ) {
         // Sort variants so the largest ones are shown first. A stable sort is
@@ -96,6 +136,7 @@ pub fn record_type_size<S: ToString>(
             overall_size: overall_size.bytes(),
             packed,
             opt_discr_size: opt_discr_size.map(|s| s.bytes()),
+            largest_niche,
             variants,
         };
         self.type_sizes.borrow_mut().insert(info);
@@ -196,4 +237,117 @@ pub fn print_type_sizes(&self) {
             }
         }
     }
+
+    /// Prints the same information as `print_type_sizes`, as a JSON array with one object per
+    /// type. The padding before each field and at the end of the type is computed the same way.
+    pub fn print_type_sizes_json(&self) {
+        #[derive(serde::Serialize)]
+        struct Type<'a> {
+            #[serde(rename = "type")]
+            type_description: &'a str,
+            kind: &'static str,
+            size: u64,
+            align: u64,
+            packed: bool,
+            discriminant_size: Option<u64>,
+            niche: Option<Niche>,
+            variants: Vec<Variant>,
+            end_padding: u64,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Niche {
+            offset: u64,
+            size: u64,
+            available: u128,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Variant {
+            name: Option<String>,
+            size: u64,
+            size_kind: &'static str,
+            align: u64,
+            fields: Vec<Field>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Field {
+            kind: String,
+            name: String,
+            offset: u64,
+            size: u64,
+            align: u64,
+            padding_before: u64,
+        }
+
+        let type_sizes = self.type_sizes.borrow();
+        let mut sorted: Vec<_> = type_sizes.iter().collect();
+        sorted.sort_by_key(|info| (cmp::Reverse(info.overall_size), &info.type_description));
+
+        let mut types = Vec::with_capacity(sorted.len());
+        for info in sorted {
+            let discr_size = info.opt_discr_size.unwrap_or(0);
+            let mut max_variant_size = discr_size;
+
+            let mut variants = Vec::with_capacity(info.variants.len());
+            for variant in &info.variants {
+                max_variant_size = cmp::max(max_variant_size, variant.size);
+
+                // Fields are listed by increasing offset, like in the text output.
+                let mut sorted_fields = variant.fields.clone();
+                sorted_fields.sort_by_key(|f| (f.offset, f.size));
+
+                let mut min_offset = discr_size;
+                let mut fields = Vec::with_capacity(sorted_fields.len());
+                for FieldInfo { kind, name, offset, size, align } in sorted_fields {
+                    let padding_before = offset.saturating_sub(min_offset);
+                    min_offset = cmp::max(min_offset, offset + size);
+                    fields.push(Field {
+                        kind: kind.to_string(),
+                        name: name.to_string(),
+                        offset,
+                        size,
+                        align,
+                        padding_before,
+                    });
+                }
+
+                variants.push(Variant {
+                    name: variant.name.map(|name| name.to_string()),
+                    size: variant.size,
+                    size_kind: match variant.kind {
+                        SizeKind::Exact => "exact",
+                        SizeKind::Min => "min",
+                    },
+                    align: variant.align,
+                    fields,
+                });
+            }
+
+            types.push(Type {
+                type_description: &info.type_description,
+                kind: match info.kind {
+                    DataTypeKind::Struct => "struct",
+                    DataTypeKind::Union => "union",
+                    DataTypeKind::Enum => "enum",
+                    DataTypeKind::Closure => "closure",
+                    DataTypeKind::Generator => "generator",
+                },
+                size: info.overall_size,
+                align: info.align,
+                packed: info.packed,
+                discriminant_size: info.opt_discr_size,
+                niche: info.largest_niche.map(|NicheInfo { offset, size, available }| Niche {
+                    offset,
+                    size,
+                    available,
+                }),
+                variants,
+                end_padding: info.overall_size.saturating_sub(max_variant_size),
+            });
+        }
+
+        println!("{}", serde_json::to_string_pretty(&types).unwrap());
+    }
 }
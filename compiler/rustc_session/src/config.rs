@@ -81,7 +81,7 @@ pub enum CFProtection {
     Full,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Hash, HashStable_Generic)]
+#[derive(Clone, Copy, Debug, PartialEq, Hash, Encodable, Decodable, HashStable_Generic)]
 pub enum OptLevel {
     No,         // -O0
     Less,       // -O1
@@ -91,6 +91,21 @@ pub enum OptLevel {
     SizeMin,    // -Oz
 }
 
+/// How generic instantiations are shared between crates, see `Options::share_generics_mode`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShareGenerics {
+    /// Every crate instantiates the generics it uses itself.
+    No,
+    /// All reachable instantiations are exported, and upstream instantiations are reused
+    /// regardless of how the upstream crate was optimized.
+    All,
+    /// Only instantiations of upstream generic items (like `Vec<u8>`) are exported, and
+    /// upstream instantiations are only reused if the upstream crate was compiled with the
+    /// same optimization level as the local crate. Only used with
+    /// `-Zshare-generics=matching-opt-level`.
+    MatchingOptLevel,
+}
+
 /// This is what the `LtoCli` values get mapped to after resolving defaults and
 /// and taking other command line options into account.
 ///
@@ -401,6 +416,8 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF 2.1.0 log, for code scanning and code review platforms.
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -852,11 +869,20 @@ pub fn will_create_output_file(&self) -> bool {
 
     #[inline]
     pub fn share_generics(&self) -> bool {
+        self.share_generics_mode() != ShareGenerics::No
+    }
+
+    /// Without an explicit `-Zshare-generics`, unoptimized builds share everything and optimized
+    /// builds share nothing.
+    #[inline]
+    pub fn share_generics_mode(&self) -> ShareGenerics {
         match self.unstable_opts.share_generics {
             Some(setting) => setting,
             None => match self.optimize {
-                OptLevel::No | OptLevel::Less | OptLevel::Size | OptLevel::SizeMin => true,
-                OptLevel::Default | OptLevel::Aggressive => false,
+                OptLevel::No | OptLevel::Less | OptLevel::Size | OptLevel::SizeMin => {
+                    ShareGenerics::All
+                }
+                OptLevel::Default | OptLevel::Aggressive => ShareGenerics::No,
             },
         }
     }
@@ -881,6 +907,7 @@ pub fn diagnostic_handler_flags(&self, can_emit_warnings: bool) -> HandlerFlags
             report_delayed_bugs: self.report_delayed_bugs,
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
+            group_diagnostics: self.group_diagnostics,
             track_diagnostics: self.track_diagnostics,
         }
     }
@@ -1541,7 +1568,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|json|sarif|short",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1722,6 +1749,7 @@ pub fn parse_error_format(
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
+            Some("sarif") => ErrorOutputType::Sarif,
 
             Some(arg) => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
@@ -1804,6 +1832,12 @@ fn check_error_format_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=sarif` is unstable",
+            );
+        }
     }
 }
 
@@ -2893,7 +2927,7 @@ pub(crate) mod dep_tracking {
     use super::{
         BranchProtection, CFGuard, CFProtection, CrateType, DebugInfo, ErrorOutputType,
         InstrumentCoverage, InstrumentXRay, LdImpl, LinkerPluginLto, LocationDetail, LtoCli,
-        OomStrategy, OptLevel, OutputType, OutputTypes, Passes, ResolveDocLinks,
+        OomStrategy, OptLevel, OutputType, OutputTypes, Passes, ResolveDocLinks, ShareGenerics,
         SourceFileHashAlgorithm, SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion,
         TraitSolver, TrimmedDefPaths,
     };
@@ -2965,6 +2999,7 @@ fn hash(
         TlsModel,
         InstrumentCoverage,
         InstrumentXRay,
+        ShareGenerics,
         CrateType,
         MergeFunctions,
         PanicStrategy,
@@ -3136,3 +3171,21 @@ pub fn extension(self) -> &'static str {
         }
     }
 }
+
+/// How `-Z fix` applies the machine-applicable suggestions
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum FixMode {
+    /// Rewrite the source files
+    InPlace,
+    /// Print a unified diff of the changes to stdout and leave the source files alone
+    Diff,
+}
+
+/// Which format to use for `-Z print-type-sizes`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum PrintTypeSizesFormat {
+    /// Print one `print-type-size` line per type, variant and field
+    Text,
+    /// Emit structured JSON
+    Json,
+}
@@ -383,6 +383,10 @@ mod desc {
     pub const parse_optimization_fuel: &str = "crate=integer";
     pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
     pub const parse_dump_mono_stats: &str = "`markdown` (default) or `json`";
+    pub const parse_print_type_sizes_format: &str = "`text` (default) or `json`";
+    pub const parse_fix: &str = "`in-place` (default) or `diff`";
+    pub const parse_share_generics: &str =
+        "either a boolean (`yes`, `no`, `on`, `off`, etc), or `matching-opt-level`";
     pub const parse_instrument_coverage: &str =
         "`all` (default), `except-unused-generics`, `except-unused-functions`, or `off`";
     pub const parse_instrument_xray: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or a comma separated list of settings: `always` or `never` (mutually exclusive), `ignore-loops`, `instruction-threshold=N`, `skip-entry`, `skip-exit`";
@@ -874,6 +878,47 @@ pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_print_type_sizes_format(
+        slot: &mut PrintTypeSizesFormat,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            None => true,
+            Some("json") => {
+                *slot = PrintTypeSizesFormat::Json;
+                true
+            }
+            Some("text") => {
+                *slot = PrintTypeSizesFormat::Text;
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub(crate) fn parse_fix(slot: &mut Option<FixMode>, v: Option<&str>) -> bool {
+        *slot = match v {
+            None | Some("in-place") => Some(FixMode::InPlace),
+            Some("diff") => Some(FixMode::Diff),
+            Some(_) => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_share_generics(slot: &mut Option<ShareGenerics>, v: Option<&str>) -> bool {
+        let mut bool_arg = None;
+        if parse_opt_bool(&mut bool_arg, v) {
+            *slot =
+                bool_arg.map(|share| if share { ShareGenerics::All } else { ShareGenerics::No });
+            return true;
+        }
+        match v {
+            Some("matching-opt-level") => *slot = Some(ShareGenerics::MatchingOptLevel),
+            _ => return false,
+        }
+        true
+    }
+
     pub(crate) fn parse_instrument_coverage(
         slot: &mut Option<InstrumentCoverage>,
         v: Option<&str>,
@@ -1452,6 +1497,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     fewer_names: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "reduce memory use by retaining fewer names within compilation artifacts (LLVM-IR) \
         (default: no)"),
+    fix: Option<FixMode> = (None, parse_fix, [UNTRACKED],
+        "apply all machine-applicable suggestions to the source files, either \
+        `in-place` (the default) or by printing a unified `diff`"),
     flatten_format_args: bool = (true, parse_bool, [TRACKED],
         "flatten nested format_args!() and literals into a simplified format_args!() call \
         (default: yes)"),
@@ -1463,12 +1511,17 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "whether each function should go in its own section"),
     future_incompat_test: bool = (false, parse_bool, [UNTRACKED],
         "forces all lints to be future incompatible, used for internal testing (default: no)"),
+    future_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "the size at which the `large_futures` lint starts to be emitted"),
     gcc_ld: Option<LdImpl> = (None, parse_gcc_ld, [TRACKED], "implementation of ld used by cc"),
     graphviz_dark_mode: bool = (false, parse_bool, [UNTRACKED],
         "use dark-themed colors in graphviz output (default: no)"),
     graphviz_font: String = ("Courier, monospace".to_string(), parse_string, [UNTRACKED],
         "use the given `fontname` in graphviz output; can be overridden by setting \
         environment variable `RUSTC_GRAPHVIZ_FONT` (default: `Courier, monospace`)"),
+    group_diagnostics: bool = (false, parse_bool, [UNTRACKED],
+        "report identical diagnostics from different expansions of the same macro only once, \
+        with a list of the expansion sites (default: no)"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about AST and HIR (default: no)"),
     human_readable_cgu_names: bool = (false, parse_bool, [TRACKED],
@@ -1632,6 +1685,8 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "print the result of the monomorphization collection pass"),
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
         "print layout information for each type encountered (default: no)"),
+    print_type_sizes_format: PrintTypeSizesFormat = (PrintTypeSizesFormat::Text, parse_print_type_sizes_format, [UNTRACKED],
+        "the format to use for -Z print-type-sizes (`text` (default) or `json`)"),
     proc_macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
          "show backtraces for panics during proc-macro execution (default: no)"),
     proc_macro_execution_strategy: ProcMacroExecutionStrategy = (ProcMacroExecutionStrategy::SameThread,
@@ -1690,8 +1745,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
-    share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
-        "make the current crate share its generic instantiations"),
+    share_generics: Option<ShareGenerics> = (None, parse_share_generics, [TRACKED],
+        "make the current crate share its generic instantiations (`matching-opt-level` only \
+        shares instantiations of upstream items, and only with crates of the same opt-level)"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
         "show spans for compiler debugging (expr|pat|ty)"),
     simulate_remapped_rust_src_base: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
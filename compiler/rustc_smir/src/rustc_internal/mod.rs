@@ -7,7 +7,8 @@
     rustc_smir::Tables,
     stable_mir::{self, with},
 };
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, TyCtxt};
 pub use rustc_span::def_id::{CrateNum, DefId};
 
 fn with_tables<R>(mut f: impl FnMut(&mut Tables<'_>) -> R) -> R {
@@ -30,15 +31,75 @@ pub fn item_def_id(&self, item: &stable_mir::CrateItem) -> DefId {
     }
 
     pub fn crate_item(&mut self, did: DefId) -> stable_mir::CrateItem {
+        stable_mir::CrateItem(self.create_def_id(did))
+    }
+
+    pub fn adt_def(&mut self, did: DefId) -> stable_mir::ty::AdtDef {
+        stable_mir::ty::AdtDef(self.create_def_id(did))
+    }
+
+    pub fn foreign_def(&mut self, did: DefId) -> stable_mir::ty::ForeignDef {
+        stable_mir::ty::ForeignDef(self.create_def_id(did))
+    }
+
+    pub fn fn_def(&mut self, did: DefId) -> stable_mir::ty::FnDef {
+        stable_mir::ty::FnDef(self.create_def_id(did))
+    }
+
+    pub fn closure_def(&mut self, did: DefId) -> stable_mir::ty::ClosureDef {
+        stable_mir::ty::ClosureDef(self.create_def_id(did))
+    }
+
+    pub fn generator_def(&mut self, did: DefId) -> stable_mir::ty::GeneratorDef {
+        stable_mir::ty::GeneratorDef(self.create_def_id(did))
+    }
+
+    pub fn alias_def(&mut self, did: DefId) -> stable_mir::ty::AliasDef {
+        stable_mir::ty::AliasDef(self.create_def_id(did))
+    }
+
+    pub fn param_def(&mut self, did: DefId) -> stable_mir::ty::ParamDef {
+        stable_mir::ty::ParamDef(self.create_def_id(did))
+    }
+
+    pub fn br_named_def(&mut self, did: DefId) -> stable_mir::ty::BrNamedDef {
+        stable_mir::ty::BrNamedDef(self.create_def_id(did))
+    }
+
+    pub fn trait_def(&mut self, did: DefId) -> stable_mir::ty::TraitDef {
+        stable_mir::ty::TraitDef(self.create_def_id(did))
+    }
+
+    pub fn const_def(&mut self, did: DefId) -> stable_mir::ty::ConstDef {
+        stable_mir::ty::ConstDef(self.create_def_id(did))
+    }
+
+    pub fn region_def(&mut self, did: DefId) -> stable_mir::ty::RegionDef {
+        stable_mir::ty::RegionDef(self.create_def_id(did))
+    }
+
+    pub fn thread_local_def(&mut self, did: DefId) -> stable_mir::ty::ThreadLocalDef {
+        stable_mir::ty::ThreadLocalDef(self.create_def_id(did))
+    }
+
+    pub fn static_def(&mut self, did: DefId) -> stable_mir::ty::StaticDef {
+        stable_mir::ty::StaticDef(self.create_def_id(did))
+    }
+
+    pub fn impl_def(&mut self, did: DefId) -> stable_mir::ty::ImplDef {
+        stable_mir::ty::ImplDef(self.create_def_id(did))
+    }
+
+    pub(crate) fn create_def_id(&mut self, did: DefId) -> stable_mir::DefId {
         // FIXME: this becomes inefficient when we have too many ids
         for (i, &d) in self.def_ids.iter().enumerate() {
             if d == did {
-                return stable_mir::CrateItem(i);
+                return i;
             }
         }
         let id = self.def_ids.len();
         self.def_ids.push(did);
-        stable_mir::CrateItem(id)
+        id
     }
 }
 
@@ -47,5 +108,47 @@ pub fn crate_num(item: &stable_mir::Crate) -> CrateNum {
 }
 
 pub fn run(tcx: TyCtxt<'_>, f: impl FnOnce()) {
-    crate::stable_mir::run(Tables { tcx, def_ids: vec![], types: vec![] }, f);
+    crate::stable_mir::run(
+        Tables {
+            tcx,
+            def_ids: vec![],
+            alloc_ids: vec![],
+            types: vec![],
+            consts: vec![],
+            instances: vec![],
+        },
+        f,
+    );
+}
+
+/// Converts a Stable MIR construct back into its compiler representation.
+///
+/// This is the inverse of `Stable`, and only works for values that were produced by the
+/// same `Tables`.
+pub(crate) trait RustcInternal<'tcx> {
+    type T;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T;
+}
+
+impl<'tcx> RustcInternal<'tcx> for stable_mir::ty::GenericArgs {
+    type T = ty::SubstsRef<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let args: Vec<_> = self.0.iter().map(|arg| arg.internal(tables)).collect();
+        tables.tcx.mk_substs_from_iter(args.into_iter())
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for stable_mir::ty::GenericArgKind {
+    type T = ty::GenericArg<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            // Regions carry no information that matters after borrowck.
+            stable_mir::ty::GenericArgKind::Lifetime(_) => tables.tcx.lifetimes.re_erased.into(),
+            stable_mir::ty::GenericArgKind::Type(ty) => tables.types[ty.0].into(),
+            stable_mir::ty::GenericArgKind::Const(cnst) => match tables.consts[cnst.id.0] {
+                mir::ConstantKind::Ty(cnst) => cnst.into(),
+                _ => unreachable!("generic arguments are always type system constants"),
+            },
+        }
+    }
 }
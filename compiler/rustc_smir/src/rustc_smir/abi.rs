@@ -0,0 +1,219 @@
+//! Conversion of layouts and call ABIs into their Stable MIR representation.
+
+use rustc_middle::ty::Ty;
+use rustc_target::abi::{self, call};
+
+use super::{Stable, Tables};
+use crate::stable_mir::abi::{
+    ArgAbi, CallConvention, FieldsShape, FnAbi, IntegerLength, LayoutShape, Niche, PassMode,
+    Primitive, Scalar, TagEncoding, ValueAbi, VariantsShape, WrappingRange,
+};
+
+impl<'tcx> Stable<'tcx> for abi::Layout<'tcx> {
+    type T = LayoutShape;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        self.0.0.stable(tables)
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::LayoutS {
+    type T = LayoutShape;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        LayoutShape {
+            fields: self.fields.stable(tables),
+            variants: self.variants.stable(tables),
+            abi: self.abi.stable(tables),
+            largest_niche: self.largest_niche.stable(tables),
+            abi_align: self.align.abi.bytes(),
+            pref_align: self.align.pref.bytes(),
+            size: self.size.bytes(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::FieldsShape {
+    type T = FieldsShape;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::FieldsShape::Primitive => FieldsShape::Primitive,
+            abi::FieldsShape::Union(count) => FieldsShape::Union(count.get()),
+            abi::FieldsShape::Array { stride, count } => {
+                FieldsShape::Array { stride: stride.bytes(), count: *count }
+            }
+            abi::FieldsShape::Arbitrary { offsets, memory_index } => FieldsShape::Arbitrary {
+                offsets: offsets.iter().map(|offset| offset.bytes()).collect(),
+                memory_index: memory_index.iter().copied().collect(),
+            },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Variants {
+    type T = VariantsShape;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::Variants::Single { index } => VariantsShape::Single { index: index.as_usize() },
+            abi::Variants::Multiple { tag, tag_encoding, tag_field, variants } => {
+                VariantsShape::Multiple {
+                    tag: tag.stable(tables),
+                    tag_encoding: tag_encoding.stable(tables),
+                    tag_field: *tag_field,
+                    variants: variants.iter().map(|variant| variant.stable(tables)).collect(),
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::TagEncoding {
+    type T = TagEncoding;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::TagEncoding::Direct => TagEncoding::Direct,
+            abi::TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
+                TagEncoding::Niche {
+                    untagged_variant: untagged_variant.as_usize(),
+                    niche_variants: niche_variants.start().as_usize()
+                        ..=niche_variants.end().as_usize(),
+                    niche_start: *niche_start,
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Abi {
+    type T = ValueAbi;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match *self {
+            abi::Abi::Uninhabited => ValueAbi::Uninhabited,
+            abi::Abi::Scalar(scalar) => ValueAbi::Scalar(scalar.stable(tables)),
+            abi::Abi::ScalarPair(first, second) => {
+                ValueAbi::ScalarPair(first.stable(tables), second.stable(tables))
+            }
+            abi::Abi::Vector { element, count } => {
+                ValueAbi::Vector { element: element.stable(tables), count }
+            }
+            abi::Abi::Aggregate { sized } => ValueAbi::Aggregate { sized },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Scalar {
+    type T = Scalar;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match *self {
+            abi::Scalar::Initialized { value, valid_range } => Scalar::Initialized {
+                value: value.stable(tables),
+                valid_range: valid_range.stable(tables),
+            },
+            abi::Scalar::Union { value } => Scalar::Union { value: value.stable(tables) },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Primitive {
+    type T = Primitive;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match *self {
+            abi::Primitive::Int(length, signed) => {
+                let length = match length {
+                    abi::Integer::I8 => IntegerLength::I8,
+                    abi::Integer::I16 => IntegerLength::I16,
+                    abi::Integer::I32 => IntegerLength::I32,
+                    abi::Integer::I64 => IntegerLength::I64,
+                    abi::Integer::I128 => IntegerLength::I128,
+                };
+                Primitive::Int { length, signed }
+            }
+            abi::Primitive::F32 => Primitive::F32,
+            abi::Primitive::F64 => Primitive::F64,
+            abi::Primitive::Pointer(address_space) => {
+                Primitive::Pointer { address_space: address_space.0 }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::WrappingRange {
+    type T = WrappingRange;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        WrappingRange { start: self.start, end: self.end }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Niche {
+    type T = Niche;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        Niche {
+            offset: self.offset.bytes(),
+            value: self.value.stable(tables),
+            valid_range: self.valid_range.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::FnAbi<'tcx, Ty<'tcx>> {
+    type T = FnAbi;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        FnAbi {
+            args: self.args.iter().map(|arg| arg.stable(tables)).collect(),
+            ret: self.ret.stable(tables),
+            fixed_count: self.fixed_count,
+            conv: self.conv.stable(tables),
+            c_variadic: self.c_variadic,
+            can_unwind: self.can_unwind,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::ArgAbi<'tcx, Ty<'tcx>> {
+    type T = ArgAbi;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        ArgAbi {
+            ty: tables.intern_ty(self.layout.ty),
+            layout: self.layout.layout.stable(tables),
+            mode: self.mode.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::PassMode {
+    type T = PassMode;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            call::PassMode::Ignore => PassMode::Ignore,
+            call::PassMode::Direct(_) => PassMode::Direct,
+            call::PassMode::Pair(..) => PassMode::Pair,
+            call::PassMode::Cast(_, pad_i32) => PassMode::Cast { pad_i32: *pad_i32 },
+            call::PassMode::Indirect { extra_attrs, on_stack, .. } => {
+                PassMode::Indirect { meta: extra_attrs.is_some(), on_stack: *on_stack }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::Conv {
+    type T = CallConvention;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            call::Conv::C => CallConvention::C,
+            call::Conv::Rust => CallConvention::Rust,
+            call::Conv::RustCold => CallConvention::RustCold,
+            call::Conv::ArmAapcs => CallConvention::ArmAapcs,
+            call::Conv::CCmseNonSecureCall => CallConvention::CCmseNonSecureCall,
+            call::Conv::Msp430Intr => CallConvention::Msp430Intr,
+            call::Conv::PtxKernel => CallConvention::PtxKernel,
+            call::Conv::X86Fastcall => CallConvention::X86Fastcall,
+            call::Conv::X86Intr => CallConvention::X86Intr,
+            call::Conv::X86Stdcall => CallConvention::X86Stdcall,
+            call::Conv::X86ThisCall => CallConvention::X86ThisCall,
+            call::Conv::X86VectorCall => CallConvention::X86VectorCall,
+            call::Conv::X86_64SysV => CallConvention::X86_64SysV,
+            call::Conv::X86_64Win64 => CallConvention::X86_64Win64,
+            call::Conv::AmdGpuKernel => CallConvention::AmdGpuKernel,
+            call::Conv::AvrInterrupt => CallConvention::AvrInterrupt,
+            call::Conv::AvrNonBlockingInterrupt => CallConvention::AvrNonBlockingInterrupt,
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Conversion of constant values into the byte-level [`Allocation`] representation
+//! exposed by Stable MIR.
+
+use rustc_middle::mir::interpret::{self, alloc_range, AllocRange, ConstValue, Scalar};
+use rustc_middle::ty::{self, Ty};
+use rustc_target::abi::{Align, Size};
+
+use super::{Stable, Tables};
+use crate::stable_mir::mir::Mutability;
+use crate::stable_mir::ty::Allocation;
+
+/// Creates the bytes of a constant of type `ty`.
+pub(super) fn new_allocation<'tcx>(
+    ty: Ty<'tcx>,
+    const_value: ConstValue<'tcx>,
+    tables: &mut Tables<'tcx>,
+) -> Allocation {
+    let align = || {
+        tables
+            .tcx
+            .layout_of(ty::ParamEnv::reveal_all().and(ty))
+            .map_or(Align::ONE, |layout| layout.align.abi)
+    };
+    match const_value {
+        ConstValue::Scalar(scalar) => {
+            let align = align();
+            scalar_allocation(&[scalar], align, tables)
+        }
+        ConstValue::ZeroSized => Allocation {
+            bytes: vec![],
+            provenance: vec![],
+            align: align().bytes(),
+            mutability: Mutability::Not,
+        },
+        ConstValue::Slice { data, start, end } => {
+            // A slice is a wide pointer: the address of the data followed by the length.
+            let align = align();
+            let alloc_id = tables.tcx.create_memory_alloc(data);
+            let ptr = interpret::Pointer::new(alloc_id, Size::from_bytes(start));
+            let scalar_ptr = Scalar::from_pointer(ptr, &tables.tcx);
+            let scalar_len = Scalar::from_target_usize((end - start) as u64, &tables.tcx);
+            scalar_allocation(&[scalar_ptr, scalar_len], align, tables)
+        }
+        ConstValue::ByRef { alloc, offset } => {
+            let ty_size = tables
+                .tcx
+                .layout_of(ty::ParamEnv::reveal_all().and(ty))
+                .map_or_else(|_| alloc.inner().size() - offset, |layout| layout.size);
+            allocation_filter(alloc.inner(), alloc_range(offset, ty_size), tables)
+        }
+    }
+}
+
+/// Lays out the given scalars one after the other, in the target's endianness.
+fn scalar_allocation<'tcx>(
+    scalars: &[Scalar],
+    align: Align,
+    tables: &mut Tables<'tcx>,
+) -> Allocation {
+    let endian = tables.tcx.data_layout.endian;
+    let mut bytes = vec![];
+    let mut provenance = vec![];
+    for scalar in scalars {
+        let (bits, size) = match *scalar {
+            Scalar::Int(int) => (int.assert_bits(int.size()), int.size()),
+            Scalar::Ptr(ptr, size) => {
+                let (alloc_id, offset) = ptr.into_parts();
+                provenance.push((bytes.len(), tables.intern_alloc(alloc_id)));
+                (u128::from(offset.bytes()), Size::from_bytes(size))
+            }
+        };
+        let mut buf = vec![0; size.bytes_usize()];
+        interpret::write_target_uint(endian, &mut buf, bits).unwrap();
+        bytes.extend(buf.into_iter().map(Some));
+    }
+    Allocation { bytes, provenance, align: align.bytes(), mutability: Mutability::Not }
+}
+
+/// Copies the bytes in `alloc_range` out of `alloc`, keeping track of which of them are
+/// initialized and which of them are part of a pointer.
+pub(super) fn allocation_filter<'tcx>(
+    alloc: &interpret::Allocation,
+    alloc_range: AllocRange,
+    tables: &mut Tables<'tcx>,
+) -> Allocation {
+    let start = alloc_range.start.bytes_usize();
+    let end = alloc_range.end().bytes_usize();
+    let bytes = alloc
+        .inspect_with_uninit_and_ptr_outside_interpreter(start..end)
+        .iter()
+        .enumerate()
+        .map(|(i, &byte)| alloc.init_mask().get(Size::from_bytes(start + i)).then_some(byte))
+        .collect();
+    let provenance = alloc
+        .provenance()
+        .ptrs()
+        .iter()
+        .filter(|(offset, _)| alloc_range.start <= *offset && *offset < alloc_range.end())
+        .map(|&(offset, alloc_id)| (offset.bytes_usize() - start, tables.intern_alloc(alloc_id)))
+        .collect();
+    Allocation {
+        bytes,
+        provenance,
+        align: alloc.align.bytes(),
+        mutability: alloc.mutability.stable(tables),
+    }
+}
@@ -0,0 +1,200 @@
+//! Layout and calling convention information of monomorphic types and function instances.
+//!
+//! This mirrors the information the compiler uses during code generation, so it is only
+//! available for types and instances that do not depend on generic parameters.
+
+use std::ops::RangeInclusive;
+
+use super::mir::VariantIdx;
+use super::ty::Ty;
+
+/// The memory layout of a type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutShape {
+    /// How the fields of this type are laid out in memory.
+    pub fields: FieldsShape,
+    /// The layout of each variant, for enums and generators.
+    pub variants: VariantsShape,
+    /// How values of this type are passed around by value.
+    pub abi: ValueAbi,
+    /// The niche with the most available values, if any.
+    pub largest_niche: Option<Niche>,
+    /// The ABI-mandated alignment in bytes.
+    pub abi_align: u64,
+    /// The preferred alignment in bytes.
+    pub pref_align: u64,
+    /// The size of this type in bytes.
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldsShape {
+    /// Scalar primitives and `!`, which never have fields.
+    Primitive,
+    /// All fields start at offset zero. The `usize` is the field count.
+    Union(usize),
+    /// Array/vector-like placement, with all fields of identical types.
+    Array { stride: u64, count: u64 },
+    /// Struct-like placement, with precomputed offsets.
+    ///
+    /// Fields are guaranteed to not overlap, but note that gaps
+    /// before, between and after all the fields are NOT always
+    /// padding, and as such their contents may not be discarded.
+    Arbitrary {
+        /// Offsets for the first byte of each field, in definition order.
+        offsets: Vec<u64>,
+        /// The position of each field in memory order, indexed by definition order.
+        memory_index: Vec<u32>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VariantsShape {
+    /// Single enum variants, structs/tuples, unions, and all non-ADTs.
+    Single { index: VariantIdx },
+    /// Enum-likes with more than one inhabited variant: each variant comes with
+    /// a *discriminant* (usually the same as the variant index but the user can
+    /// assign explicit discriminant values). That discriminant is encoded
+    /// as a *tag* on the machine.
+    Multiple {
+        tag: Scalar,
+        tag_encoding: TagEncoding,
+        tag_field: usize,
+        variants: Vec<LayoutShape>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagEncoding {
+    /// The tag directly stores the discriminant.
+    Direct,
+    /// Niche (values invalid for a type) encoding the discriminant:
+    /// the variants in `niche_variants` are encoded as the tag value
+    /// `(variant_index - niche_variants.start) + niche_start`, all other values
+    /// encode `untagged_variant`.
+    Niche {
+        untagged_variant: VariantIdx,
+        niche_variants: RangeInclusive<VariantIdx>,
+        niche_start: u128,
+    },
+}
+
+/// Describes how values of the type are passed by target ABIs,
+/// in terms of categories of C types there are ABI rules for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueAbi {
+    Uninhabited,
+    Scalar(Scalar),
+    ScalarPair(Scalar, Scalar),
+    Vector {
+        element: Scalar,
+        count: u64,
+    },
+    Aggregate {
+        /// If true, the size is exact, otherwise it's only a lower bound.
+        sized: bool,
+    },
+}
+
+/// Information about one scalar component of a Rust type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Scalar {
+    Initialized {
+        value: Primitive,
+        /// The range of values that are valid for this scalar. Values outside of it are a niche.
+        valid_range: WrappingRange,
+    },
+    /// A scalar inside a union, which may hold any value, including uninitialized bytes.
+    Union { value: Primitive },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primitive {
+    Int { length: IntegerLength, signed: bool },
+    F32,
+    F64,
+    Pointer { address_space: u32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegerLength {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+/// Inclusive wrap-around range of valid values, that is, if
+/// start > end, it represents `start..=MAX`, followed by `0..=end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrappingRange {
+    pub start: u128,
+    pub end: u128,
+}
+
+/// A range of invalid values of a scalar that can be used to store other information.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Niche {
+    /// The offset in bytes of the scalar containing the niche.
+    pub offset: u64,
+    pub value: Primitive,
+    pub valid_range: WrappingRange,
+}
+
+/// How a function instance receives its arguments and returns its value.
+#[derive(Clone, Debug)]
+pub struct FnAbi {
+    /// The type, layout and passing mode of each argument.
+    pub args: Vec<ArgAbi>,
+    /// The type, layout and passing mode of the return value.
+    pub ret: ArgAbi,
+    /// The count of non-variadic arguments.
+    pub fixed_count: u32,
+    pub conv: CallConvention,
+    pub c_variadic: bool,
+    pub can_unwind: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct ArgAbi {
+    pub ty: Ty,
+    pub layout: LayoutShape,
+    pub mode: PassMode,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PassMode {
+    /// Ignore the argument, e.g. because it is a ZST.
+    Ignore,
+    /// Pass the argument directly in a single immediate.
+    Direct,
+    /// Pass a pair's elements directly in two immediates.
+    Pair,
+    /// Pass the argument after casting it to another type, optionally padded by an `i32`.
+    Cast { pad_i32: bool },
+    /// Pass the argument indirectly via a hidden pointer. `meta` is set for unsized arguments,
+    /// which additionally pass their metadata.
+    Indirect { meta: bool, on_stack: bool },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallConvention {
+    C,
+    Rust,
+    RustCold,
+    ArmAapcs,
+    CCmseNonSecureCall,
+    Msp430Intr,
+    PtxKernel,
+    X86Fastcall,
+    X86Intr,
+    X86Stdcall,
+    X86ThisCall,
+    X86VectorCall,
+    X86_64SysV,
+    X86_64Win64,
+    AmdGpuKernel,
+    AvrInterrupt,
+    AvrNonBlockingInterrupt,
+}
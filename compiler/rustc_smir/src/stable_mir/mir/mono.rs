@@ -0,0 +1,62 @@
+use crate::stable_mir::abi::FnAbi;
+use crate::stable_mir::mir::Body;
+use crate::stable_mir::ty::{FnDef, GenericArgs, Ty};
+use crate::stable_mir::{with, Error};
+
+/// A function, closure or shim together with the generic arguments it is instantiated with.
+///
+/// This is what code generation works with: instances are fully monomorphic and can be
+/// asked for their substituted body, their signature and their call ABI.
+#[derive(Clone, Debug)]
+pub struct Instance {
+    /// The type of instance.
+    pub kind: InstanceKind,
+    /// An ID used to get the instance definition from the compiler.
+    pub def: InstanceDef,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InstanceKind {
+    /// A user defined item.
+    Item,
+    /// A compiler intrinsic function.
+    Intrinsic,
+    /// A virtual call through a vtable, i.e. a method of `dyn Trait`.
+    Virtual,
+    /// A compiler generated shim, such as drop glue or a closure's `FnOnce::call_once`.
+    Shim,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InstanceDef(pub(crate) usize);
+
+impl Instance {
+    /// Resolves a call to `def` with the given generic arguments, e.g. a trait method call to the
+    /// method of the impl that applies.
+    ///
+    /// Fails if the arguments are not fully monomorphic, or if no impl applies.
+    pub fn resolve(def: FnDef, args: &GenericArgs) -> Result<Instance, Error> {
+        with(|context| context.resolve_instance(def, args))
+    }
+
+    /// The body of this instance, with all generic parameters replaced by the instance's
+    /// arguments. Intrinsics and virtual calls do not have a body.
+    pub fn body(&self) -> Option<Body> {
+        with(|context| context.instance_body(self.def))
+    }
+
+    /// The monomorphic type of this instance, e.g. a `FnDef` or a closure type.
+    pub fn ty(&self) -> Ty {
+        with(|context| context.instance_ty(self.def))
+    }
+
+    /// How this instance receives its arguments and returns its value.
+    pub fn fn_abi(&self) -> Result<FnAbi, Error> {
+        with(|context| context.instance_abi(self.def))
+    }
+
+    /// The symbol name this instance is emitted with.
+    pub fn mangled_name(&self) -> String {
+        with(|context| context.instance_mangled_name(self.def))
+    }
+}
@@ -12,11 +12,15 @@
 //! If you need an internal construct, consider using `rustc_internal` or `rustc_smir`.
 
 use std::cell::Cell;
+use std::fmt;
 
 use crate::rustc_smir::Tables;
 
-use self::ty::{Ty, TyKind};
+use self::abi::{FnAbi, LayoutShape};
+use self::mir::mono::{Instance, InstanceDef};
+use self::ty::{FnDef, GenericArgs, ImplDef, TraitDef, TraitRef, Ty, TyKind};
 
+pub mod abi;
 pub mod mir;
 pub mod ty;
 
@@ -50,8 +54,32 @@ impl CrateItem {
     pub fn body(&self) -> mir::Body {
         with(|cx| cx.mir_body(self))
     }
+
+    /// The type of this item, e.g. a `FnDef` for functions. Generic parameters of the item
+    /// are left unsubstituted.
+    pub fn ty(&self) -> Ty {
+        with(|cx| cx.def_ty(self.0))
+    }
+}
+
+/// An error returned by a Stable MIR query, e.g. when the layout of a type cannot be computed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Error(String);
+
+impl Error {
+    pub(crate) fn new(msg: String) -> Self {
+        Error(msg)
+    }
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {}
+
 /// Return the function where execution starts if the current
 /// crate defines that. This is usually `main`, but could be
 /// `start` if the crate is a no-std crate.
@@ -79,6 +107,16 @@ pub fn all_local_items() -> CrateItems {
     with(|cx| cx.all_local_items())
 }
 
+/// Retrieve all trait impls of the local crate.
+pub fn all_trait_impls() -> Vec<ImplDef> {
+    with(|cx| cx.all_trait_impls())
+}
+
+/// Retrieve all impls of the given trait in the local crate and its dependencies.
+pub fn trait_impls(trait_def: &TraitDef) -> Vec<ImplDef> {
+    with(|cx| cx.trait_impls(trait_def))
+}
+
 pub trait Context {
     fn entry_fn(&mut self) -> Option<CrateItem>;
     /// Retrieve all items of the local crate that have a MIR associated with them.
@@ -95,6 +133,36 @@ pub trait Context {
     /// Obtain the representation of a type.
     fn ty_kind(&mut self, ty: Ty) -> TyKind;
 
+    /// Get the type of a definition, with its generic parameters left unsubstituted.
+    fn def_ty(&mut self, item: DefId) -> Ty;
+
+    /// Compute the layout of a monomorphic type.
+    fn ty_layout(&mut self, ty: Ty) -> Result<LayoutShape, Error>;
+
+    /// Retrieve all trait impls of the local crate.
+    fn all_trait_impls(&mut self) -> Vec<ImplDef>;
+
+    /// Retrieve all impls of a trait in the local crate and its dependencies.
+    fn trait_impls(&mut self, trait_def: &TraitDef) -> Vec<ImplDef>;
+
+    /// Get the trait implemented by an impl, if it is a trait impl.
+    fn trait_impl(&mut self, impl_def: &ImplDef) -> Option<TraitRef>;
+
+    /// Resolve a function with the given generic arguments to the instance that gets called.
+    fn resolve_instance(&mut self, def: FnDef, args: &GenericArgs) -> Result<Instance, Error>;
+
+    /// Get the monomorphized body of an instance, if it has one.
+    fn instance_body(&mut self, def: InstanceDef) -> Option<mir::Body>;
+
+    /// Get the monomorphic type of an instance.
+    fn instance_ty(&mut self, def: InstanceDef) -> Ty;
+
+    /// Compute the call ABI of an instance.
+    fn instance_abi(&mut self, def: InstanceDef) -> Result<FnAbi, Error>;
+
+    /// Get the symbol name of an instance.
+    fn instance_mangled_name(&mut self, def: InstanceDef) -> String;
+
     /// HACK: Until we have fully stable consumers, we need an escape hatch
     /// to get `DefId`s out of `CrateItem`s.
     fn rustc_tables(&mut self, f: &mut dyn FnMut(&mut Tables<'_>));
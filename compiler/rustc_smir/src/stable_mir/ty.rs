@@ -1,4 +1,8 @@
-use super::with;
+use super::{
+    abi::LayoutShape,
+    mir::{Mutability, Safety},
+    with, DefId, Error, Symbol,
+};
 
 #[derive(Copy, Clone, Debug)]
 pub struct Ty(pub usize);
@@ -7,9 +11,357 @@ impl Ty {
     pub fn kind(&self) -> TyKind {
         with(|context| context.ty_kind(*self))
     }
+
+    /// Computes the memory layout of this type.
+    ///
+    /// Fails if the type is not monomorphic or too big for the target.
+    pub fn layout(&self) -> Result<LayoutShape, Error> {
+        with(|context| context.ty_layout(*self))
+    }
 }
 
+#[derive(Clone, Debug)]
 pub enum TyKind {
+    RigidTy(RigidTy),
+    Alias(AliasKind, AliasTy),
+    Param(ParamTy),
+    Bound(usize, BoundTy),
+}
+
+#[derive(Clone, Debug)]
+pub enum RigidTy {
     Bool,
+    Char,
+    Int(IntTy),
+    Uint(UintTy),
+    Float(FloatTy),
+    Adt(AdtDef, GenericArgs),
+    Foreign(ForeignDef),
+    Str,
+    Array(Ty, Const),
+    Slice(Ty),
+    RawPtr(Ty, Mutability),
+    Ref(Region, Ty, Mutability),
+    FnDef(FnDef, GenericArgs),
+    FnPtr(PolyFnSig),
+    Closure(ClosureDef, GenericArgs),
+    Generator(GeneratorDef, GenericArgs, Movability),
+    Dynamic(Vec<Binder<ExistentialPredicate>>, Region, DynKind),
+    Never,
     Tuple(Vec<Ty>),
+    /// The types of the values kept alive across suspension points of a generator.
+    GeneratorWitness(Binder<Vec<Ty>>),
+    /// Same as `GeneratorWitness`, but with the hidden types computed from the generator MIR.
+    GeneratorWitnessMIR(GeneratorDef, GenericArgs),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movability {
+    Static,
+    Movable,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ForeignDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FnDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ClosureDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GeneratorDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParamDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BrNamedDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AdtDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AliasDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TraitDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConstDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ThreadLocalDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StaticDef(pub(crate) DefId);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ImplDef(pub(crate) DefId);
+
+impl ImplDef {
+    /// The trait this impl implements, with the impl's own generic parameters left unsubstituted.
+    /// Returns `None` for inherent impls.
+    pub fn trait_impl(&self) -> Option<TraitRef> {
+        with(|context| context.trait_impl(self))
+    }
+}
+
+/// A trait together with the generic arguments it is applied to. The first argument
+/// is the `Self` type.
+#[derive(Clone, Debug)]
+pub struct TraitRef {
+    pub def_id: TraitDef,
+    pub args: GenericArgs,
+}
+
+/// The generic arguments a definition is instantiated with, e.g. the `u8` in `Vec<u8>`.
+#[derive(Clone, Debug)]
+pub struct GenericArgs(pub Vec<GenericArgKind>);
+
+#[derive(Clone, Debug)]
+pub enum GenericArgKind {
+    Lifetime(Region),
+    Type(Ty),
+    Const(Const),
+}
+
+#[derive(Clone, Debug)]
+pub enum Region {
+    EarlyBound(EarlyBoundRegion),
+    LateBound(usize, BoundRegion),
+    Free(BoundRegionKind),
+    Static,
+    /// Regions are erased in the bodies of optimized MIR.
+    Erased,
+}
+
+#[derive(Clone, Debug)]
+pub struct EarlyBoundRegion {
+    pub def_id: RegionDef,
+    pub index: u32,
+    pub name: Symbol,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RegionDef(pub(crate) DefId);
+
+#[derive(Clone, Debug)]
+pub struct BoundRegion {
+    pub var: usize,
+    pub kind: BoundRegionKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum BoundRegionKind {
+    BrAnon,
+    BrNamed(BrNamedDef, Symbol),
+    BrEnv,
+}
+
+#[derive(Clone, Debug)]
+pub enum TermKind {
+    Type(Ty),
+    Const(Const),
+}
+
+#[derive(Clone, Debug)]
+pub enum AliasKind {
+    Projection,
+    Inherent,
+    Opaque,
+}
+
+#[derive(Clone, Debug)]
+pub struct AliasTy {
+    pub def_id: AliasDef,
+    pub args: GenericArgs,
+}
+
+pub type PolyFnSig = Binder<FnSig>;
+
+#[derive(Clone, Debug)]
+pub struct FnSig {
+    pub inputs_and_output: Vec<Ty>,
+    pub c_variadic: bool,
+    pub unsafety: Safety,
+    pub abi: Abi,
+}
+
+impl FnSig {
+    pub fn inputs(&self) -> &[Ty] {
+        &self.inputs_and_output[..self.inputs_and_output.len() - 1]
+    }
+
+    pub fn output(&self) -> Ty {
+        self.inputs_and_output[self.inputs_and_output.len() - 1]
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Abi {
+    Rust,
+    C { unwind: bool },
+    Cdecl { unwind: bool },
+    Stdcall { unwind: bool },
+    Fastcall { unwind: bool },
+    Vectorcall { unwind: bool },
+    Thiscall { unwind: bool },
+    Aapcs { unwind: bool },
+    Win64 { unwind: bool },
+    SysV64 { unwind: bool },
+    PtxKernel,
+    Msp430Interrupt,
+    X86Interrupt,
+    AmdGpuKernel,
+    EfiApi,
+    AvrInterrupt,
+    AvrNonBlockingInterrupt,
+    CCmseNonSecureCall,
+    Wasm,
+    System { unwind: bool },
+    RustIntrinsic,
+    RustCall,
+    PlatformIntrinsic,
+    Unadjusted,
+    RustCold,
+}
+
+#[derive(Clone, Debug)]
+pub struct Binder<T> {
+    pub value: T,
+    pub bound_vars: Vec<BoundVariableKind>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ExistentialPredicate {
+    Trait(ExistentialTraitRef),
+    Projection(ExistentialProjection),
+    AutoTrait(TraitDef),
+}
+
+#[derive(Clone, Debug)]
+pub struct ExistentialTraitRef {
+    pub def_id: TraitDef,
+    pub generic_args: GenericArgs,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExistentialProjection {
+    pub def_id: TraitDef,
+    pub generic_args: GenericArgs,
+    pub term: TermKind,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParamTy {
+    pub index: u32,
+    pub name: Symbol,
+}
+
+#[derive(Clone, Debug)]
+pub struct BoundTy {
+    pub var: usize,
+    pub kind: BoundTyKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum BoundVariableKind {
+    Ty(BoundTyKind),
+    Region(BoundRegionKind),
+    Const,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BoundTyKind {
+    Anon,
+    Param(ParamDef, Symbol),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DynKind {
+    Dyn,
+    DynStar,
+}
+
+/// A constant value together with its type, as it appears in types (e.g. array lengths),
+/// generic arguments and MIR operands.
+#[derive(Clone, Debug)]
+pub struct Const {
+    pub literal: ConstantKind,
+    pub ty: Ty,
+    /// Identifies the compiler's representation of this constant, so that it can be
+    /// translated back, e.g. when resolving an instance.
+    pub(crate) id: ConstId,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct ConstId(pub(crate) usize);
+
+#[derive(Clone, Debug)]
+pub enum ConstantKind {
+    /// A fully evaluated constant, represented by the bytes of its value.
+    Allocated(Allocation),
+    /// A constant that could not be evaluated yet, e.g. because it depends on generic parameters.
+    Unevaluated(UnevaluatedConst),
+    /// A const generic parameter, e.g. the `N` in `[u8; N]`.
+    Param(ParamConst),
+}
+
+#[derive(Clone, Debug)]
+pub struct ParamConst {
+    pub index: u32,
+    pub name: Symbol,
+}
+
+#[derive(Clone, Debug)]
+pub struct UnevaluatedConst {
+    pub def: ConstDef,
+    pub args: GenericArgs,
+    pub promoted: Option<u32>,
+}
+
+/// Identifies another allocation that a pointer stored in an [`Allocation`] points to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AllocId(pub(crate) usize);
+
+/// The memory backing a constant value.
+///
+/// Bytes that are uninitialized are `None`. Bytes that are part of a pointer hold the offset
+/// into the pointee allocation in target endianness, and the pointee itself is recorded in
+/// `provenance`.
+#[derive(Clone, Debug)]
+pub struct Allocation {
+    pub bytes: Vec<Option<u8>>,
+    /// The offset of each pointer stored in `bytes`, together with the allocation it points to.
+    pub provenance: Vec<(usize, AllocId)>,
+    pub align: u64,
+    pub mutability: Mutability,
 }
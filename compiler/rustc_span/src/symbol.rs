@@ -288,6 +288,7 @@
         Sync,
         T,
         Target,
+        TestCases,
         ToOwned,
         ToString,
         TokenStream,
@@ -9,7 +9,7 @@
 use rustc_middle::ty::{
     self, subst::SubstsRef, AdtDef, EarlyBinder, ReprOptions, Ty, TyCtxt, TypeVisitableExt,
 };
-use rustc_session::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
+use rustc_session::{DataTypeKind, FieldInfo, FieldKind, NicheInfo, SizeKind, VariantInfo};
 use rustc_span::symbol::Symbol;
 use rustc_span::DUMMY_SP;
 use rustc_target::abi::*;
@@ -823,6 +823,11 @@ fn record_layout_for_printing_outlined<'tcx>(
     // (delay format until we actually need it)
     let record = |kind, packed, opt_discr_size, variants| {
         let type_desc = format!("{:?}", layout.ty);
+        let largest_niche = layout.largest_niche.map(|niche| NicheInfo {
+            offset: niche.offset.bytes(),
+            size: niche.value.size(cx).bytes(),
+            available: niche.available(cx),
+        });
         cx.tcx.sess.code_stats.record_type_size(
             kind,
             type_desc,
@@ -830,6 +835,7 @@ fn record_layout_for_printing_outlined<'tcx>(
             layout.size,
             packed,
             opt_discr_size,
+            largest_niche,
             variants,
         );
     };
@@ -2,8 +2,9 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
+use super::options::{ColorConfig, Options, OutputFormat, RunIgnored, TestShard};
 use super::time::TestTimeOptions;
 use std::io::{self, IsTerminal};
 
@@ -23,9 +24,14 @@ pub struct TestOpts {
     pub format: OutputFormat,
     pub shuffle: bool,
     pub shuffle_seed: Option<u64>,
+    /// Only run the tests of this shard.
+    pub shard: Option<TestShard>,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
     pub time_options: Option<TestTimeOptions>,
+    /// Fail tests that run longer than this. Tests running in a subprocess are killed, and
+    /// the test run is aborted if a test running in-process exceeds it.
+    pub test_timeout: Option<Duration>,
     /// Stop at first failing test.
     /// May run a few more tests due to threading, but will
     /// abort as soon as possible.
@@ -102,8 +108,9 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document;
+            tap    = Output a TAP version 14 stream",
+            "pretty|terse|json|junit|tap",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -142,13 +149,33 @@ fn optgroups() -> getopts::Options {
             `CRITICAL_TIME` here means the limit that should not be exceeded by test.
             ",
         )
+        .optopt(
+            "",
+            "test-timeout",
+            "Fail tests that run for longer than SECONDS.
+
+            Tests running in a separate process (with `-Z panic-abort-tests`) are killed,
+            and the other tests keep running. Tests running in-process cannot be killed,
+            so the test run is aborted instead.
+
+            This can also be set with the `RUST_TEST_TIMEOUT` environment variable.",
+            "SECONDS",
+        )
         .optflag("", "shuffle", "Run tests in random order")
         .optopt(
             "",
             "shuffle-seed",
             "Run tests in random order; seed the random number generator with SEED",
             "SEED",
-        );
+        )
+        .optopt(
+            "",
+            "shard-index",
+            "Only run the tests of shard INDEX (starting at 0) out of the number of shards \
+             given by --shard-count",
+            "INDEX",
+        )
+        .optopt("", "shard-count", "Split the tests into COUNT disjoint shards", "COUNT");
     opts
 }
 
@@ -168,8 +195,15 @@ fn usage(binary: &str, options: &getopts::Options) {
 By default, the tests are run in alphabetical order. Use --shuffle or set
 RUST_TEST_SHUFFLE to run the tests in random order. Pass the generated
 "shuffle seed" to --shuffle-seed (or set RUST_TEST_SHUFFLE_SEED) to run the
-tests in the same order again. Note that --shuffle and --shuffle-seed do not
-affect whether the tests are run in parallel.
+tests in the same order again. The seed is printed again at the end of the run.
+The order also depends on the set of tests that are run, so pass the same
+filters to reproduce it. Note that --shuffle and --shuffle-seed do not affect
+whether the tests are run in parallel.
+
+The tests can be split across several machines with --shard-index and
+--shard-count. Each test is assigned to a shard based on a hash of its name,
+so every shard runs a disjoint subset of the tests, and the same subset on
+every run.
 
 All tests have their standard output and standard error captured by default.
 This can be overridden with the --nocapture flag or setting RUST_TEST_NOCAPTURE
@@ -259,8 +293,10 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     let force_run_in_process = unstable_optflag!(matches, allow_unstable, "force-run-in-process");
     let exclude_should_panic = unstable_optflag!(matches, allow_unstable, "exclude-should-panic");
     let time_options = get_time_options(&matches, allow_unstable)?;
+    let test_timeout = get_test_timeout(&matches, allow_unstable)?;
     let shuffle = get_shuffle(&matches, allow_unstable)?;
     let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
+    let shard = get_shard(&matches, allow_unstable)?;
 
     let include_ignored = matches.opt_present("include-ignored");
     let quiet = matches.opt_present("quiet");
@@ -296,9 +332,11 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         format,
         shuffle,
         shuffle_seed,
+        shard,
         test_threads,
         skip,
         time_options,
+        test_timeout,
         options,
         fail_fast: false,
     };
@@ -336,6 +374,26 @@ fn get_time_options(
     Ok(options)
 }
 
+fn get_test_timeout(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Duration>> {
+    let mut timeout = unstable_optopt!(matches, allow_unstable, "test-timeout");
+    if timeout.is_none() && allow_unstable {
+        timeout = env::var("RUST_TEST_TIMEOUT").ok();
+    }
+
+    match timeout.map(|secs| secs.parse::<u64>()) {
+        None => Ok(None),
+        Some(Ok(0)) => Err("argument for --test-timeout must not be 0".to_string()),
+        Some(Ok(secs)) => Ok(Some(Duration::from_secs(secs))),
+        Some(Err(e)) => Err(format!(
+            "argument for --test-timeout must be a number of seconds > 0 \
+             (error: {e})"
+        )),
+    }
+}
+
 fn get_shuffle(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<bool> {
     let mut shuffle = unstable_optflag!(matches, allow_unstable, "shuffle");
     if !shuffle && allow_unstable {
@@ -375,6 +433,44 @@ fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPart
     Ok(shuffle_seed)
 }
 
+fn get_shard(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<Option<TestShard>> {
+    let index = unstable_optopt!(matches, allow_unstable, "shard-index");
+    let count = unstable_optopt!(matches, allow_unstable, "shard-count");
+
+    let (index, count) = match (index, count) {
+        (None, None) => return Ok(None),
+        (Some(index), Some(count)) => (index, count),
+        _ => return Err("--shard-index and --shard-count must be passed together".to_string()),
+    };
+    let count = match count.parse::<usize>() {
+        Ok(0) => return Err("argument for --shard-count must not be 0".to_string()),
+        Ok(n) => n,
+        Err(e) => {
+            return Err(format!(
+                "argument for --shard-count must be a number > 0 \
+                 (error: {e})"
+            ));
+        }
+    };
+    let index = match index.parse::<usize>() {
+        Ok(n) if n < count => n,
+        Ok(n) => {
+            return Err(format!(
+                "argument for --shard-index must be less than --shard-count \
+                 (was {n}, with {count} shards)"
+            ));
+        }
+        Err(e) => {
+            return Err(format!(
+                "argument for --shard-index must be a number \
+                 (error: {e})"
+            ));
+        }
+    };
+
+    Ok(Some(TestShard { index, count }))
+}
+
 fn get_test_threads(matches: &getopts::Matches) -> OptPartRes<Option<usize>> {
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
@@ -414,9 +510,15 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => {
+            if !allow_unstable {
+                return Err("The \"tap\" format is only accepted on the nightly compiler".into());
+            }
+            OutputFormat::Tap
+        }
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
+                "argument for --format must be pretty, terse, json, junit or tap (was \
                  {v})"
             ));
         }
@@ -9,8 +9,11 @@
     bench::fmt_bench_samples,
     cli::TestOpts,
     event::{CompletedTest, TestEvent},
-    filter_tests,
-    formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter},
+    expand_test_cases, filter_tests,
+    formatters::{
+        JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TapFormatter,
+        TerseFormatter,
+    },
     helpers::{concurrency::get_concurrency, metrics::MetricMap},
     options::{Options, OutputFormat},
     run_tests, term,
@@ -90,6 +93,9 @@ pub struct ConsoleTestState {
     pub filtered_out: usize,
     pub measured: usize,
     pub exec_time: Option<TestSuiteExecTime>,
+    pub shuffle_seed: Option<u64>,
+    /// Whether the run was aborted because an in-process test exceeded the timeout.
+    pub aborted: bool,
     pub metrics: MetricMap,
     pub failures: Vec<(TestDesc, Vec<u8>)>,
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
@@ -114,6 +120,8 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             filtered_out: 0,
             measured: 0,
             exec_time: None,
+            shuffle_seed: None,
+            aborted: false,
             metrics: MetricMap::new(),
             failures: Vec::new(),
             not_failures: Vec::new(),
@@ -184,7 +192,7 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
     };
 
     let mut out: Box<dyn OutputFormatter> = match opts.format {
-        OutputFormat::Pretty | OutputFormat::Junit => {
+        OutputFormat::Pretty | OutputFormat::Junit | OutputFormat::Tap => {
             Box::new(PrettyFormatter::new(output, false, 0, false, None))
         }
         OutputFormat::Terse => Box::new(TerseFormatter::new(output, false, 0, false)),
@@ -193,7 +201,7 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
     let mut st = ConsoleTestDiscoveryState::new(opts)?;
 
     out.write_discovery_start()?;
-    for test in filter_tests(opts, tests).into_iter() {
+    for test in filter_tests(opts, expand_test_cases(tests)).into_iter() {
         use crate::TestFn::*;
 
         let TestDescAndFn { desc, testfn } = test;
@@ -207,6 +215,7 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
                 st.benchmarks += 1;
                 "benchmark"
             }
+            StaticCasesFn(..) => unreachable!("parameterized tests are expanded before listing"),
         };
 
         st.ignored += if desc.ignore { 1 } else { 0 };
@@ -262,25 +271,30 @@ fn on_test_event(
     event: &TestEvent,
     st: &mut ConsoleTestState,
     out: &mut dyn OutputFormatter,
+    report_time: bool,
 ) -> io::Result<()> {
     match (*event).clone() {
         TestEvent::TeFiltered(filtered_tests, shuffle_seed) => {
-            st.total = filtered_tests;
-            out.write_run_start(filtered_tests, shuffle_seed)?;
+            st.total = filtered_tests.len();
+            st.shuffle_seed = shuffle_seed;
+            out.write_run_start(&filtered_tests, shuffle_seed)?;
         }
         TestEvent::TeFilteredOut(filtered_out) => {
             st.filtered_out = filtered_out;
         }
         TestEvent::TeWait(ref test) => out.write_test_start(test)?,
         TestEvent::TeTimeout(ref test) => out.write_timeout(test)?,
+        TestEvent::TeAborted => st.aborted = true,
         TestEvent::TeResult(completed_test) => {
             let test = &completed_test.desc;
             let result = &completed_test.result;
-            let exec_time = &completed_test.exec_time;
+            let exec_time = completed_test.exec_time.as_ref();
             let stdout = &completed_test.stdout;
 
-            st.write_log_result(test, result, exec_time.as_ref())?;
-            out.write_result(test, result, exec_time.as_ref(), stdout, st)?;
+            // Without `--report-time`, execution times are only measured for the JSON output.
+            let reported_time = exec_time.filter(|_| report_time);
+            st.write_log_result(test, result, reported_time)?;
+            out.write_result(test, result, exec_time, stdout, st)?;
             handle_test_result(st, completed_test);
         }
     }
@@ -317,6 +331,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
         OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
+        OutputFormat::Tap => Box::new(TapFormatter::new(output)),
     };
     let mut st = ConsoleTestState::new(opts)?;
 
@@ -326,10 +341,13 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
     let is_instant_supported = !cfg!(target_family = "wasm") && !cfg!(miri);
 
     let start_time = is_instant_supported.then(Instant::now);
-    run_tests(opts, tests, |x| on_test_event(&x, &mut st, &mut *out))?;
+    let report_time = opts.time_options.is_some();
+    run_tests(opts, tests, |x| on_test_event(&x, &mut st, &mut *out, report_time))?;
     st.exec_time = start_time.map(|t| TestSuiteExecTime(t.elapsed()));
 
-    assert!(opts.fail_fast || st.current_test_count() == st.total);
+    // The run stops early after the first failure with `fail_fast`, and when a test that
+    // cannot be killed exceeds the timeout.
+    assert!(opts.fail_fast || st.aborted || st.current_test_count() == st.total);
 
     out.write_run_finish(&st)
 }
@@ -3,11 +3,19 @@
 use super::OutputFormatter;
 use crate::{
     console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
+    options::ShouldPanic,
     test_result::TestResult,
     time,
     types::TestDesc,
 };
 
+/// The version of the schema of the emitted events, which is reported by the first event of
+/// every run.
+///
+/// Fields may be added to events without changing the version. It is incremented whenever
+/// fields are removed or change their meaning.
+const FORMAT_VERSION: u32 = 1;
+
 pub(crate) struct JsonFormatter<T> {
     out: OutputLocation<T>,
 }
@@ -58,7 +66,10 @@ fn write_event(
 
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
     fn write_discovery_start(&mut self) -> io::Result<()> {
-        self.writeln_message(concat!(r#"{ "type": "suite", "event": "discovery" }"#, "\n"))
+        let newline = "\n";
+        self.writeln_message(&format!(
+            r#"{{ "type": "suite", "event": "discovery", "format_version": {FORMAT_VERSION} }}{newline}"#
+        ))
     }
 
     fn write_test_discovered(&mut self, desc: &TestDesc, test_type: &str) -> io::Result<()> {
@@ -94,23 +105,29 @@ fn write_discovery_finish(&mut self, state: &ConsoleTestDiscoveryState) -> io::R
             ))
     }
 
-    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+    fn write_run_start(&mut self, tests: &[TestDesc], shuffle_seed: Option<u64>) -> io::Result<()> {
+        let test_count = tests.len();
         let shuffle_seed_json = if let Some(shuffle_seed) = shuffle_seed {
             format!(r#", "shuffle_seed": {shuffle_seed}"#)
         } else {
             String::new()
         };
+        let tests_json = tests
+            .iter()
+            .map(|desc| format!("{{ {} }}", TestDescJson(desc)))
+            .collect::<Vec<_>>()
+            .join(", ");
         let newline = "\n";
         self.writeln_message(&format!(
-            r#"{{ "type": "suite", "event": "started", "test_count": {test_count}{shuffle_seed_json} }}{newline}"#
+            r#"{{ "type": "suite", "event": "started", "format_version": {FORMAT_VERSION}, "test_count": {test_count}{shuffle_seed_json}, "tests": [{tests_json}] }}{newline}"#
             ))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
-        let name = EscapedString(desc.name.as_slice());
+        let desc = TestDescJson(desc);
         let newline = "\n";
         self.writeln_message(&format!(
-            r#"{{ "type": "test", "event": "started", "name": "{name}" }}{newline}"#
+            r#"{{ "type": "test", "event": "started", {desc} }}{newline}"#
         ))
     }
 
@@ -122,6 +139,8 @@ fn write_result(
         stdout: &[u8],
         state: &ConsoleTestState,
     ) -> io::Result<()> {
+        // The captured output includes what the test printed to both stdout and stderr: tests
+        // running in-process share a single capture buffer for both, so they cannot be told apart.
         let display_stdout = state.options.display_output || *result != TestResult::TrOk;
         let stdout = if display_stdout && !stdout.is_empty() {
             Some(String::from_utf8_lossy(stdout))
@@ -207,16 +226,57 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         } else {
             String::from("")
         };
+        let shuffle_seed_json = if let Some(shuffle_seed) = state.shuffle_seed {
+            format!(r#", "shuffle_seed": {shuffle_seed}"#)
+        } else {
+            String::new()
+        };
         let newline = "\n";
 
         self.writeln_message(&format!(
-            r#"{{ "type": "suite", "event": "{event}", "passed": {passed}, "failed": {failed}, "ignored": {ignored}, "measured": {measured}, "filtered_out": {filtered_out}{exec_time_json} }}{newline}"#
+            r#"{{ "type": "suite", "event": "{event}", "passed": {passed}, "failed": {failed}, "ignored": {ignored}, "measured": {measured}, "filtered_out": {filtered_out}{exec_time_json}{shuffle_seed_json} }}{newline}"#
         ))?;
 
         Ok(state.failed == 0)
     }
 }
 
+/// The fields describing a test, from its name to where it is defined and what it is
+/// expected to do.
+struct TestDescJson<'a>(&'a TestDesc);
+
+impl std::fmt::Display for TestDescJson<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let TestDesc {
+            name,
+            ignore,
+            source_file,
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+            should_panic,
+            ..
+        } = self.0;
+
+        let name = EscapedString(name.as_slice());
+        let source_path = EscapedString(source_file);
+        write!(
+            f,
+            r#""name": "{name}", "ignore": {ignore}, "source_path": "{source_path}", "start_line": {start_line}, "start_col": {start_col}, "end_line": {end_line}, "end_col": {end_col}"#
+        )?;
+        match should_panic {
+            ShouldPanic::No => f.write_str(r#", "should_panic": false"#),
+            ShouldPanic::Yes => f.write_str(r#", "should_panic": true"#),
+            ShouldPanic::YesWithMessage(msg) => write!(
+                f,
+                r#", "should_panic": true, "should_panic_message": "{}""#,
+                EscapedString(msg)
+            ),
+        }
+    }
+}
+
 /// A formatting utility used to print strings with characters in need of escaping.
 /// Base code taken form `libserialize::json::escape_str`
 struct EscapedString<S: AsRef<str>>(S);
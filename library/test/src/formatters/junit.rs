@@ -53,7 +53,7 @@ fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::
 
     fn write_run_start(
         &mut self,
-        _test_count: usize,
+        _tests: &[TestDesc],
         _shuffle_seed: Option<u64>,
     ) -> io::Result<()> {
         // We write xml header on run start
@@ -208,7 +208,8 @@ fn plural(count: usize, s: &str) -> String {
         ))
     }
 
-    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+    fn write_run_start(&mut self, tests: &[TestDesc], shuffle_seed: Option<u64>) -> io::Result<()> {
+        let test_count = tests.len();
         let noun = if test_count != 1 { "tests" } else { "test" };
         let shuffle_seed_msg = if let Some(shuffle_seed) = shuffle_seed {
             format!(" (shuffle seed: {shuffle_seed})")
@@ -301,6 +302,11 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             self.write_plain(time_str)?;
         }
 
+        // Repeat the seed, so that the order can be reproduced without scrolling back up.
+        if let Some(shuffle_seed) = state.shuffle_seed {
+            self.write_plain(format!("; shuffle seed: {shuffle_seed}"))?;
+        }
+
         self.write_plain("\n\n")?;
 
         Ok(success)
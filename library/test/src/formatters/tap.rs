@@ -0,0 +1,208 @@
+use std::{fmt, io, io::prelude::Write};
+
+use super::OutputFormatter;
+use crate::{
+    bench::fmt_bench_samples,
+    console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
+    test_result::TestResult,
+    time,
+    types::TestDesc,
+};
+
+/// Emits a [TAP version 14](https://testanything.org/tap-version-14-specification.html) stream.
+///
+/// Every test is reported as one test point. Failures carry a YAML diagnostic block with the
+/// failure message and the captured output, and ignored tests are reported with a `# SKIP`
+/// directive. The cases of a parameterized test are reported as test points of their own, not
+/// as a subtest, as nothing tells the formatter which tests are cases of the same test.
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    /// The number of the last test point written.
+    test_number: usize,
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self { out, test_number: 0 }
+    }
+
+    fn write_message(&mut self, s: &str) -> io::Result<()> {
+        // Test points are written with a single `write_all` call, so that a test point and its
+        // diagnostics are not torn apart by output of the tests themselves.
+        assert_eq!(s.chars().last(), Some('\n'));
+
+        self.out.write_all(s.as_ref())
+    }
+
+    fn write_test_point(
+        &mut self,
+        ok: bool,
+        desc: &TestDesc,
+        directive: Option<&str>,
+        diagnostics: &[(&str, &str)],
+    ) -> io::Result<()> {
+        self.test_number += 1;
+
+        let status = if ok { "ok" } else { "not ok" };
+        let mut s = format!("{status} {} - {}", self.test_number, Escaped(desc.name.as_slice()));
+        if let Some(directive) = directive {
+            s.push_str(" # ");
+            s.push_str(directive);
+        }
+        s.push('\n');
+
+        if !diagnostics.is_empty() {
+            s.push_str("  ---\n");
+            for (key, value) in diagnostics {
+                write_yaml_field(&mut s, key, value);
+            }
+            s.push_str("  ...\n");
+        }
+
+        self.write_message(&s)
+    }
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_test_discovered(&mut self, _desc: &TestDesc, _test_type: &str) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_run_start(&mut self, tests: &[TestDesc], shuffle_seed: Option<u64>) -> io::Result<()> {
+        let test_count = tests.len();
+        self.write_message("TAP version 14\n")?;
+        if let Some(shuffle_seed) = shuffle_seed {
+            self.write_message(&format!("# shuffle seed: {shuffle_seed}\n"))?;
+        }
+        self.write_message(&format!("1..{test_count}\n"))
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // Test points are only written once the test has finished.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.write_message(&format!(
+            "# test {} has been running for over {} seconds\n",
+            desc.name,
+            time::TEST_WARN_TIMEOUT_S
+        ))
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let stdout = String::from_utf8_lossy(stdout);
+        let duration = exec_time.map(|exec_time| format!("{}", exec_time.0.as_secs_f64() * 1000.0));
+
+        let mut diagnostics = vec![];
+        let (ok, directive) = match result {
+            TestResult::TrOk => {
+                if state.options.display_output && !stdout.is_empty() {
+                    diagnostics.push(("output", &*stdout));
+                }
+                (true, None)
+            }
+            TestResult::TrIgnored => {
+                let directive = match desc.ignore_message {
+                    Some(msg) => format!("SKIP {}", Escaped(msg)),
+                    None => String::from("SKIP"),
+                };
+                (true, Some(directive))
+            }
+            TestResult::TrBench(bs) => {
+                let samples = fmt_bench_samples(bs);
+                self.write_test_point(true, desc, None, &[("bench", &samples)])?;
+                return Ok(());
+            }
+            TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrTimedFail => {
+                let message = match result {
+                    TestResult::TrFailedMsg(msg) => msg.as_str(),
+                    TestResult::TrTimedFail => "time limit exceeded",
+                    _ => panic_message(&stdout).unwrap_or("test failed"),
+                };
+                diagnostics.push(("message", message));
+                diagnostics.push(("severity", "fail"));
+                if !stdout.is_empty() {
+                    diagnostics.push(("output", &*stdout));
+                }
+                (false, None)
+            }
+        };
+        if let Some(duration) = &duration {
+            diagnostics.push(("duration_ms", duration));
+        }
+
+        self.write_test_point(ok, desc, directive.as_deref(), &diagnostics)
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        self.write_message(&format!(
+            "# {} passed; {} failed; {} ignored; {} measured; {} filtered out\n",
+            state.passed, state.failed, state.ignored, state.measured, state.filtered_out
+        ))?;
+        if let Some(ref exec_time) = state.exec_time {
+            self.write_message(&format!("# finished in {exec_time}\n"))?;
+        }
+
+        Ok(state.failed == 0)
+    }
+}
+
+/// Finds the message the test panicked with in its captured output.
+fn panic_message(stdout: &str) -> Option<&str> {
+    stdout.lines().find(|line| line.starts_with("thread '") && line.contains("' panicked at "))
+}
+
+/// Writes `key: value` as a field of a YAML diagnostic block, using a block scalar for values
+/// that span multiple lines.
+fn write_yaml_field(s: &mut String, key: &str, value: &str) {
+    use fmt::Write;
+
+    if !value.contains('\n') {
+        writeln!(s, "  {key}: '{}'", value.replace('\'', "''")).unwrap();
+        return;
+    }
+
+    // The indentation of a block scalar is inferred from its first line, unless given
+    // explicitly. Lines are indented by two spaces relative to the key.
+    let indicator = if value.starts_with(' ') { "|2" } else { "|" };
+    writeln!(s, "  {key}: {indicator}").unwrap();
+    for line in value.lines() {
+        if line.is_empty() {
+            s.push('\n');
+        } else {
+            writeln!(s, "    {line}").unwrap();
+        }
+    }
+}
+
+/// Text in the description or the directive of a test point, where `#` would start a directive.
+struct Escaped<'a>(&'a str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\\' => f.write_str("\\\\")?,
+                '#' => f.write_str("\\#")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
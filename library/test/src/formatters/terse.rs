@@ -132,7 +132,7 @@ pub fn write_failures(&mut self, state: &ConsoleTestState) -> io::Result<()> {
         self.write_plain("\nfailures:\n")?;
         let mut failures = Vec::new();
         let mut fail_out = String::new();
-        for (f, stdout) in &state.failures {
+        for (f, stdout) in state.failures.iter().chain(&state.time_failures) {
             failures.push(f.name.to_string());
             if !stdout.is_empty() {
                 fail_out.push_str(&format!("---- {} stdout ----\n", f.name));
@@ -179,7 +179,8 @@ fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::
         Ok(())
     }
 
-    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+    fn write_run_start(&mut self, tests: &[TestDesc], shuffle_seed: Option<u64>) -> io::Result<()> {
+        let test_count = tests.len();
         self.total_test_count = test_count;
         let noun = if test_count != 1 { "tests" } else { "test" };
         let shuffle_seed_msg = if let Some(shuffle_seed) = shuffle_seed {
@@ -264,6 +265,11 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             self.write_plain(time_str)?;
         }
 
+        // Repeat the seed, so that the order can be reproduced without scrolling back up.
+        if let Some(shuffle_seed) = state.shuffle_seed {
+            self.write_plain(format!("; shuffle seed: {shuffle_seed}"))?;
+        }
+
         self.write_plain("\n\n")?;
 
         // Custom handling of cases where there is only 1 test to execute and that test was ignored.
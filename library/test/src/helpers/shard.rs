@@ -0,0 +1,18 @@
+//! Deterministic partitioning of the tests into shards.
+
+use crate::options::TestShard;
+use crate::types::TestName;
+
+/// Whether the test named `name` belongs to `shard`.
+pub fn is_in_shard(shard: TestShard, name: &TestName) -> bool {
+    name_hash(name.as_slice()) % shard.count as u64 == shard.index as u64
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, its output is guaranteed to be the same across
+/// platforms and Rust releases, so tests stay in the same shard when the toolchain changes.
+fn name_hash(name: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    name.bytes().fold(OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
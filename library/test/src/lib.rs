@@ -25,7 +25,7 @@
 // Public reexports
 pub use self::bench::{black_box, Bencher};
 pub use self::console::run_tests_console;
-pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic};
+pub use self::options::{ColorConfig, Options, OutputFormat, RunIgnored, ShouldPanic, TestShard};
 pub use self::types::TestName::*;
 pub use self::types::*;
 pub use self::ColorConfig::*;
@@ -37,15 +37,15 @@ pub mod test {
         assert_test_result,
         bench::Bencher,
         cli::{parse_opts, TestOpts},
-        filter_tests,
+        expand_test_cases, filter_tests,
         helpers::metrics::{Metric, MetricMap},
-        options::{Options, RunIgnored, RunStrategy, ShouldPanic},
+        options::{Options, RunIgnored, RunStrategy, ShouldPanic, TestShard},
         run_test, test_main, test_main_static,
         test_result::{TestResult, TrFailed, TrFailedMsg, TrIgnored, TrOk},
         time::{TestExecTime, TestTimeOptions},
         types::{
-            DynTestFn, DynTestName, StaticBenchFn, StaticTestFn, StaticTestName, TestDesc,
-            TestDescAndFn, TestId, TestName, TestType,
+            DynTestFn, DynTestName, StaticBenchFn, StaticCasesFn, StaticTestFn, StaticTestName,
+            TestCases, TestDesc, TestDescAndFn, TestId, TestName, TestType,
         },
     };
 }
@@ -54,7 +54,7 @@ pub mod test {
     collections::VecDeque,
     env, io,
     io::prelude::Write,
-    mem::ManuallyDrop,
+    mem::{self, ManuallyDrop},
     panic::{self, catch_unwind, AssertUnwindSafe, PanicInfo},
     process::{self, Command, Termination},
     sync::mpsc::{channel, Sender},
@@ -83,6 +83,7 @@ pub mod test {
 use event::{CompletedTest, TestEvent};
 use helpers::concurrency::get_concurrency;
 use helpers::exit_code::get_exit_code;
+use helpers::shard::is_in_shard;
 use helpers::shuffle::{get_shuffle_seed, shuffle_tests};
 use options::RunStrategy;
 use test_result::*;
@@ -171,22 +172,36 @@ pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
     // will then exit the process.
     if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
         env::remove_var(SECONDARY_TEST_INVOKER_VAR);
-        let test = tests
-            .iter()
-            .filter(|test| test.desc.name.as_slice() == name)
-            .map(make_owned_test)
-            .next()
+        let owned_tests = tests.iter().map(make_owned_test).collect();
+        let test = expand_test_cases(owned_tests)
+            .into_iter()
+            .find(|test| test.desc.name.as_slice() == name)
             .unwrap_or_else(|| panic!("couldn't find a test with the provided name '{name}'"));
         let TestDescAndFn { desc, testfn } = test;
         let testfn = match testfn {
-            StaticTestFn(f) => f,
+            StaticTestFn(f) => Box::new(f),
+            // The cases of parameterized tests, which were generated again in this process.
+            DynTestFn(f) => f,
             _ => panic!("only static tests are supported"),
         };
-        run_test_in_spawned_subprocess(desc, Box::new(testfn));
+        run_test_in_spawned_subprocess(desc, testfn);
     }
 
     let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
+    let owned_tests = tests.iter().map(make_owned_test).collect();
+    // Every test runs in a subprocess, which generates the cases of parameterized tests itself
+    // and looks them up by name. The cases generated here are only used for filtering and
+    // reporting, so they are replaced by static tests that can be spawned.
+    let owned_tests = expand_test_cases(owned_tests)
+        .into_iter()
+        .map(|TestDescAndFn { desc, testfn }| match testfn {
+            DynTestFn(_) => TestDescAndFn {
+                desc,
+                testfn: StaticTestFn(|| unreachable!("test cases run in a subprocess")),
+            },
+            testfn => TestDescAndFn { desc, testfn },
+        })
+        .collect();
     test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
 }
 
@@ -198,10 +213,40 @@ fn make_owned_test(test: &&TestDescAndFn) -> TestDescAndFn {
     match test.testfn {
         StaticTestFn(f) => TestDescAndFn { testfn: StaticTestFn(f), desc: test.desc.clone() },
         StaticBenchFn(f) => TestDescAndFn { testfn: StaticBenchFn(f), desc: test.desc.clone() },
+        StaticCasesFn(f) => TestDescAndFn { testfn: StaticCasesFn(f), desc: test.desc.clone() },
         _ => panic!("non-static tests passed to test::test_main_static"),
     }
 }
 
+/// Replaces every parameterized test by its cases, which are named `<test>::<case>` and
+/// otherwise share the description of the test.
+///
+/// If generating the cases panics, the test is kept as a single test that fails.
+pub fn expand_test_cases(tests: Vec<TestDescAndFn>) -> Vec<TestDescAndFn> {
+    let mut expanded = Vec::with_capacity(tests.len());
+    for test in tests {
+        let TestDescAndFn { desc, testfn: StaticCasesFn(generate) } = test else {
+            expanded.push(test);
+            continue;
+        };
+
+        let mut cases = TestCases::new();
+        if catch_unwind(AssertUnwindSafe(|| generate(&mut cases))).is_err() {
+            let testfn =
+                DynTestFn(Box::new(|| Err("generating the test cases panicked".to_string())));
+            expanded.push(TestDescAndFn { desc, testfn });
+            continue;
+        }
+
+        for (name, case) in cases.cases {
+            let desc =
+                TestDesc { name: DynTestName(format!("{}::{name}", desc.name)), ..desc.clone() };
+            expanded.push(TestDescAndFn { desc, testfn: DynTestFn(case) });
+        }
+    }
+    expanded
+}
+
 /// Invoked when unit tests terminate. Returns `Result::Err` if the test is
 /// considered a failure. By default, invokes `report()` and checks for a `0`
 /// result.
@@ -288,6 +333,7 @@ struct TimeoutEntry {
         timeout: Instant,
     }
 
+    let tests = expand_test_cases(tests);
     let tests_len = tests.len();
 
     let mut filtered = FilteredTests { tests: Vec::new(), benches: Vec::new(), next_id: 0 };
@@ -323,7 +369,9 @@ struct TimeoutEntry {
 
     let shuffle_seed = get_shuffle_seed(opts);
 
-    let event = TestEvent::TeFiltered(filtered.total_len(), shuffle_seed);
+    let filtered_descs =
+        filtered.tests.iter().chain(&filtered.benches).map(|(_, test)| test.desc.clone()).collect();
+    let event = TestEvent::TeFiltered(filtered_descs, shuffle_seed);
     notify_about_test_event(event)?;
 
     let concurrency = opts.test_threads.unwrap_or_else(get_concurrency);
@@ -344,13 +392,21 @@ struct TimeoutEntry {
         RunStrategy::InProcess
     };
 
+    // Tests running in a subprocess are killed once they exceed the timeout. Tests running
+    // in-process cannot be killed, so the whole test run is aborted instead.
+    let in_process_timeout = match run_strategy {
+        RunStrategy::InProcess => opts.test_timeout,
+        RunStrategy::SpawnPrimary => None,
+    };
+
     let mut running_tests: TestMap = HashMap::default();
     let mut timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
+    let mut deadline_queue: VecDeque<TimeoutEntry> = VecDeque::new();
 
     fn get_timed_out_tests(
         running_tests: &TestMap,
         timeout_queue: &mut VecDeque<TimeoutEntry>,
-    ) -> Vec<TestDesc> {
+    ) -> Vec<TimeoutEntry> {
         let now = Instant::now();
         let mut timed_out = Vec::new();
         while let Some(timeout_entry) = timeout_queue.front() {
@@ -359,12 +415,22 @@ fn get_timed_out_tests(
             }
             let timeout_entry = timeout_queue.pop_front().unwrap();
             if running_tests.contains_key(&timeout_entry.id) {
-                timed_out.push(timeout_entry.desc);
+                timed_out.push(timeout_entry);
             }
         }
         timed_out
     }
 
+    // Reports a test that exceeded the timeout while running in-process. The caller is
+    // expected to stop running tests afterwards.
+    fn stuck_test_result(id: TestId, desc: TestDesc, timeout: Duration) -> TestEvent {
+        let message = format!(
+            "test did not finish within {timeout:?}, and cannot be killed because it runs \
+             in-process; aborting the test run\n"
+        );
+        TestEvent::TeResult(CompletedTest::new(id, desc, TrTimedFail, None, message.into_bytes()))
+    }
+
     fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
         timeout_queue.front().map(|&TimeoutEntry { timeout: next_timeout, .. }| {
             let now = Instant::now();
@@ -375,11 +441,24 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
     if concurrency == 1 {
         while !remaining.is_empty() {
             let (id, test) = remaining.pop_front().unwrap();
-            let event = TestEvent::TeWait(test.desc.clone());
+            let desc = test.desc.clone();
+            let event = TestEvent::TeWait(desc.clone());
             notify_about_test_event(event)?;
             let join_handle = run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
             // Wait for the test to complete.
-            let mut completed_test = rx.recv().unwrap();
+            let mut completed_test = match in_process_timeout {
+                Some(timeout) => match rx.recv_timeout(timeout) {
+                    Ok(completed_test) => completed_test,
+                    Err(_) => {
+                        notify_about_test_event(stuck_test_result(id, desc, timeout))?;
+                        notify_about_test_event(TestEvent::TeAborted)?;
+                        // Prevent the stuck test thread from panicking if it ever finishes
+                        std::mem::forget(rx);
+                        return Ok(());
+                    }
+                },
+                None => rx.recv().unwrap(),
+            };
             RunningTest { join_handle }.join(&mut completed_test);
 
             let fail_fast = match completed_test.result {
@@ -406,22 +485,44 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
                 let join_handle =
                     run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
                 running_tests.insert(id, RunningTest { join_handle });
+                if let Some(test_timeout) = in_process_timeout {
+                    let timeout = Instant::now() + test_timeout;
+                    deadline_queue.push_back(TimeoutEntry { id, desc: desc.clone(), timeout });
+                }
                 timeout_queue.push_back(TimeoutEntry { id, desc, timeout });
                 pending += 1;
             }
 
             let mut res;
             loop {
-                if let Some(timeout) = calc_timeout(&timeout_queue) {
+                let timeout = match (calc_timeout(&timeout_queue), calc_timeout(&deadline_queue)) {
+                    (Some(warn), Some(deadline)) => Some(warn.min(deadline)),
+                    (warn, deadline) => warn.or(deadline),
+                };
+                if let Some(timeout) = timeout {
                     res = rx.recv_timeout(timeout);
                     for test in get_timed_out_tests(&running_tests, &mut timeout_queue) {
-                        let event = TestEvent::TeTimeout(test);
+                        let event = TestEvent::TeTimeout(test.desc);
                         notify_about_test_event(event)?;
                     }
 
                     match res {
                         Err(RecvTimeoutError::Timeout) => {
-                            // Result is not yet ready, continue waiting.
+                            // Result is not yet ready, continue waiting, unless a test exceeded
+                            // the enforced timeout.
+                            let stuck_tests =
+                                get_timed_out_tests(&running_tests, &mut deadline_queue);
+                            if let (Some(stuck_test), Some(timeout)) =
+                                (stuck_tests.into_iter().next(), in_process_timeout)
+                            {
+                                let event =
+                                    stuck_test_result(stuck_test.id, stuck_test.desc, timeout);
+                                notify_about_test_event(event)?;
+                                notify_about_test_event(TestEvent::TeAborted)?;
+                                // Prevent remaining test threads from panicking
+                                std::mem::forget(rx);
+                                return Ok(());
+                            }
                         }
                         _ => {
                             // We've got a result, stop the loop.
@@ -510,6 +611,11 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
         RunIgnored::No => {}
     }
 
+    // Only keep the tests of the requested shard
+    if let Some(shard) = opts.shard {
+        filtered.retain(|test| is_in_shard(shard, &test.desc.name));
+    }
+
     filtered
 }
 
@@ -556,7 +662,9 @@ pub fn run_test(
     struct TestRunOpts {
         pub strategy: RunStrategy,
         pub nocapture: bool,
+        pub measure_time: bool,
         pub time: Option<time::TestTimeOptions>,
+        pub timeout: Option<Duration>,
     }
 
     fn run_test_inner(
@@ -573,7 +681,7 @@ fn run_test_inner(
                 id,
                 desc,
                 opts.nocapture,
-                opts.time.is_some(),
+                opts.measure_time,
                 testfn,
                 monitor_ch,
                 opts.time,
@@ -582,9 +690,10 @@ fn run_test_inner(
                 id,
                 desc,
                 opts.nocapture,
-                opts.time.is_some(),
+                opts.measure_time,
                 monitor_ch,
                 opts.time,
+                opts.timeout,
             ),
         };
 
@@ -612,8 +721,15 @@ fn run_test_inner(
         }
     }
 
-    let test_run_opts =
-        TestRunOpts { strategy, nocapture: opts.nocapture, time: opts.time_options };
+    let test_run_opts = TestRunOpts {
+        strategy,
+        nocapture: opts.nocapture,
+        // The JSON output includes the execution time of every test, everything else only
+        // reports it with `--report-time`.
+        measure_time: opts.time_options.is_some() || opts.format == OutputFormat::Json,
+        time: opts.time_options,
+        timeout: opts.test_timeout,
+    };
 
     match testfn {
         DynBenchFn(benchfn) => {
@@ -646,6 +762,7 @@ fn run_test_inner(
             Box::new(move || __rust_begin_short_backtrace(f)),
             test_run_opts,
         ),
+        StaticCasesFn(..) => panic!("parameterized tests must be expanded with expand_test_cases"),
     }
 }
 
@@ -712,6 +829,7 @@ fn spawn_test_subprocess(
     report_time: bool,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
+    timeout: Option<Duration>,
 ) {
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
@@ -722,10 +840,17 @@ fn spawn_test_subprocess(
         if nocapture {
             command.stdout(process::Stdio::inherit());
             command.stderr(process::Stdio::inherit());
+        } else {
+            command.stdout(process::Stdio::piped());
+            command.stderr(process::Stdio::piped());
         }
 
         let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let output = match timeout {
+            Some(timeout) => output_with_timeout(&mut command, timeout),
+            None => command.output().map(|output| (output, false)),
+        };
+        let (output, killed) = match output {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
@@ -742,6 +867,15 @@ fn spawn_test_subprocess(
         formatters::write_stderr_delimiter(&mut test_output, &desc.name);
         test_output.extend_from_slice(&stderr);
 
+        if let (true, Some(timeout)) = (killed, timeout) {
+            if !test_output.ends_with(b"\n") {
+                test_output.push(b'\n');
+            }
+            write!(&mut test_output, "test did not finish within {timeout:?} and was killed")
+                .unwrap();
+            return (TrTimedFail, test_output, exec_time);
+        }
+
         let result = match (|| -> Result<TestResult, String> {
             let exit_code = get_exit_code(status)?;
             Ok(get_result_from_exit_code(&desc, exit_code, &time_opts, &exec_time))
@@ -760,6 +894,85 @@ fn spawn_test_subprocess(
     monitor_ch.send(message).unwrap();
 }
 
+/// Like `Command::output`, but kills the child process once `timeout` has elapsed. Also
+/// returns whether the child was killed.
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+) -> io::Result<(process::Output, bool)> {
+    /// How long to wait for the rest of the output once the child exited or was killed. A
+    /// process started by the child may keep the pipes open, and we must not wait for it.
+    const OUTPUT_GRACE_PERIOD: Duration = Duration::from_millis(100);
+
+    let deadline = Instant::now() + timeout;
+    let mut child = command.spawn()?;
+
+    // Read the output on separate threads, so that the child cannot block on a full pipe. They
+    // report when their pipe is closed, which normally means that the child exited.
+    let (closed_tx, closed_rx) = channel();
+    let stdout = read_pipe(child.stdout.take(), closed_tx.clone());
+    let stderr = read_pipe(child.stderr.take(), closed_tx);
+    let mut open_pipes = 2;
+    let wait_for_pipes = |open_pipes: &mut usize, until: Instant| {
+        while *open_pipes > 0 {
+            match closed_rx.recv_timeout(until.saturating_duration_since(Instant::now())) {
+                Ok(()) => *open_pipes -= 1,
+                Err(_) => break,
+            }
+        }
+    };
+    wait_for_pipes(&mut open_pipes, deadline);
+
+    // The pipes are usually closed because the child exited. Otherwise, e.g. with
+    // `--nocapture`, poll for its exit with increasing pauses.
+    let mut killed = false;
+    let mut pause = Duration::from_millis(1);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            // The child may have exited in the meantime, in which case killing it fails.
+            killed = child.kill().is_ok();
+            break child.wait()?;
+        }
+        thread::sleep(pause.min(deadline - now));
+        pause = (pause * 2).min(Duration::from_millis(100));
+    };
+    wait_for_pipes(&mut open_pipes, deadline.max(Instant::now() + OUTPUT_GRACE_PERIOD));
+
+    // Threads whose pipe is still open are left behind, and we only take what they read so far.
+    let stdout = mem::take(&mut *stdout.lock().unwrap());
+    let stderr = mem::take(&mut *stderr.lock().unwrap());
+    Ok((process::Output { status, stdout, stderr }, killed))
+}
+
+/// Reads `pipe` on a new thread into the returned buffer, and sends a message to `closed` once
+/// the pipe is closed.
+fn read_pipe(
+    pipe: Option<impl io::Read + Send + 'static>,
+    closed: Sender<()>,
+) -> Arc<Mutex<Vec<u8>>> {
+    let buf = Arc::new(Mutex::new(Vec::new()));
+    let thread_buf = buf.clone();
+    thread::spawn(move || {
+        if let Some(mut pipe) = pipe {
+            let mut chunk = [0; 4096];
+            loop {
+                match pipe.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(n) => thread_buf.lock().unwrap().extend_from_slice(&chunk[..n]),
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(_) => break,
+                }
+            }
+        }
+        let _ = closed.send(());
+    });
+    buf
+}
+
 fn run_test_in_spawned_subprocess(
     desc: TestDesc,
     testfn: Box<dyn FnOnce() -> Result<(), String> + Send>,
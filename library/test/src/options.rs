@@ -34,6 +34,17 @@ pub enum OutputFormat {
     Json,
     /// JUnit output
     Junit,
+    /// TAP version 14 output
+    Tap,
+}
+
+/// A subset of the tests, so that a test suite can be split across several machines.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TestShard {
+    /// Zero-based index of the shard to run.
+    pub index: usize,
+    /// Number of shards the tests are split into.
+    pub count: usize,
 }
 
 /// Whether ignored test should be run or not
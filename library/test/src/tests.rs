@@ -15,7 +15,9 @@
         RunIgnored,
         RunStrategy,
         ShouldPanic,
+        StaticCasesFn,
         StaticTestName,
+        TestCases,
         TestDesc,
         TestDescAndFn,
         TestOpts,
@@ -47,9 +49,11 @@ fn new() -> TestOpts {
             format: OutputFormat::Pretty,
             shuffle: false,
             shuffle_seed: None,
+            shard: None,
             test_threads: None,
             skip: vec![],
             time_options: None,
+            test_timeout: None,
             options: Options::new(),
             fail_fast: false,
         }
@@ -555,6 +559,116 @@ pub fn exclude_should_panic_option() {
     assert!(filtered.iter().all(|test| test.desc.should_panic == ShouldPanic::No));
 }
 
+#[test]
+fn parse_shard_options() {
+    let args = |extra: &[&str]| {
+        let mut args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+        args.extend(extra.iter().map(|arg| arg.to_string()));
+        args
+    };
+
+    let opts = parse_opts(&args(&["--shard-index", "1", "--shard-count", "3"])).unwrap().unwrap();
+    assert_eq!(opts.shard, Some(TestShard { index: 1, count: 3 }));
+
+    assert!(parse_opts(&args(&["--shard-index", "1"])).unwrap().is_err());
+    assert!(parse_opts(&args(&["--shard-index", "3", "--shard-count", "3"])).unwrap().is_err());
+    assert!(parse_opts(&args(&["--shard-index", "0", "--shard-count", "0"])).unwrap().is_err());
+}
+
+#[test]
+pub fn shards_partition_tests() {
+    fn tests() -> Vec<TestDescAndFn> {
+        (0..50)
+            .map(|i| TestDescAndFn {
+                desc: TestDesc {
+                    name: DynTestName(format!("module::test_{i}")),
+                    ignore: false,
+                    ignore_message: None,
+                    source_file: "",
+                    start_line: 0,
+                    start_col: 0,
+                    end_line: 0,
+                    end_col: 0,
+                    should_panic: ShouldPanic::No,
+                    compile_fail: false,
+                    no_run: false,
+                    test_type: TestType::Unknown,
+                },
+                testfn: DynTestFn(Box::new(move || Ok(()))),
+            })
+            .collect()
+    }
+
+    let count = 3;
+    let mut names = Vec::new();
+    for index in 0..count {
+        let opts = TestOpts { shard: Some(TestShard { index, count }), ..TestOpts::new() };
+        let filtered = filter_tests(&opts, tests());
+        assert!(!filtered.is_empty());
+        names.extend(filtered.into_iter().map(|test| test.desc.name.to_string()));
+    }
+
+    // Every test runs in exactly one shard.
+    names.sort();
+    let mut expected: Vec<_> = tests().into_iter().map(|test| test.desc.name.to_string()).collect();
+    expected.sort();
+    assert_eq!(names, expected);
+}
+
+#[test]
+pub fn parameterized_tests_are_expanded() {
+    fn cases(cases: &mut TestCases) {
+        for (input, expected) in [("1", 1), ("-1", -1), ("x", 0)] {
+            cases.add(input, move || assert_eq!(input.parse::<i32>().unwrap_or(0), expected));
+        }
+        cases.add("fails", || Err::<(), _>("failed"));
+    }
+    fn broken(_: &mut TestCases) {
+        panic!("no cases");
+    }
+    let test = |name, testfn| TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            ignore_message: None,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::Unknown,
+        },
+        testfn,
+    };
+
+    let tests = vec![test("parse", StaticCasesFn(cases)), test("broken", StaticCasesFn(broken))];
+    let expanded = expand_test_cases(tests);
+    let names: Vec<_> = expanded.iter().map(|test| test.desc.name.to_string()).collect();
+    assert_eq!(names, ["parse::1", "parse::-1", "parse::x", "parse::fails", "broken"]);
+
+    // Every case runs and fails on its own.
+    let results: Vec<_> = expanded
+        .into_iter()
+        .enumerate()
+        .map(|(id, test)| {
+            let (tx, rx) = channel();
+            run_test(&TestOpts::new(), false, TestId(id), test, RunStrategy::InProcess, tx);
+            rx.recv().unwrap().result == TrOk
+        })
+        .collect();
+    assert_eq!(results, [true, true, true, false, false]);
+
+    // Cases are filtered by their own names.
+    let opts = TestOpts { filters: vec!["parse::-".into()], ..TestOpts::new() };
+    let filtered =
+        filter_tests(&opts, expand_test_cases(vec![test("parse", StaticCasesFn(cases))]));
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].desc.name.to_string(), "parse::-1");
+}
+
 #[test]
 pub fn exact_filter_match() {
     fn tests() -> Vec<TestDescAndFn> {
@@ -881,6 +995,8 @@ fn should_sort_failures_before_printing_them() {
         filtered_out: 0,
         measured: 0,
         exec_time: None,
+        shuffle_seed: None,
+        aborted: false,
         metrics: MetricMap::new(),
         failures: vec![(test_b, Vec::new()), (test_a, Vec::new())],
         options: Options::new(),
@@ -934,3 +1050,69 @@ fn f(_: &mut Bencher) -> Result<(), String> {
     let result = rx.recv().unwrap().result;
     assert_eq!(result, TrFailed);
 }
+
+#[test]
+#[cfg(not(target_os = "emscripten"))]
+fn test_timeout_aborts_in_process_run() {
+    fn desc(name: &'static str, testfn: TestFn) -> TestDescAndFn {
+        TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName(name),
+                ignore: false,
+                ignore_message: None,
+                source_file: "",
+                start_line: 0,
+                start_col: 0,
+                end_line: 0,
+                end_col: 0,
+                should_panic: ShouldPanic::No,
+                compile_fail: false,
+                no_run: false,
+                test_type: TestType::Unknown,
+            },
+            testfn,
+        }
+    }
+    let tests = || {
+        vec![
+            desc(
+                "stuck",
+                DynTestFn(Box::new(|| {
+                    std::thread::sleep(Duration::from_secs(60));
+                    Ok(())
+                })),
+            ),
+            desc("never_run", DynTestFn(Box::new(|| Ok(())))),
+        ]
+    };
+
+    for test_threads in [1, 2] {
+        let (tx, rx) = channel();
+        let notify = move |event: TestEvent| {
+            match event {
+                TestEvent::TeResult(result) => tx.send(Some(result)).unwrap(),
+                TestEvent::TeAborted => tx.send(None).unwrap(),
+                _ => {}
+            }
+            Ok(())
+        };
+        let opts = TestOpts {
+            run_tests: true,
+            test_threads: Some(test_threads),
+            test_timeout: Some(Duration::from_millis(100)),
+            ..TestOpts::new()
+        };
+        run_tests(&opts, tests(), notify).unwrap();
+
+        let events: Vec<_> = rx.iter().collect();
+        // The abort is reported after the result of the stuck test.
+        assert!(events.last().unwrap().is_none());
+        let results: Vec<_> = events.into_iter().flatten().collect();
+        let stuck = results.iter().find(|result| result.desc.name.as_slice() == "stuck").unwrap();
+        assert_eq!(stuck.result, TrTimedFail);
+        if test_threads == 1 {
+            // The run is aborted before the next test starts.
+            assert_eq!(results.len(), 1);
+        }
+    }
+}
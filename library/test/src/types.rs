@@ -2,7 +2,9 @@
 
 use std::borrow::Cow;
 use std::fmt;
+use std::process::Termination;
 
+use super::assert_test_result;
 use super::bench::Bencher;
 use super::options;
 
@@ -84,6 +86,9 @@ pub enum TestFn {
     StaticBenchFn(fn(&mut Bencher) -> Result<(), String>),
     DynTestFn(Box<dyn FnOnce() -> Result<(), String> + Send>),
     DynBenchFn(Box<dyn Fn(&mut Bencher) -> Result<(), String> + Send>),
+    /// A parameterized test, which adds its cases to a [`TestCases`] before the tests are
+    /// filtered. Every case is then run as a test of its own.
+    StaticCasesFn(fn(&mut TestCases)),
 }
 
 impl TestFn {
@@ -93,6 +98,7 @@ pub fn padding(&self) -> NamePadding {
             StaticBenchFn(..) => PadOnRight,
             DynTestFn(..) => PadNone,
             DynBenchFn(..) => PadOnRight,
+            StaticCasesFn(..) => PadNone,
         }
     }
 }
@@ -104,6 +110,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             StaticBenchFn(..) => "StaticBenchFn(..)",
             DynTestFn(..) => "DynTestFn(..)",
             DynBenchFn(..) => "DynBenchFn(..)",
+            StaticCasesFn(..) => "StaticCasesFn(..)",
         })
     }
 }
@@ -171,3 +178,54 @@ pub struct TestDescAndFn {
     pub desc: TestDesc,
     pub testfn: TestFn,
 }
+
+/// The cases of a parameterized test.
+///
+/// A `#[test]` function that takes a `&mut TestCases` argument is called once before the tests
+/// are filtered. Every case it adds becomes a test of its own, named `<test>::<case>`, which is
+/// filtered, run and reported separately from the other cases.
+///
+/// ```
+/// #![feature(test)]
+/// extern crate test;
+///
+/// #[test]
+/// fn parse(cases: &mut test::TestCases) {
+///     for (input, expected) in [("1", 1), ("-1", -1), ("+1", 1)] {
+///         cases.add(input, move || assert_eq!(input.parse::<i32>().unwrap(), expected));
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct TestCases {
+    pub(crate) cases: Vec<(String, Box<dyn FnOnce() -> Result<(), String> + Send>)>,
+}
+
+impl TestCases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a case called `name`, which fails if `case` panics or reports a failure.
+    pub fn add<T, F>(&mut self, name: impl Into<String>, case: F)
+    where
+        T: Termination,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        self.cases.push((name.into(), Box::new(move || assert_test_result(case()))));
+    }
+
+    pub fn len(&self) -> usize {
+        self.cases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cases.is_empty()
+    }
+}
+
+impl fmt::Debug for TestCases {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.cases.iter().map(|(name, _)| name)).finish()
+    }
+}
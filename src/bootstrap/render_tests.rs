@@ -46,11 +46,15 @@ fn run_tests(builder: &Builder<'_>, cmd: &mut Command) -> bool {
 
     builder.verbose(&format!("running: {cmd:?}"));
 
+    // The JSON output includes the execution time of every test, but libtest only shows it with
+    // `--report-time`.
+    let report_time = cmd.get_args().any(|arg| arg == "--report-time");
+
     let mut process = cmd.spawn().unwrap();
 
     // This runs until the stdout of the child is closed, which means the child exited. We don't
     // run this on another thread since the builder is not Sync.
-    Renderer::new(process.stdout.take().unwrap(), builder).render_all();
+    Renderer::new(process.stdout.take().unwrap(), builder, report_time).render_all();
 
     let result = process.wait_with_output().unwrap();
     if !result.status.success() && builder.is_verbose() {
@@ -69,18 +73,20 @@ struct Renderer<'a> {
     failures: Vec<TestOutcome>,
     benches: Vec<BenchOutcome>,
     builder: &'a Builder<'a>,
+    report_time: bool,
     tests_count: Option<usize>,
     executed_tests: usize,
     terse_tests_in_line: usize,
 }
 
 impl<'a> Renderer<'a> {
-    fn new(stdout: ChildStdout, builder: &'a Builder<'a>) -> Self {
+    fn new(stdout: ChildStdout, builder: &'a Builder<'a>, report_time: bool) -> Self {
         Self {
             stdout: BufReader::new(stdout),
             benches: Vec::new(),
             failures: Vec::new(),
             builder,
+            report_time,
             tests_count: None,
             executed_tests: 0,
             terse_tests_in_line: 0,
@@ -137,7 +143,7 @@ fn render_test_outcome(&mut self, outcome: Outcome<'_>, test: &TestOutcome) {
     fn render_test_outcome_verbose(&self, outcome: Outcome<'_>, test: &TestOutcome) {
         print!("test {} ... ", test.name);
         self.builder.colored_stdout(|stdout| outcome.write_long(stdout)).unwrap();
-        if let Some(exec_time) = test.exec_time {
+        if let Some(exec_time) = test.exec_time.filter(|_| self.report_time) {
             print!(" ({exec_time:.2?})");
         }
         println!();
@@ -219,6 +219,20 @@ fn should_use_with_in_description(&self) -> bool {
         matches!(self, Cfg::Cfg(sym::target_feature, _))
     }
 
+    /// The configuration options that this configuration requires, written like `unix` or
+    /// `feature=serde`, as they are filtered in the search.
+    ///
+    /// Options that are only part of an `any(...)` or a `not(...)` aren't required by themselves,
+    /// so they are left out.
+    pub(crate) fn required_options(&self) -> Vec<String> {
+        match *self {
+            Cfg::Cfg(name, None) => vec![name.to_string()],
+            Cfg::Cfg(name, Some(value)) => vec![format!("{}={}", name, value)],
+            Cfg::All(ref sub_cfgs) => sub_cfgs.iter().flat_map(Cfg::required_options).collect(),
+            Cfg::True | Cfg::False | Cfg::Not(..) | Cfg::Any(..) => Vec::new(),
+        }
+    }
+
     /// Attempt to simplify this cfg by assuming that `assume` is already known to be true, will
     /// return `None` if simplification managed to completely eliminate any requirements from this
     /// `Cfg`.
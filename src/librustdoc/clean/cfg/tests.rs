@@ -474,3 +474,27 @@ fn test_simplify_with() {
         assert_eq!(foobar.simplify_with(&foobarbaz), None);
     });
 }
+
+#[test]
+fn test_required_options() {
+    create_default_session_globals_then(|| {
+        assert_eq!(word_cfg("unix").required_options(), ["unix"]);
+        assert_eq!(name_value_cfg("feature", "serde").required_options(), ["feature=serde"]);
+        assert_eq!(
+            Cfg::All(vec![word_cfg("unix"), name_value_cfg("feature", "serde")]).required_options(),
+            ["unix", "feature=serde"]
+        );
+        assert!(
+            Cfg::Any(vec![word_cfg("unix"), word_cfg("windows")]).required_options().is_empty()
+        );
+        assert!((!word_cfg("unix")).required_options().is_empty());
+        assert_eq!(
+            Cfg::All(vec![
+                name_value_cfg("feature", "std"),
+                Cfg::Any(vec![word_cfg("unix"), word_cfg("windows")]),
+            ])
+            .required_options(),
+            ["feature=std"]
+        );
+    });
+}
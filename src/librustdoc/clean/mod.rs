@@ -145,7 +145,7 @@ fn generate_item_with_correct_attrs(
         target_attrs.iter().map(|attr| (Cow::Borrowed(attr), None)).collect()
     };
 
-    let cfg = attrs.cfg(cx.tcx, &cx.cache.hidden_cfg);
+    let cfg = attrs.cfg(cx.tcx, &cx.cache.hidden_cfg, cx.cache.auto_cfg);
     let attrs = Attributes::from_ast_iter(attrs.iter().map(|(attr, did)| (&**attr, *did)), false);
 
     let name = renamed.or(Some(name));
@@ -2487,7 +2487,7 @@ fn clean_extern_crate<'tcx>(
         attrs: Box::new(Attributes::from_ast(attrs)),
         item_id: crate_def_id.into(),
         kind: Box::new(ExternCrateItem { src: orig_name }),
-        cfg: attrs.cfg(cx.tcx, &cx.cache.hidden_cfg),
+        cfg: attrs.cfg(cx.tcx, &cx.cache.hidden_cfg, cx.cache.auto_cfg),
         inline_stmt_id: Some(krate_owner_def_id),
     }]
 }
@@ -420,7 +420,7 @@ pub(crate) fn from_def_id_and_parts(
             name,
             kind,
             Box::new(Attributes::from_ast(ast_attrs)),
-            ast_attrs.cfg(cx.tcx, &cx.cache.hidden_cfg),
+            ast_attrs.cfg(cx.tcx, &cx.cache.hidden_cfg, cx.cache.auto_cfg),
         )
     }
 
@@ -923,10 +923,16 @@ pub(crate) trait AttributesExt {
 
     fn iter<'a>(&'a self) -> Self::Attributes<'a>;
 
-    fn cfg(&self, tcx: TyCtxt<'_>, hidden_cfg: &FxHashSet<Cfg>) -> Option<Arc<Cfg>> {
+    /// `auto_cfg` enables `doc_auto_cfg` even if the crate doesn't enable the feature.
+    fn cfg(
+        &self,
+        tcx: TyCtxt<'_>,
+        hidden_cfg: &FxHashSet<Cfg>,
+        auto_cfg: bool,
+    ) -> Option<Arc<Cfg>> {
         let sess = tcx.sess;
         let doc_cfg_active = tcx.features().doc_cfg;
-        let doc_auto_cfg_active = tcx.features().doc_auto_cfg;
+        let doc_auto_cfg_active = tcx.features().doc_auto_cfg || auto_cfg;
 
         fn single<T: IntoIterator>(it: T) -> Option<T::Item> {
             let mut iter = it.into_iter();
@@ -1554,7 +1560,7 @@ pub(crate) fn is_borrowed_ref(&self) -> bool {
     /// # // see types/tests.rs:is_same_generic for the real test
     /// use rustdoc::format::cache::Cache;
     /// use rustdoc::clean::types::{Type, PrimitiveType};
-    /// let cache = Cache::new(false);
+    /// let cache = Cache::new(false, false);
     /// let generic = Type::Generic(rustc_span::symbol::sym::Any);
     /// let unit = Type::Primitive(PrimitiveType::Unit);
     /// assert!(!generic.is_same(&unit, &cache));
@@ -1654,10 +1660,6 @@ pub(crate) fn is_full_generic(&self) -> bool {
         matches!(self, Type::Generic(_))
     }
 
-    pub(crate) fn is_impl_trait(&self) -> bool {
-        matches!(self, Type::ImplTrait(_))
-    }
-
     pub(crate) fn projection(&self) -> Option<(&Type, DefId, PathSegment)> {
         if let QPath(box QPathData { self_type, trait_, assoc, .. }) = self {
             Some((self_type, trait_.as_ref()?.def_id(), assoc.clone()))
@@ -26,7 +26,7 @@
 use crate::html::static_files;
 use crate::opts;
 use crate::passes::{self, Condition};
-use crate::scrape_examples::{AllCallLocations, ScrapeExamplesOptions};
+use crate::scrape_examples::{AllCallLocations, AllUsages, ScrapeExamplesOptions};
 use crate::theme;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
@@ -34,6 +34,8 @@ pub(crate) enum OutputFormat {
     Json,
     #[default]
     Html,
+    Markdown,
+    Man,
 }
 
 impl OutputFormat {
@@ -49,6 +51,8 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "json" => Ok(OutputFormat::Json),
             "html" => Ok(OutputFormat::Html),
+            "markdown" => Ok(OutputFormat::Markdown),
+            "man" => Ok(OutputFormat::Man),
             _ => Err(format!("unknown output format `{}`", value)),
         }
     }
@@ -153,6 +157,11 @@ pub(crate) struct Options {
     /// the compiler will scrape examples and not generate documentation.
     pub(crate) scrape_examples_options: Option<ScrapeExamplesOptions>,
 
+    /// The rustdoc JSON file of a previous version of the crate. If this option is Some(..) then
+    /// the input is the rustdoc JSON file of the new version, and instead of generating
+    /// documentation, rustdoc reports the changes made to the public API.
+    pub(crate) api_diff_baseline: Option<PathBuf>,
+
     /// Note: this field is duplicated in `RenderOptions` because it's useful
     /// to have it in both places.
     pub(crate) unstable_features: rustc_feature::UnstableFeatures,
@@ -199,6 +208,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("no_run", &self.no_run)
             .field("nocapture", &self.nocapture)
             .field("scrape_examples_options", &self.scrape_examples_options)
+            .field("api_diff_baseline", &self.api_diff_baseline)
             .field("unstable_features", &self.unstable_features)
             .finish()
     }
@@ -258,6 +268,9 @@ pub(crate) struct RenderOptions {
     pub(crate) document_private: bool,
     /// Document items that have `doc(hidden)`.
     pub(crate) document_hidden: bool,
+    /// Infer the `doc(cfg)` of items from their `#[cfg]` attributes, as if the crate enabled
+    /// `#![feature(doc_auto_cfg)]`.
+    pub(crate) auto_cfg: bool,
     /// If `true`, generate a JSON file in the crate folder instead of HTML redirection files.
     pub(crate) generate_redirect_map: bool,
     /// Show the memory layout of types in the docs.
@@ -270,6 +283,10 @@ pub(crate) struct RenderOptions {
     pub(crate) generate_link_to_definition: bool,
     /// Set of function-call locations to include as examples
     pub(crate) call_locations: AllCallLocations,
+    /// If `true`, item pages will show where the items are used in the crate.
+    pub(crate) show_usages: bool,
+    /// Usages of the items of the crate in other crates, such as its examples and tests.
+    pub(crate) usages: AllUsages,
     /// If `true`, Context::init will not emit shared files.
     pub(crate) no_emit_shared: bool,
 }
@@ -640,9 +657,10 @@ fn println_condition(condition: Condition) {
             Some(s) => match OutputFormat::try_from(s.as_str()) {
                 Ok(out_fmt) => {
                     if !out_fmt.is_json() && show_coverage {
-                        diag.struct_err(
-                            "html output format isn't supported for the --show-coverage option",
-                        )
+                        diag.struct_err(format!(
+                            "{} output format isn't supported for the --show-coverage option",
+                            s
+                        ))
                         .emit();
                         return Err(1);
                     }
@@ -684,13 +702,16 @@ fn println_condition(condition: Condition) {
         let enable_per_target_ignores = matches.opt_present("enable-per-target-ignores");
         let document_private = matches.opt_present("document-private-items");
         let document_hidden = matches.opt_present("document-hidden-items");
+        let auto_cfg = matches.opt_present("auto-cfg");
         let run_check = matches.opt_present("check");
         let generate_redirect_map = matches.opt_present("generate-redirect-map");
         let show_type_layout = matches.opt_present("show-type-layout");
         let nocapture = matches.opt_present("nocapture");
         let generate_link_to_definition = matches.opt_present("generate-link-to-definition");
+        let show_usages = matches.opt_present("show-usages");
         let extern_html_root_takes_precedence =
             matches.opt_present("extern-html-root-takes-precedence");
+        let api_diff_baseline = matches.opt_str("api-diff").map(PathBuf::from);
 
         if generate_link_to_definition && (show_coverage || output_format != OutputFormat::Html) {
             diag.struct_err(
@@ -702,7 +723,8 @@ fn println_condition(condition: Condition) {
 
         let scrape_examples_options = ScrapeExamplesOptions::new(matches, &diag)?;
         let with_examples = matches.opt_strs("with-examples");
-        let call_locations = crate::scrape_examples::load_call_locations(with_examples, &diag)?;
+        let (call_locations, usages) =
+            crate::scrape_examples::load_call_locations(with_examples, &diag)?;
 
         let unstable_features =
             rustc_feature::UnstableFeatures::from_environment(crate_name.as_deref());
@@ -745,6 +767,7 @@ fn println_condition(condition: Condition) {
             output_format,
             json_unused_externs,
             scrape_examples_options,
+            api_diff_baseline,
             unstable_features,
         };
         let render_options = RenderOptions {
@@ -767,12 +790,15 @@ fn println_condition(condition: Condition) {
             markdown_playground_url,
             document_private,
             document_hidden,
+            auto_cfg,
             generate_redirect_map,
             show_type_layout,
             unstable_features,
             emit,
             generate_link_to_definition,
             call_locations,
+            show_usages,
+            usages,
             no_emit_shared: false,
         };
         Ok((options, render_options))
@@ -1223,7 +1223,7 @@ fn visit_testable<F: FnOnce(&mut Self)>(
         nested: F,
     ) {
         let ast_attrs = self.tcx.hir().attrs(self.tcx.hir().local_def_id_to_hir_id(def_id));
-        if let Some(ref cfg) = ast_attrs.cfg(self.tcx, &FxHashSet::default()) {
+        if let Some(ref cfg) = ast_attrs.cfg(self.tcx, &FxHashSet::default(), false) {
             if !cfg.matches(&self.sess.parse_sess, Some(self.tcx.features())) {
                 return;
             }
@@ -121,6 +121,9 @@ pub(crate) struct Cache {
     pub(crate) intra_doc_links: FxHashMap<ItemId, FxIndexSet<clean::ItemLink>>,
     /// Cfg that have been hidden via #![doc(cfg_hide(...))]
     pub(crate) hidden_cfg: FxHashSet<clean::cfg::Cfg>,
+    /// Whether the `doc(cfg)` of items is inferred from their `#[cfg]` attributes, even if the
+    /// crate doesn't enable `#![feature(doc_auto_cfg)]`.
+    pub(crate) auto_cfg: bool,
 }
 
 /// This struct is used to wrap the `cache` and `tcx` in order to run `DocFolder`.
@@ -132,8 +135,8 @@ struct CacheBuilder<'a, 'tcx> {
 }
 
 impl Cache {
-    pub(crate) fn new(document_private: bool) -> Self {
-        Cache { document_private, ..Cache::default() }
+    pub(crate) fn new(document_private: bool, auto_cfg: bool) -> Self {
+        Cache { document_private, auto_cfg, ..Cache::default() }
     }
 
     /// Populates the `Cache` with more data. The returned `Crate` will be missing some data that was
@@ -339,6 +342,7 @@ fn fold_item(&mut self, item: clean::Item) -> Option<clean::Item> {
                             // not useful for rustdoc search.
                             self.cache.search_index.push(IndexItem {
                                 ty,
+                                defid: item.item_id.as_def_id(),
                                 name: s,
                                 path: join_with_double_colon(path),
                                 desc,
@@ -352,6 +356,7 @@ fn fold_item(&mut self, item: clean::Item) -> Option<clean::Item> {
                                 ),
                                 aliases: item.attrs.get_doc_aliases(),
                                 deprecation: item.deprecation(self.tcx),
+                                cfg: item.cfg.clone(),
                             });
                         }
                     }
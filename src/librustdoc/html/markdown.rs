@@ -174,6 +174,18 @@ fn map_line(s: &str) -> Line<'_> {
     }
 }
 
+/// Returns the lines of a Rust code block that are shown in the documentation, leaving out the
+/// hidden ones (starting with `# `) and unescaping `##`.
+pub(crate) fn visible_code_lines(code: &str) -> impl Iterator<Item = Cow<'_, str>> {
+    code.lines().filter_map(|line| map_line(line).for_html())
+}
+
+/// Returns whether a fenced code block with the given lang string contains Rust code.
+pub(crate) fn is_rust_code_block(lang_string: &str) -> bool {
+    lang_string.is_empty()
+        || LangString::parse_without_check(lang_string, ErrorCodes::No, false).rust
+}
+
 /// Convert chars from a title for an id.
 ///
 /// "Hello, world!" -> "hello-world"
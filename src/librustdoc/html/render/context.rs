@@ -20,6 +20,7 @@
     collect_spans_and_sources, scrape_examples_help,
     sidebar::print_sidebar,
     sidebar::{sidebar_module_like, Sidebar},
+    usages::{add_local_usages, sort_usages},
     AllTypes, LinkFromSrc, StylePath,
 };
 use crate::clean::{self, types::ExternalLocation, ExternalCrate};
@@ -34,7 +35,7 @@
 use crate::html::markdown::{self, plain_text_summary, ErrorCodes, IdMap};
 use crate::html::url_parts_builder::UrlPartsBuilder;
 use crate::html::{layout, sources, static_files};
-use crate::scrape_examples::AllCallLocations;
+use crate::scrape_examples::{AllCallLocations, AllUsages};
 use crate::try_err;
 use askama::Template;
 
@@ -129,6 +130,8 @@ pub(crate) struct SharedContext<'tcx> {
     pub(crate) cache: Cache,
 
     pub(crate) call_locations: AllCallLocations,
+    /// The places where the items are used, shown in their "Used by" sections.
+    pub(crate) usages: AllUsages,
 }
 
 impl SharedContext<'_> {
@@ -450,6 +453,8 @@ fn init(
             show_type_layout,
             generate_link_to_definition,
             call_locations,
+            show_usages,
+            usages,
             no_emit_shared,
             ..
         } = options;
@@ -504,12 +509,13 @@ fn init(
             }
         }
 
-        let (local_sources, matches) = collect_spans_and_sources(
+        let (local_sources, matches, local_usages) = collect_spans_and_sources(
             tcx,
             &krate,
             &src_root,
             include_sources,
             generate_link_to_definition,
+            show_usages,
         );
 
         let (sender, receiver) = channel();
@@ -534,6 +540,7 @@ fn init(
             span_correspondence_map: matches,
             cache,
             call_locations,
+            usages,
         };
 
         let dst = output;
@@ -550,13 +557,21 @@ fn init(
             types_with_notable_traits: FxHashSet::default(),
         };
 
+        let mut usages = std::mem::take(&mut Rc::get_mut(&mut cx.shared).unwrap().usages);
+        if show_usages {
+            add_local_usages(&cx, &krate, &local_usages, &mut usages);
+        }
+        sort_usages(&mut usages);
+        Rc::get_mut(&mut cx.shared).unwrap().usages = usages;
+
         if emit_crate {
             sources::render(&mut cx, &krate)?;
         }
 
         if !no_emit_shared {
             // Build our search index
-            let index = build_index(&krate, &mut Rc::get_mut(&mut cx.shared).unwrap().cache, tcx);
+            let shared = Rc::get_mut(&mut cx.shared).unwrap();
+            let index = build_index(&krate, &mut shared.cache, &shared.usages, tcx);
 
             // Write shared runs within a flock; disable thread dispatching of IO temporarily.
             Rc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(true);
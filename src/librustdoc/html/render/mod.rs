@@ -33,10 +33,11 @@
 mod sidebar;
 mod span_map;
 mod type_layout;
+mod usages;
 mod write_shared;
 
 pub(crate) use self::context::*;
-pub(crate) use self::span_map::{collect_spans_and_sources, LinkFromSrc};
+pub(crate) use self::span_map::{collect_spans_and_sources, collect_usages, LinkFromSrc};
 
 use std::collections::VecDeque;
 use std::fmt::{self, Write};
@@ -46,6 +47,7 @@
 use std::rc::Rc;
 use std::str;
 use std::string::ToString;
+use std::sync::Arc;
 
 use askama::Template;
 use rustc_attr::{ConstStability, Deprecation, StabilityLevel};
@@ -62,6 +64,7 @@
 use serde::ser::{SerializeMap, SerializeSeq};
 use serde::{Serialize, Serializer};
 
+use crate::clean::cfg::Cfg;
 use crate::clean::{self, ItemId, RenderedLink, SelfTy};
 use crate::error::Error;
 use crate::formats::cache::Cache;
@@ -79,7 +82,7 @@
 };
 use crate::html::sources;
 use crate::html::static_files::SCRAPE_EXAMPLES_HELP_MD;
-use crate::scrape_examples::{CallData, CallLocation};
+use crate::scrape_examples::{CallData, CallLocation, UsageKind};
 use crate::try_none;
 use crate::DOC_RUST_LANG_ORG_CHANNEL;
 
@@ -97,6 +100,7 @@ pub(crate) fn ensure_trailing_slash(v: &str) -> impl fmt::Display + '_ {
 #[derive(Debug)]
 pub(crate) struct IndexItem {
     pub(crate) ty: ItemType,
+    pub(crate) defid: Option<DefId>,
     pub(crate) name: Symbol,
     pub(crate) path: String,
     pub(crate) desc: String,
@@ -105,6 +109,7 @@ pub(crate) struct IndexItem {
     pub(crate) search_type: Option<IndexItemFunctionType>,
     pub(crate) aliases: Box<[Symbol]>,
     pub(crate) deprecation: Option<Deprecation>,
+    pub(crate) cfg: Option<Arc<Cfg>>,
 }
 
 /// A type used for the search index.
@@ -122,7 +127,9 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         let id = match &self.id {
             // 0 is a sentinel, everything else is one-indexed
             None => 0,
-            Some(RenderTypeId::Index(idx)) => idx + 1,
+            Some(RenderTypeId::Index(idx)) => *idx as isize + 1,
+            // type parameters are negative, so `-1` is the first one of the function
+            Some(RenderTypeId::Generic(idx)) => -(*idx as isize) - 1,
             _ => panic!("must convert render types to indexes before serializing"),
         };
         if let Some(generics) = &self.generics {
@@ -141,6 +148,9 @@ pub(crate) enum RenderTypeId {
     DefId(DefId),
     Primitive(clean::PrimitiveType),
     Index(usize),
+    /// A type parameter of the function, numbered in order of appearance. Its trait bounds are
+    /// in the `where_clause` of the [`IndexItemFunctionType`].
+    Generic(usize),
 }
 
 /// Full type of functions/methods in the search index.
@@ -148,6 +158,9 @@ pub(crate) enum RenderTypeId {
 pub(crate) struct IndexItemFunctionType {
     inputs: Vec<RenderType>,
     output: Vec<RenderType>,
+    /// The trait bounds of each type parameter of the function, including the anonymous ones
+    /// introduced by `impl Trait`.
+    where_clause: Vec<Vec<RenderType>>,
 }
 
 impl Serialize for IndexItemFunctionType {
@@ -170,10 +183,13 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
                 _ => seq.serialize_element(&self.inputs)?,
             }
             match &self.output[..] {
-                [] => {}
+                [] if self.where_clause.is_empty() => {}
                 [one] if one.generics.is_none() => seq.serialize_element(one)?,
                 _ => seq.serialize_element(&self.output)?,
             }
+            if !self.where_clause.is_empty() {
+                seq.serialize_element(&self.where_clause)?;
+            }
             seq.end()
         }
     }
@@ -532,8 +548,9 @@ fn document_full_inner<'a, 'cx: 'a>(
         };
 
         if let clean::ItemKind::FunctionItem(..) | clean::ItemKind::MethodItem(..) = kind {
-            render_call_locations(f, cx, item);
+            render_call_locations(&mut *f, cx, item);
         }
+        render_usages(f, cx, item);
         Ok(())
     })
 }
@@ -1982,7 +1999,9 @@ pub(crate) fn get_filtered_impls_for_reference<'a>(
 ) -> (Vec<&'a Impl>, Vec<&'a Impl>, Vec<&'a Impl>) {
     let def_id = it.item_id.expect_def_id();
     // If the reference primitive is somehow not defined, exit early.
-    let Some(v) = shared.cache.impls.get(&def_id) else { return (Vec::new(), Vec::new(), Vec::new()) };
+    let Some(v) = shared.cache.impls.get(&def_id) else {
+        return (Vec::new(), Vec::new(), Vec::new());
+    };
     // Since there is no "direct implementation" on the reference primitive type, we filter out
     // every implementation which isn't a trait implementation.
     let traits = v.iter().filter(|i| i.inner_impl().trait_.is_some());
@@ -2212,6 +2231,59 @@ fn collect_paths_for_type(first_ty: clean::Type, cache: &Cache) -> Vec<String> {
     out
 }
 
+const MAX_USAGES_PER_KIND: usize = 20;
+
+/// Generates the HTML for the places where an item is used, collected with `--show-usages` or
+/// scraped from other crates.
+fn render_usages<W: fmt::Write>(mut w: W, cx: &mut Context<'_>, item: &clean::Item) {
+    let Some(def_id) = item.item_id.as_def_id() else { return };
+    let shared = Rc::clone(&cx.shared);
+    let Some(usages) = shared.usages.get(&cx.tcx().def_path_hash(def_id)) else { return };
+    if usages.is_empty() {
+        return;
+    }
+
+    let id = cx.id_map.derive("used-by");
+    write!(
+        w,
+        "<details class=\"toggle used-by\" id=\"{id}\">\
+            <summary class=\"hideme\"><span>Used by ({count})</span></summary>\
+            <div class=\"docblock\">",
+        count = usages.len(),
+    )
+    .unwrap();
+    let root_path = cx.root_path();
+    // The usages are sorted by kind.
+    let mut rest = &usages[..];
+    while let Some(first) = rest.first() {
+        let (usages, tail) =
+            rest.split_at(rest.iter().take_while(|u| u.kind == first.kind).count());
+        rest = tail;
+        let title = match first.kind {
+            UsageKind::Call => "Called by",
+            UsageKind::TraitImpl => "Implemented by",
+            UsageKind::Mention => "Named by",
+            UsageKind::Doctest => "Named in the examples of",
+        };
+        write!(w, "<h5>{title}</h5><ul>").unwrap();
+        for usage in usages.iter().take(MAX_USAGES_PER_KIND) {
+            write!(
+                w,
+                "<li><a href=\"{root_path}{url}\"><code>{item}</code></a> ({location})</li>",
+                url = usage.url,
+                item = Escape(&usage.item),
+                location = Escape(&usage.location),
+            )
+            .unwrap();
+        }
+        if usages.len() > MAX_USAGES_PER_KIND {
+            write!(w, "<li>and {} more</li>", usages.len() - MAX_USAGES_PER_KIND).unwrap();
+        }
+        w.write_str("</ul>").unwrap();
+    }
+    w.write_str("</div></details>").unwrap();
+}
+
 const MAX_FULL_EXAMPLES: usize = 5;
 const NUM_VISIBLE_LINES: usize = 10;
 
@@ -363,7 +363,7 @@ fn cmp(
                     let import_item = clean::Item {
                         item_id: import_def_id.into(),
                         attrs: import_attrs,
-                        cfg: ast_attrs.cfg(cx.tcx(), &cx.cache().hidden_cfg),
+                        cfg: ast_attrs.cfg(cx.tcx(), &cx.cache().hidden_cfg, cx.cache().auto_cfg),
                         ..myitem.clone()
                     };
 
@@ -7,17 +7,21 @@
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 
 use crate::clean;
-use crate::clean::types::{FnRetTy, Function, Generics, ItemId, Type, WherePredicate};
+use crate::clean::types::{
+    FnRetTy, Function, GenericBound, Generics, ItemId, Term, Type, TypeBindingKind, WherePredicate,
+};
 use crate::formats::cache::{Cache, OrphanImplItem};
 use crate::formats::item_type::ItemType;
 use crate::html::format::join_with_double_colon;
 use crate::html::markdown::short_markdown_summary;
 use crate::html::render::{IndexItem, IndexItemFunctionType, RenderType, RenderTypeId};
+use crate::scrape_examples::AllUsages;
 
 /// Builds the search index from the collected metadata
 pub(crate) fn build_index<'tcx>(
     krate: &clean::Crate,
     cache: &mut Cache,
+    usages: &AllUsages,
     tcx: TyCtxt<'tcx>,
 ) -> String {
     let mut itemid_to_pathid = FxHashMap::default();
@@ -33,6 +37,7 @@ pub(crate) fn build_index<'tcx>(
                 .map_or_else(String::new, |s| short_markdown_summary(&s, &item.link_names(cache)));
             cache.search_index.push(IndexItem {
                 ty: item.type_(),
+                defid: item.item_id.as_def_id(),
                 name: item.name.unwrap(),
                 path: join_with_double_colon(&fqp[..fqp.len() - 1]),
                 desc,
@@ -41,6 +46,7 @@ pub(crate) fn build_index<'tcx>(
                 search_type: get_function_type_for_search(item, tcx, impl_generics.as_ref(), cache),
                 aliases: item.attrs.get_doc_aliases(),
                 deprecation: item.deprecation(tcx),
+                cfg: item.cfg.clone(),
             });
         }
     }
@@ -153,7 +159,7 @@ fn convert_render_type(
                         sym,
                     );
                 }
-                RenderTypeId::Index(_) => {}
+                RenderTypeId::Index(_) | RenderTypeId::Generic(_) => {}
             }
         }
         if let Some(search_type) = &mut item.search_type {
@@ -177,6 +183,20 @@ fn convert_render_type(
                     &mut crate_paths,
                 );
             }
+            for bounds in &mut search_type.where_clause {
+                for item in bounds.iter_mut() {
+                    convert_render_type(
+                        item,
+                        cache,
+                        &mut itemid_to_pathid,
+                        &mut primitives,
+                        &mut lastpathid,
+                        &mut crate_paths,
+                    );
+                }
+                // Bounds on traits that aren't documented are useless to the search.
+                bounds.retain(|b| b.id.is_some() || b.generics.is_some());
+            }
         }
     }
 
@@ -222,6 +242,12 @@ struct CrateData<'a> {
         //
         // To be noted: the `usize` elements are indexes to `items`.
         aliases: &'a BTreeMap<String, Vec<usize>>,
+        // The number of places where the items are used, for the items used anywhere, as pairs
+        // of an index to `items` and a count.
+        usages: Vec<(usize, usize)>,
+        // The configuration options required by the items, like `feature=serde`, and the
+        // indexes to `items` of the items requiring them.
+        cfgs: BTreeMap<String, Vec<usize>>,
     }
 
     impl<'a> Serialize for CrateData<'a> {
@@ -231,7 +257,7 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         {
             let has_aliases = !self.aliases.is_empty();
             let mut crate_data =
-                serializer.serialize_struct("CrateData", if has_aliases { 9 } else { 8 })?;
+                serializer.serialize_struct("CrateData", if has_aliases { 11 } else { 10 })?;
             crate_data.serialize_field("doc", &self.doc)?;
             crate_data.serialize_field(
                 "t",
@@ -329,6 +355,8 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
                 "p",
                 &self.paths.iter().map(|(it, s)| (it, s.as_str())).collect::<Vec<_>>(),
             )?;
+            crate_data.serialize_field("u", &self.usages)?;
+            crate_data.serialize_field("g", &self.cfgs)?;
             if has_aliases {
                 crate_data.serialize_field("a", &self.aliases)?;
             }
@@ -336,6 +364,22 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         }
     }
 
+    let usage_counts = crate_items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            let count = usages.get(&tcx.def_path_hash(item.defid?))?.len();
+            Some((index, count))
+        })
+        .collect();
+
+    let mut cfgs: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (index, item) in crate_items.iter().enumerate() {
+        for option in item.cfg.iter().flat_map(|cfg| cfg.required_options()) {
+            cfgs.entry(option).or_default().push(index);
+        }
+    }
+
     // Collect the index into a string
     format!(
         r#""{}":{}"#,
@@ -345,6 +389,8 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             items: crate_items,
             paths: crate_paths,
             aliases: &aliases,
+            usages: usage_counts,
+            cfgs,
         })
         .expect("failed serde conversion")
         // All these `replace` calls are because we have to go through JS string for JSON content.
@@ -361,7 +407,7 @@ pub(crate) fn get_function_type_for_search<'tcx>(
     impl_generics: Option<&(clean::Type, clean::Generics)>,
     cache: &Cache,
 ) -> Option<IndexItemFunctionType> {
-    let (mut inputs, mut output) = match *item.kind {
+    let (mut inputs, mut output, where_clause) = match *item.kind {
         clean::FunctionItem(ref f) => get_fn_inputs_and_outputs(f, tcx, impl_generics, cache),
         clean::MethodItem(ref m, _) => get_fn_inputs_and_outputs(m, tcx, impl_generics, cache),
         clean::TyMethodItem(ref m) => get_fn_inputs_and_outputs(m, tcx, impl_generics, cache),
@@ -371,7 +417,7 @@ pub(crate) fn get_function_type_for_search<'tcx>(
     inputs.retain(|a| a.id.is_some() || a.generics.is_some());
     output.retain(|a| a.id.is_some() || a.generics.is_some());
 
-    Some(IndexItemFunctionType { inputs, output })
+    Some(IndexItemFunctionType { inputs, output, where_clause })
 }
 
 fn get_index_type(clean_type: &clean::Type, generics: Vec<RenderType>) -> RenderType {
@@ -391,96 +437,92 @@ fn get_index_type_id(clean_type: &clean::Type) -> Option<RenderTypeId> {
         clean::BorrowedRef { ref type_, .. } | clean::RawPointer(_, ref type_) => {
             get_index_type_id(type_)
         }
-        // The type parameters are converted to generics in `add_generics_and_bounds_as_types`
+        // The type parameters are converted to generics in `simplify_fn_type`
         clean::Slice(_) => Some(RenderTypeId::Primitive(clean::PrimitiveType::Slice)),
         clean::Array(_, _) => Some(RenderTypeId::Primitive(clean::PrimitiveType::Array)),
+        // Type parameters and `impl Trait` are numbered in `simplify_fn_type`
+        clean::Generic(_) | clean::ImplTrait(_) => None,
         // Not supported yet
-        clean::BareFunction(_)
-        | clean::Generic(_)
-        | clean::ImplTrait(_)
-        | clean::Tuple(_)
-        | clean::QPath { .. }
-        | clean::Infer => None,
+        clean::BareFunction(_) | clean::Tuple(_) | clean::QPath { .. } | clean::Infer => None,
     }
 }
 
-/// The point of this function is to replace bounds with types.
+/// The type parameters of a function, as they are found in its signature: the name of the
+/// named ones, and the trait bounds of each one.
+type FnGenerics = Vec<(Option<Symbol>, Vec<RenderType>)>;
+
+/// Converts a type of a function signature to the types of the search index.
 ///
-/// i.e. `[T, U]` when you have the following bounds: `T: Display, U: Option<T>` will return
-/// `[Display, Option]`. If a type parameter has no trait bound, it is discarded.
+/// Type parameters, and the anonymous ones of `impl Trait`, are kept as
+/// [`RenderTypeId::Generic`], so that the search can tell that the `T` of
+/// `fn pop(&mut Vec<T>) -> Option<T>` is the same type in the argument and in the return type.
+/// Their trait bounds are added to `fn_generics`, and the generic arguments of trait bounds are
+/// converted the same way: `T: AsRef<U>` is the generic `T` bound by `AsRef` with the generic
+/// `U` as argument.
 ///
 /// Important note: It goes through generics recursively. So if you have
 /// `T: Option<Result<(), ()>>`, it'll go into `Option` and then into `Result`.
-#[instrument(level = "trace", skip(tcx, res, cache))]
-fn add_generics_and_bounds_as_types<'tcx, 'a>(
+#[instrument(level = "trace", skip(tcx, res, fn_generics, cache))]
+fn simplify_fn_type<'tcx, 'a>(
     self_: Option<&'a Type>,
     generics: &Generics,
     arg: &'a Type,
     tcx: TyCtxt<'tcx>,
     recurse: usize,
     res: &mut Vec<RenderType>,
+    fn_generics: &mut FnGenerics,
     cache: &Cache,
 ) {
-    fn insert_ty(res: &mut Vec<RenderType>, ty: Type, mut generics: Vec<RenderType>) {
-        // generics and impl trait are both identified by their generics,
-        // rather than a type name itself
-        let anonymous = ty.is_full_generic() || ty.is_impl_trait();
+    fn insert_ty(res: &mut Vec<RenderType>, ty: &Type, generics: Vec<RenderType>) {
         let generics_empty = generics.is_empty();
-
-        if anonymous {
-            if generics_empty {
-                // This is a type parameter with no trait bounds (for example: `T` in
-                // `fn f<T>(p: T)`, so not useful for the rustdoc search because we would end up
-                // with an empty type with an empty name. Let's just discard it.
-                return;
-            } else if generics.len() == 1 {
-                // In this case, no need to go through an intermediate state if the type parameter
-                // contains only one trait bound.
-                //
-                // For example:
-                //
-                // `fn foo<T: Display>(r: Option<T>) {}`
-                //
-                // In this case, it would contain:
-                //
-                // ```
-                // [{
-                //     name: "option",
-                //     generics: [{
-                //         name: "",
-                //         generics: [
-                //             name: "Display",
-                //             generics: []
-                //         }]
-                //     }]
-                // }]
-                // ```
-                //
-                // After removing the intermediate (unnecessary) type parameter, it'll become:
-                //
-                // ```
-                // [{
-                //     name: "option",
-                //     generics: [{
-                //         name: "Display",
-                //         generics: []
-                //     }]
-                // }]
-                // ```
-                //
-                // To be noted that it can work if there is ONLY ONE trait bound, otherwise we still
-                // need to keep it as is!
-                res.push(generics.pop().unwrap());
-                return;
-            }
-        }
-        let index_ty = get_index_type(&ty, generics);
+        let index_ty = get_index_type(ty, generics);
         if index_ty.id.is_none() && generics_empty {
             return;
         }
         res.push(index_ty);
     }
 
+    /// Adds an anonymous type parameter bound by `bounds`, or the named type parameter `name` if
+    /// it hasn't been seen yet, and returns its index.
+    fn insert_generic<'tcx>(
+        self_: Option<&Type>,
+        generics: &Generics,
+        name: Option<Symbol>,
+        bounds: &[GenericBound],
+        tcx: TyCtxt<'tcx>,
+        recurse: usize,
+        fn_generics: &mut FnGenerics,
+        cache: &Cache,
+    ) -> usize {
+        if let Some(index) = name.and_then(|name| {
+            fn_generics.iter().position(|(generic_name, _)| *generic_name == Some(name))
+        }) {
+            return index;
+        }
+        let index = fn_generics.len();
+        // The parameter is added before its bounds are converted, so that bounds mentioning it,
+        // like `T: PartialEq<T>`, refer to it rather than adding it again.
+        fn_generics.push((name, Vec::new()));
+        let mut ty_bounds = Vec::new();
+        for bound in bounds {
+            if let Some(path) = bound.get_trait_path() {
+                let ty = Type::Path { path };
+                simplify_fn_type(
+                    self_,
+                    generics,
+                    &ty,
+                    tcx,
+                    recurse + 1,
+                    &mut ty_bounds,
+                    fn_generics,
+                    cache,
+                );
+            }
+        }
+        fn_generics[index].1 = ty_bounds;
+        index
+    }
+
     if recurse >= 10 {
         // FIXME: remove this whole recurse thing when the recursion bug is fixed
         // See #59502 for the original issue.
@@ -503,126 +545,92 @@ fn insert_ty(res: &mut Vec<RenderType>, ty: Type, mut generics: Vec<RenderType>)
         arg = &*type_;
     }
 
-    // If this argument is a type parameter and not a trait bound or a type, we need to look
-    // for its bounds.
     if let Type::Generic(arg_s) = *arg {
-        // First we check if the bounds are in a `where` predicate...
-        for where_pred in generics.where_predicates.iter().filter(|g| match g {
-            WherePredicate::BoundPredicate { ty: Type::Generic(ty_s), .. } => *ty_s == arg_s,
-            _ => false,
-        }) {
-            let mut ty_generics = Vec::new();
-            let bounds = where_pred.get_bounds().unwrap_or_else(|| &[]);
-            for bound in bounds.iter() {
-                if let Some(path) = bound.get_trait_path() {
-                    let ty = Type::Path { path };
-                    add_generics_and_bounds_as_types(
-                        self_,
-                        generics,
-                        &ty,
-                        tcx,
-                        recurse + 1,
-                        &mut ty_generics,
-                        cache,
-                    );
+        // The bounds of a type parameter are either in a `where` predicate, or "inlined" like
+        // `T: Option<u32>`.
+        let bounds: Vec<GenericBound> = generics
+            .where_predicates
+            .iter()
+            .filter_map(|where_pred| match where_pred {
+                WherePredicate::BoundPredicate { ty: Type::Generic(ty_s), bounds, .. }
+                    if *ty_s == arg_s =>
+                {
+                    Some(&bounds[..])
                 }
-            }
-            insert_ty(res, arg.clone(), ty_generics);
-        }
-        // Otherwise we check if the trait bounds are "inlined" like `T: Option<u32>`...
-        if let Some(bound) = generics.params.iter().find(|g| g.is_type() && g.name == arg_s) {
-            let mut ty_generics = Vec::new();
-            for bound in bound.get_bounds().unwrap_or(&[]) {
-                if let Some(path) = bound.get_trait_path() {
-                    let ty = Type::Path { path };
-                    add_generics_and_bounds_as_types(
-                        self_,
-                        generics,
-                        &ty,
-                        tcx,
-                        recurse + 1,
-                        &mut ty_generics,
-                        cache,
-                    );
-                }
-            }
-            insert_ty(res, arg.clone(), ty_generics);
-        }
+                _ => None,
+            })
+            .chain(
+                generics
+                    .params
+                    .iter()
+                    .filter(|g| g.is_type() && g.name == arg_s)
+                    .filter_map(|g| g.get_bounds()),
+            )
+            .flatten()
+            .cloned()
+            .collect();
+        let index =
+            insert_generic(self_, generics, Some(arg_s), &bounds, tcx, recurse, fn_generics, cache);
+        res.push(RenderType { id: Some(RenderTypeId::Generic(index)), generics: None });
     } else if let Type::ImplTrait(ref bounds) = *arg {
+        let index = insert_generic(self_, generics, None, bounds, tcx, recurse, fn_generics, cache);
+        res.push(RenderType { id: Some(RenderTypeId::Generic(index)), generics: None });
+    } else if let Type::Slice(ref ty) | Type::Array(ref ty, _) = *arg {
         let mut ty_generics = Vec::new();
-        for bound in bounds {
-            if let Some(path) = bound.get_trait_path() {
-                let ty = Type::Path { path };
-                add_generics_and_bounds_as_types(
-                    self_,
-                    generics,
-                    &ty,
-                    tcx,
-                    recurse + 1,
-                    &mut ty_generics,
-                    cache,
-                );
-            }
-        }
-        insert_ty(res, arg.clone(), ty_generics);
-    } else if let Type::Slice(ref ty) = *arg {
-        let mut ty_generics = Vec::new();
-        add_generics_and_bounds_as_types(
+        simplify_fn_type(
             self_,
             generics,
             &ty,
             tcx,
             recurse + 1,
             &mut ty_generics,
+            fn_generics,
             cache,
         );
-        insert_ty(res, arg.clone(), ty_generics);
-    } else if let Type::Array(ref ty, _) = *arg {
-        let mut ty_generics = Vec::new();
-        add_generics_and_bounds_as_types(
-            self_,
-            generics,
-            &ty,
-            tcx,
-            recurse + 1,
-            &mut ty_generics,
-            cache,
-        );
-        insert_ty(res, arg.clone(), ty_generics);
+        insert_ty(res, arg, ty_generics);
     } else {
         // This is not a type parameter. So for example if we have `T, U: Option<T>`, and we're
         // looking at `Option`, we enter this "else" condition, otherwise if it's `T`, we don't.
         //
         // So in here, we can add it directly and look for its own type parameters (so for `Option`,
-        // we will look for them but not for `T`).
+        // we will look for them but not for `T`). The types of associated type bindings, like
+        // `u8` in `Iterator<Item = u8>`, are searched like type parameters.
         let mut ty_generics = Vec::new();
-        if let Some(arg_generics) = arg.generics() {
-            for gen in arg_generics.iter() {
-                add_generics_and_bounds_as_types(
-                    self_,
-                    generics,
-                    gen,
-                    tcx,
-                    recurse + 1,
-                    &mut ty_generics,
-                    cache,
-                );
-            }
+        let bindings = match arg {
+            Type::Path { path } => path.bindings().unwrap_or(&[]),
+            _ => &[],
+        };
+        let binding_types = bindings.iter().filter_map(|binding| match binding.kind {
+            TypeBindingKind::Equality { term: Term::Type(ref ty) } => Some(ty),
+            _ => None,
+        });
+        for gen in arg.generics().unwrap_or_default().into_iter().chain(binding_types) {
+            simplify_fn_type(
+                self_,
+                generics,
+                gen,
+                tcx,
+                recurse + 1,
+                &mut ty_generics,
+                fn_generics,
+                cache,
+            );
         }
-        insert_ty(res, arg.clone(), ty_generics);
+        insert_ty(res, arg, ty_generics);
     }
 }
 
-/// Return the full list of types when bounds have been resolved.
+/// Return the full list of types when bounds have been resolved, and the bounds of the type
+/// parameters.
 ///
-/// i.e. `fn foo<A: Display, B: Option<A>>(x: u32, y: B)` will return
-/// `[u32, Display, Option]`.
+/// i.e. `fn foo<A: Display, B: Option<A>>(x: u32, y: B)` will return `[u32, B]` as inputs, and
+/// `[[Option<A>], [Display]]` as the bounds of `B` and `A`, in order of appearance.
 fn get_fn_inputs_and_outputs<'tcx>(
     func: &Function,
     tcx: TyCtxt<'tcx>,
     impl_generics: Option<&(clean::Type, clean::Generics)>,
     cache: &Cache,
-) -> (Vec<RenderType>, Vec<RenderType>) {
+) -> (Vec<RenderType>, Vec<RenderType>, Vec<Vec<RenderType>>) {
     let decl = &func.decl;
 
     let combined_generics;
@@ -648,10 +656,11 @@ fn get_fn_inputs_and_outputs<'tcx>(
         (None, &func.generics)
     };
 
+    let mut fn_generics = FnGenerics::new();
     let mut all_types = Vec::new();
     for arg in decl.inputs.values.iter() {
         let mut args = Vec::new();
-        add_generics_and_bounds_as_types(self_, generics, &arg.type_, tcx, 0, &mut args, cache);
+        simplify_fn_type(self_, generics, &arg.type_, tcx, 0, &mut args, &mut fn_generics, cache);
         if !args.is_empty() {
             all_types.extend(args);
         } else {
@@ -662,13 +671,14 @@ fn get_fn_inputs_and_outputs<'tcx>(
     let mut ret_types = Vec::new();
     match decl.output {
         FnRetTy::Return(ref return_type) => {
-            add_generics_and_bounds_as_types(
+            simplify_fn_type(
                 self_,
                 generics,
                 return_type,
                 tcx,
                 0,
                 &mut ret_types,
+                &mut fn_generics,
                 cache,
             );
             if ret_types.is_empty() {
@@ -677,5 +687,6 @@ fn get_fn_inputs_and_outputs<'tcx>(
         }
         _ => {}
     };
-    (all_types, ret_types)
+    let where_clause = fn_generics.into_iter().map(|(_, bounds)| bounds).collect();
+    (all_types, ret_types, where_clause)
 }
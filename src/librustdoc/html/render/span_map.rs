@@ -1,13 +1,15 @@
 use crate::clean::{self, PrimitiveType};
+use crate::html::render::Context;
 use crate::html::sources;
+use crate::scrape_examples::{Usage, UsageKind};
 
 use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::def::{DefKind, Res};
-use rustc_hir::def_id::DefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_hir::intravisit::{self, Visitor};
-use rustc_hir::{ExprKind, HirId, Mod, Node};
+use rustc_hir::{ExprKind, HirId, Item, ItemKind, Mod, Node, QPath};
 use rustc_middle::hir::nested_filter;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{TyCtxt, TypeckResults};
 use rustc_span::hygiene::MacroKind;
 use rustc_span::{BytePos, ExpnKind, Span};
 
@@ -27,12 +29,45 @@ pub(crate) enum LinkFromSrc {
     Primitive(PrimitiveType),
 }
 
-/// This function will do at most two things:
+/// A place in the source code of the local crate where an item is used.
+#[derive(Debug)]
+pub(crate) struct LocalUsage {
+    pub(crate) kind: UsageKind,
+    /// The span of the path or method name referring to the item.
+    pub(crate) span: Span,
+    /// The item containing the usage.
+    pub(crate) owner: LocalDefId,
+}
+
+impl LocalUsage {
+    /// Links the usage to its source code page. Returns `None` if the source code of the file
+    /// containing the usage isn't rendered.
+    ///
+    /// The link is relative to the root of the documentation, so `cx` must not be rendering a
+    /// page yet.
+    pub(crate) fn resolve(&self, cx: &Context<'_>) -> Option<Usage> {
+        let tcx = cx.tcx();
+        let url = cx.href_from_span(clean::Span::new(self.span), true)?;
+        let loc = tcx.sess.source_map().lookup_char_pos(self.span.lo());
+        Some(Usage {
+            kind: self.kind,
+            item: tcx.def_path_str(self.owner.to_def_id()),
+            location: format!("{}:{}", loc.file.name.prefer_local(), loc.line),
+            url,
+        })
+    }
+}
+
+/// The usages of items found in the local crate, keyed by the used item.
+pub(crate) type LocalUsages = FxHashMap<DefId, Vec<LocalUsage>>;
+
+/// This function will do at most three things:
 ///
 /// 1. Generate a `span` correspondence map which links an item `span` to its definition `span`.
-/// 2. Collect the source code files.
+/// 2. Collect the places where items are used, if `collect_usages` is `true`.
+/// 3. Collect the source code files.
 ///
-/// It returns the `krate`, the source code files and the `span` correspondence map.
+/// It returns the source code files, the `span` correspondence map and the usages.
 ///
 /// Note about the `span` correspondence map: the keys are actually `(lo, hi)` of `span`s. We don't
 /// need the `span` context later on, only their position, so instead of keep a whole `Span`, we
@@ -43,33 +78,121 @@ pub(crate) fn collect_spans_and_sources(
     src_root: &Path,
     include_sources: bool,
     generate_link_to_definition: bool,
-) -> (FxHashMap<PathBuf, String>, FxHashMap<Span, LinkFromSrc>) {
-    let mut visitor = SpanMapVisitor { tcx, matches: FxHashMap::default() };
+    collect_usages: bool,
+) -> (FxHashMap<PathBuf, String>, FxHashMap<Span, LinkFromSrc>, LocalUsages) {
+    let mut visitor = SpanMapVisitor::new(tcx, collect_usages);
 
     if include_sources {
-        if generate_link_to_definition {
+        if generate_link_to_definition || collect_usages {
             tcx.hir().walk_toplevel_module(&mut visitor);
         }
+        if !generate_link_to_definition {
+            visitor.matches.clear();
+        }
         let sources = sources::collect_local_sources(tcx, src_root, krate);
-        (sources, visitor.matches)
+        (sources, visitor.matches, visitor.usages.unwrap_or_default())
     } else {
-        (Default::default(), Default::default())
+        (Default::default(), Default::default(), Default::default())
     }
 }
 
+/// Collects the places where items are used in the local crate, without building a span map.
+pub(crate) fn collect_usages(tcx: TyCtxt<'_>) -> LocalUsages {
+    let mut visitor = SpanMapVisitor::new(tcx, true);
+    tcx.hir().walk_toplevel_module(&mut visitor);
+    visitor.usages.unwrap_or_default()
+}
+
 struct SpanMapVisitor<'tcx> {
     pub(crate) tcx: TyCtxt<'tcx>,
     pub(crate) matches: FxHashMap<Span, LinkFromSrc>,
+    /// The usages found so far, if they are collected.
+    usages: Option<LocalUsages>,
+    /// The kind of usage of the paths that aren't just mentions, like the callee of a call.
+    path_usage_kinds: FxHashMap<Span, UsageKind>,
 }
 
 impl<'tcx> SpanMapVisitor<'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, collect_usages: bool) -> Self {
+        SpanMapVisitor {
+            tcx,
+            matches: FxHashMap::default(),
+            usages: collect_usages.then(FxHashMap::default),
+            path_usage_kinds: FxHashMap::default(),
+        }
+    }
+
+    fn typeck_results(&self, hir_id: HirId) -> &'tcx TypeckResults<'tcx> {
+        let hir = self.tcx.hir();
+        let body_id = hir.enclosing_body_owner(hir_id);
+        // FIXME: this is showing error messages for parts of the code that are not
+        // compiled (because of cfg)!
+        //
+        // See discussion in https://github.com/rust-lang/rust/issues/69426#issuecomment-1019412352
+        self.tcx.typeck_body(hir.maybe_body_owned_by(body_id).expect("a body which isn't a body"))
+    }
+
+    /// If `owner` is an implementation or one of its items, returns the type it is implemented
+    /// for.
+    fn impl_self_type(&self, owner: LocalDefId) -> Option<DefId> {
+        let owner = owner.to_def_id();
+        let impl_ = match self.tcx.def_kind(owner) {
+            DefKind::Impl { .. } => owner,
+            DefKind::AssocFn | DefKind::AssocConst | DefKind::AssocTy => {
+                self.tcx.impl_of_method(owner)?
+            }
+            _ => return None,
+        };
+        Some(self.tcx.type_of(impl_).subst_identity().ty_adt_def()?.did())
+    }
+
+    /// Records that the item `def_id` is used at `span`, in the item `owner`.
+    fn record_usage(&mut self, kind: UsageKind, def_id: DefId, span: Span, owner: LocalDefId) {
+        if self.usages.is_none() || span.from_expansion() {
+            return;
+        }
+        let def_id = match self.tcx.def_kind(def_id) {
+            // Constructors are documented with their struct or variant.
+            DefKind::Ctor(..) => self.tcx.parent(def_id),
+            DefKind::Struct
+            | DefKind::Union
+            | DefKind::Enum
+            | DefKind::Variant
+            | DefKind::Trait
+            | DefKind::TraitAlias
+            | DefKind::TyAlias
+            | DefKind::ForeignTy
+            | DefKind::Fn
+            | DefKind::Const
+            | DefKind::Static(_)
+            | DefKind::AssocFn
+            | DefKind::AssocConst
+            | DefKind::AssocTy
+            | DefKind::Macro(_) => def_id,
+            _ => return,
+        };
+        // Items referring to themselves, like recursive functions, or types named in their own
+        // implementations, aren't interesting.
+        if def_id == owner.to_def_id() || self.impl_self_type(owner) == Some(def_id) {
+            return;
+        }
+        if let Some(usages) = &mut self.usages {
+            usages.entry(def_id).or_default().push(LocalUsage { kind, span, owner });
+        }
+    }
+
     /// This function is where we handle `hir::Path` elements and add them into the "span map".
-    fn handle_path(&mut self, path: &rustc_hir::Path<'_>) {
+    fn handle_path(&mut self, path: &rustc_hir::Path<'_>, id: HirId) {
         let info = match path.res {
             // FIXME: For now, we handle `DefKind` if it's not a `DefKind::TyParam`.
             // Would be nice to support them too alongside the other `DefKind`
             // (such as primitive types!).
-            Res::Def(kind, def_id) if kind != DefKind::TyParam => Some(def_id),
+            Res::Def(kind, def_id) if kind != DefKind::TyParam => {
+                let usage_kind =
+                    self.path_usage_kinds.remove(&path.span).unwrap_or(UsageKind::Mention);
+                self.record_usage(usage_kind, def_id, path.span, id.owner.def_id);
+                Some(def_id)
+            }
             Res::Local(_) => None,
             Res::PrimTy(p) => {
                 // FIXME: Doesn't handle "path-like" primitives like arrays or tuples.
@@ -140,14 +263,33 @@ fn nested_visit_map(&mut self) -> Self::Map {
         self.tcx.hir()
     }
 
-    fn visit_path(&mut self, path: &rustc_hir::Path<'tcx>, _id: HirId) {
+    fn visit_path(&mut self, path: &rustc_hir::Path<'tcx>, id: HirId) {
         if self.handle_macro(path.span) {
             return;
         }
-        self.handle_path(path);
+        self.handle_path(path, id);
         intravisit::walk_path(self, path);
     }
 
+    fn visit_item(&mut self, item: &'tcx Item<'tcx>) {
+        match item.kind {
+            // Imports only bring items into scope, they don't use them.
+            ItemKind::Use(..) => {
+                let usages = self.usages.take();
+                intravisit::walk_item(self, item);
+                self.usages = usages;
+                return;
+            }
+            ItemKind::Impl(impl_) => {
+                if let Some(trait_ref) = impl_.of_trait {
+                    self.path_usage_kinds.insert(trait_ref.path.span, UsageKind::TraitImpl);
+                }
+            }
+            _ => {}
+        }
+        intravisit::walk_item(self, item);
+    }
+
     fn visit_mod(&mut self, m: &'tcx Mod<'tcx>, span: Span, id: HirId) {
         // To make the difference between "mod foo {}" and "mod foo;". In case we "import" another
         // file, we want to link to it. Otherwise no need to create a link.
@@ -167,14 +309,7 @@ fn visit_mod(&mut self, m: &'tcx Mod<'tcx>, span: Span, id: HirId) {
     fn visit_expr(&mut self, expr: &'tcx rustc_hir::Expr<'tcx>) {
         if let ExprKind::MethodCall(segment, ..) = expr.kind {
             let hir = self.tcx.hir();
-            let body_id = hir.enclosing_body_owner(segment.hir_id);
-            // FIXME: this is showing error messages for parts of the code that are not
-            // compiled (because of cfg)!
-            //
-            // See discussion in https://github.com/rust-lang/rust/issues/69426#issuecomment-1019412352
-            let typeck_results = self
-                .tcx
-                .typeck_body(hir.maybe_body_owned_by(body_id).expect("a body which isn't a body"));
+            let typeck_results = self.typeck_results(segment.hir_id);
             if let Some(def_id) = typeck_results.type_dependent_def_id(expr.hir_id) {
                 self.matches.insert(
                     segment.ident.span,
@@ -183,10 +318,36 @@ fn visit_expr(&mut self, expr: &'tcx rustc_hir::Expr<'tcx>) {
                         None => LinkFromSrc::External(def_id),
                     },
                 );
+                self.record_usage(
+                    UsageKind::Call,
+                    def_id,
+                    segment.ident.span,
+                    expr.hir_id.owner.def_id,
+                );
             }
         } else if self.handle_macro(expr.span) {
             // We don't want to go deeper into the macro.
             return;
+        } else if let ExprKind::Call(callee, _) = expr.kind
+            && self.usages.is_some()
+        {
+            match callee.kind {
+                ExprKind::Path(QPath::Resolved(_, path)) => {
+                    self.path_usage_kinds.insert(path.span, UsageKind::Call);
+                }
+                ExprKind::Path(QPath::TypeRelative(_, segment)) => {
+                    let typeck_results = self.typeck_results(callee.hir_id);
+                    if let Some(def_id) = typeck_results.type_dependent_def_id(callee.hir_id) {
+                        self.record_usage(
+                            UsageKind::Call,
+                            def_id,
+                            segment.ident.span,
+                            expr.hir_id.owner.def_id,
+                        );
+                    }
+                }
+                _ => {}
+            }
         }
         intravisit::walk_expr(self, expr);
     }
@@ -0,0 +1,170 @@
+//! Collects the "Used by" sections of items: the places where they are used in the crate, found
+//! with the span map, in the doctests of other items, and in the crates scraped with
+//! `--scrape-examples-output-path`.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+use rustc_lexer::TokenKind;
+use rustc_span::Symbol;
+
+use super::span_map::LocalUsages;
+use super::Context;
+use crate::clean::{self, ItemId};
+use crate::doctest::Tester;
+use crate::formats::item_type::ItemType;
+use crate::html::format::{href, join_with_double_colon};
+use crate::html::markdown::{find_testable_code, ErrorCodes, LangString};
+use crate::scrape_examples::{AllUsages, Usage, UsageKind};
+use crate::visit::DocVisitor;
+
+/// Adds the usages found in the local crate to `usages`.
+///
+/// The links of the usages are relative to the root of the documentation, so this must be called
+/// before `cx` starts rendering pages.
+pub(crate) fn add_local_usages(
+    cx: &Context<'_>,
+    krate: &clean::Crate,
+    local_usages: &LocalUsages,
+    usages: &mut AllUsages,
+) {
+    let tcx = cx.tcx();
+    for (&def_id, local_usages) in local_usages {
+        let entries = usages.entry(tcx.def_path_hash(def_id)).or_default();
+        entries.extend(local_usages.iter().filter_map(|usage| usage.resolve(cx)));
+    }
+    for (def_id, doctest_usages) in doctest_usages(cx, krate) {
+        usages.entry(tcx.def_path_hash(def_id)).or_default().extend(doctest_usages);
+    }
+}
+
+/// Sorts the usages of each item by kind, and removes duplicates, such as a function called
+/// twice on the same line.
+pub(crate) fn sort_usages(usages: &mut AllUsages) {
+    for entries in usages.values_mut() {
+        entries.sort_by(|a, b| (a.kind, &a.item, &a.location).cmp(&(b.kind, &b.item, &b.location)));
+        entries.dedup_by(|a, b| a.kind == b.kind && a.url == b.url);
+    }
+}
+
+/// Finds the items named in the doctests of other items.
+///
+/// Doctests aren't compiled while documenting, so names are matched against the identifiers of
+/// the doctests. Only the items whose name is unique in the crate are considered, to avoid
+/// attributing a `new` or a `Error` to the wrong item.
+fn doctest_usages(cx: &Context<'_>, krate: &clean::Crate) -> FxHashMap<DefId, Vec<Usage>> {
+    let cache = cx.cache();
+    let mut finder = DoctestFinder { cx, page: None, mentions: FxHashMap::default() };
+    finder.visit_crate(krate);
+    let mentions = finder.mentions;
+
+    let mut items_by_name: FxHashMap<Symbol, Vec<DefId>> = FxHashMap::default();
+    for (&def_id, (path, ty)) in &cache.paths {
+        if def_id.is_local() && !matches!(ty, ItemType::Module | ItemType::Primitive) {
+            items_by_name.entry(*path.last().unwrap()).or_default().push(def_id);
+        }
+    }
+
+    let mut usages: FxHashMap<DefId, Vec<Usage>> = FxHashMap::default();
+    for (name, items) in items_by_name {
+        let [def_id] = items[..] else { continue };
+        let Some(mentions) = mentions.get(&name) else { continue };
+        let entries = usages.entry(def_id).or_default();
+        for mention in mentions.iter().filter(|mention| mention.item != def_id) {
+            entries.push(Usage {
+                kind: UsageKind::Doctest,
+                item: mention.display.clone(),
+                location: String::from("doctest"),
+                url: mention.url.clone(),
+            });
+        }
+    }
+    usages
+}
+
+/// An item whose doctests name an identifier.
+struct Mention {
+    item: DefId,
+    /// The path of the item, like `Foo::new`.
+    display: String,
+    url: String,
+}
+
+struct DoctestFinder<'a, 'tcx> {
+    cx: &'a Context<'tcx>,
+    /// The page documenting the item being visited, and its path.
+    page: Option<(String, Vec<Symbol>)>,
+    /// The items whose doctests name an identifier, by identifier.
+    mentions: FxHashMap<Symbol, Vec<Mention>>,
+}
+
+impl DocVisitor for DoctestFinder<'_, '_> {
+    fn visit_item(&mut self, item: &clean::Item) {
+        if item.is_stripped() {
+            return;
+        }
+        let outer_page = self.page.take();
+        let own_page = item.item_id.as_def_id().and_then(|did| href(did, self.cx).ok());
+        let has_own_page = own_page.is_some();
+        self.page = match (own_page, &*item.kind) {
+            (Some((url, _, path)), _) => Some((url, path)),
+            // The items of implementations are documented on the page of their type.
+            (None, clean::ImplItem(impl_)) => impl_
+                .for_
+                .def_id(self.cx.cache())
+                .and_then(|did| href(did, self.cx).ok())
+                .map(|(url, _, path)| (url, path)),
+            (None, _) => outer_page.clone(),
+        };
+
+        if let (Some(doc), ItemId::DefId(item_did), Some((page_url, page_path))) =
+            (item.doc_value(), item.item_id, &self.page)
+        {
+            let mut tests = Doctests::default();
+            find_testable_code(&doc, &mut tests, ErrorCodes::No, false, None);
+            if !tests.identifiers.is_empty() {
+                let (url, display) = match item.name {
+                    Some(name) if !has_own_page && !matches!(*item.kind, clean::ImplItem(_)) => (
+                        format!("{}#{}.{}", page_url, item.type_(), name),
+                        format!("{}::{}", join_with_double_colon(page_path), name),
+                    ),
+                    _ => (page_url.clone(), join_with_double_colon(page_path)),
+                };
+                for identifier in tests.identifiers {
+                    self.mentions.entry(identifier).or_default().push(Mention {
+                        item: item_did,
+                        display: display.clone(),
+                        url: url.clone(),
+                    });
+                }
+            }
+        }
+
+        self.visit_item_recur(item);
+        self.page = outer_page;
+    }
+}
+
+/// The identifiers of the Rust doctests of an item.
+#[derive(Default)]
+struct Doctests {
+    identifiers: Vec<Symbol>,
+}
+
+impl Tester for Doctests {
+    fn add_test(&mut self, test: String, config: LangString, _: usize) {
+        if !config.rust {
+            return;
+        }
+        let mut pos = 0;
+        for token in rustc_lexer::tokenize(&test) {
+            let end = pos + token.len as usize;
+            if token.kind == TokenKind::Ident {
+                let identifier = Symbol::intern(&test[pos..end]);
+                if !self.identifiers.contains(&identifier) {
+                    self.identifiers.push(identifier);
+                }
+            }
+            pos = end;
+        }
+    }
+}
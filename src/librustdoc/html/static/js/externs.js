@@ -9,7 +9,7 @@ function initSearch(searchIndex){}
 /**
  * @typedef {{
  *     name: string,
- *     id: integer,
+ *     id: (null|integer),
  *     fullPath: Array<string>,
  *     pathWithoutLast: Array<string>,
  *     pathLast: string,
@@ -39,6 +39,7 @@ let ParserState;
  *     foundElems: number,
  *     literalSearch: boolean,
  *     corrections: Array<{from: string, to: integer}>,
+ *     cfgs: Array<string>,
  * }}
  */
 let ParsedQuery;
@@ -53,7 +54,8 @@ let ParsedQuery;
  *    parent: (Object|null|undefined),
  *    path: string,
  *    ty: (Number|null|number),
- *    type: (Array<?>|null)
+ *    type: (Array<?>|null),
+ *    cfgs: Array<string>,
  * }}
  */
 let Row;
@@ -91,7 +93,8 @@ let Results;
 let ResultObject;
 
 /**
- * A pair of [inputs, outputs], or 0 for null. This is stored in the search index.
+ * A pair of [inputs, outputs], or a triple of [inputs, outputs, where clause], or 0 for null.
+ * This is stored in the search index.
  * The JavaScript deserializes this into FunctionSearchType.
  *
  * Numeric IDs are *ONE-indexed* into the paths array (`p`). Zero is used as a sentinel for `null`
@@ -113,10 +116,19 @@ let ResultObject;
  * in favor of the pair of types interpretation. This is why the `(number|Array<RawFunctionType>)`
  * is used instead of `(RawFunctionType|Array<RawFunctionType>)`.
  *
+ * The where clause has the trait bounds of each type parameter of the function. It is only
+ * present if the function has type parameters, which are referred to with negative IDs: `-1` is
+ * the first entry of the where clause.
+ *
  * @typedef {(
  *     0 |
  *     [(number|Array<RawFunctionType>)] |
- *     [(number|Array<RawFunctionType>), (number|Array<RawFunctionType>)]
+ *     [(number|Array<RawFunctionType>), (number|Array<RawFunctionType>)] |
+ *     [
+ *         (number|Array<RawFunctionType>),
+ *         (number|Array<RawFunctionType>),
+ *         Array<Array<RawFunctionType>>
+ *     ]
  * )}
  */
 let RawFunctionSearchType;
@@ -126,7 +138,7 @@ let RawFunctionSearchType;
  * [path ID, generics].
  *
  * Numeric IDs are *ONE-indexed* into the paths array (`p`). Zero is used as a sentinel for `null`
- * because `null` is four bytes while `0` is one byte.
+ * because `null` is four bytes while `0` is one byte. Negative IDs are type parameters.
  *
  * @typedef {number | [number, Array<RawFunctionType>]}
  */
@@ -135,12 +147,16 @@ let RawFunctionType;
 /**
  * @typedef {{
  *     inputs: Array<FunctionType>,
- *     outputs: Array<FunctionType>,
+ *     output: Array<FunctionType>,
+ *     where_clause: Array<Array<FunctionType>>,
  * }}
  */
 let FunctionSearchType;
 
 /**
+ * The `id` of a type parameter is negative: `-1` is the first entry of the `where_clause` of the
+ * function.
+ *
  * @typedef {{
  *     id: (null|number),
  *     ty: (null|number),
@@ -947,6 +947,8 @@ function preLoadCss(cssUrl) {
              and <code>const</code>.",
             "Search functions by type signature (e.g., <code>vec -&gt; usize</code> or \
              <code>-&gt; vec</code> or <code>String, enum:Cow -&gt; bool</code>)",
+            "Single letters are type parameters, matched consistently across the signature \
+             (e.g., <code>Vec&lt;T&gt; -&gt; Option&lt;T&gt;</code>)",
             "You can look for items with an exact name by putting double quotes around \
              your request: <code>\"string\"</code>",
             "Look for items inside another one by searching for a path: <code>vec::Vec</code>",
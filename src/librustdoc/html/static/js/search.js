@@ -194,6 +194,9 @@ function editDistance(a, b, limit) {
 function initSearch(rawSearchIndex) {
     const MAX_RESULTS = 200;
     const NO_TYPE_FILTER = -1;
+    // A `cfg:` filter, like `cfg:unix` or `cfg:feature="serde"`, with its separator from the
+    // previous element of the query.
+    const CFG_FILTER = /(^|[\s,])cfg:\s*(\w+(?:\s*=\s*(?:"[\w.-]*"|[\w.-]+))?)/gi;
     /**
      *  @type {Array<Row>}
      */
@@ -473,6 +476,24 @@ function initSearch(rawSearchIndex) {
             end = parserState.pos - 1;
         } else {
             end = getIdentEndPosition(parserState);
+            // `impl Trait` and `dyn Trait` are searched like `Trait`.
+            const keyword = parserState.userQuery.slice(start, end);
+            if (keyword === "impl" || keyword === "dyn") {
+                let pos = parserState.pos;
+                while (pos < parserState.length &&
+                    isWhitespaceCharacter(parserState.userQuery[pos])
+                ) {
+                    pos += 1;
+                }
+                if (pos > parserState.pos &&
+                    pos < parserState.length &&
+                    isIdentCharacter(parserState.userQuery[pos])
+                ) {
+                    parserState.pos = pos;
+                    start = pos;
+                    end = getIdentEndPosition(parserState);
+                }
+            }
         }
         if (parserState.pos < parserState.length &&
             parserState.userQuery[parserState.pos] === "<"
@@ -735,6 +756,8 @@ function initSearch(rawSearchIndex) {
             literalSearch: false,
             error: null,
             correction: null,
+            // The configuration options required by the `cfg:` filters, like `feature=serde`.
+            cfgs: [],
         };
     }
 
@@ -779,7 +802,7 @@ function initSearch(rawSearchIndex) {
      *
      * ident = *(ALPHA / DIGIT / "_")
      * path = ident *(DOUBLE-COLON ident) [!]
-     * arg = [type-filter *WS COLON *WS] path [generics]
+     * arg = [type-filter *WS COLON *WS] [("impl" / "dyn") 1*WS] path [generics]
      * type-sep = COMMA/WS *(COMMA/WS)
      * nonempty-arg-list = *(type-sep) arg *(type-sep arg) *(type-sep)
      * generics = OPEN-ANGLE-BRACKET [ nonempty-arg-list ] *(type-sep)
@@ -791,6 +814,12 @@ function initSearch(rawSearchIndex) {
      *
      * query = *WS (exact-search / type-search) *WS
      *
+     * cfg-filter = "cfg:" *WS ident [*WS "=" *WS (ident / QUOTE *(ALPHA / DIGIT / "_" / "-"
+     *              / ".") QUOTE)]
+     *
+     * The `cfg-filter`s can be anywhere in the query, separated from the rest by COMMA or WS. They
+     * are removed before parsing it.
+     *
      * type-filter = (
      *     "mod" /
      *     "externcrate" /
@@ -851,14 +880,21 @@ function initSearch(rawSearchIndex) {
             }
         }
         userQuery = userQuery.trim();
+        // The `cfg:` filters aren't part of the grammar of the rest of the query, so they are
+        // taken out of it before parsing it.
+        const cfgs = [];
+        const typeQuery = userQuery.replace(CFG_FILTER, (_, separator, cfg) => {
+            cfgs.push(cfg.replace(/[\s"]/g, "").toLowerCase());
+            return separator;
+        }).trim();
         const parserState = {
-            length: userQuery.length,
+            length: typeQuery.length,
             pos: 0,
             // Total number of elements (includes generics).
             totalElems: 0,
             genericsElems: 0,
             typeFilter: null,
-            userQuery: userQuery.toLowerCase(),
+            userQuery: typeQuery.toLowerCase(),
         };
         let query = newParsedQuery(userQuery);
 
@@ -882,6 +918,7 @@ function initSearch(rawSearchIndex) {
             query.literalSearch = parserState.totalElems > 1;
         }
         query.foundElems = query.elems.length + query.returned.length;
+        query.cfgs = cfgs;
         return query;
     }
 
@@ -1031,6 +1068,13 @@ function initSearch(rawSearchIndex) {
                     return a - b;
                 }
 
+                // sort by number of usages (most used items come first)
+                a = aaa.item.usages;
+                b = bbb.item.usages;
+                if (a !== b) {
+                    return b - a;
+                }
+
                 // sort by item name length (longer goes later)
                 a = aaa.word.length;
                 b = bbb.word.length;
@@ -1103,131 +1147,149 @@ function initSearch(rawSearchIndex) {
         }
 
         /**
-         * This function checks if the object (`row`) generics match the given type (`elem`)
-         * generics. If there are no generics on `row`, `defaultDistance` is returned.
+         * This function checks if the types of a function (`fnTypes`) contain the elements of
+         * the query (`queryElems`), and calls `solutionCb` with each way they can be unified.
+         *
+         * This search engine implements order-agnostic unification. There should be no missing
+         * duplicates (generics have "bag semantics"), so each query element must match a
+         * different type of the function, and the function is allowed to have extras.
+         *
+         * The generics of the query (single-letter names that aren't the name of a type, like
+         * `T`) only match the generics of the function, and must do so consistently: in
+         * `Vec<T> -> Option<T>`, both `T` must be the same type parameter of the function. The
+         * mapping from the generics of the query to the generics of the function found so far is
+         * `mgens`.
          *
-         * @param {Row} row                 - The object to check.
-         * @param {QueryElement} elem       - The element from the parsed query.
+         * @param {Array<FunctionType>} fnTypes         - The types of the function to check.
+         * @param {Array<QueryElement>} queryElems      - The elements from the parsed query.
+         * @param {Array<Array<FunctionType>>} whereClause - The trait bounds of the generics
+         *                                                 of the function.
+         * @param {Map<integer, integer>|null} mgens    - The generics already unified.
+         * @param {function(Map<integer, integer>|null): boolean} solutionCb - Called with each
+         *                                                 solution. If it returns `true`, the
+         *                                                 search stops.
+         * @param {boolean} unbox                       - Whether the query elements can match
+         *                                                the generics of `fnTypes`.
          *
-         * @return {boolean}           - Returns true if a match, false otherwise.
+         * @return {boolean} - Returns true if `solutionCb` accepted a solution.
          */
-        function checkGenerics(row, elem) {
-            if (row.generics.length === 0 || elem.generics.length === 0) {
-                return false;
-            }
-            // This function is called if the names match, but we need to make
-            // sure that all generics match as well.
-            //
-            // This search engine implements order-agnostic unification. There
-            // should be no missing duplicates (generics have "bag semantics"),
-            // and the row is allowed to have extras.
-            if (elem.generics.length > 0 && row.generics.length >= elem.generics.length) {
-                const elems = new Map();
-                const addEntryToElems = function addEntryToElems(entry) {
-                    if (entry.id === -1) {
-                        // Pure generic, needs to check into it.
-                        for (const inner_entry of entry.generics) {
-                            addEntryToElems(inner_entry);
-                        }
-                        return;
-                    }
-                    let currentEntryElems;
-                    if (elems.has(entry.id)) {
-                        currentEntryElems = elems.get(entry.id);
-                    } else {
-                        currentEntryElems = [];
-                        elems.set(entry.id, currentEntryElems);
-                    }
-                    currentEntryElems.push(entry);
-                };
-                for (const entry of row.generics) {
-                    addEntryToElems(entry);
-                }
-                // We need to find the type that matches the most to remove it in order
-                // to move forward.
-                const handleGeneric = generic => {
-                    if (!elems.has(generic.id)) {
-                        return false;
-                    }
-                    const matchElems = elems.get(generic.id);
-                    const matchIdx = matchElems.findIndex(tmp_elem => {
-                        if (generic.generics.length > 0 && !checkGenerics(tmp_elem, generic)) {
-                            return false;
-                        }
-                        return typePassesFilter(generic.typeFilter, tmp_elem.ty);
-                    });
-                    if (matchIdx === -1) {
-                        return false;
-                    }
-                    matchElems.splice(matchIdx, 1);
-                    if (matchElems.length === 0) {
-                        elems.delete(generic.id);
-                    }
+        function unifyFunctionTypes(fnTypes, queryElems, whereClause, mgens, solutionCb, unbox) {
+            if (queryElems.length === 0) {
+                return solutionCb(mgens);
+            }
+            // To do the right thing with type filters, we first process the query elements that
+            // have them, so that the ones with no type filter, which can match any function type
+            // regardless of its own type, don't take the types they need.
+            let pos = queryElems.findIndex(elem => elem.typeFilter !== NO_TYPE_FILTER);
+            if (pos === -1) {
+                pos = 0;
+            }
+            const queryElem = queryElems[pos];
+            const otherQueryElems = queryElems.filter((_, i) => i !== pos);
+            for (let i = 0, len = fnTypes.length; i < len; ++i) {
+                const otherFnTypes = fnTypes.filter((_, j) => j !== i);
+                const unified = unifyFunctionType(
+                    fnTypes[i],
+                    queryElem,
+                    whereClause,
+                    mgens,
+                    newMgens => unifyFunctionTypes(
+                        otherFnTypes,
+                        otherQueryElems,
+                        whereClause,
+                        newMgens,
+                        solutionCb,
+                        unbox
+                    ),
+                    unbox
+                );
+                if (unified) {
                     return true;
-                };
-                // To do the right thing with type filters, we first process generics
-                // that have them, removing matching ones from the "bag," then do the
-                // ones with no type filter, which can match any entry regardless of its
-                // own type.
-                for (const generic of elem.generics) {
-                    if (generic.typeFilter !== -1 && !handleGeneric(generic)) {
-                        return false;
-                    }
-                }
-                for (const generic of elem.generics) {
-                    if (generic.typeFilter === -1 && !handleGeneric(generic)) {
-                        return false;
-                    }
                 }
-                return true;
             }
             return false;
         }
 
         /**
-          * This function checks if the object (`row`) matches the given type (`elem`) and its
-          * generics (if any).
-          *
-          * @param {Row} row
-          * @param {QueryElement} elem    - The element from the parsed query.
-          *
-          * @return {boolean} - Returns true if found, false otherwise.
-          */
-        function checkIfInGenerics(row, elem) {
-            for (const entry of row.generics) {
-                if (checkType(entry, elem)) {
-                    return true;
+         * This function checks if the type of a function (`fnType`) matches the given query
+         * element (`queryElem`) and its generics (if any), and calls `solutionCb` with each way
+         * they can be unified. See `unifyFunctionTypes`.
+         *
+         * @param {FunctionType} fnType
+         * @param {QueryElement} queryElem
+         * @param {Array<Array<FunctionType>>} whereClause
+         * @param {Map<integer, integer>|null} mgens
+         * @param {function(Map<integer, integer>|null): boolean} solutionCb
+         * @param {boolean} unbox
+         *
+         * @return {boolean} - Returns true if `solutionCb` accepted a solution.
+         */
+        function unifyFunctionType(fnType, queryElem, whereClause, mgens, solutionCb, unbox) {
+            const isGeneric = fnType.id !== null && fnType.id < 0;
+            if (queryElem.id !== null && queryElem.id < 0) {
+                // The generics of the query only match the generics of the function.
+                if (!isGeneric) {
+                    return false;
                 }
+                if (mgens !== null && mgens.has(queryElem.id)) {
+                    return mgens.get(queryElem.id) === fnType.id && solutionCb(mgens);
+                }
+                if (mgens !== null) {
+                    for (const fid of mgens.values()) {
+                        if (fid === fnType.id) {
+                            return false;
+                        }
+                    }
+                }
+                const newMgens = new Map(mgens);
+                newMgens.set(queryElem.id, fnType.id);
+                return solutionCb(newMgens);
+            }
+            if (isGeneric) {
+                // A type parameter matches what its trait bounds match.
+                for (const bound of whereClause[-fnType.id - 1]) {
+                    if (unifyFunctionType(bound, queryElem, whereClause, mgens, solutionCb, true)) {
+                        return true;
+                    }
+                }
+                return false;
             }
-            return false;
-        }
-
-        /**
-          * This function checks if the object (`row`) matches the given type (`elem`) and its
-          * generics (if any).
-          *
-          * @param {Row} row
-          * @param {QueryElement} elem      - The element from the parsed query.
-          *
-          * @return {boolean} - Returns true if the type matches, false otherwise.
-          */
-        function checkType(row, elem) {
-            if (row.id === -1) {
-                // This is a pure "generic" search, no need to run other checks.
-                return row.generics.length > 0 ? checkIfInGenerics(row, elem) : false;
-            }
-
-            if (row.id === elem.id && typePassesFilter(elem.typeFilter, row.ty)) {
-                if (elem.generics.length > 0) {
-                    return checkGenerics(row, elem);
+            if (fnType.id !== null &&
+                fnType.id === queryElem.id &&
+                typePassesFilter(queryElem.typeFilter, fnType.ty)
+            ) {
+                const unified = unifyFunctionTypes(
+                    fnType.generics,
+                    queryElem.generics,
+                    whereClause,
+                    mgens,
+                    solutionCb,
+                    false
+                );
+                if (unified) {
+                    return true;
                 }
-                return true;
             }
-
-            // If the current item does not match, try [unboxing] the generic.
+            // If the current item does not match, try [unboxing] the generic. Types with no
+            // name, which only group the generics they contain, are always unboxed.
             // [unboxing]:
             //   https://ndmitchell.com/downloads/slides-hoogle_fast_type_searching-09_aug_2008.pdf
-            return checkIfInGenerics(row, elem);
+            if (unbox || fnType.id === null) {
+                for (const generic of fnType.generics) {
+                    const unified = unifyFunctionType(
+                        generic,
+                        queryElem,
+                        whereClause,
+                        mgens,
+                        solutionCb,
+                        unbox
+                    );
+                    if (unified) {
+                        return true;
+                    }
+                }
+            }
+            return false;
         }
 
         /**
@@ -1235,25 +1297,16 @@ function initSearch(rawSearchIndex) {
          *
          * @param {Row} row
          * @param {QueryElement} elem    - The element from the parsed query.
-         * @param {Array<integer>} skipPositions - Do not return one of these positions.
          *
-         * @return {integer} - Returns the position of the match, or -1 if none.
+         * @return {boolean} - Returns true if found, false otherwise.
          */
-        function findArg(row, elem, skipPositions) {
-            if (row && row.type && row.type.inputs && row.type.inputs.length > 0) {
-                let i = 0;
-                for (const input of row.type.inputs) {
-                    if (skipPositions.indexOf(i) !== -1) {
-                        i += 1;
-                        continue;
-                    }
-                    if (checkType(input, elem)) {
-                        return i;
-                    }
-                    i += 1;
-                }
+        function checkArg(row, elem) {
+            if (!row.type) {
+                return false;
             }
-            return -1;
+            return unifyFunctionTypes(
+                row.type.inputs, [elem], row.type.where_clause, null, () => true, true
+            );
         }
 
         /**
@@ -1261,25 +1314,16 @@ function initSearch(rawSearchIndex) {
          *
          * @param {Row} row
          * @param {QueryElement} elem   - The element from the parsed query.
-         * @param {Array<integer>} skipPositions - Do not return one of these positions.
          *
-         * @return {integer} - Returns the position of the matching item, or -1 if none.
+         * @return {boolean} - Returns true if found, false otherwise.
          */
-        function checkReturned(row, elem, skipPositions) {
-            if (row && row.type && row.type.output.length > 0) {
-                let i = 0;
-                for (const ret_ty of row.type.output) {
-                    if (skipPositions.indexOf(i) !== -1) {
-                        i += 1;
-                        continue;
-                    }
-                    if (checkType(ret_ty, elem)) {
-                        return i;
-                    }
-                    i += 1;
-                }
+        function checkReturned(row, elem) {
+            if (!row.type) {
+                return false;
             }
-            return -1;
+            return unifyFunctionTypes(
+                row.type.output, [elem], row.type.where_clause, null, () => true, true
+            );
         }
 
         function checkPath(contains, ty, maxEditDistance) {
@@ -1351,6 +1395,8 @@ function initSearch(rawSearchIndex) {
                 type: item.type,
                 is_alias: true,
                 deprecated: item.deprecated,
+                usages: item.usages,
+                cfgs: item.cfgs,
             };
         }
 
@@ -1431,6 +1477,9 @@ function initSearch(rawSearchIndex) {
          * @param {integer} path_dist
          */
         function addIntoResults(results, fullId, id, index, dist, path_dist, maxEditDistance) {
+            if (!passesCfgFilters(searchIndex[id])) {
+                return;
+            }
             const inBounds = dist <= maxEditDistance || index !== -1;
             if (dist === 0 || (!parsedQuery.literalSearch && inBounds)) {
                 if (results.has(fullId)) {
@@ -1449,6 +1498,18 @@ function initSearch(rawSearchIndex) {
             }
         }
 
+        /**
+         * Checks that the item (`row`) requires all the configuration options of the `cfg:`
+         * filters of the query.
+         *
+         * @param {Row} row
+         *
+         * @return {boolean}
+         */
+        function passesCfgFilters(row) {
+            return parsedQuery.cfgs.every(cfg => row.cfgs.indexOf(cfg) !== -1);
+        }
+
         /**
          * This function is called in case the query is only one element (with or without generics).
          * This element will be compared to arguments' and returned values' items and also to items.
@@ -1480,14 +1541,12 @@ function initSearch(rawSearchIndex) {
             const fullId = row.id;
             const searchWord = searchWords[pos];
 
-            const in_args = findArg(row, elem, []);
-            if (in_args !== -1) {
+            if (checkArg(row, elem)) {
                 // path_dist is 0 because no parent path information is currently stored
                 // in the search index
                 addIntoResults(results_in_args, fullId, pos, -1, 0, 0, maxEditDistance);
             }
-            const returned = checkReturned(row, elem, []);
-            if (returned !== -1) {
+            if (checkReturned(row, elem)) {
                 addIntoResults(results_returned, fullId, pos, -1, 0, 0, maxEditDistance);
             }
 
@@ -1547,28 +1606,28 @@ function initSearch(rawSearchIndex) {
                 return;
             }
 
-            // If the result is too "bad", we return false and it ends this search.
-            function checkArgs(elems, callback) {
-                const skipPositions = [];
-                for (const elem of elems) {
-                    // There is more than one parameter to the query so all checks should be "exact"
-                    const position = callback(
-                        row,
-                        elem,
-                        skipPositions
-                    );
-                    if (position !== -1) {
-                        skipPositions.push(position);
-                    } else {
-                        return false;
-                    }
-                }
-                return true;
-            }
-            if (!checkArgs(parsedQuery.elems, findArg)) {
+            const fnType = row.type;
+            if (!fnType) {
                 return;
             }
-            if (!checkArgs(parsedQuery.returned, checkReturned)) {
+            // There is more than one element in the query, so all of them must match, with the
+            // generics of the query unified the same way in the arguments and returned values.
+            const unified = unifyFunctionTypes(
+                fnType.inputs,
+                parsedQuery.elems,
+                fnType.where_clause,
+                null,
+                mgens => unifyFunctionTypes(
+                    fnType.output,
+                    parsedQuery.returned,
+                    fnType.where_clause,
+                    mgens,
+                    () => true,
+                    true
+                ),
+                true
+            );
+            if (!unified) {
                 return;
             }
 
@@ -1576,7 +1635,7 @@ function initSearch(rawSearchIndex) {
         }
 
         function innerRunQuery() {
-            let elem, i, nSearchWords, in_returned, row;
+            let elem, i, nSearchWords, row;
 
             let queryLen = 0;
             for (const elem of parsedQuery.elems) {
@@ -1586,6 +1645,7 @@ function initSearch(rawSearchIndex) {
                 queryLen += elem.name.length;
             }
             const maxEditDistance = Math.floor(queryLen / 3);
+            const queryGenerics = new Map();
 
             /**
              * Convert names to ids in parsed query elements.
@@ -1595,6 +1655,10 @@ function initSearch(rawSearchIndex) {
              * If there is no matching item, but a close-enough match, this
              * function also that correction.
              *
+             * Single-letter names that aren't the name of a type, like `T`,
+             * are the generics of the query. They get negative ids, the same
+             * for every occurrence of a name.
+             *
              * See `buildTypeMapIndex` for more information.
              *
              * @param {QueryElement} elem
@@ -1602,8 +1666,13 @@ function initSearch(rawSearchIndex) {
             function convertNameToId(elem) {
                 if (typeNameIdMap.has(elem.name)) {
                     elem.id = typeNameIdMap.get(elem.name);
+                } else if (elem.name.length === 1 && elem.name >= "a" && elem.name <= "z") {
+                    if (!queryGenerics.has(elem.name)) {
+                        queryGenerics.set(elem.name, -queryGenerics.size - 1);
+                    }
+                    elem.id = queryGenerics.get(elem.name);
                 } else if (!parsedQuery.literalSearch) {
-                    let match = -1;
+                    let match = null;
                     let matchDist = maxEditDistance + 1;
                     let matchName = "";
                     for (const [name, id] of typeNameIdMap) {
@@ -1617,10 +1686,12 @@ function initSearch(rawSearchIndex) {
                             matchName = name;
                         }
                     }
-                    if (match !== -1) {
+                    if (match !== null) {
                         parsedQuery.correction = matchName;
                     }
                     elem.id = match;
+                } else {
+                    elem.id = null;
                 }
                 for (const elem2 of elem.generics) {
                     convertNameToId(elem2);
@@ -1655,12 +1726,7 @@ function initSearch(rawSearchIndex) {
                     elem = parsedQuery.returned[0];
                     for (i = 0, nSearchWords = searchWords.length; i < nSearchWords; ++i) {
                         row = searchIndex[i];
-                        in_returned = checkReturned(
-                            row,
-                            elem,
-                            []
-                        );
-                        if (in_returned !== -1) {
+                        if (checkReturned(row, elem)) {
                             addIntoResults(
                                 results_others,
                                 row.id,
@@ -1676,6 +1742,14 @@ function initSearch(rawSearchIndex) {
                 for (i = 0, nSearchWords = searchWords.length; i < nSearchWords; ++i) {
                     handleArgs(searchIndex[i], i, results_others);
                 }
+            } else if (parsedQuery.cfgs.length > 0) {
+                // Only `cfg:` filters: list all the items they let through.
+                for (i = 0, nSearchWords = searchWords.length; i < nSearchWords; ++i) {
+                    row = searchIndex[i];
+                    if (filterCrates === null || row.crate === filterCrates) {
+                        addIntoResults(results_others, row.id, i, -1, 0, 0, Number.MAX_VALUE);
+                    }
+                }
             }
         }
 
@@ -2084,7 +2158,7 @@ function initSearch(rawSearchIndex) {
 
     /**
      * Add an item to the type Name->ID map, or, if one already exists, use it.
-     * Returns the number. If name is "" or null, return -1 (pure generic).
+     * Returns the number. If name is "" or null, return null (no name).
      *
      * This is effectively string interning, so that function matching can be
      * done more quickly. Two types with the same name but different item kinds
@@ -2093,12 +2167,12 @@ function initSearch(rawSearchIndex) {
      * @param {Map<string, integer>} typeNameIdMap
      * @param {string} name
      *
-     * @returns {integer}
+     * @returns {integer|null}
      */
     function buildTypeMapIndex(typeNameIdMap, name) {
 
         if (name === "" || name === null) {
-            return -1;
+            return null;
         }
 
         if (typeNameIdMap.has(name)) {
@@ -2129,30 +2203,47 @@ function initSearch(rawSearchIndex) {
      * @return {Array<FunctionSearchType>}
      */
     function buildItemSearchTypeAll(types, lowercasePaths, typeNameIdMap) {
+        return types.map(type => buildItemSearchType(type, lowercasePaths, typeNameIdMap));
+    }
+
+    /**
+     * Converts a single type.
+     *
+     * @param {RawFunctionType} type
+     * @param {Array<{name: string, ty: number}>} lowercasePaths
+     * @param {Map<string, integer>} typeNameIdMap
+     *
+     * @return {FunctionType}
+     */
+    function buildItemSearchType(type, lowercasePaths, typeNameIdMap) {
         const PATH_INDEX_DATA = 0;
         const GENERICS_DATA = 1;
-        return types.map(type => {
-            let pathIndex, generics;
-            if (typeof type === "number") {
-                pathIndex = type;
-                generics = [];
-            } else {
-                pathIndex = type[PATH_INDEX_DATA];
-                generics = buildItemSearchTypeAll(
-                    type[GENERICS_DATA],
-                    lowercasePaths,
-                    typeNameIdMap
-                );
-            }
+        let pathIndex, generics;
+        if (typeof type === "number") {
+            pathIndex = type;
+            generics = [];
+        } else {
+            pathIndex = type[PATH_INDEX_DATA];
+            generics = buildItemSearchTypeAll(
+                type[GENERICS_DATA],
+                lowercasePaths,
+                typeNameIdMap
+            );
+        }
+        if (pathIndex <= 0) {
             return {
-                // `0` is used as a sentinel because it's fewer bytes than `null`
-                id: pathIndex === 0
-                    ? -1
-                    : buildTypeMapIndex(typeNameIdMap, lowercasePaths[pathIndex - 1].name),
-                ty: pathIndex === 0 ? null : lowercasePaths[pathIndex - 1].ty,
+                // `0` is used as a sentinel because it's fewer bytes than `null`, and negative
+                // numbers are the type parameters of the function, which keep their id
+                id: pathIndex === 0 ? null : pathIndex,
+                ty: null,
                 generics: generics,
             };
-        });
+        }
+        return {
+            id: buildTypeMapIndex(typeNameIdMap, lowercasePaths[pathIndex - 1].name),
+            ty: lowercasePaths[pathIndex - 1].ty,
+            generics: generics,
+        };
     }
 
     /**
@@ -2174,20 +2265,16 @@ function initSearch(rawSearchIndex) {
     function buildFunctionSearchType(functionSearchType, lowercasePaths, typeNameIdMap) {
         const INPUTS_DATA = 0;
         const OUTPUT_DATA = 1;
+        const WHERE_CLAUSE_DATA = 2;
         // `0` is used as a sentinel because it's fewer bytes than `null`
         if (functionSearchType === 0) {
             return null;
         }
-        let inputs, output;
+        let inputs, output, where_clause;
         if (typeof functionSearchType[INPUTS_DATA] === "number") {
-            const pathIndex = functionSearchType[INPUTS_DATA];
-            inputs = [{
-                id: pathIndex === 0
-                    ? -1
-                    : buildTypeMapIndex(typeNameIdMap, lowercasePaths[pathIndex - 1].name),
-                ty: pathIndex === 0 ? null : lowercasePaths[pathIndex - 1].ty,
-                generics: [],
-            }];
+            inputs = [
+                buildItemSearchType(functionSearchType[INPUTS_DATA], lowercasePaths, typeNameIdMap),
+            ];
         } else {
             inputs = buildItemSearchTypeAll(
                 functionSearchType[INPUTS_DATA],
@@ -2197,14 +2284,13 @@ function initSearch(rawSearchIndex) {
         }
         if (functionSearchType.length > 1) {
             if (typeof functionSearchType[OUTPUT_DATA] === "number") {
-                const pathIndex = functionSearchType[OUTPUT_DATA];
-                output = [{
-                    id: pathIndex === 0
-                        ? -1
-                        : buildTypeMapIndex(typeNameIdMap, lowercasePaths[pathIndex - 1].name),
-                    ty: pathIndex === 0 ? null : lowercasePaths[pathIndex - 1].ty,
-                    generics: [],
-                }];
+                output = [
+                    buildItemSearchType(
+                        functionSearchType[OUTPUT_DATA],
+                        lowercasePaths,
+                        typeNameIdMap
+                    ),
+                ];
             } else {
                 output = buildItemSearchTypeAll(
                     functionSearchType[OUTPUT_DATA],
@@ -2215,8 +2301,15 @@ function initSearch(rawSearchIndex) {
         } else {
             output = [];
         }
+        if (functionSearchType.length > 2) {
+            where_clause = functionSearchType[WHERE_CLAUSE_DATA].map(
+                bounds => buildItemSearchTypeAll(bounds, lowercasePaths, typeNameIdMap)
+            );
+        } else {
+            where_clause = [];
+        }
         return {
-            inputs, output,
+            inputs, output, where_clause,
         };
     }
 
@@ -2296,6 +2389,8 @@ function initSearch(rawSearchIndex) {
                 id: id,
                 normalizedName: crate.indexOf("_") === -1 ? crate : crate.replace(/_/g, ""),
                 deprecated: null,
+                usages: 0,
+                cfgs: [],
             };
             id += 1;
             searchIndex.push(crateRow);
@@ -2319,6 +2414,21 @@ function initSearch(rawSearchIndex) {
             const itemFunctionSearchTypes = crateCorpus.f;
             // an array of (Number) indices for the deprecated items
             const deprecatedItems = new Set(crateCorpus.c);
+            // an array of [(Number) item index,
+            //              (Number) number of places where the item is used]
+            // items that aren't used anywhere we know of are not present
+            const usageCounts = new Map(crateCorpus.u);
+            // an object of (String) configuration option => [Number] indices of the items
+            // requiring it
+            const itemCfgs = new Map();
+            for (const [cfg, indices] of Object.entries(crateCorpus.g)) {
+                for (const i of indices) {
+                    if (!itemCfgs.has(i)) {
+                        itemCfgs.set(i, []);
+                    }
+                    itemCfgs.get(i).push(cfg.toLowerCase());
+                }
+            }
             // an array of [(Number) item type,
             //              (String) name]
             const paths = crateCorpus.p;
@@ -2369,6 +2479,8 @@ function initSearch(rawSearchIndex) {
                     id: id,
                     normalizedName: word.indexOf("_") === -1 ? word : word.replace(/_/g, ""),
                     deprecated: deprecatedItems.has(i),
+                    usages: usageCounts.get(i) || 0,
+                    cfgs: itemCfgs.get(i) || [],
                 };
                 id += 1;
                 searchIndex.push(row);
@@ -0,0 +1,1135 @@
+//! Comparison of the public API of two versions of a crate, based on their rustdoc JSON output.
+//!
+//! Every added, removed or changed public item is classified according to the [Cargo SemVer
+//! guidelines], so that the result can tell which version bump a release requires.
+//!
+//! Items are matched up by the path they are publicly reachable at, and types are compared by
+//! their printed form, so the (unstable) item IDs of the two files never need to agree.
+//!
+//! [Cargo SemVer guidelines]: https://doc.rust-lang.org/cargo/reference/semver.html
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use rustdoc_json_types as types;
+use serde::{Deserialize, Serialize};
+
+use crate::config::OutputFormat;
+
+/// The version bump a change requires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Severity {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::Patch => "patch",
+            Severity::Minor => "minor",
+            Severity::Major => "major",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+struct Change {
+    severity: Severity,
+    path: String,
+    kind: ChangeKind,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    changes: &'a [Change],
+    required_bump: Option<Severity>,
+}
+
+/// Compares the rustdoc JSON files at `old` and `new` and prints the changes to the public API of
+/// the crate, either as plain text or, with `--output-format json`, as a JSON report.
+pub(crate) fn run(old: &Path, new: &Path, output_format: OutputFormat) -> Result<(), String> {
+    let old = load(old)?;
+    let new = load(new)?;
+    let changes = diff(&old, &new);
+    let required_bump = changes.iter().map(|change| change.severity).max();
+
+    if output_format.is_json() {
+        let report = Report { changes: &changes, required_bump };
+        let json = serde_json::to_string(&report).map_err(|e| e.to_string())?;
+        println!("{}", json);
+    } else {
+        for change in &changes {
+            println!("{}: {}", change.severity.as_str(), change.message);
+        }
+        match required_bump {
+            Some(bump) => println!("required version bump: {}", bump.as_str()),
+            None => println!("no changes to the public API"),
+        }
+    }
+    Ok(())
+}
+
+fn load(path: &Path) -> Result<types::Crate, String> {
+    #[derive(Deserialize)]
+    struct FormatVersion {
+        format_version: u32,
+    }
+
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read `{}`: {}", path.display(), e))?;
+    // Check the format version on its own first, so that files from other rustdoc versions are
+    // reported as such instead of with an obscure deserialization error.
+    let version: FormatVersion = serde_json::from_str(&contents)
+        .map_err(|e| format!("`{}` is not a rustdoc JSON file: {}", path.display(), e))?;
+    if version.format_version != types::FORMAT_VERSION {
+        return Err(format!(
+            "`{}` uses version {} of the rustdoc JSON format, but only version {} is supported",
+            path.display(),
+            version.format_version,
+            types::FORMAT_VERSION,
+        ));
+    }
+    serde_json::from_str(&contents)
+        .map_err(|e| format!("failed to parse `{}`: {}", path.display(), e))
+}
+
+fn diff(old: &types::Crate, new: &types::Crate) -> Vec<Change> {
+    let old_api = Api::new(old);
+    let new_api = Api::new(new);
+    let mut differ =
+        Differ { old: Printer { krate: old }, new: Printer { krate: new }, changes: Vec::new() };
+
+    // Paths are visited in order, so a removed or added item is always seen before its members,
+    // which are then not reported separately.
+    let mut removed_parents: Vec<&str> = Vec::new();
+    for (path, old_item) in &old_api.items {
+        match new_api.items.get(path) {
+            Some(new_item) => differ.compare(path, old_item, new_item),
+            None if is_member_of(path, &removed_parents) => {}
+            None => {
+                removed_parents.push(path);
+                differ.push(
+                    Severity::Major,
+                    path,
+                    ChangeKind::Removed,
+                    format!("{} `{}` was removed", descr(old_item), path),
+                );
+            }
+        }
+    }
+    let mut added_parents: Vec<&str> = Vec::new();
+    for (path, new_item) in &new_api.items {
+        if old_api.items.contains_key(path) || is_member_of(path, &added_parents) {
+            continue;
+        }
+        added_parents.push(path);
+        differ.added(path, new_item);
+    }
+
+    let no_impls = BTreeMap::new();
+    for (path, old_impls) in &old_api.impls {
+        if !new_api.items.contains_key(path) {
+            continue;
+        }
+        let new_impls = new_api.impls.get(path).unwrap_or(&no_impls);
+        differ.compare_impls(path, old_impls, new_impls);
+    }
+    for (path, new_impls) in &new_api.impls {
+        if old_api.items.contains_key(path) && !old_api.impls.contains_key(path) {
+            differ.compare_impls(path, &no_impls, new_impls);
+        }
+    }
+
+    let mut changes = differ.changes;
+    changes.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.cmp(b)));
+    changes
+}
+
+fn is_member_of(path: &str, parents: &[&str]) -> bool {
+    parents.iter().any(|parent| {
+        path.strip_prefix(parent)
+            .map_or(false, |rest| rest.starts_with("::") || rest.starts_with('.'))
+    })
+}
+
+/// A public item, along with the item it is a member of, if any.
+#[derive(Clone, Copy)]
+struct ApiItem<'a> {
+    item: &'a types::Item,
+    /// The enum, variant, struct, union or trait this item is a field, variant or item of.
+    parent: Option<&'a types::Item>,
+}
+
+/// A trait implementation, as seen from the implementing type.
+struct ImplInfo {
+    /// The full `impl` header, including bounds.
+    header: String,
+    /// Whether this is an implementation of an auto trait.
+    synthetic: bool,
+    /// Whether the type is promised to not implement the trait.
+    negative: bool,
+}
+
+/// The public API of a crate, keyed by the paths items are reachable at.
+struct Api<'a> {
+    krate: &'a types::Crate,
+    printer: Printer<'a>,
+    items: BTreeMap<String, ApiItem<'a>>,
+    /// The trait implementations of every type, keyed by the path of the type and then by the
+    /// implemented trait.
+    impls: BTreeMap<String, BTreeMap<String, ImplInfo>>,
+}
+
+impl<'a> Api<'a> {
+    fn new(krate: &'a types::Crate) -> Self {
+        let mut api = Api {
+            krate,
+            printer: Printer { krate },
+            items: BTreeMap::new(),
+            impls: BTreeMap::new(),
+        };
+        if let Some(root) = krate.index.get(&krate.root) {
+            let name = root.name.clone().unwrap_or_default();
+            api.add_module_items(root, &name, &mut Vec::new());
+        }
+        api
+    }
+
+    fn get(&self, id: &types::Id) -> Option<&'a types::Item> {
+        self.krate.index.get(id)
+    }
+
+    /// Adds the public items of `module` as if they were declared at `path`. `stack` holds the
+    /// modules currently being visited, to break cycles of glob imports.
+    fn add_module_items(
+        &mut self,
+        module: &'a types::Item,
+        path: &str,
+        stack: &mut Vec<&'a types::Id>,
+    ) {
+        let types::ItemEnum::Module(ref inner) = module.inner else { return };
+        if stack.contains(&&module.id) {
+            return;
+        }
+        stack.push(&module.id);
+        for id in &inner.items {
+            let Some(item) = self.get(id) else { continue };
+            if item.visibility != types::Visibility::Public {
+                continue;
+            }
+            match item.inner {
+                types::ItemEnum::Import(ref import) => {
+                    match import.id.as_ref().and_then(|id| self.get(id)) {
+                        Some(target) if import.glob => self.add_module_items(target, path, stack),
+                        Some(target) => {
+                            self.add_item(format!("{}::{}", path, import.name), target, None, stack)
+                        }
+                        None if import.glob => {}
+                        // A re-export of an item from another crate.
+                        None => {
+                            let path = format!("{}::{}", path, import.name);
+                            self.items.insert(path, ApiItem { item, parent: None });
+                        }
+                    }
+                }
+                types::ItemEnum::Impl(_) => {}
+                _ => {
+                    if let Some(ref name) = item.name {
+                        self.add_item(format!("{}::{}", path, name), item, None, stack);
+                    }
+                }
+            }
+        }
+        stack.pop();
+    }
+
+    fn add_item(
+        &mut self,
+        path: String,
+        item: &'a types::Item,
+        parent: Option<&'a types::Item>,
+        stack: &mut Vec<&'a types::Id>,
+    ) {
+        match item.inner {
+            types::ItemEnum::Module(_) => self.add_module_items(item, &path, stack),
+            types::ItemEnum::Struct(ref struct_) => {
+                match struct_.kind {
+                    types::StructKind::Unit => {}
+                    types::StructKind::Tuple(ref fields) => {
+                        self.add_fields(&path, item, fields.iter().flatten())
+                    }
+                    types::StructKind::Plain { ref fields, .. } => {
+                        self.add_fields(&path, item, fields)
+                    }
+                }
+                self.add_impls(&path, &struct_.impls, stack);
+            }
+            types::ItemEnum::Union(ref union_) => {
+                self.add_fields(&path, item, &union_.fields);
+                self.add_impls(&path, &union_.impls, stack);
+            }
+            types::ItemEnum::Enum(ref enum_) => {
+                for id in &enum_.variants {
+                    let Some(variant) = self.get(id) else { continue };
+                    let Some(ref name) = variant.name else { continue };
+                    self.add_item(format!("{}::{}", path, name), variant, Some(item), stack);
+                }
+                self.add_impls(&path, &enum_.impls, stack);
+            }
+            types::ItemEnum::Variant(ref variant) => match variant.kind {
+                types::VariantKind::Plain => {}
+                types::VariantKind::Tuple(ref fields) => {
+                    self.add_fields(&path, item, fields.iter().flatten())
+                }
+                types::VariantKind::Struct { ref fields, .. } => {
+                    self.add_fields(&path, item, fields)
+                }
+            },
+            types::ItemEnum::Trait(ref trait_) => {
+                for id in &trait_.items {
+                    let Some(trait_item) = self.get(id) else { continue };
+                    let Some(ref name) = trait_item.name else { continue };
+                    self.add_item(format!("{}::{}", path, name), trait_item, Some(item), stack);
+                }
+            }
+            _ => {}
+        }
+        self.items.insert(path, ApiItem { item, parent });
+    }
+
+    fn add_fields(
+        &mut self,
+        path: &str,
+        parent: &'a types::Item,
+        fields: impl IntoIterator<Item = &'a types::Id>,
+    ) {
+        for id in fields {
+            let Some(field) = self.get(id) else { continue };
+            let Some(ref name) = field.name else { continue };
+            // Fields of variants have the default visibility, but are as public as the enum.
+            if matches!(field.visibility, types::Visibility::Public | types::Visibility::Default) {
+                let path = format!("{}.{}", path, name);
+                self.items.insert(path, ApiItem { item: field, parent: Some(parent) });
+            }
+        }
+    }
+
+    fn add_impls(&mut self, path: &str, impls: &[types::Id], stack: &mut Vec<&'a types::Id>) {
+        for id in impls {
+            let Some(item) = self.get(id) else { continue };
+            let types::ItemEnum::Impl(ref impl_) = item.inner else { continue };
+            match impl_.trait_ {
+                None => {
+                    for id in &impl_.items {
+                        let Some(assoc) = self.get(id) else { continue };
+                        if assoc.visibility != types::Visibility::Public {
+                            continue;
+                        }
+                        let Some(ref name) = assoc.name else { continue };
+                        self.add_item(format!("{}::{}", path, name), assoc, None, stack);
+                    }
+                }
+                // Blanket implementations apply to every type satisfying their bounds, so they
+                // don't say anything about this type in particular.
+                Some(_) if impl_.blanket_impl.is_some() => {}
+                Some(ref trait_) => {
+                    let info = ImplInfo {
+                        header: self.printer.impl_header(impl_),
+                        synthetic: impl_.synthetic,
+                        negative: impl_.negative,
+                    };
+                    let key = self.printer.path(trait_);
+                    self.impls.entry(path.to_owned()).or_default().insert(key, info);
+                }
+            }
+        }
+    }
+}
+
+fn descr(item: &ApiItem<'_>) -> &'static str {
+    match item.item.inner {
+        types::ItemEnum::Module(_) => "module",
+        types::ItemEnum::ExternCrate { .. } => "extern crate",
+        types::ItemEnum::Import(_) => "re-export",
+        types::ItemEnum::Union(_) => "union",
+        types::ItemEnum::Struct(_) => "struct",
+        types::ItemEnum::StructField(_) => "field",
+        types::ItemEnum::Enum(_) => "enum",
+        types::ItemEnum::Variant(_) => "variant",
+        types::ItemEnum::Function(_) if item.parent.is_some() => "associated function",
+        types::ItemEnum::Function(_) => "function",
+        types::ItemEnum::Trait(_) => "trait",
+        types::ItemEnum::TraitAlias(_) => "trait alias",
+        types::ItemEnum::Impl(_) => "impl",
+        types::ItemEnum::Typedef(_) => "type alias",
+        types::ItemEnum::OpaqueTy(_) => "opaque type",
+        types::ItemEnum::Constant(_) => "constant",
+        types::ItemEnum::Static(_) => "static",
+        types::ItemEnum::ForeignType => "foreign type",
+        types::ItemEnum::Macro(_) => "macro",
+        types::ItemEnum::ProcMacro(_) => "proc macro",
+        types::ItemEnum::Primitive(_) => "primitive",
+        types::ItemEnum::AssocConst { .. } => "associated constant",
+        types::ItemEnum::AssocType { .. } => "associated type",
+    }
+}
+
+fn is_non_exhaustive(item: &types::Item) -> bool {
+    item.attrs.iter().any(|attr| attr == "#[non_exhaustive]")
+}
+
+struct Differ<'a> {
+    old: Printer<'a>,
+    new: Printer<'a>,
+    changes: Vec<Change>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, severity: Severity, path: &str, kind: ChangeKind, message: String) {
+        self.changes.push(Change { severity, path: path.to_owned(), kind, message });
+    }
+
+    fn changed(&mut self, severity: Severity, path: &str, message: String) {
+        self.push(severity, path, ChangeKind::Changed, message);
+    }
+
+    fn added(&mut self, path: &str, item: &ApiItem<'_>) {
+        let parent = item.parent.map(|parent| &parent.inner);
+        let breaking = match (&item.item.inner, parent) {
+            // Exhaustive enums can be matched on without a wildcard arm.
+            (types::ItemEnum::Variant(_), Some(types::ItemEnum::Enum(_))) => item
+                .parent
+                .map_or(false, |parent| !is_non_exhaustive(parent))
+                .then_some("a variant was added to an exhaustive enum"),
+            // Structs without private fields can be constructed with struct literals, and
+            // variants always can.
+            (types::ItemEnum::StructField(_), Some(types::ItemEnum::Struct(struct_))) => {
+                let has_private_fields = match struct_.kind {
+                    types::StructKind::Unit => false,
+                    types::StructKind::Tuple(ref fields) => fields.iter().any(Option::is_none),
+                    types::StructKind::Plain { fields_stripped, .. } => fields_stripped,
+                };
+                (!has_private_fields && item.parent.map_or(false, |p| !is_non_exhaustive(p)))
+                    .then_some("a public field was added to a struct without private fields")
+            }
+            (types::ItemEnum::StructField(_), Some(types::ItemEnum::Variant(_))) => item
+                .parent
+                .map_or(false, |parent| !is_non_exhaustive(parent))
+                .then_some("a field was added to an exhaustive variant"),
+            // Implementors of the trait have to provide items without a default.
+            (types::ItemEnum::Function(function), Some(types::ItemEnum::Trait(_))) => {
+                (!function.has_body).then_some("a trait method without a default was added")
+            }
+            (
+                types::ItemEnum::AssocConst { default: None, .. }
+                | types::ItemEnum::AssocType { default: None, .. },
+                Some(types::ItemEnum::Trait(_)),
+            ) => Some("a trait item without a default was added"),
+            _ => None,
+        };
+        match breaking {
+            Some(reason) => self.push(
+                Severity::Major,
+                path,
+                ChangeKind::Added,
+                format!("{} `{}` was added: {}", descr(item), path, reason),
+            ),
+            None => self.push(
+                Severity::Minor,
+                path,
+                ChangeKind::Added,
+                format!("{} `{}` was added", descr(item), path),
+            ),
+        }
+    }
+
+    fn compare(&mut self, path: &str, old: &ApiItem<'_>, new: &ApiItem<'_>) {
+        use types::ItemEnum::*;
+
+        match (&old.item.inner, &new.item.inner) {
+            (Function(old_fn), Function(new_fn)) => self.compare_fn(path, old_fn, new_fn),
+            (Struct(old_struct), Struct(new_struct)) => {
+                let old_kind = struct_kind(&old_struct.kind);
+                let new_kind = struct_kind(&new_struct.kind);
+                if old_kind != new_kind {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!("struct `{}` changed from a {} to a {}", path, old_kind, new_kind),
+                    );
+                }
+                let old_private = struct_has_private_fields(&old_struct.kind);
+                let new_private = struct_has_private_fields(&new_struct.kind);
+                if !old_private && new_private {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!("struct `{}` now has private fields", path),
+                    );
+                }
+                self.compare_generics(path, &old_struct.generics, &new_struct.generics);
+            }
+            (Union(old_union), Union(new_union)) => {
+                if !old_union.fields_stripped && new_union.fields_stripped {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!("union `{}` now has private fields", path),
+                    );
+                }
+                self.compare_generics(path, &old_union.generics, &new_union.generics);
+            }
+            (Enum(old_enum), Enum(new_enum)) => {
+                self.compare_generics(path, &old_enum.generics, &new_enum.generics);
+            }
+            (Variant(old_variant), Variant(new_variant)) => {
+                let old_kind = variant_kind(&old_variant.kind);
+                let new_kind = variant_kind(&new_variant.kind);
+                if old_kind != new_kind {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!("variant `{}` changed from a {} to a {}", path, old_kind, new_kind),
+                    );
+                }
+            }
+            (StructField(old_ty), StructField(new_ty)) => {
+                self.compare_types(path, "field", "type", old_ty, new_ty);
+            }
+            (Trait(old_trait), Trait(new_trait)) => {
+                if !old_trait.is_unsafe && new_trait.is_unsafe {
+                    self.changed(Severity::Major, path, format!("trait `{}` is now unsafe", path));
+                } else if old_trait.is_unsafe && !new_trait.is_unsafe {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!("trait `{}` is no longer unsafe", path),
+                    );
+                }
+                let old_bounds = self.old.bounds(&old_trait.bounds);
+                let new_bounds = self.new.bounds(&new_trait.bounds);
+                if old_bounds != new_bounds {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!(
+                            "the supertraits of trait `{}` changed from `{}` to `{}`",
+                            path, old_bounds, new_bounds
+                        ),
+                    );
+                }
+                self.compare_generics(path, &old_trait.generics, &new_trait.generics);
+            }
+            (Typedef(old_typedef), Typedef(new_typedef)) => {
+                self.compare_types(
+                    path,
+                    "type alias",
+                    "type",
+                    &old_typedef.type_,
+                    &new_typedef.type_,
+                );
+                self.compare_generics(path, &old_typedef.generics, &new_typedef.generics);
+            }
+            (Constant(old_const), Constant(new_const)) => {
+                self.compare_types(path, "constant", "type", &old_const.type_, &new_const.type_);
+            }
+            (Static(old_static), Static(new_static)) => {
+                self.compare_types(path, "static", "type", &old_static.type_, &new_static.type_);
+                if old_static.mutable != new_static.mutable {
+                    let now = if new_static.mutable { "mutable" } else { "immutable" };
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!("static `{}` is now {}", path, now),
+                    );
+                }
+            }
+            (
+                AssocConst { type_: old_ty, default: old_default },
+                AssocConst { type_: new_ty, default: new_default },
+            ) => {
+                self.compare_types(path, "associated constant", "type", old_ty, new_ty);
+                self.compare_default(path, old, old_default.is_some(), new_default.is_some());
+            }
+            (
+                AssocType { generics: old_generics, bounds: old_bounds, default: old_default },
+                AssocType { generics: new_generics, bounds: new_bounds, default: new_default },
+            ) => {
+                let old_bounds = self.old.bounds(old_bounds);
+                let new_bounds = self.new.bounds(new_bounds);
+                if old_bounds != new_bounds {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!(
+                            "the bounds of associated type `{}` changed from `{}` to `{}`",
+                            path, old_bounds, new_bounds
+                        ),
+                    );
+                }
+                self.compare_generics(path, old_generics, new_generics);
+                self.compare_default(path, old, old_default.is_some(), new_default.is_some());
+            }
+            (Import(old_import), Import(new_import)) => {
+                if old_import.source != new_import.source {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!(
+                            "`{}` now re-exports `{}` instead of `{}`",
+                            path, new_import.source, old_import.source
+                        ),
+                    );
+                }
+            }
+            (Macro(old_macro), Macro(new_macro)) => {
+                if old_macro != new_macro {
+                    self.changed(
+                        Severity::Minor,
+                        path,
+                        format!("the definition of macro `{}` changed", path),
+                    );
+                }
+            }
+            (old_inner, new_inner) => {
+                if std::mem::discriminant(old_inner) != std::mem::discriminant(new_inner) {
+                    self.changed(
+                        Severity::Major,
+                        path,
+                        format!("`{}` changed from a {} to a {}", path, descr(old), descr(new)),
+                    );
+                }
+            }
+        }
+
+        let old_non_exhaustive = is_non_exhaustive(old.item);
+        let new_non_exhaustive = is_non_exhaustive(new.item);
+        if !old_non_exhaustive && new_non_exhaustive {
+            self.changed(
+                Severity::Major,
+                path,
+                format!("{} `{}` is now `#[non_exhaustive]`", descr(new), path),
+            );
+        } else if old_non_exhaustive && !new_non_exhaustive {
+            self.changed(
+                Severity::Minor,
+                path,
+                format!("{} `{}` is no longer `#[non_exhaustive]`", descr(new), path),
+            );
+        }
+        if old.item.deprecation.is_none() && new.item.deprecation.is_some() {
+            self.changed(
+                Severity::Minor,
+                path,
+                format!("{} `{}` was deprecated", descr(new), path),
+            );
+        }
+        if old.item.docs != new.item.docs {
+            self.changed(
+                Severity::Patch,
+                path,
+                format!("the documentation of {} `{}` changed", descr(new), path),
+            );
+        }
+    }
+
+    fn compare_fn(&mut self, path: &str, old: &types::Function, new: &types::Function) {
+        let old_sig = self.old.fn_decl(&old.decl);
+        let new_sig = self.new.fn_decl(&new.decl);
+        if old_sig != new_sig {
+            self.changed(
+                Severity::Major,
+                path,
+                format!("the signature of `{}` changed from `{}` to `{}`", path, old_sig, new_sig),
+            );
+        }
+        if !old.header.unsafe_ && new.header.unsafe_ {
+            self.changed(Severity::Major, path, format!("function `{}` is now unsafe", path));
+        } else if old.header.unsafe_ && !new.header.unsafe_ {
+            self.changed(Severity::Minor, path, format!("function `{}` is no longer unsafe", path));
+        }
+        if old.header.const_ && !new.header.const_ {
+            self.changed(Severity::Major, path, format!("function `{}` is no longer const", path));
+        } else if !old.header.const_ && new.header.const_ {
+            self.changed(Severity::Minor, path, format!("function `{}` is now const", path));
+        }
+        if old.header.async_ != new.header.async_ {
+            let now = if new.header.async_ { "now" } else { "no longer" };
+            self.changed(Severity::Major, path, format!("function `{}` is {} async", path, now));
+        }
+        let old_abi = abi(&old.header.abi);
+        let new_abi = abi(&new.header.abi);
+        if old_abi != new_abi {
+            self.changed(
+                Severity::Major,
+                path,
+                format!("the ABI of `{}` changed from `{}` to `{}`", path, old_abi, new_abi),
+            );
+        }
+        self.compare_generics(path, &old.generics, &new.generics);
+    }
+
+    fn compare_types(
+        &mut self,
+        path: &str,
+        descr: &str,
+        what: &str,
+        old: &types::Type,
+        new: &types::Type,
+    ) {
+        let old_ty = self.old.ty(old);
+        let new_ty = self.new.ty(new);
+        if old_ty != new_ty {
+            self.changed(
+                Severity::Major,
+                path,
+                format!(
+                    "the {} of {} `{}` changed from `{}` to `{}`",
+                    what, descr, path, old_ty, new_ty
+                ),
+            );
+        }
+    }
+
+    fn compare_default(&mut self, path: &str, item: &ApiItem<'_>, old: bool, new: bool) {
+        if old && !new {
+            self.changed(
+                Severity::Major,
+                path,
+                format!("{} `{}` no longer has a default", descr(item), path),
+            );
+        } else if !old && new {
+            self.changed(
+                Severity::Minor,
+                path,
+                format!("{} `{}` now has a default", descr(item), path),
+            );
+        }
+    }
+
+    fn compare_generics(&mut self, path: &str, old: &types::Generics, new: &types::Generics) {
+        let old_params = self.old.generic_params(&old.params);
+        let new_params = self.new.generic_params(&new.params);
+        if old_params != new_params {
+            // Adding type parameters with a default at the end is fine.
+            let only_defaulted_added = new.params.len() > old.params.len()
+                && old_params == new_params[..old_params.len()]
+                && new.params[old.params.len()..].iter().all(|param| {
+                    matches!(
+                        param.kind,
+                        types::GenericParamDefKind::Type { default: Some(_), .. }
+                            | types::GenericParamDefKind::Const { default: Some(_), .. }
+                    )
+                });
+            let severity = if only_defaulted_added { Severity::Minor } else { Severity::Major };
+            self.changed(
+                severity,
+                path,
+                format!(
+                    "the generic parameters of `{}` changed from `<{}>` to `<{}>`",
+                    path,
+                    old_params.join(", "),
+                    new_params.join(", ")
+                ),
+            );
+        }
+
+        let old_bounds = self.old.generics_bounds(old);
+        let new_bounds = self.new.generics_bounds(new);
+        for bound in new_bounds.difference(&old_bounds) {
+            self.changed(Severity::Major, path, format!("`{}` now requires `{}`", path, bound));
+        }
+        for bound in old_bounds.difference(&new_bounds) {
+            self.changed(
+                Severity::Minor,
+                path,
+                format!("`{}` no longer requires `{}`", path, bound),
+            );
+        }
+    }
+
+    fn compare_impls(
+        &mut self,
+        path: &str,
+        old: &BTreeMap<String, ImplInfo>,
+        new: &BTreeMap<String, ImplInfo>,
+    ) {
+        let auto = |info: &ImplInfo| if info.synthetic { "auto trait " } else { "" };
+        let implements = |info: Option<&ImplInfo>| info.map_or(false, |info| !info.negative);
+        for (trait_, old_impl) in old {
+            let new_impl = new.get(trait_);
+            if implements(Some(old_impl)) && !implements(new_impl) {
+                self.changed(
+                    Severity::Major,
+                    path,
+                    format!("`{}` no longer implements {}`{}`", path, auto(old_impl), trait_),
+                );
+            } else if let Some(new_impl) = new_impl
+                && !new_impl.negative
+                && new_impl.header != old_impl.header
+            {
+                self.changed(
+                    Severity::Major,
+                    path,
+                    format!(
+                        "the implementation of {}`{}` for `{}` changed from `{}` to `{}`",
+                        auto(old_impl),
+                        trait_,
+                        path,
+                        old_impl.header,
+                        new_impl.header
+                    ),
+                );
+            }
+        }
+        for (trait_, new_impl) in new {
+            if implements(Some(new_impl)) && !implements(old.get(trait_)) {
+                self.changed(
+                    Severity::Minor,
+                    path,
+                    format!("`{}` now implements {}`{}`", path, auto(new_impl), trait_),
+                );
+            }
+        }
+    }
+}
+
+fn struct_kind(kind: &types::StructKind) -> &'static str {
+    match kind {
+        types::StructKind::Unit => "unit struct",
+        types::StructKind::Tuple(_) => "tuple struct",
+        types::StructKind::Plain { .. } => "struct with named fields",
+    }
+}
+
+fn struct_has_private_fields(kind: &types::StructKind) -> bool {
+    match kind {
+        types::StructKind::Unit => false,
+        types::StructKind::Tuple(fields) => fields.iter().any(Option::is_none),
+        types::StructKind::Plain { fields_stripped, .. } => *fields_stripped,
+    }
+}
+
+fn variant_kind(kind: &types::VariantKind) -> &'static str {
+    match kind {
+        types::VariantKind::Plain => "unit variant",
+        types::VariantKind::Tuple(_) => "tuple variant",
+        types::VariantKind::Struct { .. } => "struct variant",
+    }
+}
+
+fn abi(abi: &types::Abi) -> String {
+    let (name, unwind) = match abi {
+        types::Abi::Rust => return String::from("Rust"),
+        types::Abi::C { unwind } => ("C", unwind),
+        types::Abi::Cdecl { unwind } => ("cdecl", unwind),
+        types::Abi::Stdcall { unwind } => ("stdcall", unwind),
+        types::Abi::Fastcall { unwind } => ("fastcall", unwind),
+        types::Abi::Aapcs { unwind } => ("aapcs", unwind),
+        types::Abi::Win64 { unwind } => ("win64", unwind),
+        types::Abi::SysV64 { unwind } => ("sysv64", unwind),
+        types::Abi::System { unwind } => ("system", unwind),
+        types::Abi::Other(name) => return name.clone(),
+    };
+    if *unwind { format!("{}-unwind", name) } else { name.to_owned() }
+}
+
+/// Prints types and bounds as Rust source, with paths resolved to the canonical path of the item
+/// they refer to.
+#[derive(Clone, Copy)]
+struct Printer<'a> {
+    krate: &'a types::Crate,
+}
+
+impl<'a> Printer<'a> {
+    fn path(&self, path: &types::Path) -> String {
+        let mut s = match self.krate.paths.get(&path.id) {
+            Some(summary) => summary.path.join("::"),
+            None => path.name.clone(),
+        };
+        if let Some(ref args) = path.args {
+            self.write_generic_args(&mut s, args);
+        }
+        s
+    }
+
+    fn write_generic_args(&self, s: &mut String, args: &types::GenericArgs) {
+        match args {
+            types::GenericArgs::AngleBracketed { args, bindings } => {
+                if args.is_empty() && bindings.is_empty() {
+                    return;
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| match arg {
+                        types::GenericArg::Lifetime(lifetime) => lifetime.clone(),
+                        types::GenericArg::Type(ty) => self.ty(ty),
+                        types::GenericArg::Const(constant) => constant.expr.clone(),
+                        types::GenericArg::Infer => String::from("_"),
+                    })
+                    .chain(bindings.iter().map(|binding| self.type_binding(binding)));
+                s.push('<');
+                s.push_str(&args.collect::<Vec<_>>().join(", "));
+                s.push('>');
+            }
+            types::GenericArgs::Parenthesized { inputs, output } => {
+                let inputs = inputs.iter().map(|ty| self.ty(ty)).collect::<Vec<_>>();
+                write!(s, "({})", inputs.join(", ")).unwrap();
+                if let Some(output) = output {
+                    write!(s, " -> {}", self.ty(output)).unwrap();
+                }
+            }
+        }
+    }
+
+    fn type_binding(&self, binding: &types::TypeBinding) -> String {
+        let mut s = binding.name.clone();
+        self.write_generic_args(&mut s, &binding.args);
+        match binding.binding {
+            types::TypeBindingKind::Equality(ref term) => {
+                write!(s, " = {}", self.term(term)).unwrap()
+            }
+            types::TypeBindingKind::Constraint(ref bounds) => {
+                write!(s, ": {}", self.bounds(bounds)).unwrap()
+            }
+        }
+        s
+    }
+
+    fn term(&self, term: &types::Term) -> String {
+        match term {
+            types::Term::Type(ty) => self.ty(ty),
+            types::Term::Constant(constant) => constant.expr.clone(),
+        }
+    }
+
+    fn ty(&self, ty: &types::Type) -> String {
+        match ty {
+            types::Type::ResolvedPath(path) => self.path(path),
+            types::Type::DynTrait(dyn_trait) => {
+                let mut bounds: Vec<_> =
+                    dyn_trait.traits.iter().map(|poly| self.poly_trait(poly)).collect();
+                bounds.extend(dyn_trait.lifetime.clone());
+                format!("dyn {}", bounds.join(" + "))
+            }
+            types::Type::Generic(name) | types::Type::Primitive(name) => name.clone(),
+            types::Type::FunctionPointer(fn_ptr) => {
+                let mut s = self.for_lifetimes(&fn_ptr.generic_params);
+                if fn_ptr.header.unsafe_ {
+                    s.push_str("unsafe ");
+                }
+                if fn_ptr.header.abi != types::Abi::Rust {
+                    write!(s, "extern \"{}\" ", abi(&fn_ptr.header.abi)).unwrap();
+                }
+                s.push_str("fn");
+                s.push_str(&self.fn_decl(&fn_ptr.decl));
+                s
+            }
+            types::Type::Tuple(types) if types.len() == 1 => format!("({},)", self.ty(&types[0])),
+            types::Type::Tuple(types) => {
+                format!("({})", types.iter().map(|ty| self.ty(ty)).collect::<Vec<_>>().join(", "))
+            }
+            types::Type::Slice(ty) => format!("[{}]", self.ty(ty)),
+            types::Type::Array { type_, len } => format!("[{}; {}]", self.ty(type_), len),
+            types::Type::ImplTrait(bounds) => format!("impl {}", self.bounds(bounds)),
+            types::Type::Infer => String::from("_"),
+            types::Type::RawPointer { mutable, type_ } => {
+                format!("*{} {}", if *mutable { "mut" } else { "const" }, self.ty(type_))
+            }
+            types::Type::BorrowedRef { lifetime, mutable, type_ } => {
+                let mut s = String::from("&");
+                if let Some(lifetime) = lifetime {
+                    write!(s, "{} ", lifetime).unwrap();
+                }
+                if *mutable {
+                    s.push_str("mut ");
+                }
+                s.push_str(&self.ty(type_));
+                s
+            }
+            types::Type::QualifiedPath { name, args, self_type, trait_ } => {
+                let mut s = match trait_ {
+                    Some(trait_) => {
+                        format!("<{} as {}>::{}", self.ty(self_type), self.path(trait_), name)
+                    }
+                    None => format!("{}::{}", self.ty(self_type), name),
+                };
+                self.write_generic_args(&mut s, args);
+                s
+            }
+        }
+    }
+
+    fn for_lifetimes(&self, params: &[types::GenericParamDef]) -> String {
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("for<{}> ", self.generic_params(params).join(", "))
+        }
+    }
+
+    fn poly_trait(&self, poly: &types::PolyTrait) -> String {
+        format!("{}{}", self.for_lifetimes(&poly.generic_params), self.path(&poly.trait_))
+    }
+
+    fn bound(&self, bound: &types::GenericBound) -> String {
+        match bound {
+            types::GenericBound::TraitBound { trait_, generic_params, modifier } => {
+                let modifier = match modifier {
+                    types::TraitBoundModifier::None => "",
+                    types::TraitBoundModifier::Maybe => "?",
+                    types::TraitBoundModifier::MaybeConst => "~const ",
+                };
+                format!("{}{}{}", self.for_lifetimes(generic_params), modifier, self.path(trait_))
+            }
+            types::GenericBound::Outlives(lifetime) => lifetime.clone(),
+        }
+    }
+
+    fn bounds(&self, bounds: &[types::GenericBound]) -> String {
+        bounds.iter().map(|bound| self.bound(bound)).collect::<Vec<_>>().join(" + ")
+    }
+
+    /// Prints the generic parameters without their bounds, which are compared separately by
+    /// [`Printer::generics_bounds`]. Parameters introduced by `impl Trait` arguments are skipped,
+    /// as they are part of the argument types.
+    fn generic_params(&self, params: &[types::GenericParamDef]) -> Vec<String> {
+        params
+            .iter()
+            .filter_map(|param| match param.kind {
+                types::GenericParamDefKind::Lifetime { .. } => Some(param.name.clone()),
+                types::GenericParamDefKind::Type { synthetic: true, .. } => None,
+                types::GenericParamDefKind::Type { default: None, .. } => Some(param.name.clone()),
+                types::GenericParamDefKind::Type { default: Some(ref default), .. } => {
+                    Some(format!("{} = {}", param.name, self.ty(default)))
+                }
+                types::GenericParamDefKind::Const { ref type_, ref default } => {
+                    let mut s = format!("const {}: {}", param.name, self.ty(type_));
+                    if let Some(default) = default {
+                        write!(s, " = {}", default).unwrap();
+                    }
+                    Some(s)
+                }
+            })
+            .collect()
+    }
+
+    /// Collects every bound of the generic parameters and where clauses as a separate
+    /// `Type: Bound` string, so that added and removed bounds can be told apart.
+    fn generics_bounds(&self, generics: &types::Generics) -> BTreeSet<String> {
+        let mut bounds = BTreeSet::new();
+        for param in &generics.params {
+            match param.kind {
+                types::GenericParamDefKind::Lifetime { ref outlives } => {
+                    for lifetime in outlives {
+                        bounds.insert(format!("{}: {}", param.name, lifetime));
+                    }
+                }
+                types::GenericParamDefKind::Type { synthetic: true, .. } => {}
+                types::GenericParamDefKind::Type { bounds: ref param_bounds, .. } => {
+                    for bound in param_bounds {
+                        bounds.insert(format!("{}: {}", param.name, self.bound(bound)));
+                    }
+                }
+                types::GenericParamDefKind::Const { .. } => {}
+            }
+        }
+        for predicate in &generics.where_predicates {
+            match predicate {
+                types::WherePredicate::BoundPredicate {
+                    type_,
+                    bounds: pred_bounds,
+                    generic_params,
+                } => {
+                    let ty = format!("{}{}", self.for_lifetimes(generic_params), self.ty(type_));
+                    for bound in pred_bounds {
+                        bounds.insert(format!("{}: {}", ty, self.bound(bound)));
+                    }
+                }
+                types::WherePredicate::RegionPredicate { lifetime, bounds: pred_bounds } => {
+                    for bound in pred_bounds {
+                        bounds.insert(format!("{}: {}", lifetime, self.bound(bound)));
+                    }
+                }
+                types::WherePredicate::EqPredicate { lhs, rhs } => {
+                    bounds.insert(format!("{} == {}", self.ty(lhs), self.term(rhs)));
+                }
+            }
+        }
+        bounds
+    }
+
+    fn fn_decl(&self, decl: &types::FnDecl) -> String {
+        let mut inputs: Vec<_> = decl
+            .inputs
+            .iter()
+            .map(|(name, ty)| match (name.as_str(), ty) {
+                ("self", types::Type::Generic(self_ty)) if self_ty == "Self" => {
+                    String::from("self")
+                }
+                ("self", types::Type::BorrowedRef { lifetime, mutable, type_ })
+                    if **type_ == types::Type::Generic(String::from("Self")) =>
+                {
+                    let mut s = String::from("&");
+                    if let Some(lifetime) = lifetime {
+                        write!(s, "{} ", lifetime).unwrap();
+                    }
+                    if *mutable {
+                        s.push_str("mut ");
+                    }
+                    s.push_str("self");
+                    s
+                }
+                // Argument names don't matter to callers.
+                _ => self.ty(ty),
+            })
+            .collect();
+        if decl.c_variadic {
+            inputs.push(String::from("..."));
+        }
+        let mut s = format!("({})", inputs.join(", "));
+        if let Some(ref output) = decl.output {
+            write!(s, " -> {}", self.ty(output)).unwrap();
+        }
+        s
+    }
+
+    fn impl_header(&self, impl_: &types::Impl) -> String {
+        let mut s = String::new();
+        if impl_.is_unsafe {
+            s.push_str("unsafe ");
+        }
+        s.push_str("impl");
+        let params = self.generic_params(&impl_.generics.params);
+        if !params.is_empty() {
+            write!(s, "<{}>", params.join(", ")).unwrap();
+        }
+        s.push(' ');
+        if impl_.negative {
+            s.push('!');
+        }
+        if let Some(ref trait_) = impl_.trait_ {
+            write!(s, "{} for ", self.path(trait_)).unwrap();
+        }
+        s.push_str(&self.ty(&impl_.for_));
+        let bounds = self.generics_bounds(&impl_.generics);
+        if !bounds.is_empty() {
+            write!(s, " where {}", bounds.into_iter().collect::<Vec<_>>().join(", ")).unwrap();
+        }
+        s
+    }
+}
@@ -119,6 +119,7 @@ macro_rules! map {
 mod markdown;
 mod passes;
 mod scrape_examples;
+mod text;
 mod theme;
 mod visit;
 mod visit_ast;
@@ -243,7 +244,9 @@ fn opts() -> Vec<RustcOptGroup> {
         stable("h", |o| o.optflagmulti("h", "help", "show this help message")),
         stable("V", |o| o.optflagmulti("V", "version", "print rustdoc's version")),
         stable("v", |o| o.optflagmulti("v", "verbose", "use verbose output")),
-        stable("w", |o| o.optopt("w", "output-format", "the output type to write", "[html]")),
+        stable("w", |o| {
+            o.optopt("w", "output-format", "the output type to write", "[html|json|markdown|man]")
+        }),
         stable("output", |o| {
             o.optopt(
                 "",
@@ -289,6 +292,14 @@ fn opts() -> Vec<RustcOptGroup> {
         unstable("document-hidden-items", |o| {
             o.optflagmulti("", "document-hidden-items", "document items that have doc(hidden)")
         }),
+        unstable("auto-cfg", |o| {
+            o.optflag(
+                "",
+                "auto-cfg",
+                "show the features and targets each item is available on, inferred from the cfg \
+                attributes of the item and its parents",
+            )
+        }),
         stable("test", |o| o.optflagmulti("", "test", "run code examples as tests")),
         stable("test-args", |o| {
             o.optmulti("", "test-args", "arguments to pass to the test runner", "ARGS")
@@ -465,7 +476,7 @@ fn opts() -> Vec<RustcOptGroup> {
                 "",
                 "error-format",
                 "How errors and other messages are produced",
-                "human|json|short",
+                "human|json|sarif|short",
             )
         }),
         stable("diagnostic-width", |o| {
@@ -616,6 +627,23 @@ fn opts() -> Vec<RustcOptGroup> {
                 "path to function call information (for displaying examples in the documentation)",
             )
         }),
+        unstable("show-usages", |o| {
+            o.optflag(
+                "",
+                "show-usages",
+                "show where each item is used in the crate, its doctests and the crates given \
+                with --with-examples",
+            )
+        }),
+        unstable("api-diff", |o| {
+            o.optopt(
+                "",
+                "api-diff",
+                "compare the public API of the rustdoc JSON file given as input against the one \
+                at PATH, and report the version bump the changes require",
+                "PATH",
+            )
+        }),
         // deprecated / removed options
         unstable("disable-minification", |o| o.optflagmulti("", "disable-minification", "removed")),
         stable("plugin-path", |o| {
@@ -746,6 +774,13 @@ fn main_args(at_args: &[String]) -> MainResult {
         &options.unstable_opts,
     );
 
+    if let Some(ref baseline) = options.api_diff_baseline {
+        return wrap_return(
+            &diag,
+            json::diff::run(baseline, &options.input, options.output_format),
+        );
+    }
+
     match (options.should_test, options.markdown_input()) {
         (true, true) => return wrap_return(&diag, markdown::test(options)),
         (true, false) => return doctest::run(options),
@@ -844,6 +879,22 @@ fn main_args(at_args: &[String]) -> MainResult {
                     config::OutputFormat::Json => sess.time("render_json", || {
                         run_renderer::<json::JsonRenderer<'_>>(krate, render_opts, cache, tcx)
                     }),
+                    config::OutputFormat::Markdown => sess.time("render_markdown", || {
+                        run_renderer::<text::TextRenderer<'_, text::Markdown>>(
+                            krate,
+                            render_opts,
+                            cache,
+                            tcx,
+                        )
+                    }),
+                    config::OutputFormat::Man => sess.time("render_man", || {
+                        run_renderer::<text::TextRenderer<'_, text::Man>>(
+                            krate,
+                            render_opts,
+                            cache,
+                            tcx,
+                        )
+                    }),
                 }
             })
         })
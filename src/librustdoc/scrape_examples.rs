@@ -4,7 +4,7 @@
 use crate::config;
 use crate::formats;
 use crate::formats::renderer::FormatRenderer;
-use crate::html::render::Context;
+use crate::html::render::{collect_usages, Context};
 
 use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::{
@@ -116,6 +116,32 @@ pub(crate) struct CallData {
 pub(crate) type FnCallLocations = FxHashMap<PathBuf, CallData>;
 pub(crate) type AllCallLocations = FxHashMap<DefPathHash, FnCallLocations>;
 
+/// How an item is used.
+#[derive(Encodable, Decodable, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum UsageKind {
+    /// The function or method is called.
+    Call,
+    /// The trait is implemented.
+    TraitImpl,
+    /// The item is named in a type, a pattern or an expression.
+    Mention,
+    /// The item is named in the doctest of another item.
+    Doctest,
+}
+
+/// A place where an item is used, as shown in its "Used by" section.
+#[derive(Encodable, Decodable, Debug, Clone)]
+pub(crate) struct Usage {
+    pub(crate) kind: UsageKind,
+    /// The item containing the usage, like `main` or `tests::roundtrip`.
+    pub(crate) item: String,
+    /// The file and line of the usage, like `examples/demo.rs:12`.
+    pub(crate) location: String,
+    pub(crate) url: String,
+}
+
+pub(crate) type AllUsages = FxHashMap<DefPathHash, Vec<Usage>>;
+
 /// Visitor for traversing a crate and finding instances of function calls.
 struct FindCalls<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -308,6 +334,7 @@ pub(crate) fn run(
         let mut finder =
             FindCalls { calls: &mut calls, tcx, map: tcx.hir(), cx, target_crates, bin_crate };
         tcx.hir().visit_all_item_likes_in_crate(&mut finder);
+        let FindCalls { cx, target_crates, .. } = finder;
 
         // The visitor might have found a type error, which we need to
         // promote to a fatal error
@@ -322,9 +349,21 @@ pub(crate) fn run(
             }
         }
 
+        // Collect all the other ways the items of the target crates are used, for their
+        // "Used by" sections
+        let mut usages: AllUsages = FxHashMap::default();
+        for (def_id, local_usages) in collect_usages(tcx) {
+            if !target_crates.contains(&def_id.krate) {
+                continue;
+            }
+            let entries = usages.entry(tcx.def_path_hash(def_id)).or_default();
+            entries.extend(local_usages.iter().filter_map(|usage| usage.resolve(&cx)));
+        }
+
         // Save output to provided path
         let mut encoder = FileEncoder::new(options.output_path).map_err(|e| e.to_string())?;
         calls.encode(&mut encoder);
+        usages.encode(&mut encoder);
         encoder.finish().map_err(|e| e.to_string())?;
 
         Ok(())
@@ -341,20 +380,25 @@ pub(crate) fn run(
 pub(crate) fn load_call_locations(
     with_examples: Vec<String>,
     diag: &rustc_errors::Handler,
-) -> Result<AllCallLocations, i32> {
+) -> Result<(AllCallLocations, AllUsages), i32> {
     let inner = || {
         let mut all_calls: AllCallLocations = FxHashMap::default();
+        let mut all_usages: AllUsages = FxHashMap::default();
         for path in with_examples {
             let bytes = fs::read(&path).map_err(|e| format!("{} (for path {})", e, path))?;
             let mut decoder = MemDecoder::new(&bytes, 0);
             let calls = AllCallLocations::decode(&mut decoder);
+            let usages = AllUsages::decode(&mut decoder);
 
             for (function, fn_calls) in calls.into_iter() {
                 all_calls.entry(function).or_default().extend(fn_calls.into_iter());
             }
+            for (item, item_usages) in usages.into_iter() {
+                all_usages.entry(item).or_default().extend(item_usages.into_iter());
+            }
         }
 
-        Ok(all_calls)
+        Ok((all_calls, all_usages))
     };
 
     inner().map_err(|e: String| {
@@ -0,0 +1,263 @@
+//! The man page backend (`--output-format man`), which writes a section 3 page in roff.
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, CowStr, Event, Parser, Tag};
+use rustc_span::Symbol;
+
+use super::{Backend, Link, Section};
+use crate::html::markdown::{is_rust_code_block, main_body_opts, visible_code_lines};
+
+pub(crate) struct Man;
+
+impl Backend for Man {
+    const DESCR: &'static str = "man";
+    const EXTENSION: &'static str = "3";
+
+    fn render(krate: Symbol, version: Option<&str>, sections: &[Section]) -> String {
+        let mut roff = Roff::default();
+        let footer = match version {
+            Some(version) => format!("{} {}", krate, version),
+            None => krate.to_string(),
+        };
+        roff.request(&format!(
+            "TH {} 3 \"\" {} \"Rust Library Documentation\"",
+            quote(&krate.as_str().to_uppercase()),
+            quote(&footer)
+        ));
+        roff.request("SH NAME");
+        let summary = sections
+            .first()
+            .and_then(|s| s.docs.lines().map(str::trim).find(|l| !l.is_empty()))
+            .unwrap_or("Rust library");
+        roff.text(&format!("{} - {}", krate, strip_markdown(summary)));
+
+        for (index, section) in sections.iter().enumerate() {
+            if index == 0 {
+                roff.request("SH DESCRIPTION");
+            } else {
+                let title = section.title.replace('`', "");
+                match section.level {
+                    0..=2 => roff.request(&format!("SH {}", quote(&title))),
+                    3 => roff.request(&format!("SS {}", quote(&title))),
+                    _ => {
+                        roff.request("PP");
+                        roff.font("B");
+                        roff.text(&title);
+                        roff.font("R");
+                    }
+                }
+            }
+            if let Some(ref code) = section.code {
+                roff.code_block(code.lines());
+            }
+            if let Some(ref note) = section.note {
+                roff.request("PP");
+                roff.font("I");
+                roff.text(note);
+                roff.font("R");
+            }
+            docs(&mut roff, &section.docs, &section.links);
+        }
+        roff.out
+    }
+}
+
+/// Quotes an argument of a request.
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", escape(arg).replace('"', "\"\""))
+}
+
+/// Escapes characters that have a special meaning in roff text.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\e").replace('-', "\\-")
+}
+
+/// Removes the Markdown markup that is commonly used in the first line of documentation.
+fn strip_markdown(line: &str) -> String {
+    line.replace(['`', '*'], "")
+}
+
+/// A roff document being written.
+#[derive(Default)]
+pub(super) struct Roff {
+    pub(super) out: String,
+}
+
+impl Roff {
+    fn start_line(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    /// Writes a request (like `PP`), on its own line.
+    fn request(&mut self, request: &str) {
+        self.start_line();
+        self.out.push('.');
+        self.out.push_str(request);
+        self.out.push('\n');
+    }
+
+    fn font(&mut self, font: &str) {
+        self.out.push_str("\\f");
+        self.out.push_str(font);
+    }
+
+    /// Writes escaped text, making sure no line of it is taken for a request.
+    fn text(&mut self, text: &str) {
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.out.push('\n');
+            }
+            if (self.out.is_empty() || self.out.ends_with('\n'))
+                && (line.starts_with('.') || line.starts_with('\''))
+            {
+                self.out.push_str("\\&");
+            }
+            self.out.push_str(&escape(line));
+        }
+    }
+
+    fn code_block<'a>(&mut self, lines: impl Iterator<Item = &'a str>) {
+        self.request("PP");
+        self.request("RS 4");
+        self.request("nf");
+        for line in lines {
+            self.text(line);
+            self.out.push('\n');
+        }
+        self.request("fi");
+        self.request("RE");
+    }
+}
+
+/// Converts the Markdown documentation of an item to roff. Intra-doc links are rendered as their
+/// text, and other links as their text followed by the URL.
+pub(super) fn docs(roff: &mut Roff, md: &str, links: &[Link]) {
+    let mut broken_link_callback = |link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|l| l.original_text == *link.reference)
+            .map(|l| (CowStr::from(l.original_text.clone()), CowStr::Borrowed("")))
+    };
+    let mut events = Parser::new_with_broken_link_callback(
+        md,
+        main_body_opts(),
+        Some(&mut broken_link_callback),
+    );
+
+    // The ordered lists being written, with the number of their next item (`None` for bullet
+    // lists).
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    // Whether the next paragraph starts a list item, which already begins a paragraph.
+    let mut in_item_start = false;
+    // The URLs of the links being written, to be printed after their text.
+    let mut link_urls: Vec<Option<String>> = Vec::new();
+    while let Some(event) = events.next() {
+        match event {
+            Event::Start(Tag::Paragraph) => {
+                if in_item_start {
+                    in_item_start = false;
+                } else if lists.is_empty() {
+                    roff.request("PP");
+                } else {
+                    roff.request("sp");
+                }
+            }
+            Event::Start(Tag::Heading(..)) => {
+                roff.request("PP");
+                roff.font("B");
+            }
+            Event::End(Tag::Heading(..)) => {
+                roff.font("R");
+                roff.out.push('\n');
+            }
+            Event::Start(Tag::BlockQuote) => roff.request("RS 4"),
+            Event::End(Tag::BlockQuote) => roff.request("RE"),
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let is_rust = match kind {
+                    CodeBlockKind::Fenced(ref lang) => is_rust_code_block(lang),
+                    CodeBlockKind::Indented => true,
+                };
+                let mut code = String::new();
+                for inner in events.by_ref() {
+                    match inner {
+                        Event::Text(text) => code.push_str(&text),
+                        Event::End(Tag::CodeBlock(_)) => break,
+                        _ => {}
+                    }
+                }
+                if is_rust {
+                    let lines: Vec<_> = visible_code_lines(&code).collect();
+                    roff.code_block(lines.iter().map(|l| &**l));
+                } else {
+                    roff.code_block(code.lines());
+                }
+            }
+            Event::Start(Tag::List(start)) => {
+                if !lists.is_empty() {
+                    roff.request("RS 2");
+                }
+                lists.push(start);
+            }
+            Event::End(Tag::List(_)) => {
+                lists.pop();
+                if !lists.is_empty() {
+                    roff.request("RE");
+                }
+            }
+            Event::Start(Tag::Item) => {
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        roff.request(&format!("IP \"{}.\" 4", number));
+                        *number += 1;
+                    }
+                    _ => roff.request("IP \\(bu 2"),
+                }
+                in_item_start = true;
+            }
+            Event::Start(Tag::Emphasis) => roff.font("I"),
+            Event::Start(Tag::Strong) => roff.font("B"),
+            Event::End(Tag::Emphasis | Tag::Strong) => roff.font("R"),
+            Event::Start(Tag::Link(_, dest, _)) => {
+                let is_intra_doc_link = links.iter().any(|l| l.original_text == *dest);
+                let url = (!is_intra_doc_link && !dest.is_empty() && !dest.starts_with('#'))
+                    .then(|| dest.to_string());
+                link_urls.push(url);
+            }
+            Event::End(Tag::Link(..)) => {
+                if let Some(Some(url)) = link_urls.pop() {
+                    roff.text(&format!(" <{}>", url));
+                }
+            }
+            Event::End(Tag::TableHead | Tag::TableRow) => roff.request("br"),
+            Event::End(Tag::TableCell) => roff.text(" | "),
+            Event::Text(text) => {
+                // Intra-doc links are written with their resolved text.
+                match links.iter().find(|l| l.original_text.trim_matches('`') == &*text) {
+                    Some(link) if !link_urls.is_empty() => {
+                        roff.text(link.new_text.trim_matches('`'))
+                    }
+                    _ => roff.text(&text.replace('\n', " ")),
+                }
+            }
+            Event::Code(code) => {
+                roff.font("B");
+                match links.iter().find(|l| l.original_text.trim_matches('`') == &*code) {
+                    Some(link) if !link_urls.is_empty() => {
+                        roff.text(link.new_text.trim_matches('`'))
+                    }
+                    _ => roff.text(&code),
+                }
+                roff.font("R");
+            }
+            Event::SoftBreak => roff.out.push('\n'),
+            Event::HardBreak => roff.request("br"),
+            Event::Rule => roff.request("sp"),
+            Event::FootnoteReference(name) => roff.text(&format!("[{}]", name)),
+            Event::TaskListMarker(checked) => roff.text(if checked { "[x] " } else { "[ ] " }),
+            Event::Html(_) => {}
+            Event::Start(_) | Event::End(_) => {}
+        }
+    }
+    roff.start_line();
+}
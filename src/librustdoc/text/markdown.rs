@@ -0,0 +1,178 @@
+//! The Markdown backend (`--output-format markdown`).
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, CowStr, Event, Parser, Tag};
+use rustc_span::Symbol;
+
+use super::{Backend, Link, Section};
+use crate::html::markdown::{is_rust_code_block, main_body_opts, visible_code_lines};
+
+pub(crate) struct Markdown;
+
+impl Backend for Markdown {
+    const DESCR: &'static str = "markdown";
+    const EXTENSION: &'static str = "md";
+
+    fn render(_krate: Symbol, version: Option<&str>, sections: &[Section]) -> String {
+        let mut out = String::new();
+        for (index, section) in sections.iter().enumerate() {
+            let level = section.level.min(6);
+            writeln!(out, "<a id=\"{}\"></a>\n", section.anchor).unwrap();
+            writeln!(out, "{} {}\n", "#".repeat(level), section.title).unwrap();
+            if index == 0 {
+                if let Some(version) = version {
+                    writeln!(out, "Version {}\n", version).unwrap();
+                }
+            }
+            if let Some(ref code) = section.code {
+                writeln!(out, "```rust\n{}\n```\n", code).unwrap();
+            }
+            if let Some(ref note) = section.note {
+                writeln!(out, "> **{}**\n", note).unwrap();
+            }
+            let docs = docs(&section.docs, &section.links, level);
+            if !docs.trim().is_empty() {
+                writeln!(out, "{}\n", docs.trim_end()).unwrap();
+            }
+            if index == 0 {
+                contents(&mut out, &sections[1..]);
+            }
+        }
+        out
+    }
+}
+
+/// Writes a list of links to the item sections.
+fn contents(out: &mut String, sections: &[Section]) {
+    let items: Vec<_> = sections.iter().filter(|s| s.level == 2).collect();
+    if items.is_empty() {
+        return;
+    }
+    out.push_str("**Contents**\n\n");
+    for section in items {
+        writeln!(out, "- [{}](#{})", section.title, section.anchor).unwrap();
+    }
+    out.push('\n');
+}
+
+/// Rewrites the documentation of an item for inclusion in a section at `level`: intra-doc links
+/// are replaced by the link they resolve to, headings are nested below the section heading, and
+/// hidden lines are removed from Rust code blocks.
+pub(super) fn docs(md: &str, links: &[Link], level: usize) -> String {
+    let mut broken_link_callback = |link: BrokenLink<'_>| {
+        links
+            .iter()
+            .find(|l| l.original_text == *link.reference)
+            .map(|l| (CowStr::from(l.original_text.clone()), CowStr::Borrowed("")))
+    };
+    let mut events = Parser::new_with_broken_link_callback(
+        md,
+        main_body_opts(),
+        Some(&mut broken_link_callback),
+    )
+    .into_offset_iter();
+
+    // Replacements of ranges of `md`, which never overlap.
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    let mut last_end = 0;
+    while let Some((event, range)) = events.next() {
+        match event {
+            Event::Start(Tag::Link(_, ref dest, _)) => {
+                if let Some(link) = links.iter().find(|l| l.original_text == **dest) {
+                    // Collect the source of the link text.
+                    let mut text = range.start..range.start;
+                    for (inner, inner_range) in events.by_ref() {
+                        if let Event::End(Tag::Link(..)) = inner {
+                            break;
+                        }
+                        if text.is_empty() {
+                            text.start = inner_range.start;
+                        }
+                        text.end = inner_range.end;
+                    }
+                    let text = &md[text];
+                    let original = link.original_text.trim_matches('`');
+                    let text = if text.trim_matches('`') == original {
+                        text.replace(original, link.new_text.trim_matches('`'))
+                    } else {
+                        text.to_owned()
+                    };
+                    let replacement = match link.href {
+                        Some(ref href) => format!("[{}]({})", text, href),
+                        None => text,
+                    };
+                    edits.push((range.clone(), replacement));
+                }
+            }
+            Event::End(Tag::Heading(heading_level, ..)) => {
+                let heading_level = heading_level as usize;
+                let new_level = (heading_level + level).min(6);
+                let hashes = "#".repeat(new_level - heading_level.min(new_level));
+                if md[range.start..].starts_with('#') {
+                    edits.push((range.start..range.start, hashes));
+                } else {
+                    // A setext heading, underlined with `=` or `-`: turn it into an ATX one.
+                    let end =
+                        if md[..range.end].ends_with('\n') { range.end - 1 } else { range.end };
+                    edits.push((range.start..range.start, "#".repeat(new_level) + " "));
+                    edits.push((last_end..end.max(last_end), String::new()));
+                }
+            }
+            Event::Start(Tag::CodeBlock(ref kind)) => {
+                let is_rust = match kind {
+                    CodeBlockKind::Fenced(lang) => is_rust_code_block(lang),
+                    CodeBlockKind::Indented => true,
+                };
+                let mut code = String::new();
+                for (inner, _) in events.by_ref() {
+                    match inner {
+                        Event::Text(text) => code.push_str(&text),
+                        Event::End(Tag::CodeBlock(_)) => break,
+                        _ => {}
+                    }
+                }
+                if is_rust {
+                    let indent = line_indent(md, range.start);
+                    let mut replacement = String::from("```rust\n");
+                    for line in visible_code_lines(&code) {
+                        replacement.push_str(&indent);
+                        replacement.push_str(&line);
+                        replacement.push('\n');
+                    }
+                    replacement.push_str(&indent);
+                    replacement.push_str("```");
+                    if md[range.clone()].ends_with('\n') {
+                        replacement.push('\n');
+                    }
+                    edits.push((range.clone(), replacement));
+                }
+            }
+            _ => {}
+        }
+        last_end = range.end;
+    }
+
+    // Insertions go before replacements starting at the same position.
+    edits.sort_by_key(|(range, _)| (range.start, range.end));
+    let mut out = String::with_capacity(md.len());
+    let mut pos = 0;
+    for (range, replacement) in edits {
+        if range.start < pos {
+            continue;
+        }
+        out.push_str(&md[pos..range.start]);
+        out.push_str(&replacement);
+        pos = range.end;
+    }
+    out.push_str(&md[pos..]);
+    out
+}
+
+/// Returns the whitespace before `pos` on its line, if there is only whitespace before it.
+fn line_indent(md: &str, pos: usize) -> String {
+    let line_start = md[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let prefix = &md[line_start..pos];
+    if prefix.chars().all(char::is_whitespace) { prefix.to_owned() } else { String::new() }
+}
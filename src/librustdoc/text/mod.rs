@@ -0,0 +1,460 @@
+//! Rustdoc's plain-text backends.
+//!
+//! These render a whole crate into a single self-contained file, which can be read offline in a
+//! pager, a terminal or a git forge: Markdown (`--output-format markdown`) and a roff man page
+//! (`--output-format man`). Both share the walk over the cleaned crate implemented here, which
+//! turns every documented item into a [`Section`]; the backends only differ in how they write the
+//! sections out.
+
+mod man;
+mod markdown;
+mod print;
+#[cfg(test)]
+mod tests;
+
+use std::cell::RefCell;
+use std::fs::{create_dir_all, File};
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Symbol;
+
+use crate::clean::types::ExternalLocation;
+use crate::clean::{self, ItemKind};
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+use crate::error::Error;
+use crate::formats::cache::Cache;
+use crate::formats::item_type::ItemType;
+use crate::formats::FormatRenderer;
+use crate::passes::collect_intra_doc_links::UrlFragment;
+use crate::try_err;
+
+pub(crate) use self::man::Man;
+pub(crate) use self::markdown::Markdown;
+use self::print::{item_kind_name, join_path, Printer};
+
+/// A part of the generated document describing a single item, or a group of implementations.
+#[derive(Debug)]
+pub(crate) struct Section {
+    /// The anchor other sections link to, like `struct.krate.Foo` or
+    /// `struct.krate.Foo.method.new`.
+    pub(crate) anchor: String,
+    /// The nesting depth of the section: 1 for the crate, 2 for items, 3 for their fields,
+    /// variants and implementations, and 4 for the items of implementations.
+    pub(crate) level: usize,
+    pub(crate) title: String,
+    /// The declaration of the item, as Rust source.
+    pub(crate) code: Option<String>,
+    /// A note to show before the documentation, such as a deprecation notice.
+    pub(crate) note: Option<String>,
+    /// The documentation of the item, as Markdown.
+    pub(crate) docs: String,
+    /// The resolved intra-doc links of `docs`.
+    pub(crate) links: Vec<Link>,
+}
+
+/// An intra-doc link, resolved for a text backend.
+#[derive(Debug)]
+pub(crate) struct Link {
+    /// The link as it was written in the documentation.
+    pub(crate) original_text: String,
+    /// The text to display instead of `original_text`, without disambiguators.
+    pub(crate) new_text: String,
+    /// Where the link points to: an anchor in the same document, the document of another crate
+    /// in the same directory, or a remote URL. `None` if the target isn't documented anywhere
+    /// we know of, in which case only the text is kept.
+    pub(crate) href: Option<String>,
+}
+
+/// A format the text renderer can write sections in.
+pub(crate) trait Backend {
+    /// Describes the backend, for performance profiling.
+    const DESCR: &'static str;
+    /// The extension of the generated file.
+    const EXTENSION: &'static str;
+
+    /// Renders the sections of `krate` into a complete document.
+    fn render(krate: Symbol, version: Option<&str>, sections: &[Section]) -> String;
+}
+
+pub(crate) struct TextRenderer<'tcx, B> {
+    tcx: TyCtxt<'tcx>,
+    /// The directory the document is written to.
+    out_path: PathBuf,
+    cache: Rc<Cache>,
+    /// The sections rendered so far, shared with the child renderers.
+    sections: Rc<RefCell<Vec<Section>>>,
+    krate: Symbol,
+    backend: PhantomData<B>,
+}
+
+impl<'tcx, B> Clone for TextRenderer<'tcx, B> {
+    fn clone(&self) -> Self {
+        TextRenderer {
+            tcx: self.tcx,
+            out_path: self.out_path.clone(),
+            cache: Rc::clone(&self.cache),
+            sections: Rc::clone(&self.sections),
+            krate: self.krate,
+            backend: PhantomData,
+        }
+    }
+}
+
+impl<'tcx, B: Backend> FormatRenderer<'tcx> for TextRenderer<'tcx, B> {
+    fn descr() -> &'static str {
+        B::DESCR
+    }
+
+    const RUN_ON_MODULE: bool = false;
+
+    fn init(
+        krate: clean::Crate,
+        options: RenderOptions,
+        cache: Cache,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Self, clean::Crate), Error> {
+        debug!("Initializing {} renderer", B::DESCR);
+
+        let renderer = TextRenderer {
+            tcx,
+            out_path: options.output,
+            cache: Rc::new(cache),
+            sections: Rc::new(RefCell::new(Vec::new())),
+            krate: krate.name(tcx),
+            backend: PhantomData,
+        };
+        Ok((renderer, krate))
+    }
+
+    fn make_child_renderer(&self) -> Self {
+        self.clone()
+    }
+
+    /// Only called for the crate root, since `RUN_ON_MODULE` is `false`: the whole crate is
+    /// walked from here, so that the sections come out in a stable order.
+    fn item(&mut self, item: clean::Item) -> Result<(), Error> {
+        let mut builder = Builder {
+            tcx: self.tcx,
+            cache: &self.cache,
+            printer: Printer { tcx: self.tcx },
+            extension: B::EXTENSION,
+            sections: Vec::new(),
+        };
+        builder.module(&item, &mut vec![self.krate]);
+        self.sections.borrow_mut().extend(builder.sections);
+        Ok(())
+    }
+
+    fn mod_item_in(&mut self, _item: &clean::Item) -> Result<(), Error> {
+        unreachable!("RUN_ON_MODULE = false should never call mod_item_in")
+    }
+
+    fn after_krate(&mut self) -> Result<(), Error> {
+        let sections = self.sections.borrow();
+        let output = B::render(self.krate, self.cache.crate_version.as_deref(), &sections);
+
+        let out_dir = self.out_path.clone();
+        try_err!(create_dir_all(&out_dir), out_dir);
+        let mut p = out_dir;
+        p.push(self.krate.as_str());
+        p.set_extension(B::EXTENSION);
+        let mut file = BufWriter::new(try_err!(File::create(&p), p));
+        try_err!(file.write_all(output.as_bytes()), p);
+        try_err!(file.flush(), p);
+        Ok(())
+    }
+
+    fn cache(&self) -> &Cache {
+        &self.cache
+    }
+}
+
+/// Returns the anchor of the section of the item at `path`, which has its own page in the HTML
+/// output.
+fn anchor(ty: ItemType, path: &[Symbol]) -> String {
+    let path: Vec<_> = path.iter().map(|s| s.as_str()).collect();
+    format!("{}.{}", ty, path.join("."))
+}
+
+struct Builder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    cache: &'a Cache,
+    printer: Printer<'tcx>,
+    extension: &'static str,
+    sections: Vec<Section>,
+}
+
+impl<'a, 'tcx> Builder<'a, 'tcx> {
+    fn push(&mut self, item: &clean::Item, anchor: String, level: usize, title: String) {
+        let code = self.printer.declaration(item);
+        self.push_with_code(item, anchor, level, title, code);
+    }
+
+    fn push_with_code(
+        &mut self,
+        item: &clean::Item,
+        anchor: String,
+        level: usize,
+        title: String,
+        code: Option<String>,
+    ) {
+        let note = item.deprecation(self.tcx).map(|depr| {
+            let mut note = String::from("Deprecated");
+            if let Some(since) = depr.since {
+                note.push_str(" since ");
+                note.push_str(since.as_str());
+            }
+            if let Some(reason) = depr.note {
+                note.push_str(": ");
+                note.push_str(reason.as_str());
+            }
+            note
+        });
+        let docs = item.collapsed_doc_value().unwrap_or_default();
+        let links = self.links(item);
+        self.sections.push(Section { anchor, level, title, code, note, docs, links });
+    }
+
+    fn module(&mut self, item: &clean::Item, path: &mut Vec<Symbol>) {
+        let ItemKind::ModuleItem(ref module) = *item.kind else { return };
+
+        let reexports: Vec<_> = module
+            .items
+            .iter()
+            .filter_map(|i| match *i.kind {
+                ItemKind::ImportItem(ref import) if import.should_be_displayed => {
+                    Some(self.printer.import(i, import))
+                }
+                _ => None,
+            })
+            .collect();
+        let code = (!reexports.is_empty()).then(|| reexports.join("\n"));
+        let (level, title) = if path.len() == 1 {
+            (1, format!("Crate `{}`", path[0]))
+        } else {
+            (2, format!("Module `{}`", join_path(path)))
+        };
+        self.push_with_code(item, anchor(ItemType::Module, path), level, title, code);
+
+        let mut items: Vec<_> = module
+            .items
+            .iter()
+            .filter(|i| i.name.is_some() && !i.is_stripped())
+            .filter(|i| {
+                !matches!(*i.kind, ItemKind::ImportItem(_) | ItemKind::ExternCrateItem { .. })
+            })
+            .collect();
+        // Submodules go last, so that every module is directly followed by its own items.
+        items.sort_by_key(|i| (i.is_mod(), i.type_(), i.name));
+        for child in items {
+            if child.is_mod() {
+                path.push(child.name.unwrap());
+                self.module(child, path);
+                path.pop();
+            } else {
+                self.item(child, path);
+            }
+        }
+    }
+
+    fn item(&mut self, item: &clean::Item, module_path: &[Symbol]) {
+        let mut path = module_path.to_vec();
+        path.push(item.name.unwrap());
+        let ty = item.type_();
+        let anchor = anchor(ty, &path);
+        self.push(
+            item,
+            anchor.clone(),
+            2,
+            format!("{} `{}`", item_kind_name(ty), join_path(&path)),
+        );
+
+        match *item.kind {
+            ItemKind::StructItem(clean::Struct { ref fields, .. })
+            | ItemKind::UnionItem(clean::Union { ref fields, .. }) => {
+                for field in fields.iter().filter(|f| !f.is_stripped()) {
+                    let name = field.name.unwrap();
+                    let field_anchor = format!("{}.structfield.{}", anchor, name);
+                    self.push(field, field_anchor, 3, format!("Field `{}`", name));
+                }
+            }
+            ItemKind::EnumItem(ref enum_) => {
+                for variant in enum_.variants() {
+                    let name = variant.name.unwrap();
+                    let variant_anchor = format!("{}.variant.{}", anchor, name);
+                    self.push(variant, variant_anchor.clone(), 3, format!("Variant `{}`", name));
+                    if let ItemKind::VariantItem(clean::Variant {
+                        kind: clean::VariantKind::Struct(ref struct_),
+                        ..
+                    }) = *variant.kind
+                    {
+                        for field in struct_.fields.iter().filter(|f| !f.is_stripped()) {
+                            let field_name = field.name.unwrap();
+                            let field_anchor = format!("{}.field.{}", variant_anchor, field_name);
+                            let title = format!("Field `{}::{}`", name, field_name);
+                            self.push(field, field_anchor, 4, title);
+                        }
+                    }
+                }
+            }
+            ItemKind::TraitItem(ref trait_) => {
+                for trait_item in &trait_.items {
+                    let Some(name) = trait_item.name else { continue };
+                    let ty = trait_item.type_();
+                    let item_anchor = format!("{}.{}.{}", anchor, ty, name);
+                    self.push(
+                        trait_item,
+                        item_anchor,
+                        3,
+                        format!("{} `{}`", item_kind_name(ty), name),
+                    );
+                }
+                let implementors = item
+                    .item_id
+                    .as_def_id()
+                    .and_then(|did| self.cache.implementors.get(&did))
+                    .map(|impls| impls.iter().map(|i| i.inner_impl()).collect::<Vec<_>>())
+                    .unwrap_or_default();
+                self.impl_list(&anchor, "implementors", "Implementors", &implementors);
+            }
+            _ => {}
+        }
+
+        if matches!(
+            *item.kind,
+            ItemKind::StructItem(_)
+                | ItemKind::UnionItem(_)
+                | ItemKind::EnumItem(_)
+                | ItemKind::ForeignTypeItem
+                | ItemKind::PrimitiveItem(_)
+        ) && let Some(did) = item.item_id.as_def_id()
+        {
+            self.impls(did, &anchor);
+        }
+    }
+
+    /// Adds sections for the inherent implementations of the type with `did`, and lists its
+    /// trait implementations.
+    fn impls(&mut self, did: DefId, anchor: &str) {
+        let Some(impls) = self.cache.impls.get(&did) else { return };
+        let (inherent, trait_impls): (Vec<_>, Vec<_>) =
+            impls.iter().partition(|i| i.inner_impl().trait_.is_none());
+
+        for (index, impl_) in inherent.iter().enumerate() {
+            let inner = impl_.inner_impl();
+            let items: Vec<_> = inner.items.iter().filter(|i| !i.is_stripped()).collect();
+            if items.is_empty() {
+                continue;
+            }
+            let header = self.printer.impl_header(inner);
+            let title = format!("Implementation `{}`", header.replace('\n', " "));
+            let impl_anchor = format!("{}.impl-{}", anchor, index);
+            self.push_with_code(&impl_.impl_item, impl_anchor, 3, title, None);
+            for item in items {
+                let Some(name) = item.name else { continue };
+                let ty = item.type_();
+                let item_anchor = format!("{}.{}.{}", anchor, ty, name);
+                self.push(item, item_anchor, 4, format!("{} `{}`", item_kind_name(ty), name));
+            }
+        }
+
+        let (auto, rest): (Vec<_>, Vec<_>) =
+            trait_impls.iter().map(|i| i.inner_impl()).partition(|i| i.kind.is_auto());
+        let (blanket, normal): (Vec<_>, Vec<_>) =
+            rest.into_iter().partition(|i| i.kind.is_blanket());
+        self.impl_list(anchor, "trait-implementations", "Trait Implementations", &normal);
+        self.impl_list(anchor, "synthetic-implementations", "Auto Trait Implementations", &auto);
+        self.impl_list(anchor, "blanket-implementations", "Blanket Implementations", &blanket);
+    }
+
+    /// Adds a section listing the headers of `impls`, if there are any.
+    fn impl_list(&mut self, anchor: &str, id: &str, title: &str, impls: &[&clean::Impl]) {
+        if impls.is_empty() {
+            return;
+        }
+        let mut headers: Vec<_> = impls.iter().map(|i| self.printer.impl_header(i)).collect();
+        headers.sort();
+        // Keep headers with a where clause apart from each other.
+        let separator = if headers.iter().any(|h| h.contains('\n')) { "\n\n" } else { "\n" };
+        self.sections.push(Section {
+            anchor: format!("{}.{}", anchor, id),
+            level: 3,
+            title: title.to_owned(),
+            code: Some(headers.join(separator)),
+            note: None,
+            docs: String::new(),
+            links: Vec::new(),
+        });
+    }
+
+    fn links(&self, item: &clean::Item) -> Vec<Link> {
+        let Some(links) = self.cache.intra_doc_links.get(&item.item_id) else { return Vec::new() };
+        links
+            .iter()
+            .map(|link| Link {
+                original_text: link.link.to_string(),
+                new_text: link.link_text.to_string(),
+                href: self.href(link.page_id, link.fragment.as_ref()),
+            })
+            .collect()
+    }
+
+    /// Returns where a link to the item with `did`, which has its own page in the HTML output,
+    /// should point to.
+    fn href(&self, did: DefId, fragment: Option<&UrlFragment>) -> Option<String> {
+        let (path, ty) =
+            self.cache.paths.get(&did).or_else(|| self.cache.external_paths.get(&did))?;
+        let mut rendered_fragment = String::new();
+        if let Some(fragment) = fragment {
+            fragment.render(&mut rendered_fragment, self.tcx);
+        }
+
+        let location = if did.is_local() {
+            &ExternalLocation::Local
+        } else {
+            self.cache.extern_locations.get(&did.krate)?
+        };
+        match location {
+            ExternalLocation::Local => {
+                let mut anchor = anchor(*ty, path);
+                // Fragments written by users point to headings in the HTML output, which have no
+                // counterpart here, so the link goes to the item instead.
+                if let Some(UrlFragment::Item(_)) = fragment {
+                    anchor.push('.');
+                    anchor.push_str(&rendered_fragment[1..]);
+                }
+                if did.is_local() {
+                    Some(format!("#{}", anchor))
+                } else {
+                    Some(format!("{}.{}#{}", path[0], self.extension, anchor))
+                }
+            }
+            ExternalLocation::Remote(root) => {
+                let mut url = root.clone();
+                if !url.ends_with('/') {
+                    url.push('/');
+                }
+                let (name, modules) = path.split_last()?;
+                for module in modules {
+                    url.push_str(module.as_str());
+                    url.push('/');
+                }
+                if *ty == ItemType::Module {
+                    url.push_str(name.as_str());
+                    url.push_str("/index.html");
+                } else {
+                    url.push_str(&format!("{}.{}.html", ty, name));
+                }
+                url.push_str(&rendered_fragment);
+                Some(url)
+            }
+            ExternalLocation::Unknown => None,
+        }
+    }
+}
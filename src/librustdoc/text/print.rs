@@ -0,0 +1,631 @@
+//! Plain-text printing of item declarations, for the text-based backends.
+//!
+//! This mirrors what `html::format` does with the alternate (`{:#}`) flag, but without needing an
+//! HTML rendering context.
+
+use std::fmt::Write;
+
+use rustc_hir as hir;
+use rustc_hir::def::CtorKind;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::symbol::kw;
+use rustc_span::Symbol;
+
+use crate::clean::{self, PrimitiveType, SelfTy};
+use crate::html::format::{
+    print_constness_with_space, visibility_to_src_with_space, PrintWithSpace,
+};
+
+#[derive(Clone, Copy)]
+pub(super) struct Printer<'tcx> {
+    pub(super) tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> Printer<'tcx> {
+    /// Returns the Rust declaration of `item`, without bodies, or `None` for items that don't
+    /// have one (such as modules, impls and primitives).
+    pub(super) fn declaration(&self, item: &clean::Item) -> Option<String> {
+        let name = item.name?;
+        let mut s = self.visibility(item);
+        match *item.kind {
+            clean::StructItem(ref struct_) => {
+                write!(s, "struct {}{}", name, self.generic_params(&struct_.generics.params))
+                    .unwrap();
+                match struct_.ctor_kind {
+                    None => {
+                        s.push_str(&self.where_clause(&struct_.generics));
+                        open_block(&mut s);
+                        s.push_str(&self.fields_block(&struct_.fields, item.is_non_exhaustive()));
+                    }
+                    Some(CtorKind::Fn) => {
+                        s.push_str(&self.tuple_fields(&struct_.fields));
+                        s.push_str(&self.where_clause(&struct_.generics));
+                        s.push(';');
+                    }
+                    Some(CtorKind::Const) => {
+                        s.push_str(&self.where_clause(&struct_.generics));
+                        s.push(';');
+                    }
+                }
+            }
+            clean::UnionItem(ref union_) => {
+                write!(s, "union {}{}", name, self.generic_params(&union_.generics.params))
+                    .unwrap();
+                s.push_str(&self.where_clause(&union_.generics));
+                open_block(&mut s);
+                s.push_str(&self.fields_block(&union_.fields, false));
+            }
+            clean::EnumItem(ref enum_) => {
+                write!(s, "enum {}{}", name, self.generic_params(&enum_.generics.params)).unwrap();
+                s.push_str(&self.where_clause(&enum_.generics));
+                open_block(&mut s);
+                s.push('\n');
+                for variant in enum_.variants() {
+                    if let Some(decl) = self.declaration(variant) {
+                        writeln!(s, "    {},", decl).unwrap();
+                    }
+                }
+                if enum_.has_stripped_entries() {
+                    s.push_str("    // some variants omitted\n");
+                }
+                s.push('}');
+            }
+            clean::VariantItem(ref variant) => {
+                s.push_str(name.as_str());
+                match variant.kind {
+                    clean::VariantKind::CLike => {}
+                    clean::VariantKind::Tuple(ref fields) => s.push_str(&self.tuple_fields(fields)),
+                    clean::VariantKind::Struct(ref struct_) => {
+                        let fields: Vec<_> =
+                            struct_.fields.iter().filter_map(|f| self.declaration(f)).collect();
+                        write!(s, " {{ {} }}", fields.join(", ")).unwrap();
+                    }
+                }
+            }
+            clean::StructFieldItem(ref ty) => write!(s, "{}: {}", name, self.ty(ty)).unwrap(),
+            clean::FunctionItem(ref f)
+            | clean::MethodItem(ref f, _)
+            | clean::TyMethodItem(ref f)
+            | clean::ForeignFunctionItem(ref f) => {
+                let header = item.fn_header(self.tcx).expect("function items have a header");
+                s.push_str(print_constness_with_space(
+                    &header.constness,
+                    item.const_stability(self.tcx),
+                ));
+                s.push_str(header.asyncness.print_with_space());
+                s.push_str(header.unsafety.print_with_space());
+                s.push_str(&abi(header.abi));
+                write!(s, "fn {}{}", name, self.generic_params(&f.generics.params)).unwrap();
+                s.push_str(&self.fn_args(&f.decl));
+                if let clean::FnRetTy::Return(ref ty) = f.decl.output {
+                    write!(s, " -> {}", self.ty(ty)).unwrap();
+                }
+                s.push_str(&self.where_clause(&f.generics));
+            }
+            clean::TypedefItem(ref typedef) => {
+                write!(
+                    s,
+                    "type {}{}{} = {};",
+                    name,
+                    self.generic_params(&typedef.generics.params),
+                    self.where_clause(&typedef.generics),
+                    self.ty(&typedef.type_)
+                )
+                .unwrap();
+            }
+            clean::OpaqueTyItem(ref opaque) => {
+                write!(
+                    s,
+                    "type {}{}{} = impl {};",
+                    name,
+                    self.generic_params(&opaque.generics.params),
+                    self.where_clause(&opaque.generics),
+                    self.bounds(&opaque.bounds)
+                )
+                .unwrap();
+            }
+            clean::StaticItem(ref static_) | clean::ForeignStaticItem(ref static_) => {
+                write!(
+                    s,
+                    "static {}{}: {};",
+                    static_.mutability.print_with_space(),
+                    name,
+                    self.ty(&static_.type_)
+                )
+                .unwrap();
+            }
+            clean::ConstantItem(ref constant) => {
+                write!(
+                    s,
+                    "const {}: {} = {};",
+                    name,
+                    self.ty(&constant.type_),
+                    constant.expr(self.tcx)
+                )
+                .unwrap();
+            }
+            clean::TraitItem(ref trait_) => {
+                s.push_str(trait_.unsafety(self.tcx).print_with_space());
+                if trait_.is_auto(self.tcx) {
+                    s.push_str("auto ");
+                }
+                write!(s, "trait {}{}", name, self.generic_params(&trait_.generics.params))
+                    .unwrap();
+                if !trait_.bounds.is_empty() {
+                    write!(s, ": {}", self.bounds(&trait_.bounds)).unwrap();
+                }
+                s.push_str(&self.where_clause(&trait_.generics));
+                open_block(&mut s);
+                if trait_.items.is_empty() {
+                    s.push('}');
+                } else {
+                    s.push('\n');
+                    for trait_item in &trait_.items {
+                        let Some(decl) = self.declaration(trait_item) else { continue };
+                        let decl = decl.replace('\n', "\n    ");
+                        if decl.ends_with(';') {
+                            writeln!(s, "    {}", decl).unwrap();
+                        } else if matches!(*trait_item.kind, clean::MethodItem(..)) {
+                            writeln!(s, "    {} {{ ... }}", decl).unwrap();
+                        } else {
+                            writeln!(s, "    {};", decl).unwrap();
+                        }
+                    }
+                    s.push('}');
+                }
+            }
+            clean::TraitAliasItem(ref alias) => {
+                write!(
+                    s,
+                    "trait {}{} = {}{};",
+                    name,
+                    self.generic_params(&alias.generics.params),
+                    self.bounds(&alias.bounds),
+                    self.where_clause(&alias.generics)
+                )
+                .unwrap();
+            }
+            clean::TyAssocConstItem(ref ty) => {
+                write!(s, "const {}: {};", name, self.ty(ty)).unwrap();
+            }
+            clean::AssocConstItem(ref ty, ref default) => {
+                write!(s, "const {}: {} = {};", name, self.ty(ty), default.expr(self.tcx)).unwrap();
+            }
+            clean::TyAssocTypeItem(ref generics, ref bounds) => {
+                write!(s, "type {}{}", name, self.generic_params(&generics.params)).unwrap();
+                if !bounds.is_empty() {
+                    write!(s, ": {}", self.bounds(bounds)).unwrap();
+                }
+                write!(s, "{};", self.where_clause(generics)).unwrap();
+            }
+            clean::AssocTypeItem(ref typedef, _) => {
+                write!(
+                    s,
+                    "type {}{}{} = {};",
+                    name,
+                    self.generic_params(&typedef.generics.params),
+                    self.where_clause(&typedef.generics),
+                    self.ty(&typedef.type_)
+                )
+                .unwrap();
+            }
+            clean::ForeignTypeItem => write!(s, "type {};", name).unwrap(),
+            clean::MacroItem(ref macro_) => return Some(macro_.source.clone()),
+            clean::ProcMacroItem(ref proc_macro) => {
+                return Some(match proc_macro.kind {
+                    rustc_span::hygiene::MacroKind::Bang => {
+                        format!("{}!() {{ /* proc-macro */ }}", name)
+                    }
+                    rustc_span::hygiene::MacroKind::Attr => format!("#[{}]", name),
+                    rustc_span::hygiene::MacroKind::Derive => {
+                        let mut s = format!("#[derive({})]", name);
+                        if !proc_macro.helpers.is_empty() {
+                            s.push_str("\n\n// Helper attributes:");
+                            for helper in &proc_macro.helpers {
+                                write!(s, "\n#[{}]", helper).unwrap();
+                            }
+                        }
+                        s
+                    }
+                });
+            }
+            clean::ExternCrateItem { .. }
+            | clean::ImportItem(_)
+            | clean::ModuleItem(_)
+            | clean::ImplItem(_)
+            | clean::PrimitiveItem(_)
+            | clean::StrippedItem(_)
+            | clean::KeywordItem => return None,
+        }
+        Some(s)
+    }
+
+    pub(super) fn import(&self, item: &clean::Item, import: &clean::Import) -> String {
+        let mut s = self.visibility(item);
+        write!(s, "use {}", self.path(&import.source.path)).unwrap();
+        match import.kind {
+            clean::ImportKind::Simple(name) => {
+                if import.source.path.last_opt() != Some(name) {
+                    write!(s, " as {}", name).unwrap();
+                }
+            }
+            clean::ImportKind::Glob => s.push_str("::*"),
+        }
+        s.push(';');
+        s
+    }
+
+    fn visibility(&self, item: &clean::Item) -> String {
+        match item.item_id.as_def_id() {
+            Some(def_id) => {
+                visibility_to_src_with_space(item.visibility(self.tcx), self.tcx, def_id)
+                    .to_string()
+            }
+            None => String::new(),
+        }
+    }
+
+    fn fields_block(&self, fields: &[clean::Item], non_exhaustive: bool) -> String {
+        let mut s = String::from("\n");
+        for field in fields {
+            if let Some(decl) = self.declaration(field) {
+                writeln!(s, "    {},", decl).unwrap();
+            }
+        }
+        if fields.iter().any(|f| f.is_stripped()) {
+            s.push_str("    /* private fields */\n");
+        } else if non_exhaustive {
+            s.push_str("    /* non-exhaustive */\n");
+        }
+        s.push('}');
+        s
+    }
+
+    fn tuple_fields(&self, fields: &[clean::Item]) -> String {
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|field| match *field.kind {
+                clean::StructFieldItem(ref ty) => {
+                    format!("{}{}", self.visibility(field), self.ty(ty))
+                }
+                _ => String::from("_"),
+            })
+            .collect();
+        format!("({})", fields.join(", "))
+    }
+
+    fn fn_args(&self, decl: &clean::FnDecl) -> String {
+        let mut args: Vec<_> = decl
+            .inputs
+            .values
+            .iter()
+            .map(|arg| match arg.to_self() {
+                Some(SelfTy::SelfValue) => String::from("self"),
+                Some(SelfTy::SelfBorrowed(lifetime, mutability)) => {
+                    let mut s = String::from("&");
+                    if let Some(lifetime) = lifetime {
+                        write!(s, "{} ", lifetime.0).unwrap();
+                    }
+                    write!(s, "{}self", mutability.print_with_space()).unwrap();
+                    s
+                }
+                Some(SelfTy::SelfExplicit(ref ty)) => format!("self: {}", self.ty(ty)),
+                None if arg.is_const => format!("const {}: {}", arg.name, self.ty(&arg.type_)),
+                None => format!("{}: {}", arg.name, self.ty(&arg.type_)),
+            })
+            .collect();
+        if decl.c_variadic {
+            args.push(String::from("..."));
+        }
+        format!("({})", args.join(", "))
+    }
+
+    fn fn_decl(&self, decl: &clean::FnDecl) -> String {
+        let mut inputs: Vec<_> = decl.inputs.values.iter().map(|arg| self.ty(&arg.type_)).collect();
+        if decl.c_variadic {
+            inputs.push(String::from("..."));
+        }
+        let mut s = format!("({})", inputs.join(", "));
+        if let clean::FnRetTy::Return(ref ty) = decl.output {
+            write!(s, " -> {}", self.ty(ty)).unwrap();
+        }
+        s
+    }
+
+    pub(super) fn impl_header(&self, impl_: &clean::Impl) -> String {
+        let mut s = String::new();
+        s.push_str(impl_.unsafety.print_with_space());
+        write!(s, "impl{} ", self.generic_params(&impl_.generics.params)).unwrap();
+        if let Some(ref trait_) = impl_.trait_ {
+            if impl_.polarity == rustc_middle::ty::ImplPolarity::Negative {
+                s.push('!');
+            }
+            write!(s, "{} for ", self.path(trait_)).unwrap();
+        }
+        match impl_.kind.as_blanket_ty() {
+            Some(ty) => s.push_str(&self.ty(ty)),
+            None => s.push_str(&self.ty(&impl_.for_)),
+        }
+        s.push_str(&self.where_clause(&impl_.generics));
+        s
+    }
+
+    pub(super) fn ty(&self, ty: &clean::Type) -> String {
+        match *ty {
+            clean::Type::Path { ref path } => self.path(path),
+            clean::Type::DynTrait(ref traits, ref lifetime) => {
+                let mut bounds: Vec<_> = traits.iter().map(|t| self.poly_trait(t)).collect();
+                bounds.extend(lifetime.iter().map(|l| l.0.to_string()));
+                format!("dyn {}", bounds.join(" + "))
+            }
+            clean::Type::Generic(name) => name.to_string(),
+            clean::Type::Primitive(PrimitiveType::Never) => String::from("!"),
+            clean::Type::Primitive(PrimitiveType::Unit) => String::from("()"),
+            clean::Type::Primitive(prim) => prim.as_sym().to_string(),
+            clean::Type::BareFunction(ref f) => {
+                format!(
+                    "{}{}{}fn{}",
+                    self.for_lifetimes(&f.generic_params),
+                    f.unsafety.print_with_space(),
+                    abi(f.abi),
+                    self.fn_decl(&f.decl)
+                )
+            }
+            clean::Type::Tuple(ref types) if types.len() == 1 => {
+                format!("({},)", self.ty(&types[0]))
+            }
+            clean::Type::Tuple(ref types) => {
+                let types: Vec<_> = types.iter().map(|t| self.ty(t)).collect();
+                format!("({})", types.join(", "))
+            }
+            clean::Type::Slice(ref ty) => format!("[{}]", self.ty(ty)),
+            clean::Type::Array(ref ty, ref len) => format!("[{}; {}]", self.ty(ty), len),
+            clean::Type::RawPointer(mutability, ref ty) => {
+                let m = if mutability == hir::Mutability::Mut { "mut" } else { "const" };
+                format!("*{} {}", m, self.ty(ty))
+            }
+            clean::Type::BorrowedRef { ref lifetime, mutability, ref type_ } => {
+                let mut s = String::from("&");
+                if let Some(lifetime) = lifetime {
+                    write!(s, "{} ", lifetime.0).unwrap();
+                }
+                write!(s, "{}{}", mutability.print_with_space(), self.ty(type_)).unwrap();
+                s
+            }
+            clean::Type::QPath(ref qpath) => {
+                let mut s = match qpath.trait_ {
+                    Some(ref trait_) if qpath.should_show_cast => {
+                        format!("<{} as {}>", self.ty(&qpath.self_type), self.path(trait_))
+                    }
+                    _ => self.ty(&qpath.self_type),
+                };
+                write!(s, "::{}", qpath.assoc.name).unwrap();
+                s.push_str(&self.generic_args(&qpath.assoc.args));
+                s
+            }
+            clean::Type::Infer => String::from("_"),
+            clean::Type::ImplTrait(ref bounds) => format!("impl {}", self.bounds(bounds)),
+        }
+    }
+
+    fn path(&self, path: &clean::Path) -> String {
+        let segments: Vec<_> = path
+            .segments
+            .iter()
+            .map(|segment| {
+                let name = if segment.name == kw::PathRoot { "" } else { segment.name.as_str() };
+                format!("{}{}", name, self.generic_args(&segment.args))
+            })
+            .collect();
+        segments.join("::")
+    }
+
+    fn generic_args(&self, args: &clean::GenericArgs) -> String {
+        match *args {
+            clean::GenericArgs::AngleBracketed { ref args, ref bindings } => {
+                if args.is_empty() && bindings.is_empty() {
+                    return String::new();
+                }
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|arg| match *arg {
+                        clean::GenericArg::Lifetime(ref lifetime) => lifetime.0.to_string(),
+                        clean::GenericArg::Type(ref ty) => self.ty(ty),
+                        clean::GenericArg::Const(ref constant) => constant.expr(self.tcx),
+                        clean::GenericArg::Infer => String::from("_"),
+                    })
+                    .chain(bindings.iter().map(|binding| self.type_binding(binding)))
+                    .collect();
+                format!("<{}>", args.join(", "))
+            }
+            clean::GenericArgs::Parenthesized { ref inputs, ref output } => {
+                let inputs: Vec<_> = inputs.iter().map(|ty| self.ty(ty)).collect();
+                let mut s = format!("({})", inputs.join(", "));
+                if let Some(output) = output {
+                    write!(s, " -> {}", self.ty(output)).unwrap();
+                }
+                s
+            }
+        }
+    }
+
+    fn type_binding(&self, binding: &clean::TypeBinding) -> String {
+        let mut s = format!("{}{}", binding.assoc.name, self.generic_args(&binding.assoc.args));
+        match binding.kind {
+            clean::TypeBindingKind::Equality { ref term } => {
+                write!(s, " = {}", self.term(term)).unwrap()
+            }
+            clean::TypeBindingKind::Constraint { ref bounds } => {
+                if !bounds.is_empty() {
+                    write!(s, ": {}", self.bounds(bounds)).unwrap()
+                }
+            }
+        }
+        s
+    }
+
+    fn term(&self, term: &clean::Term) -> String {
+        match *term {
+            clean::Term::Type(ref ty) => self.ty(ty),
+            clean::Term::Constant(ref constant) => constant.expr(self.tcx),
+        }
+    }
+
+    fn poly_trait(&self, poly: &clean::PolyTrait) -> String {
+        format!("{}{}", self.for_lifetimes(&poly.generic_params), self.path(&poly.trait_))
+    }
+
+    fn bound(&self, bound: &clean::GenericBound) -> String {
+        match *bound {
+            clean::GenericBound::TraitBound(ref poly, modifier) => {
+                let modifier = match modifier {
+                    hir::TraitBoundModifier::None => "",
+                    hir::TraitBoundModifier::Maybe => "?",
+                    hir::TraitBoundModifier::Negative => "!",
+                    hir::TraitBoundModifier::MaybeConst => "~const ",
+                };
+                format!("{}{}", modifier, self.poly_trait(poly))
+            }
+            clean::GenericBound::Outlives(ref lifetime) => lifetime.0.to_string(),
+        }
+    }
+
+    fn bounds(&self, bounds: &[clean::GenericBound]) -> String {
+        let bounds: Vec<_> = bounds.iter().map(|b| self.bound(b)).collect();
+        bounds.join(" + ")
+    }
+
+    fn for_lifetimes(&self, params: &[clean::GenericParamDef]) -> String {
+        if params.is_empty() {
+            String::new()
+        } else {
+            let params: Vec<_> = params.iter().map(|p| self.generic_param(p)).collect();
+            format!("for<{}> ", params.join(", "))
+        }
+    }
+
+    fn generic_param(&self, param: &clean::GenericParamDef) -> String {
+        match param.kind {
+            clean::GenericParamDefKind::Lifetime { ref outlives } => {
+                let mut s = param.name.to_string();
+                if !outlives.is_empty() {
+                    let outlives: Vec<_> = outlives.iter().map(|l| l.0.as_str()).collect();
+                    write!(s, ": {}", outlives.join(" + ")).unwrap();
+                }
+                s
+            }
+            clean::GenericParamDefKind::Type { ref bounds, ref default, .. } => {
+                let mut s = param.name.to_string();
+                if !bounds.is_empty() {
+                    write!(s, ": {}", self.bounds(bounds)).unwrap();
+                }
+                if let Some(default) = default {
+                    write!(s, " = {}", self.ty(default)).unwrap();
+                }
+                s
+            }
+            clean::GenericParamDefKind::Const { ref ty, ref default } => {
+                let mut s = format!("const {}: {}", param.name, self.ty(ty));
+                if let Some(default) = default {
+                    write!(s, " = {}", default).unwrap();
+                }
+                s
+            }
+        }
+    }
+
+    /// Prints the `<...>` list of generic parameters, leaving out the ones introduced by
+    /// `impl Trait` arguments.
+    fn generic_params(&self, params: &[clean::GenericParamDef]) -> String {
+        let params: Vec<_> = params
+            .iter()
+            .filter(|p| !p.is_synthetic_type_param())
+            .map(|p| self.generic_param(p))
+            .collect();
+        if params.is_empty() { String::new() } else { format!("<{}>", params.join(", ")) }
+    }
+
+    fn where_clause(&self, generics: &clean::Generics) -> String {
+        if generics.where_predicates.is_empty() {
+            return String::new();
+        }
+        let mut s = String::from("\nwhere");
+        for predicate in &generics.where_predicates {
+            let predicate = match *predicate {
+                clean::WherePredicate::BoundPredicate { ref ty, ref bounds, ref bound_params } => {
+                    format!(
+                        "{}{}: {}",
+                        self.for_lifetimes(bound_params),
+                        self.ty(ty),
+                        self.bounds(bounds)
+                    )
+                }
+                clean::WherePredicate::RegionPredicate { ref lifetime, ref bounds } => {
+                    format!("{}: {}", lifetime.0, self.bounds(bounds))
+                }
+                clean::WherePredicate::EqPredicate { ref lhs, ref rhs, ref bound_params } => {
+                    format!(
+                        "{}{} == {}",
+                        self.for_lifetimes(bound_params),
+                        self.ty(lhs),
+                        self.term(rhs)
+                    )
+                }
+            };
+            write!(s, "\n    {},", predicate).unwrap();
+        }
+        s
+    }
+}
+
+/// Opens a `{ ... }` block after a declaration, on a new line if it ends with a `where` clause.
+fn open_block(s: &mut String) {
+    s.push_str(if s.ends_with(',') { "\n{" } else { " {" });
+}
+
+fn abi(abi: rustc_target::spec::abi::Abi) -> String {
+    match abi {
+        rustc_target::spec::abi::Abi::Rust => String::new(),
+        abi => format!("extern \"{}\" ", abi.name()),
+    }
+}
+
+/// The name of the section kind for items of type `ty`, as used in headings.
+pub(super) fn item_kind_name(ty: crate::formats::item_type::ItemType) -> &'static str {
+    use crate::formats::item_type::ItemType;
+
+    match ty {
+        ItemType::Module => "Module",
+        ItemType::ExternCrate => "Extern Crate",
+        ItemType::Import => "Re-export",
+        ItemType::Struct => "Struct",
+        ItemType::Union => "Union",
+        ItemType::Enum => "Enum",
+        ItemType::Function => "Function",
+        ItemType::Typedef => "Type Alias",
+        ItemType::Static => "Static",
+        ItemType::Trait => "Trait",
+        ItemType::Impl => "Implementation",
+        ItemType::TyMethod => "Required Method",
+        ItemType::Method => "Method",
+        ItemType::StructField => "Field",
+        ItemType::Variant => "Variant",
+        ItemType::Macro => "Macro",
+        ItemType::Primitive => "Primitive Type",
+        ItemType::AssocType => "Associated Type",
+        ItemType::Constant => "Constant",
+        ItemType::AssocConst => "Associated Constant",
+        ItemType::ForeignType => "Foreign Type",
+        ItemType::Keyword => "Keyword",
+        ItemType::OpaqueTy => "Opaque Type",
+        ItemType::ProcAttribute => "Attribute Macro",
+        ItemType::ProcDerive => "Derive Macro",
+        ItemType::TraitAlias => "Trait Alias",
+    }
+}
+
+/// Joins the segments of an item path with `::`.
+pub(super) fn join_path(path: &[Symbol]) -> String {
+    let path: Vec<_> = path.iter().map(|s| s.as_str()).collect();
+    path.join("::")
+}
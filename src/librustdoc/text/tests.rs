@@ -0,0 +1,75 @@
+use super::{man, markdown, Link};
+
+fn link(original_text: &str, new_text: &str, href: Option<&str>) -> Link {
+    Link {
+        original_text: original_text.to_owned(),
+        new_text: new_text.to_owned(),
+        href: href.map(str::to_owned),
+    }
+}
+
+fn roff(md: &str, links: &[Link]) -> String {
+    let mut roff = man::Roff::default();
+    man::docs(&mut roff, md, links);
+    roff.out
+}
+
+#[test]
+fn markdown_intra_doc_links() {
+    let links = [
+        link("Foo", "Foo", Some("#struct.krate.Foo")),
+        link("`struct@Bar`", "`Bar`", Some("other.md#struct.other.Bar")),
+        link("Baz", "Baz", None),
+    ];
+    assert_eq!(
+        markdown::docs("See [Foo], [`struct@Bar`] and [Baz].", &links, 2),
+        "See [Foo](#struct.krate.Foo), [`Bar`](other.md#struct.other.Bar) and Baz.",
+    );
+    assert_eq!(
+        markdown::docs("A [link](Foo) and [another](https://example.com).", &links, 2),
+        "A [link](#struct.krate.Foo) and [another](https://example.com).",
+    );
+}
+
+#[test]
+fn markdown_headings() {
+    assert_eq!(markdown::docs("# Examples\n\nText", &[], 2), "### Examples\n\nText");
+    assert_eq!(markdown::docs("#### Deep\n", &[], 4), "###### Deep\n");
+    assert_eq!(markdown::docs("Examples\n========\n\nText", &[], 2), "### Examples\n\nText");
+}
+
+#[test]
+fn markdown_code_blocks() {
+    assert_eq!(
+        markdown::docs("```\n# fn main() {\nlet x = 1;\n# }\n```\n", &[], 2),
+        "```rust\nlet x = 1;\n```\n",
+    );
+    assert_eq!(
+        markdown::docs("```text\n# not hidden\n```\n", &[], 2),
+        "```text\n# not hidden\n```\n",
+    );
+    assert_eq!(
+        markdown::docs("- item\n\n  ```\n  # hidden\n  shown\n  ```\n", &[], 2),
+        "- item\n\n  ```rust\n  shown\n  ```\n",
+    );
+}
+
+#[test]
+fn man_escaping() {
+    assert_eq!(roff("a-b \\ c", &[]), ".PP\na\\-b \\e c\n");
+    assert_eq!(
+        roff("```text\n.TH x\n'quote\n```", &[]),
+        ".PP\n.RS 4\n.nf\n\\&.TH x\n\\&'quote\n.fi\n.RE\n"
+    );
+}
+
+#[test]
+fn man_markup() {
+    let links = [link("Foo", "Foo", Some("#struct.krate.Foo"))];
+    assert_eq!(
+        roff("*a* **b** `c`, [Foo] and [site](https://example.com)", &links),
+        ".PP\n\\fIa\\fR \\fBb\\fR \\fBc\\fR, Foo and site <https://example.com>\n",
+    );
+    assert_eq!(roff("- one\n- two\n", &[]), ".IP \\(bu 2\none\n.IP \\(bu 2\ntwo\n");
+    assert_eq!(roff("```\n# hidden\nshown\n```", &[]), ".PP\n.RS 4\n.nf\nshown\n.fi\n.RE\n");
+}
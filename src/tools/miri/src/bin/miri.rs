@@ -494,6 +494,24 @@ fn main() {
                     ),
             };
             miri_config.preemption_rate = rate;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-coverage=") {
+            miri_config.coverage = Some(param.into());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-record-schedule=") {
+            miri_config.record_schedule = Some(param.into());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay-schedule=") {
+            let schedule = match miri::Schedule::read(param.as_ref()) {
+                Ok(schedule) => schedule,
+                Err(err) => show_error!("-Zmiri-replay-schedule cannot read `{}`: {}", param, err),
+            };
+            miri_config.replay_schedule = Some(schedule);
+        } else if arg == "-Zmiri-minimize-schedule" {
+            miri_config.minimize_schedule = true;
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-explore-schedules=") {
+            let bound = match param.parse::<usize>() {
+                Ok(bound) => bound,
+                Err(err) => show_error!("-Zmiri-explore-schedules requires a `usize`: {}", err),
+            };
+            miri_config.explore_schedules = Some(bound);
         } else if arg == "-Zmiri-report-progress" {
             // This makes it take a few seconds between progress reports on my laptop.
             miri_config.report_progress = Some(1_000_000);
@@ -556,6 +574,23 @@ fn main() {
         }
     }
 
+    if miri_config.explore_schedules.is_some() {
+        if miri_config.replay_schedule.is_some() {
+            show_error!(
+                "-Zmiri-explore-schedules cannot be used along with -Zmiri-replay-schedule"
+            );
+        }
+        if !miri_config.data_race_detector {
+            show_error!(
+                "-Zmiri-explore-schedules cannot be used along with -Zmiri-disable-data-race-detector"
+            );
+        }
+    } else if miri_config.minimize_schedule && miri_config.replay_schedule.is_none() {
+        show_error!(
+            "-Zmiri-minimize-schedule requires -Zmiri-replay-schedule or -Zmiri-explore-schedules"
+        );
+    }
+
     debug!("rustc arguments: {:?}", rustc_args);
     debug!("crate arguments: {:?}", miri_config.args);
     run_compiler(rustc_args, /* target_crate: */ true, &mut MiriCompilerCalls { miri_config })
@@ -1239,6 +1239,12 @@ pub struct GlobalState {
 
     /// Track when an outdated (weak memory) load happens.
     pub track_outdated_loads: bool,
+
+    /// Set whenever a thread performs an operation that synchronizes with other threads (an
+    /// atomic access, a fence, a lock operation, or creating or joining a thread), and reset by
+    /// the scheduler. When exploring schedules, these are the only points after which preempting
+    /// a thread can make a difference.
+    synchronized: Cell<bool>,
 }
 
 impl VisitTags for GlobalState {
@@ -1262,6 +1268,7 @@ pub fn new(config: &MiriConfig) -> Self {
             last_sc_fence: RefCell::new(VClock::default()),
             last_sc_write: RefCell::new(VClock::default()),
             track_outdated_loads: config.track_outdated_loads,
+            synchronized: Cell::new(false),
         };
 
         // Setup the main-thread since it is not explicitly created:
@@ -1336,6 +1343,7 @@ pub fn thread_created(
         // Enable multi-threaded execution, there are now at least two threads
         // so data-races are now possible.
         self.multi_threaded.set(true);
+        self.synchronized.set(true);
 
         // Load and setup the associated thread metadata
         let mut thread_info = self.thread_info.borrow_mut();
@@ -1401,6 +1409,7 @@ pub fn thread_joined(
         joiner: ThreadId,
         joinee: ThreadId,
     ) {
+        self.synchronized.set(true);
         let clocks_vec = self.vector_clocks.get_mut();
         let thread_info = self.thread_info.get_mut();
 
@@ -1493,6 +1502,7 @@ fn maybe_perform_sync_operation<'tcx>(
         op: impl FnOnce(VectorIdx, RefMut<'_, ThreadClockSet>) -> InterpResult<'tcx, bool>,
     ) -> InterpResult<'tcx> {
         if self.multi_threaded.get() {
+            self.synchronized.set(true);
             let (index, clocks) = self.current_thread_state_mut(thread_mgr);
             if op(index, clocks)? {
                 let (_, mut clocks) = self.current_thread_state_mut(thread_mgr);
@@ -1519,6 +1529,7 @@ fn print_thread_metadata(
     /// As this is an acquire operation, the thread timestamp is not
     /// incremented.
     pub fn validate_lock_acquire(&self, lock: &VClock, thread: ThreadId) {
+        self.synchronized.set(true);
         let (_, mut clocks) = self.load_thread_state_mut(thread);
         clocks.clock.join(lock);
     }
@@ -1530,6 +1541,7 @@ pub fn validate_lock_acquire(&self, lock: &VClock, thread: ThreadId) {
     /// for futex & condvar operations this is not the case and this
     /// operation must be used.
     pub fn validate_lock_release(&self, lock: &mut VClock, thread: ThreadId, current_span: Span) {
+        self.synchronized.set(true);
         let (index, mut clocks) = self.load_thread_state_mut(thread);
         lock.clone_from(&clocks.clock);
         clocks.increment_clock(index, current_span);
@@ -1548,11 +1560,17 @@ pub fn validate_lock_release_shared(
         thread: ThreadId,
         current_span: Span,
     ) {
+        self.synchronized.set(true);
         let (index, mut clocks) = self.load_thread_state_mut(thread);
         lock.join(&clocks.clock);
         clocks.increment_clock(index, current_span);
     }
 
+    /// Returns whether a synchronizing operation happened since the last call.
+    pub fn take_synchronized(&self) -> bool {
+        self.synchronized.replace(false)
+    }
+
     /// Load the vector index used by the given thread as well as the set of vector clocks
     /// used by the thread.
     #[inline]
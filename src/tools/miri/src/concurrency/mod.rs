@@ -1,8 +1,9 @@
 pub mod data_race;
 mod range_object_map;
+pub mod schedule;
 #[macro_use]
 pub mod sync;
 pub mod init_once;
 pub mod thread;
-mod vector_clock;
+pub mod vector_clock;
 pub mod weak_memory;
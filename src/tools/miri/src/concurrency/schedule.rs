@@ -0,0 +1,373 @@
+//! Recording and replaying the scheduling decisions taken during an execution.
+//!
+//! Most of what happens during an execution is determined by the program, but some decisions are
+//! up to Miri: when the active thread gets preempted and which thread runs instead, and which
+//! store a weak-memory load reads from. By default these are taken randomly (driven by
+//! `-Zmiri-seed`). This module allows writing them to a file and replaying them exactly.
+//!
+//! Decisions are identified by the thread taking them and the number of basic blocks that thread
+//! has executed so far. That way, a decision stays meaningful as long as the execution of its
+//! thread up to that point does not change, even if decisions in other threads are dropped. When
+//! replaying, decision points for which the schedule contains nothing take the default choice:
+//! the active thread keeps running, and loads read from the latest store.
+//!
+//! Instead of taking decisions randomly, they can also be enumerated systematically, in the style
+//! of CHESS: every execution follows a prefix of choices given up front and takes the default
+//! choice at all later decision points, and `ScheduleTracker::next_prefix` then picks the next
+//! prefix in depth-first order. To keep this tractable, threads are only preempted right after
+//! they performed an operation that synchronizes with other threads, and each execution contains
+//! at most a given number of preemptions. Everything else a thread does is only observable by
+//! other threads through a data race, which the data race detector reports no matter how the
+//! threads are interleaved.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::str::FromStr;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_index::IndexVec;
+
+use crate::*;
+
+/// A single decision of the scheduler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// After executing `block` basic blocks, `thread` got preempted in favor of `next`. `next` is
+    /// `thread` itself if there was no other thread to run; that still gives timeouts a chance to
+    /// fire.
+    Preempt { thread: ThreadId, block: u64, next: ThreadId },
+    /// A weak-memory load in the `block`-th basic block of `thread` read from the `index`-th newest
+    /// of the stores it could observe. Loads from the latest store (index 0) are not recorded.
+    Load { thread: ThreadId, block: u64, index: usize },
+}
+
+/// A sequence of scheduler decisions, in the order in which they were taken.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schedule {
+    pub decisions: Vec<Decision>,
+}
+
+impl Schedule {
+    /// Reads a schedule written by `write`.
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        contents.parse()
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// The number of preemptions in this schedule.
+    pub fn preemptions(&self) -> usize {
+        self.decisions.iter().filter(|d| matches!(d, Decision::Preempt { .. })).count()
+    }
+
+    /// Returns this schedule without the preemptions with the given indices.
+    fn without_preemptions(&self, remove: Range<usize>) -> Self {
+        let mut preemptions = 0..;
+        let decisions = self
+            .decisions
+            .iter()
+            .filter(|d| {
+                !matches!(d, Decision::Preempt { .. })
+                    || !remove.contains(&preemptions.next().unwrap())
+            })
+            .copied()
+            .collect();
+        Schedule { decisions }
+    }
+
+    /// Shrinks this schedule to as few preemptions as possible. `run` replays a schedule and
+    /// returns the schedule it actually followed if the execution still failed the same way, or
+    /// `None` otherwise.
+    ///
+    /// This tries to drop preemptions in chunks, halving the chunk size whenever no chunk can be
+    /// dropped, until no single preemption can be dropped any more. The result is not necessarily
+    /// the smallest schedule that makes the execution fail, but usually it is close.
+    pub fn minimize(&self, mut run: impl FnMut(&Schedule) -> Option<Schedule>) -> Schedule {
+        let mut schedule = self.clone();
+        let mut chunk = ((schedule.preemptions() + 1) / 2).max(1);
+        loop {
+            let mut start = 0;
+            let mut dropped_any = false;
+            while start < schedule.preemptions() {
+                match run(&schedule.without_preemptions(start..start.saturating_add(chunk))) {
+                    // The schedule that was followed can be shorter than the candidate if the
+                    // error now happens earlier.
+                    Some(followed) => {
+                        schedule = followed;
+                        dropped_any = true;
+                    }
+                    None => start += chunk,
+                }
+            }
+            if chunk == 1 && !dropped_any {
+                return schedule;
+            }
+            chunk = (chunk + 1) / 2;
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Miri schedule: one scheduler decision per line.")?;
+        writeln!(f, "# `preempt <thread> <block> <next>`: preempt <thread> after it executed")?;
+        writeln!(f, "#     <block> basic blocks, and continue with thread <next>.")?;
+        writeln!(f, "# `load <thread> <block> <index>`: the weak-memory load in the <block>-th")?;
+        writeln!(f, "#     basic block of <thread> reads from the <index>-th newest store it can")?;
+        writeln!(f, "#     observe (0 is the latest).")?;
+        for decision in &self.decisions {
+            match decision {
+                Decision::Preempt { thread, block, next } =>
+                    writeln!(f, "preempt {} {block} {}", thread.to_u32(), next.to_u32())?,
+                Decision::Load { thread, block, index } =>
+                    writeln!(f, "load {} {block} {index}", thread.to_u32())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut decisions = Vec::new();
+        for (line_no, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let err = || format!("line {}: invalid scheduler decision `{line}`", line_no + 1);
+            let thread = |s: &str| s.parse::<u32>().map(ThreadId::from).map_err(|_| err());
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let decision = match words[..] {
+                ["preempt", t, block, next] =>
+                    Decision::Preempt {
+                        thread: thread(t)?,
+                        block: block.parse().map_err(|_| err())?,
+                        next: thread(next)?,
+                    },
+                ["load", t, block, index] =>
+                    Decision::Load {
+                        thread: thread(t)?,
+                        block: block.parse().map_err(|_| err())?,
+                        index: index.parse().map_err(|_| err())?,
+                    },
+                _ => return Err(err()),
+            };
+            decisions.push(decision);
+        }
+        Ok(Schedule { decisions })
+    }
+}
+
+/// The scheduling decisions of the current execution: the ones taken so far, if we record them,
+/// and the ones still to be replayed, if we replay a schedule.
+#[derive(Debug)]
+pub struct ScheduleTracker {
+    recorded: Option<Vec<Decision>>,
+    replay: Option<Replay>,
+    exploration: Option<Exploration>,
+    /// The number of basic blocks each thread has executed. Only counted if we record or replay.
+    blocks: IndexVec<ThreadId, u64>,
+    /// Stop the execution once this many basic blocks have been executed.
+    pub block_limit: Option<u64>,
+}
+
+/// The decisions of a schedule that have not been replayed yet, by thread and basic block.
+#[derive(Debug)]
+struct Replay {
+    preemptions: FxHashMap<(ThreadId, u64), ThreadId>,
+    loads: FxHashMap<(ThreadId, u64), VecDeque<usize>>,
+}
+
+/// The state of a systematic exploration of schedules, see `ScheduleTracker::explore`.
+#[derive(Debug)]
+struct Exploration {
+    /// The choices to take at the first decision points of this execution.
+    prefix: Vec<usize>,
+    /// The choices taken at the decision points of this execution so far, and how many
+    /// alternatives there were at each of them.
+    points: Vec<(usize, usize)>,
+    /// The maximal number of preemptions in an execution.
+    bound: usize,
+    /// The number of preemptions in this execution so far.
+    preemptions: usize,
+    /// The number of points at which a thread could have been preempted, but was not because the
+    /// bound was already reached.
+    pruned: usize,
+}
+
+impl Exploration {
+    /// Takes the choice the prefix says, or the default choice 0 once the prefix is exhausted.
+    fn choose(&mut self, alternatives: usize) -> usize {
+        let choice = self.prefix.get(self.points.len()).map_or(0, |&c| c.min(alternatives - 1));
+        self.points.push((choice, alternatives));
+        choice
+    }
+
+    fn preempt(
+        &mut self,
+        synchronized: bool,
+        others: impl FnOnce() -> Vec<ThreadId>,
+    ) -> Option<ThreadId> {
+        if !synchronized {
+            return None;
+        }
+        let others = others();
+        if others.is_empty() {
+            return None;
+        }
+        if self.preemptions >= self.bound {
+            self.pruned += 1;
+            return None;
+        }
+        // Alternative 0 keeps the active thread running.
+        let next = self.choose(others.len() + 1).checked_sub(1).map(|i| others[i]);
+        if next.is_some() {
+            self.preemptions += 1;
+        }
+        next
+    }
+}
+
+impl ScheduleTracker {
+    pub fn new(record: bool, replay: Option<&Schedule>) -> Self {
+        let replay = replay.map(|schedule| {
+            let mut preemptions = FxHashMap::default();
+            let mut loads: FxHashMap<_, VecDeque<_>> = FxHashMap::default();
+            for decision in &schedule.decisions {
+                match *decision {
+                    Decision::Preempt { thread, block, next } => {
+                        preemptions.insert((thread, block), next);
+                    }
+                    Decision::Load { thread, block, index } =>
+                        loads.entry((thread, block)).or_default().push_back(index),
+                }
+            }
+            Replay { preemptions, loads }
+        });
+        ScheduleTracker {
+            recorded: record.then(Vec::new),
+            replay,
+            exploration: None,
+            blocks: IndexVec::new(),
+            block_limit: None,
+        }
+    }
+
+    /// Makes this execution take the choices in `prefix` at its first decision points, and the
+    /// default choice afterwards, preempting threads at most `bound` times. Also records the
+    /// decisions, so that the execution can be replayed.
+    pub fn explore(&mut self, bound: usize, prefix: Vec<usize>) {
+        assert!(self.replay.is_none(), "cannot explore schedules while replaying one");
+        self.recorded.get_or_insert_with(Vec::new);
+        self.exploration =
+            Some(Exploration { prefix, points: Vec::new(), bound, preemptions: 0, pruned: 0 });
+    }
+
+    /// After an execution started with `explore`, returns the prefix of the next execution to
+    /// explore, or `None` if all executions within the preemption bound have been explored.
+    pub fn next_prefix(&self) -> Option<Vec<usize>> {
+        let points = &self.exploration.as_ref()?.points;
+        let last = points.iter().rposition(|&(choice, alternatives)| choice + 1 < alternatives)?;
+        let mut prefix: Vec<usize> = points[..last].iter().map(|&(choice, _)| choice).collect();
+        prefix.push(points[last].0 + 1);
+        Some(prefix)
+    }
+
+    /// The number of decision points with more than one alternative in this execution.
+    pub fn decision_points(&self) -> usize {
+        self.exploration.as_ref().map_or(0, |exploration| exploration.points.len())
+    }
+
+    /// The number of points in this execution at which a thread was not preempted because the
+    /// preemption bound was reached.
+    pub fn pruned_preemptions(&self) -> usize {
+        self.exploration.as_ref().map_or(0, |exploration| exploration.pruned)
+    }
+
+    /// Returns the decisions recorded so far.
+    pub fn recorded(&self) -> Schedule {
+        Schedule { decisions: self.recorded.clone().unwrap_or_default() }
+    }
+
+    /// Notes that `thread` reached the end of another basic block.
+    pub fn count_block(&mut self, thread: ThreadId) {
+        if self.recorded.is_some() || self.replay.is_some() {
+            *self.blocks.ensure_contains_elem(thread, || 0) += 1;
+        }
+    }
+
+    fn block(&self, thread: ThreadId) -> u64 {
+        self.blocks.get(thread).copied().unwrap_or(0)
+    }
+
+    /// Decides whether to preempt `thread` at the end of its current basic block, and in favor of
+    /// which thread. `random` says whether to preempt when neither replaying nor exploring
+    /// schedules, `synchronized` whether the current basic block synchronized with other threads,
+    /// and `others` lists the other enabled threads in round-robin order.
+    pub fn preempt(
+        &mut self,
+        thread: ThreadId,
+        random: bool,
+        synchronized: bool,
+        others: impl FnOnce() -> Vec<ThreadId>,
+    ) -> Option<ThreadId> {
+        let block = self.block(thread);
+        let next = if let Some(replay) = &mut self.replay {
+            replay.preemptions.remove(&(thread, block))
+        } else if let Some(exploration) = &mut self.exploration {
+            exploration.preempt(synchronized, others)
+        } else {
+            // Without another thread to run, preempting still gives timeouts a chance to fire.
+            random.then(|| others().first().copied().unwrap_or(thread))
+        };
+        if let (Some(recorded), Some(next)) = (&mut self.recorded, next) {
+            recorded.push(Decision::Preempt { thread, block, next });
+        }
+        next
+    }
+
+    /// Decides which of `count` candidate stores a weak-memory load in the current basic block of
+    /// `thread` reads from, 0 being the latest. `random` is the choice to make when neither
+    /// replaying nor exploring schedules. Returns `None` if the random choice should be used and we
+    /// do not need to know it.
+    pub fn load(
+        &mut self,
+        thread: ThreadId,
+        random: impl FnOnce() -> usize,
+        count: impl FnOnce() -> usize,
+    ) -> Option<usize> {
+        let block = self.block(thread);
+        let index = if let Some(replay) = &mut self.replay {
+            match replay.loads.get_mut(&(thread, block)).and_then(VecDeque::pop_front) {
+                // Do not read from a store that does not exist if the execution diverged.
+                Some(index) => index.min(count().saturating_sub(1)),
+                None => 0,
+            }
+        } else if let Some(exploration) = &mut self.exploration {
+            match count() {
+                count if count > 1 => exploration.choose(count),
+                _ => 0,
+            }
+        } else if self.recorded.is_some() {
+            random()
+        } else {
+            return None;
+        };
+        if let Some(recorded) = &mut self.recorded {
+            if index != 0 {
+                recorded.push(Decision::Load { thread, block, index });
+            }
+        }
+        Some(index)
+    }
+}
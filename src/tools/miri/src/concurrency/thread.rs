@@ -27,6 +27,8 @@ enum SchedulingAction {
     ExecuteStep,
     /// Execute a timeout callback.
     ExecuteTimeoutCallback,
+    /// Execute the callbacks of the threads blocked on I/O.
+    ExecuteIoCallbacks,
     /// Wait for a bit, until there is a timeout to be called.
     Sleep(Duration),
 }
@@ -38,6 +40,8 @@ pub trait MachineCallback<'mir, 'tcx>: VisitTags {
 
 type TimeoutCallback<'mir, 'tcx> = Box<dyn MachineCallback<'mir, 'tcx> + 'tcx>;
 
+type IoCallback<'mir, 'tcx> = Box<dyn MachineCallback<'mir, 'tcx> + 'tcx>;
+
 /// A thread identifier.
 #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub struct ThreadId(u32);
@@ -295,6 +299,20 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
+/// I/O callbacks are used to implement blocking file descriptor operations. For example, reading
+/// from an empty socket creates a callback that retries the read whenever the readiness of file
+/// descriptors may have changed, and unblocks the thread once the read succeeds.
+struct IoCallbackInfo<'mir, 'tcx> {
+    /// The called function.
+    callback: IoCallback<'mir, 'tcx>,
+}
+
+impl<'mir, 'tcx> std::fmt::Debug for IoCallbackInfo<'mir, 'tcx> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IoCallback")
+    }
+}
+
 /// A set of threads.
 #[derive(Debug)]
 pub struct ThreadManager<'mir, 'tcx> {
@@ -312,8 +330,17 @@ pub struct ThreadManager<'mir, 'tcx> {
     thread_local_alloc_ids: RefCell<FxHashMap<(DefId, ThreadId), Pointer<Provenance>>>,
     /// A flag that indicates that we should change the active thread.
     yield_active_thread: bool,
+    /// The thread to switch to when changing the active thread, if the active thread got
+    /// preempted in favor of a particular thread.
+    next_thread: Option<ThreadId>,
     /// Callbacks that are called once the specified time passes.
     timeout_callbacks: FxHashMap<ThreadId, TimeoutCallbackInfo<'mir, 'tcx>>,
+    /// Callbacks of the threads blocked on I/O, that are called when file descriptors may have
+    /// become ready.
+    io_callbacks: FxHashMap<ThreadId, IoCallbackInfo<'mir, 'tcx>>,
+    /// A flag that indicates that the readiness of file descriptors may have changed since the
+    /// I/O callbacks were last called.
+    io_readiness_changed: bool,
 }
 
 impl VisitTags for ThreadManager<'_, '_> {
@@ -322,8 +349,11 @@ fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
             threads,
             thread_local_alloc_ids,
             timeout_callbacks,
+            io_callbacks,
             active_thread: _,
             yield_active_thread: _,
+            next_thread: _,
+            io_readiness_changed: _,
             sync,
         } = self;
 
@@ -336,6 +366,9 @@ fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
         for callback in timeout_callbacks.values() {
             callback.callback.visit_tags(visit);
         }
+        for callback in io_callbacks.values() {
+            callback.callback.visit_tags(visit);
+        }
         sync.visit_tags(visit);
     }
 }
@@ -351,7 +384,10 @@ fn default() -> Self {
             sync: SynchronizationState::default(),
             thread_local_alloc_ids: Default::default(),
             yield_active_thread: false,
+            next_thread: None,
             timeout_callbacks: FxHashMap::default(),
+            io_callbacks: FxHashMap::default(),
+            io_readiness_changed: false,
         }
     }
 }
@@ -564,6 +600,7 @@ fn unblock_thread(&mut self, thread: ThreadId) {
         let state = &mut self.threads[thread].state;
         assert_eq!(*state, ThreadState::BlockedOnSync);
         *state = ThreadState::Enabled;
+        self.io_callbacks.remove(&thread);
     }
 
     /// Change the active thread to some enabled thread.
@@ -574,6 +611,30 @@ fn yield_active_thread(&mut self) {
         self.yield_active_thread = true;
     }
 
+    /// Change the active thread to `next`, if it is still enabled at the next scheduling point.
+    fn preempt_active_thread(&mut self, next: ThreadId) {
+        self.yield_active_thread = true;
+        self.next_thread = Some(next);
+    }
+
+    /// The enabled threads other than the active thread, in round-robin order.
+    fn other_enabled_threads(&self) -> Vec<ThreadId> {
+        // Crucially, we start searching at the current active thread ID, rather than at 0, since
+        // we want to avoid always scheduling threads 0 and 1 without ever making progress in
+        // thread 2.
+        //
+        // `skip(N)` means we start iterating at thread N, so we skip 1 more to start just *after*
+        // the active thread. Then after that we look at `take(N)`, i.e., the threads *before* the
+        // active thread.
+        self.threads
+            .iter_enumerated()
+            .skip(self.active_thread.index() + 1)
+            .chain(self.threads.iter_enumerated().take(self.active_thread.index()))
+            .filter(|(_, thread)| thread.state == ThreadState::Enabled)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
     /// Register the given `callback` to be called once the `call_time` passes.
     ///
     /// The callback will be called with `thread` being the active thread, and
@@ -594,6 +655,23 @@ fn unregister_timeout_callback_if_exists(&mut self, thread: ThreadId) {
         self.timeout_callbacks.remove(&thread);
     }
 
+    /// Register the given `callback` to be called whenever the readiness of file descriptors may
+    /// have changed, until it unblocks the blocked `thread`. The callback is dropped when the
+    /// thread is unblocked, by it or by anything else (like a timeout callback).
+    ///
+    /// The callback will be called with `thread` being the active thread, and
+    /// the callback may not change the active thread.
+    fn register_io_callback(&mut self, thread: ThreadId, callback: IoCallback<'mir, 'tcx>) {
+        assert_eq!(self.threads[thread].state, ThreadState::BlockedOnSync);
+        self.io_callbacks.try_insert(thread, IoCallbackInfo { callback }).unwrap();
+    }
+
+    /// Records that the readiness of file descriptors may have changed, so that the threads
+    /// blocked on I/O check whether they can proceed before any thread takes another step.
+    fn io_readiness_changed(&mut self) {
+        self.io_readiness_changed = true;
+    }
+
     /// Get a callback that is ready to be called.
     fn get_ready_callback(
         &mut self,
@@ -664,6 +742,11 @@ fn thread_terminated(
     /// long as we can and switch only when we have to (the active thread was
     /// blocked, terminated, or has explicitly asked to be preempted).
     fn schedule(&mut self, clock: &Clock) -> InterpResult<'tcx, SchedulingAction> {
+        // File descriptors may have become ready. Let the threads blocked on them check, as the
+        // kernel would wake them up right away.
+        if std::mem::take(&mut self.io_readiness_changed) && !self.io_callbacks.is_empty() {
+            return Ok(SchedulingAction::ExecuteIoCallbacks);
+        }
         // This thread and the program can keep going.
         if self.threads[self.active_thread].state == ThreadState::Enabled
             && !self.yield_active_thread
@@ -683,24 +766,15 @@ fn schedule(&mut self, clock: &Clock) -> InterpResult<'tcx, SchedulingAction> {
             return Ok(SchedulingAction::ExecuteTimeoutCallback);
         }
         // No callbacks immediately scheduled, pick a regular thread to execute.
-        // The active thread blocked or yielded. So we go search for another enabled thread.
-        // Crucially, we start searching at the current active thread ID, rather than at 0, since we
-        // want to avoid always scheduling threads 0 and 1 without ever making progress in thread 2.
-        //
-        // `skip(N)` means we start iterating at thread N, so we skip 1 more to start just *after*
-        // the active thread. Then after that we look at `take(N)`, i.e., the threads *before* the
-        // active thread.
-        let threads = self
-            .threads
-            .iter_enumerated()
-            .skip(self.active_thread.index() + 1)
-            .chain(self.threads.iter_enumerated().take(self.active_thread.index()));
-        for (id, thread) in threads {
-            debug_assert_ne!(self.active_thread, id);
-            if thread.state == ThreadState::Enabled {
-                self.active_thread = id;
-                break;
-            }
+        // The active thread blocked or yielded. So we go search for another enabled thread, unless
+        // it got preempted in favor of a thread that can still run.
+        let next = self
+            .next_thread
+            .take()
+            .filter(|&next| self.threads[next].state == ThreadState::Enabled)
+            .or_else(|| self.other_enabled_threads().first().copied());
+        if let Some(next) = next {
+            self.active_thread = next;
         }
         self.yield_active_thread = false;
         if self.threads[self.active_thread].state == ThreadState::Enabled {
@@ -751,6 +825,31 @@ fn run_timeout_callback(&mut self) -> InterpResult<'tcx> {
         Ok(())
     }
 
+    /// Calls the callbacks of the threads blocked on I/O, in the order of the thread indices to
+    /// keep the scheduler deterministic. The callbacks of the threads that stay blocked are kept.
+    fn run_io_callbacks(&mut self) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        let mut threads: Vec<ThreadId> =
+            this.machine.threads.io_callbacks.keys().copied().collect();
+        threads.sort();
+        for thread in threads {
+            // A previous callback may have unblocked this thread.
+            let Some(callback) = this.machine.threads.io_callbacks.remove(&thread) else {
+                continue;
+            };
+            // See `run_timeout_callback` for why the caller changes the active thread.
+            let old_thread = this.set_active_thread(thread);
+            callback.callback.call(this)?;
+            this.set_active_thread(old_thread);
+            if this.machine.threads.threads[thread].state == ThreadState::BlockedOnSync {
+                this.machine.threads.io_callbacks.try_insert(thread, callback).unwrap();
+            } else {
+                this.machine.threads.unregister_timeout_callback_if_exists(thread);
+            }
+        }
+        Ok(())
+    }
+
     #[inline]
     fn run_on_stack_empty(&mut self) -> InterpResult<'tcx, Poll<()>> {
         let this = self.eval_context_mut();
@@ -984,8 +1083,19 @@ fn maybe_preempt_active_thread(&mut self) {
         use rand::Rng as _;
 
         let this = self.eval_context_mut();
-        if this.machine.rng.get_mut().gen_bool(this.machine.preemption_rate) {
-            this.yield_active_thread();
+        // Always ask the RNG, even when replaying a schedule, so that the remaining random
+        // decisions are the same as in the recorded execution.
+        let random = this.machine.rng.get_mut().gen_bool(this.machine.preemption_rate);
+        let synchronized = this.machine.data_race.as_ref().is_some_and(|d| d.take_synchronized());
+        let threads = &this.machine.threads;
+        let active_thread = threads.get_active_thread_id();
+        let next =
+            this.machine
+                .schedule
+                .get_mut()
+                .preempt(active_thread, random, synchronized, || threads.other_enabled_threads());
+        if let Some(next) = next {
+            this.machine.threads.preempt_active_thread(next);
         }
     }
 
@@ -1009,6 +1119,18 @@ fn unregister_timeout_callback_if_exists(&mut self, thread: ThreadId) {
         this.machine.threads.unregister_timeout_callback_if_exists(thread);
     }
 
+    #[inline]
+    fn register_io_callback(&mut self, thread: ThreadId, callback: IoCallback<'mir, 'tcx>) {
+        let this = self.eval_context_mut();
+        this.machine.threads.register_io_callback(thread, callback);
+    }
+
+    #[inline]
+    fn io_readiness_changed(&mut self) {
+        let this = self.eval_context_mut();
+        this.machine.threads.io_readiness_changed();
+    }
+
     /// Run the core interpreter loop. Returns only when an interrupt occurs (an error or program
     /// termination).
     fn run_threads(&mut self) -> InterpResult<'tcx, !> {
@@ -1027,6 +1149,9 @@ fn run_threads(&mut self) -> InterpResult<'tcx, !> {
                 SchedulingAction::ExecuteTimeoutCallback => {
                     this.run_timeout_callback()?;
                 }
+                SchedulingAction::ExecuteIoCallbacks => {
+                    this.run_io_callbacks()?;
+                }
                 SchedulingAction::Sleep(duration) => {
                     this.machine.clock.sleep(duration);
                 }
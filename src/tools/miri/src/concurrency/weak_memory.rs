@@ -90,6 +90,7 @@
 use super::{
     data_race::{GlobalState as DataRaceState, ThreadClockSet},
     range_object_map::{AccessType, RangeObjectMap},
+    schedule::ScheduleTracker,
     vector_clock::{VClock, VTimestamp, VectorIdx},
 };
 
@@ -287,6 +288,7 @@ fn buffered_read(
         thread_mgr: &ThreadManager<'_, '_>,
         is_seqcst: bool,
         rng: &mut (impl rand::Rng + ?Sized),
+        schedule: &mut ScheduleTracker,
         validate: impl FnOnce() -> InterpResult<'tcx>,
     ) -> InterpResult<'tcx, (Scalar<Provenance>, LoadRecency)> {
         // Having a live borrow to store_buffer while calling validate_atomic_load is fine
@@ -297,7 +299,13 @@ fn buffered_read(
             // as the race detector will update it
             let (.., clocks) = global.current_thread_state(thread_mgr);
             // Load from a valid entry in the store buffer
-            self.fetch_store(is_seqcst, &clocks, &mut *rng)
+            self.fetch_store(
+                is_seqcst,
+                &clocks,
+                &mut *rng,
+                schedule,
+                thread_mgr.get_active_thread_id(),
+            )
         };
 
         // Unlike in buffered_atomic_write, thread clock updates have to be done
@@ -331,79 +339,93 @@ fn fetch_store<R: rand::Rng + ?Sized>(
         is_seqcst: bool,
         clocks: &ThreadClockSet,
         rng: &mut R,
+        schedule: &mut ScheduleTracker,
+        thread: ThreadId,
     ) -> (&StoreElement, LoadRecency) {
         use rand::seq::IteratorRandom;
-        let mut found_sc = false;
-        // FIXME: we want an inclusive take_while (stops after a false predicate, but
-        // includes the element that gave the false), but such function doesn't yet
-        // exist in the standard library https://github.com/rust-lang/rust/issues/62208
-        // so we have to hack around it with keep_searching
-        let mut keep_searching = true;
-        let candidates = self
-            .buffer
-            .iter()
-            .rev()
-            .take_while(move |&store_elem| {
-                if !keep_searching {
-                    return false;
-                }
-
-                keep_searching = if store_elem.timestamp <= clocks.clock[store_elem.store_index] {
-                    // CoWR: if a store happens-before the current load,
-                    // then we can't read-from anything earlier in modification order.
-                    // C++20 §6.9.2.2 [intro.races] paragraph 18
-                    false
-                } else if store_elem.load_info.borrow().timestamps.iter().any(
-                    |(&load_index, &load_timestamp)| load_timestamp <= clocks.clock[load_index],
-                ) {
-                    // CoRR: if there was a load from this store which happened-before the current load,
-                    // then we cannot read-from anything earlier in modification order.
-                    // C++20 §6.9.2.2 [intro.races] paragraph 16
-                    false
-                } else if store_elem.timestamp <= clocks.fence_seqcst[store_elem.store_index] {
-                    // The current load, which may be sequenced-after an SC fence, cannot read-before
-                    // the last store sequenced-before an SC fence in another thread.
-                    // C++17 §32.4 [atomics.order] paragraph 6
-                    false
-                } else if store_elem.timestamp <= clocks.write_seqcst[store_elem.store_index]
-                    && store_elem.is_seqcst
-                {
-                    // The current non-SC load, which may be sequenced-after an SC fence,
-                    // cannot read-before the last SC store executed before the fence.
-                    // C++17 §32.4 [atomics.order] paragraph 4
-                    false
-                } else if is_seqcst
-                    && store_elem.timestamp <= clocks.read_seqcst[store_elem.store_index]
-                {
-                    // The current SC load cannot read-before the last store sequenced-before
-                    // the last SC fence.
-                    // C++17 §32.4 [atomics.order] paragraph 5
-                    false
-                } else if is_seqcst && store_elem.load_info.borrow().sc_loaded {
-                    // The current SC load cannot read-before a store that an earlier SC load has observed.
-                    // See https://github.com/rust-lang/miri/issues/2301#issuecomment-1222720427
-                    // Consequences of C++20 §31.4 [atomics.order] paragraph 3.1, 3.3 (coherence-ordered before)
-                    // and 4.1 (coherence-ordered before between SC makes global total order S)
-                    false
-                } else {
-                    true
-                };
+        let candidates = || {
+            let mut found_sc = false;
+            // FIXME: we want an inclusive take_while (stops after a false predicate, but
+            // includes the element that gave the false), but such function doesn't yet
+            // exist in the standard library https://github.com/rust-lang/rust/issues/62208
+            // so we have to hack around it with keep_searching
+            let mut keep_searching = true;
+            self.buffer
+                .iter()
+                .rev()
+                .take_while(move |&store_elem| {
+                    if !keep_searching {
+                        return false;
+                    }
+
+                    keep_searching = if store_elem.timestamp <= clocks.clock[store_elem.store_index]
+                    {
+                        // CoWR: if a store happens-before the current load,
+                        // then we can't read-from anything earlier in modification order.
+                        // C++20 §6.9.2.2 [intro.races] paragraph 18
+                        false
+                    } else if store_elem.load_info.borrow().timestamps.iter().any(
+                        |(&load_index, &load_timestamp)| load_timestamp <= clocks.clock[load_index],
+                    ) {
+                        // CoRR: if there was a load from this store which happened-before the current load,
+                        // then we cannot read-from anything earlier in modification order.
+                        // C++20 §6.9.2.2 [intro.races] paragraph 16
+                        false
+                    } else if store_elem.timestamp <= clocks.fence_seqcst[store_elem.store_index] {
+                        // The current load, which may be sequenced-after an SC fence, cannot read-before
+                        // the last store sequenced-before an SC fence in another thread.
+                        // C++17 §32.4 [atomics.order] paragraph 6
+                        false
+                    } else if store_elem.timestamp <= clocks.write_seqcst[store_elem.store_index]
+                        && store_elem.is_seqcst
+                    {
+                        // The current non-SC load, which may be sequenced-after an SC fence,
+                        // cannot read-before the last SC store executed before the fence.
+                        // C++17 §32.4 [atomics.order] paragraph 4
+                        false
+                    } else if is_seqcst
+                        && store_elem.timestamp <= clocks.read_seqcst[store_elem.store_index]
+                    {
+                        // The current SC load cannot read-before the last store sequenced-before
+                        // the last SC fence.
+                        // C++17 §32.4 [atomics.order] paragraph 5
+                        false
+                    } else if is_seqcst && store_elem.load_info.borrow().sc_loaded {
+                        // The current SC load cannot read-before a store that an earlier SC load has observed.
+                        // See https://github.com/rust-lang/miri/issues/2301#issuecomment-1222720427
+                        // Consequences of C++20 §31.4 [atomics.order] paragraph 3.1, 3.3 (coherence-ordered before)
+                        // and 4.1 (coherence-ordered before between SC makes global total order S)
+                        false
+                    } else {
+                        true
+                    };
 
-                true
-            })
-            .filter(|&store_elem| {
-                if is_seqcst && store_elem.is_seqcst {
-                    // An SC load needs to ignore all but last store maked SC (stores not marked SC are not
-                    // affected)
-                    let include = !found_sc;
-                    found_sc = true;
-                    include
-                } else {
                     true
-                }
-            });
+                })
+                .filter(move |&store_elem| {
+                    if is_seqcst && store_elem.is_seqcst {
+                        // An SC load needs to ignore all but last store maked SC (stores not marked SC are not
+                        // affected)
+                        let include = !found_sc;
+                        found_sc = true;
+                        include
+                    } else {
+                        true
+                    }
+                })
+        };
 
-        let chosen = candidates.choose(rng).expect("store buffer cannot be empty");
+        let chosen = candidates().choose(rng).expect("store buffer cannot be empty");
+        // The schedule may override the random choice, given as an index into the candidates.
+        let index = schedule.load(
+            thread,
+            || candidates().position(|store_elem| std::ptr::eq(store_elem, chosen)).unwrap(),
+            || candidates().count(),
+        );
+        let chosen = match index {
+            Some(index) => candidates().nth(index).unwrap(),
+            None => chosen,
+        };
         if std::ptr::eq(chosen, self.buffer.back().expect("store buffer cannot be empty")) {
             (chosen, LoadRecency::Latest)
         } else {
@@ -545,6 +567,7 @@ fn buffered_atomic_read(
                     global.sc_read(&this.machine.threads);
                 }
                 let mut rng = this.machine.rng.borrow_mut();
+                let mut schedule = this.machine.schedule.borrow_mut();
                 let buffer = alloc_buffers.get_or_create_store_buffer(
                     alloc_range(base_offset, place.layout.size),
                     latest_in_mo,
@@ -554,6 +577,7 @@ fn buffered_atomic_read(
                     &this.machine.threads,
                     atomic == AtomicReadOrd::SeqCst,
                     &mut *rng,
+                    &mut schedule,
                     validate,
                 )?;
                 if global.track_outdated_loads && recency == LoadRecency::Outdated {
@@ -0,0 +1,289 @@
+//! Line and branch coverage of the interpreted program (`-Zmiri-coverage`).
+//!
+//! We count how often each basic block of the functions in the local crates executes its
+//! terminator, and for each `SwitchInt` terminator how often it branched to each of its targets.
+//! At the end of the execution, these counts are mapped back to source lines and written in the
+//! lcov format, so they can be merged with the coverage of native test runs. Cleanup blocks, which
+//! only run while unwinding, are not reported.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::hygiene::walk_chain;
+use rustc_span::Span;
+
+use crate::*;
+
+/// The execution counts collected so far.
+#[derive(Debug, Default)]
+pub struct Coverage {
+    functions: FxHashMap<DefId, FunctionCoverage>,
+}
+
+#[derive(Debug, Default)]
+struct FunctionCoverage {
+    /// How often each basic block executed its terminator.
+    blocks: FxHashMap<mir::BasicBlock, u64>,
+    /// How often the `SwitchInt` terminator of the first block branched to the second block.
+    branches: FxHashMap<(mir::BasicBlock, mir::BasicBlock), u64>,
+}
+
+/// The coverage of a single function, ready to be written out.
+struct FunctionReport {
+    name: String,
+    line: usize,
+    calls: u64,
+    /// For each line, the number of times it executed.
+    lines: BTreeMap<usize, u64>,
+    /// For each branch: its line, the block and target index it belongs to, and the number of
+    /// times it was taken, if its block executed at all.
+    branches: Vec<(usize, usize, usize, Option<u64>)>,
+}
+
+impl Coverage {
+    /// Writes the coverage to `path` in the lcov format. Functions that never executed are only
+    /// included if they are in the crate being interpreted.
+    pub fn write_lcov(&self, tcx: TyCtxt<'_>, path: &Path) -> io::Result<()> {
+        let mut def_ids: FxHashSet<DefId> = self.functions.keys().copied().collect();
+        def_ids.extend(tcx.mir_keys(()).iter().map(|def_id| def_id.to_def_id()).filter(
+            |&def_id| {
+                matches!(
+                    tcx.def_kind(def_id),
+                    DefKind::Fn | DefKind::AssocFn | DefKind::Closure | DefKind::Generator
+                )
+            },
+        ));
+
+        let mut files: BTreeMap<String, Vec<FunctionReport>> = BTreeMap::new();
+        for def_id in def_ids {
+            let body = tcx.instance_mir(ty::InstanceDef::Item(def_id));
+            let file = tcx.sess.source_map().lookup_char_pos(body.span.lo()).file;
+            let report = self.report(tcx, def_id, body);
+            files.entry(file.name.prefer_local().to_string()).or_default().push(report);
+        }
+        fs::write(path, lcov(files))
+    }
+
+    fn report<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        def_id: DefId,
+        body: &mir::Body<'tcx>,
+    ) -> FunctionReport {
+        let source_map = tcx.sess.source_map();
+        let file = source_map.lookup_char_pos(body.span.lo()).file;
+        // The line a span starts on, if it is part of this function in the source code. Code
+        // expanded from macros is attributed to the macro invocation.
+        let line_of = |span: Span| {
+            let span = walk_chain(span, body.span.ctxt());
+            if span.is_dummy() || !body.span.contains(span) {
+                return None;
+            }
+            let loc = source_map.lookup_char_pos(span.lo());
+            (loc.file.name == file.name).then_some(loc.line)
+        };
+
+        let empty = FunctionCoverage::default();
+        let coverage = self.functions.get(&def_id).unwrap_or(&empty);
+        let count = |block| coverage.blocks.get(&block).copied().unwrap_or(0);
+
+        let mut lines: BTreeMap<usize, u64> = BTreeMap::new();
+        let mut branches = Vec::new();
+        for (block, data) in body.basic_blocks.iter_enumerated() {
+            if data.is_cleanup {
+                continue;
+            }
+            let spans = data.statements.iter().map(|stmt| stmt.source_info.span);
+            for line in spans.chain([data.terminator().source_info.span]).filter_map(line_of) {
+                let entry = lines.entry(line).or_default();
+                *entry = (*entry).max(count(block));
+            }
+            if let mir::TerminatorKind::SwitchInt { targets, .. } = &data.terminator().kind {
+                let Some(line) = line_of(data.terminator().source_info.span) else { continue };
+                for (index, target) in targets.all_targets().iter().enumerate() {
+                    // Targets that cannot be reached, like the otherwise branch of a `match` on
+                    // an enum that covers every variant, are not branches of the source code.
+                    let target_kind = &body.basic_blocks[*target].terminator().kind;
+                    if matches!(target_kind, mir::TerminatorKind::Unreachable) {
+                        continue;
+                    }
+                    let taken = (count(block) > 0)
+                        .then(|| coverage.branches.get(&(block, *target)).copied().unwrap_or(0));
+                    branches.push((line, block.index(), index, taken));
+                }
+            }
+        }
+
+        FunctionReport {
+            name: tcx.def_path_str(def_id),
+            line: source_map.lookup_char_pos(body.span.lo()).line,
+            calls: count(mir::START_BLOCK),
+            lines,
+            branches,
+        }
+    }
+}
+
+/// Formats the reports of the functions in each file in the lcov format.
+fn lcov(files: BTreeMap<String, Vec<FunctionReport>>) -> String {
+    let mut out = String::new();
+    for (file, mut functions) in files {
+        functions.sort_by(|a, b| (a.line, &a.name).cmp(&(b.line, &b.name)));
+        writeln!(out, "TN:").unwrap();
+        writeln!(out, "SF:{file}").unwrap();
+        for function in &functions {
+            writeln!(out, "FN:{},{}", function.line, function.name).unwrap();
+        }
+        for function in &functions {
+            writeln!(out, "FNDA:{},{}", function.calls, function.name).unwrap();
+        }
+        writeln!(out, "FNF:{}", functions.len()).unwrap();
+        writeln!(out, "FNH:{}", functions.iter().filter(|f| f.calls > 0).count()).unwrap();
+
+        let branches: Vec<_> = functions.iter().flat_map(|f| &f.branches).collect();
+        for (line, block, target, taken) in &branches {
+            match taken {
+                Some(taken) => writeln!(out, "BRDA:{line},{block},{target},{taken}").unwrap(),
+                None => writeln!(out, "BRDA:{line},{block},{target},-").unwrap(),
+            }
+        }
+        writeln!(out, "BRF:{}", branches.len()).unwrap();
+        let hit = branches.iter().filter(|(.., taken)| taken.is_some_and(|t| t > 0)).count();
+        writeln!(out, "BRH:{hit}").unwrap();
+
+        // Several functions can share a line, e.g. a closure and its parent.
+        let mut lines: BTreeMap<usize, u64> = BTreeMap::new();
+        for (&line, &count) in functions.iter().flat_map(|f| &f.lines) {
+            let entry = lines.entry(line).or_default();
+            *entry = (*entry).max(count);
+        }
+        for (line, count) in &lines {
+            writeln!(out, "DA:{line},{count}").unwrap();
+        }
+        writeln!(out, "LF:{}", lines.len()).unwrap();
+        writeln!(out, "LH:{}", lines.values().filter(|&&count| count > 0).count()).unwrap();
+        writeln!(out, "end_of_record").unwrap();
+    }
+    out
+}
+
+impl<'mir, 'tcx: 'mir> EvalContextExt<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
+pub trait EvalContextExt<'mir, 'tcx: 'mir>: crate::MiriInterpCxExt<'mir, 'tcx> {
+    /// Records that the active frame is about to execute the terminator of its current basic
+    /// block. Must only be called when coverage is being collected.
+    fn record_coverage(&mut self) {
+        let this = self.eval_context_mut();
+        let frame = this.frame_mut();
+        let ty::InstanceDef::Item(def_id) = frame.instance.def else { return };
+        let Some(location) = frame.loc.left() else { return };
+        let block = location.block;
+        // The block that was executed before this one branched to it, if any.
+        let switch = frame.extra.coverage_switch.take();
+        if matches!(
+            frame.body.basic_blocks[block].terminator().kind,
+            mir::TerminatorKind::SwitchInt { .. }
+        ) {
+            frame.extra.coverage_switch = Some(block);
+        }
+        if !def_id.is_local() && !this.machine.local_crates.contains(&def_id.krate) {
+            return;
+        }
+
+        let coverage = this.machine.coverage.as_mut().unwrap();
+        let function = coverage.functions.entry(def_id).or_default();
+        *function.blocks.entry(block).or_default() += 1;
+        if let Some(switch) = switch {
+            *function.branches.entry((switch, block)).or_default() += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(
+        name: &str,
+        line: usize,
+        calls: u64,
+        lines: &[(usize, u64)],
+        branches: &[(usize, usize, usize, Option<u64>)],
+    ) -> FunctionReport {
+        FunctionReport {
+            name: name.to_owned(),
+            line,
+            calls,
+            lines: lines.iter().copied().collect(),
+            branches: branches.to_vec(),
+        }
+    }
+
+    #[test]
+    fn lcov_records() {
+        let mut files = BTreeMap::new();
+        files.insert(
+            "src/main.rs".to_owned(),
+            vec![
+                function("unused", 9, 0, &[(9, 0), (10, 0)], &[(10, 1, 0, None), (10, 1, 1, None)]),
+                // The closure shares line 3 with `main`, and executed it more often.
+                function("main::{closure#0}", 3, 4, &[(3, 4)], &[]),
+                function(
+                    "main",
+                    1,
+                    1,
+                    &[(1, 1), (3, 1), (5, 0)],
+                    &[(3, 0, 0, Some(1)), (3, 0, 1, Some(0))],
+                ),
+            ],
+        );
+        files.insert("src/lib.rs".to_owned(), vec![function("lib", 1, 2, &[(1, 2)], &[])]);
+
+        let expected = "\
+TN:
+SF:src/lib.rs
+FN:1,lib
+FNDA:2,lib
+FNF:1
+FNH:1
+BRF:0
+BRH:0
+DA:1,2
+LF:1
+LH:1
+end_of_record
+TN:
+SF:src/main.rs
+FN:1,main
+FN:3,main::{closure#0}
+FN:9,unused
+FNDA:1,main
+FNDA:4,main::{closure#0}
+FNDA:0,unused
+FNF:3
+FNH:2
+BRDA:3,0,0,1
+BRDA:3,0,1,0
+BRDA:10,1,0,-
+BRDA:10,1,1,-
+BRF:4
+BRH:1
+DA:1,1
+DA:3,4
+DA:5,0
+DA:9,0
+DA:10,0
+LF:5
+LH:2
+end_of_record
+";
+        assert_eq!(lcov(files), expected);
+    }
+}
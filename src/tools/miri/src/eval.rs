@@ -2,6 +2,7 @@
 
 use std::ffi::{OsStr, OsString};
 use std::iter;
+use std::mem;
 use std::panic::{self, AssertUnwindSafe};
 use std::path::PathBuf;
 use std::task::Poll;
@@ -13,7 +14,7 @@
 use crate::diagnostics::report_leaks;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_hir::def::Namespace;
-use rustc_hir::def_id::DefId;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_middle::ty::{
     self,
     layout::{LayoutCx, LayoutOf},
@@ -148,6 +149,19 @@ pub struct MiriConfig {
     pub page_size: Option<u64>,
     /// Whether to collect a backtrace when each allocation is created, just in case it leaks.
     pub collect_leak_backtraces: bool,
+    /// Write the scheduling decisions taken during the execution to this file.
+    pub record_schedule: Option<PathBuf>,
+    /// Take the scheduling decisions from this schedule instead of making them randomly.
+    pub replay_schedule: Option<Schedule>,
+    /// Before replaying `replay_schedule`, shrink it to as few preemptions as possible while the
+    /// program still fails the same way.
+    pub minimize_schedule: bool,
+    /// Before the actual execution, run the program under all schedules with at most this many
+    /// preemptions until one of them fails, and then replay that one.
+    pub explore_schedules: Option<usize>,
+    /// Write line and branch coverage of the local crates in the lcov format, to a file whose name
+    /// starts with this prefix.
+    pub coverage: Option<String>,
 }
 
 impl Default for MiriConfig {
@@ -183,6 +197,11 @@ fn default() -> MiriConfig {
             num_cpus: 1,
             page_size: None,
             collect_leak_backtraces: true,
+            record_schedule: None,
+            replay_schedule: None,
+            minimize_schedule: false,
+            explore_schedules: None,
+            coverage: None,
         }
     }
 }
@@ -414,11 +433,21 @@ pub fn eval_entry<'tcx>(
     tcx: TyCtxt<'tcx>,
     entry_id: DefId,
     entry_type: EntryFnType,
-    config: MiriConfig,
+    mut config: MiriConfig,
 ) -> Option<i64> {
     // Copy setting before we move `config`.
     let ignore_leaks = config.ignore_leaks;
 
+    if let Some(bound) = config.explore_schedules {
+        // If no schedule fails, replaying the empty schedule repeats the first execution that was
+        // explored.
+        config.replay_schedule =
+            Some(explore_schedules(tcx, entry_id, entry_type, &config, bound).unwrap_or_default());
+    }
+    if config.minimize_schedule {
+        config.replay_schedule = Some(minimize_schedule(tcx, entry_id, entry_type, &config));
+    }
+
     let mut ecx = match create_ecx(tcx, entry_id, entry_type, &config) {
         Ok(v) => v,
         Err(err) => {
@@ -440,6 +469,26 @@ pub fn eval_entry<'tcx>(
         Ok(never) => match never {},
     };
 
+    if let Some(path) = &config.record_schedule {
+        let schedule = ecx.machine.schedule.borrow().recorded();
+        if let Err(err) = schedule.write(path) {
+            tcx.sess.err(format!("failed to write the schedule to {}: {err}", path.display()));
+        }
+    }
+    if let Some(prefix) = &config.coverage {
+        // `cargo miri test` interprets several binaries with the same flags, so each of them
+        // writes its own file.
+        let path = PathBuf::from(format!(
+            "{prefix}-{}-{}.lcov",
+            tcx.crate_name(LOCAL_CRATE),
+            std::process::id()
+        ));
+        let coverage = ecx.machine.coverage.as_ref().unwrap();
+        if let Err(err) = coverage.write_lcov(tcx, &path) {
+            tcx.sess.err(format!("failed to write the coverage to {}: {err}", path.display()));
+        }
+    }
+
     // Machine cleanup. Only do this if all threads have terminated; threads that are still running
     // might cause Stacked Borrows errors (https://github.com/rust-lang/miri/issues/2396).
     if ecx.have_all_terminated() {
@@ -480,6 +529,166 @@ pub fn eval_entry<'tcx>(
     Some(return_code)
 }
 
+/// Runs the program once without reporting anything, after letting `setup` configure the scheduler.
+/// Returns how the execution failed, if it did (see `failure_of`), the scheduler's state at the
+/// end of the execution, and the number of basic blocks that were executed.
+///
+/// An ICE during the run is reported like one during the main execution, and ends the whole
+/// exploration or minimization: queries that were running when the ICE happened stay poisoned,
+/// so later runs could not be trusted.
+fn run_silently<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    entry_id: DefId,
+    entry_type: EntryFnType,
+    config: &MiriConfig,
+    setup: impl FnOnce(&mut ScheduleTracker),
+) -> (Option<String>, ScheduleTracker, u64) {
+    let config = MiriConfig { mute_stdout_stderr: true, ..config.clone() };
+    let mut ecx = match create_ecx(tcx, entry_id, entry_type, &config) {
+        Ok(v) => v,
+        Err(err) => {
+            err.print_backtrace();
+            panic!("Miri initialization error: {}", err.kind())
+        }
+    };
+    setup(ecx.machine.schedule.get_mut());
+    let res: thread::Result<InterpResult<'_, !>> =
+        panic::catch_unwind(AssertUnwindSafe(|| ecx.run_threads()));
+    let res = res.unwrap_or_else(|panic_payload| {
+        ecx.handle_ice();
+        panic::resume_unwind(panic_payload)
+    });
+    let res = match res {
+        Err(res) => res,
+        // `Ok` can never happen
+        Ok(never) => match never {},
+    };
+    let schedule = mem::replace(ecx.machine.schedule.get_mut(), ScheduleTracker::new(false, None));
+    (failure_of(&ecx, &res), schedule, ecx.machine.basic_block_count)
+}
+
+/// Describes how an execution failed, so that executions under different schedules can be
+/// compared: the kind of failure and where it happened. Returns `None` if the program exited
+/// successfully.
+fn failure_of<'tcx>(ecx: &MiriInterpCx<'_, 'tcx>, e: &InterpErrorInfo<'tcx>) -> Option<String> {
+    use TerminationInfo::*;
+    let kind = match e.kind() {
+        InterpError::UndefinedBehavior(_) => "Undefined Behavior",
+        InterpError::Unsupported(_) => "unsupported operation",
+        InterpError::InvalidProgram(_) => "invalid program",
+        InterpError::ResourceExhaustion(_) => "resource exhaustion",
+        InterpError::MachineStop(info) =>
+            match info.downcast_ref::<TerminationInfo>().expect("invalid MachineStop payload") {
+                Exit { code: 0, .. } => return None,
+                // This is how panics end up, so there is no useful location.
+                Exit { code, .. } => return Some(format!("exit code {code}")),
+                StackedBorrowsUb { .. } | TreeBorrowsUb { .. } | DataRace { .. } =>
+                    "Undefined Behavior",
+                Deadlock => "deadlock",
+                _ => "abnormal termination",
+            },
+    };
+    let span = ecx.machine.current_span();
+    Some(format!("{kind} at {}", ecx.tcx.sess.source_map().span_to_diagnostic_string(span)))
+}
+
+/// Shrinks `config.replay_schedule` to as few preemptions as possible while the program still
+/// fails the same way.
+fn minimize_schedule<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    entry_id: DefId,
+    entry_type: EntryFnType,
+    config: &MiriConfig,
+) -> Schedule {
+    let schedule = config.replay_schedule.clone().expect("minimizing requires a schedule");
+    let run = |schedule: &Schedule, block_limit| {
+        let config = MiriConfig {
+            replay_schedule: Some(schedule.clone()),
+            record_schedule: None,
+            ..config.clone()
+        };
+        let (failure, tracker, blocks) =
+            run_silently(tcx, entry_id, entry_type, &config, |tracker| {
+                tracker.block_limit = block_limit;
+            });
+        (failure, tracker.recorded(), blocks)
+    };
+    let (failure, followed, blocks) = run(&schedule, None);
+    let Some(failure) = failure else {
+        tcx.sess.warn("the program does not fail with this schedule, so it cannot be minimized");
+        return schedule;
+    };
+    // Without some preemption, threads may wait for each other forever. Give up on executions
+    // that take much longer than the original one.
+    let block_limit = blocks.saturating_mul(4).saturating_add(100_000);
+    let minimized = followed.minimize(|candidate| {
+        let (candidate_failure, followed, _) = run(candidate, Some(block_limit));
+        (candidate_failure.as_ref() == Some(&failure)).then_some(followed)
+    });
+    tcx.sess.note_without_error(format!(
+        "reduced the number of preemptions in the schedule from {} to {}",
+        schedule.preemptions(),
+        minimized.preemptions()
+    ));
+    minimized
+}
+
+/// Runs the program under all schedules with at most `bound` preemptions, in depth-first order,
+/// until one of them fails. Returns the schedule of the failing execution, if any.
+fn explore_schedules<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    entry_id: DefId,
+    entry_type: EntryFnType,
+    config: &MiriConfig,
+    bound: usize,
+) -> Option<Schedule> {
+    let config = MiriConfig { record_schedule: None, ..config.clone() };
+    let mut prefix = Vec::new();
+    let mut block_limit = None;
+    let mut executions = 0u64;
+    let mut nonterminating = 0u64;
+    let mut max_decision_points = 0;
+    let mut pruned_preemptions = 0;
+    loop {
+        let (failure, tracker, blocks) =
+            run_silently(tcx, entry_id, entry_type, &config, |tracker| {
+                tracker.block_limit = block_limit;
+                tracker.explore(bound, prefix);
+            });
+        executions += 1;
+        max_decision_points = max_decision_points.max(tracker.decision_points());
+        pruned_preemptions += tracker.pruned_preemptions();
+        if block_limit.is_some_and(|limit| blocks > limit) {
+            // Some preemptions make threads wait for each other forever, e.g. in a spin loop.
+            nonterminating += 1;
+        } else if let Some(failure) = failure {
+            tcx.sess.note_without_error(format!(
+                "found a schedule that fails with {failure} after exploring {executions} \
+                executions with a preemption bound of {bound}"
+            ));
+            return Some(tracker.recorded());
+        }
+        // The first execution does not preempt any thread, so it tells us roughly how long the
+        // program runs.
+        block_limit.get_or_insert(blocks.saturating_mul(10).saturating_add(1_000_000));
+        match tracker.next_prefix() {
+            Some(next) => prefix = next,
+            None => break,
+        }
+    }
+    tcx.sess.note_without_error(format!(
+        "explored all {executions} executions with a preemption bound of {bound} without finding a \
+        failing schedule"
+    ));
+    tcx.sess.note_without_error(format!(
+        "executions had up to {max_decision_points} decision points; {pruned_preemptions} \
+        preemption points were skipped because of the preemption bound; {nonterminating} \
+        executions did not terminate within {} basic blocks",
+        block_limit.unwrap_or_default(),
+    ));
+    None
+}
+
 /// Turns an array of arguments into a Windows command line string.
 ///
 /// The string will be UTF-16 encoded and NUL terminated.
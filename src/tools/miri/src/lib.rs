@@ -62,6 +62,7 @@
 mod borrow_tracker;
 mod clock;
 mod concurrency;
+mod coverage;
 mod diagnostics;
 mod eval;
 mod helpers;
@@ -101,9 +102,11 @@
 pub use crate::concurrency::{
     data_race::{AtomicFenceOrd, AtomicReadOrd, AtomicRwOrd, AtomicWriteOrd, EvalContextExt as _},
     init_once::{EvalContextExt as _, InitOnceId},
+    schedule::{Decision, Schedule, ScheduleTracker},
     sync::{CondvarId, EvalContextExt as _, MutexId, RwLockId, SyncId},
     thread::{EvalContextExt as _, StackEmptyCallback, ThreadId, ThreadManager, Time},
 };
+pub use crate::coverage::{Coverage, EvalContextExt as _};
 pub use crate::diagnostics::{
     report_error, EvalContextExt as _, NonHaltingDiagnostic, TerminationInfo,
 };
@@ -62,12 +62,22 @@ pub struct FrameExtra<'tcx> {
     /// optimization.
     /// This is used by `MiriMachine::current_span` and `MiriMachine::caller_span`
     pub is_user_relevant: bool,
+
+    /// If we collect coverage and the last terminator this frame executed was a `SwitchInt`,
+    /// the block of that terminator.
+    pub coverage_switch: Option<mir::BasicBlock>,
 }
 
 impl<'tcx> std::fmt::Debug for FrameExtra<'tcx> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // Omitting `timing`, it does not support `Debug`.
-        let FrameExtra { borrow_tracker, catch_unwind, timing: _, is_user_relevant: _ } = self;
+        let FrameExtra {
+            borrow_tracker,
+            catch_unwind,
+            timing: _,
+            is_user_relevant: _,
+            coverage_switch: _,
+        } = self;
         f.debug_struct("FrameData")
             .field("borrow_tracker", borrow_tracker)
             .field("catch_unwind", catch_unwind)
@@ -77,7 +87,13 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 
 impl VisitTags for FrameExtra<'_> {
     fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
-        let FrameExtra { catch_unwind, borrow_tracker, timing: _, is_user_relevant: _ } = self;
+        let FrameExtra {
+            catch_unwind,
+            borrow_tracker,
+            timing: _,
+            is_user_relevant: _,
+            coverage_switch: _,
+        } = self;
 
         catch_unwind.visit_tags(visit);
         borrow_tracker.visit_tags(visit);
@@ -402,6 +418,8 @@ pub struct MiriMachine<'mir, 'tcx> {
     pub(crate) file_handler: shims::unix::FileHandler,
     /// The table of directory descriptors.
     pub(crate) dir_handler: shims::unix::DirHandler,
+    /// The addresses of the sockets of the in-memory loopback network.
+    pub(crate) network: shims::unix::Network,
 
     /// This machine's monotone clock.
     pub(crate) clock: Clock,
@@ -467,6 +485,11 @@ pub struct MiriMachine<'mir, 'tcx> {
     pub(crate) report_progress: Option<u32>,
     // The total number of blocks that have been executed.
     pub(crate) basic_block_count: u64,
+    /// The scheduling decisions to record or replay.
+    /// Needs to be queried by weak memory loads, hence needs interior mutability.
+    pub(crate) schedule: RefCell<ScheduleTracker>,
+    /// The execution counts for `-Zmiri-coverage`, if enabled.
+    pub(crate) coverage: Option<Coverage>,
 
     /// Handle of the optional shared object file for external functions.
     #[cfg(target_os = "linux")]
@@ -545,6 +568,7 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             enforce_abi: config.check_abi,
             file_handler: FileHandler::new(config.mute_stdout_stderr),
             dir_handler: Default::default(),
+            network: Default::default(),
             layouts,
             threads: ThreadManager::default(),
             static_roots: Vec::new(),
@@ -564,6 +588,11 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             preemption_rate: config.preemption_rate,
             report_progress: config.report_progress,
             basic_block_count: 0,
+            schedule: RefCell::new(ScheduleTracker::new(
+                config.record_schedule.is_some() || config.replay_schedule.is_some(),
+                config.replay_schedule.as_ref(),
+            )),
+            coverage: config.coverage.is_some().then(Coverage::default),
             clock: Clock::new(config.isolated_op == IsolatedOp::Allow),
             #[cfg(target_os = "linux")]
             external_so_lib: config.external_so_file.as_ref().map(|lib_file_path| {
@@ -716,6 +745,7 @@ fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
             data_race,
             intptrcast,
             file_handler,
+            network: _,
             tcx: _,
             isolated_op: _,
             validate: _,
@@ -738,6 +768,8 @@ fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
             preemption_rate: _,
             report_progress: _,
             basic_block_count: _,
+            schedule: _,
+            coverage: _,
             external_so_lib: _,
             gc_interval: _,
             since_gc: _,
@@ -1190,6 +1222,7 @@ fn init_frame_extra(
             catch_unwind: None,
             timing,
             is_user_relevant: ecx.machine.is_user_relevant(&frame),
+            coverage_switch: None,
         };
 
         Ok(frame.with_extra(extra))
@@ -1210,6 +1243,16 @@ fn stack_mut<'a>(
     fn before_terminator(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
         ecx.machine.basic_block_count += 1u64; // a u64 that is only incremented by 1 will "never" overflow
         ecx.machine.since_gc += 1;
+        let active_thread = ecx.machine.threads.get_active_thread_id();
+        ecx.machine.schedule.get_mut().count_block(active_thread);
+        if let Some(limit) = ecx.machine.schedule.get_mut().block_limit {
+            if ecx.machine.basic_block_count > limit {
+                throw_machine_stop!(TerminationInfo::BlockLimit { limit });
+            }
+        }
+        if ecx.machine.coverage.is_some() {
+            ecx.record_coverage();
+        }
         // Possibly report our progress.
         if let Some(report_progress) = ecx.machine.report_progress {
             if ecx.machine.basic_block_count % u64::from(report_progress) == 0 {
@@ -10,6 +10,7 @@
 use crate::*;
 use shims::foreign_items::EmulateByNameResult;
 use shims::unix::fs::EvalContextExt as _;
+use shims::unix::socket::EvalContextExt as _;
 use shims::unix::sync::EvalContextExt as _;
 use shims::unix::thread::EvalContextExt as _;
 
@@ -79,8 +80,7 @@ fn emulate_foreign_item_by_name(
                 let fd = this.read_scalar(fd)?.to_i32()?;
                 let buf = this.read_pointer(buf)?;
                 let count = this.read_target_usize(count)?;
-                let result = this.read(fd, buf, count)?;
-                this.write_scalar(Scalar::from_target_isize(result, this), dest)?;
+                this.read(fd, buf, count, dest)?;
             }
             "write" => {
                 let [fd, buf, n] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
@@ -88,9 +88,7 @@ fn emulate_foreign_item_by_name(
                 let buf = this.read_pointer(buf)?;
                 let count = this.read_target_usize(n)?;
                 trace!("Called write({:?}, {:?}, {:?})", fd, buf, count);
-                let result = this.write(fd, buf, count)?;
-                // Now, `result` is the value we return back to the program.
-                this.write_scalar(Scalar::from_target_isize(result, this), dest)?;
+                this.write(fd, buf, count, dest)?;
             }
             "unlink" => {
                 let [path] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
@@ -174,6 +172,80 @@ fn emulate_foreign_item_by_name(
                 this.write_scalar(Scalar::from_i32(result), dest)?;
             }
 
+            // Socket related shims
+            "socket" => {
+                let [domain, type_, protocol] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.socket(domain, type_, protocol)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "bind" => {
+                let [fd, addr, addrlen] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.bind(fd, addr, addrlen)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "listen" => {
+                let [fd, backlog] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.listen(fd, backlog)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "connect" => {
+                let [fd, addr, addrlen] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.connect(fd, addr, addrlen)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "accept" => {
+                let [fd, addr, addrlen] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.accept4(fd, addr, addrlen, None, dest)?;
+            }
+            "send" => {
+                let [fd, buf, len, flags] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.sendto(fd, buf, len, flags, None, dest)?;
+            }
+            "sendto" => {
+                let [fd, buf, len, flags, addr, addrlen] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.sendto(fd, buf, len, flags, Some((addr, addrlen)), dest)?;
+            }
+            "recv" => {
+                let [fd, buf, len, flags] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.recvfrom(fd, buf, len, flags, None, dest)?;
+            }
+            "recvfrom" => {
+                let [fd, buf, len, flags, addr, addrlen] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.recvfrom(fd, buf, len, flags, Some((addr, addrlen)), dest)?;
+            }
+            "shutdown" => {
+                let [fd, how] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.shutdown(fd, how)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "getsockname" => {
+                let [fd, addr, addrlen] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockname(fd, addr, addrlen, /* peer */ false)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "getpeername" => {
+                let [fd, addr, addrlen] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockname(fd, addr, addrlen, /* peer */ true)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "setsockopt" => {
+                let [fd, level, name, value, len] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.setsockopt(fd, level, name, value, len)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "getsockopt" => {
+                let [fd, level, name, value, len] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockopt(fd, level, name, value, len)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+            "ioctl" => {
+                // `ioctl` is variadic. The argument count is checked in `this.ioctl()`, so we do
+                // not use `check_shim` here.
+                this.check_abi_and_shim_symbol_clash(abi, Abi::C { unwind: false }, link_name)?;
+                let result = this.ioctl(args)?;
+                this.write_scalar(Scalar::from_i32(result), dest)?;
+            }
+
             // Time related shims
             "gettimeofday" => {
                 let [tv, tz] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
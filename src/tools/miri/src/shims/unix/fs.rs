@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::cell::RefMut;
 use std::collections::BTreeMap;
 use std::convert::TryInto;
 use std::fs::{
@@ -14,6 +15,8 @@
 use rustc_middle::ty::TyCtxt;
 use rustc_target::abi::{Align, Size};
 
+use crate::concurrency::thread::MachineCallback;
+use crate::concurrency::vector_clock::VClock;
 use crate::shims::os_str::bytes_to_os_str;
 use crate::*;
 use shims::os_str::os_str_to_bytes;
@@ -71,6 +74,51 @@ fn is_tty(&self) -> bool {
     fn as_unix_host_fd(&self) -> Option<i32> {
         None
     }
+
+    /// Returns the readiness of this file descriptor for I/O, if it can be watched by `epoll`.
+    fn readiness(&self) -> Option<Readiness> {
+        None
+    }
+
+    /// Whether the reads and writes that cannot proceed right away block the thread until they
+    /// can, rather than fail with `EWOULDBLOCK`. Only the file descriptors implemented by Miri
+    /// block the thread: the host takes care of blocking for the others.
+    fn is_blocking(&self) -> bool {
+        false
+    }
+
+    /// The vector clock of the data that can be read from this file descriptor, if it was
+    /// written by the program itself. Reads acquire it, so that the threads writing data
+    /// happen-before the threads reading it.
+    fn read_clock(&self) -> Option<RefMut<'_, VClock>> {
+        None
+    }
+
+    /// The vector clock released by the writes to this file descriptor, see `read_clock`.
+    fn write_clock(&self) -> Option<RefMut<'_, VClock>> {
+        None
+    }
+
+    /// Called when the file descriptor `fd` of the program is closed.
+    fn fd_closed(&self, _fd: i32) {}
+
+    fn visit_tags(&self, _visit: &mut dyn FnMut(BorTag)) {}
+}
+
+/// The readiness of a file descriptor for I/O, as reported by `epoll`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Readiness {
+    /// Reading would not block (`EPOLLIN`).
+    pub readable: bool,
+    /// Writing would not block (`EPOLLOUT`).
+    pub writable: bool,
+    /// The peer closed its end or shut down writing (`EPOLLRDHUP`).
+    pub read_closed: bool,
+    /// The connection is closed in both directions (`EPOLLHUP`).
+    pub hung_up: bool,
+    /// A counter of the operations that would have woken up the waiters of the file descriptor
+    /// on Linux, which is how edge-triggered notifications are implemented.
+    pub generation: u64,
 }
 
 impl FileDescriptor for FileHandle {
@@ -277,8 +325,10 @@ pub struct FileHandler {
 }
 
 impl VisitTags for FileHandler {
-    fn visit_tags(&self, _visit: &mut dyn FnMut(BorTag)) {
-        // All our FileDescriptor do not have any tags.
+    fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
+        for file_descriptor in self.handles.values() {
+            file_descriptor.visit_tags(visit);
+        }
     }
 }
 
@@ -330,8 +380,147 @@ fn insert_fd_with_min_fd(&mut self, file_handle: Box<dyn FileDescriptor>, min_fd
     }
 }
 
+/// A read or write of a thread blocked until it can proceed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IoOp {
+    Read,
+    Write,
+}
+
+struct BlockedIo<'tcx> {
+    op: IoOp,
+    fd: i32,
+    buf: Pointer<Option<Provenance>>,
+    count: u64,
+    dest: PlaceTy<'tcx, Provenance>,
+}
+
+impl<'tcx> VisitTags for BlockedIo<'tcx> {
+    fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
+        let BlockedIo { op: _, fd: _, buf, count: _, dest } = self;
+        buf.visit_tags(visit);
+        dest.visit_tags(visit);
+    }
+}
+
+impl<'mir, 'tcx: 'mir> MachineCallback<'mir, 'tcx> for BlockedIo<'tcx> {
+    fn call(&self, this: &mut MiriInterpCx<'mir, 'tcx>) -> InterpResult<'tcx> {
+        let result = match self.op {
+            IoOp::Read => this.try_read(self.fd, self.buf, self.count)?,
+            IoOp::Write => this.try_write(self.fd, self.buf, self.count)?,
+        };
+        if let Some(result) = result {
+            this.write_scalar(Scalar::from_target_isize(result, this), &self.dest)?;
+            let thread = this.get_active_thread();
+            this.unblock_thread(thread);
+        }
+        Ok(())
+    }
+}
+
 impl<'mir, 'tcx: 'mir> EvalContextExtPrivate<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
 trait EvalContextExtPrivate<'mir, 'tcx: 'mir>: crate::MiriInterpCxExt<'mir, 'tcx> {
+    /// Reads at most `count` bytes from `fd` to `buf`. Returns the value to return from `read`,
+    /// or `None` if the read would block the thread.
+    fn try_read(
+        &mut self,
+        fd: i32,
+        buf: Pointer<Option<Provenance>>,
+        count: u64,
+    ) -> InterpResult<'tcx, Option<i64>> {
+        let this = self.eval_context_mut();
+        let communicate = this.machine.communicate();
+
+        if let Some(file_descriptor) = this.machine.file_handler.handles.get_mut(&fd) {
+            trace!("read: FD mapped to {:?}", file_descriptor);
+            // We want to read at most `count` bytes. We are sure that `count` is not negative
+            // because it was a target's `usize`. Also we are sure that its smaller than
+            // `usize::MAX` because it is bounded by the host's `isize`.
+            let mut bytes = vec![0; usize::try_from(count).unwrap()];
+            // `File::read` never returns a value larger than `count`,
+            // so this cannot fail.
+            let result = file_descriptor
+                .read(communicate, &mut bytes, *this.tcx)?
+                .map(|c| i64::try_from(c).unwrap());
+
+            match result {
+                Ok(read_bytes) => {
+                    if let (Some(data_race), Some(clock)) =
+                        (&this.machine.data_race, file_descriptor.read_clock())
+                    {
+                        data_race.validate_lock_acquire(
+                            &clock,
+                            this.machine.threads.get_active_thread_id(),
+                        );
+                    }
+                    // If reading to `bytes` did not fail, we write those bytes to the buffer.
+                    this.write_bytes_ptr(buf, bytes)?;
+                    this.io_readiness_changed();
+                    Ok(Some(read_bytes))
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock && file_descriptor.is_blocking() =>
+                    Ok(None),
+                Err(e) => {
+                    this.set_last_error_from_io_error(e.kind())?;
+                    Ok(Some(-1))
+                }
+            }
+        } else {
+            trace!("read: FD not found");
+            this.handle_not_found().map(Some)
+        }
+    }
+
+    /// Writes `count` bytes from `buf` to `fd`. Returns the value to return from `write`, or
+    /// `None` if the write would block the thread.
+    fn try_write(
+        &mut self,
+        fd: i32,
+        buf: Pointer<Option<Provenance>>,
+        count: u64,
+    ) -> InterpResult<'tcx, Option<i64>> {
+        let this = self.eval_context_mut();
+        let communicate = this.machine.communicate();
+
+        if let Some(file_descriptor) = this.machine.file_handler.handles.get(&fd) {
+            let bytes = this.read_bytes_ptr_strip_provenance(buf, Size::from_bytes(count))?;
+            let result = file_descriptor
+                .write(communicate, bytes, *this.tcx)?
+                .map(|c| i64::try_from(c).unwrap());
+            match result {
+                Ok(written_bytes) => {
+                    if let (Some(data_race), Some(mut clock)) =
+                        (&this.machine.data_race, file_descriptor.write_clock())
+                    {
+                        data_race.validate_lock_release_shared(
+                            &mut clock,
+                            this.machine.threads.get_active_thread_id(),
+                            this.machine.current_span(),
+                        );
+                    }
+                    this.io_readiness_changed();
+                    Ok(Some(written_bytes))
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock && file_descriptor.is_blocking() =>
+                    Ok(None),
+                Err(e) => {
+                    this.set_last_error_from_io_error(e.kind())?;
+                    Ok(Some(-1))
+                }
+            }
+        } else {
+            this.handle_not_found().map(Some)
+        }
+    }
+
+    /// Blocks the active thread until the read or write `io` can proceed.
+    fn block_on_io(&mut self, io: BlockedIo<'tcx>) {
+        let this = self.eval_context_mut();
+        let thread = this.get_active_thread();
+        this.block_thread(thread);
+        this.register_io_callback(thread, Box::new(io));
+    }
+
     fn macos_stat_write_buf(
         &mut self,
         metadata: FileMetadata,
@@ -712,6 +901,11 @@ fn close(&mut self, fd_op: &OpTy<'tcx, Provenance>) -> InterpResult<'tcx, Scalar
         Ok(Scalar::from_i32(
             if let Some(file_descriptor) = this.machine.file_handler.handles.remove(&fd) {
                 let result = file_descriptor.close(this.machine.communicate())?;
+                for file_descriptor in this.machine.file_handler.handles.values() {
+                    file_descriptor.fd_closed(fd);
+                }
+                // Closing a file descriptor may hang up its peer.
+                this.io_readiness_changed();
                 this.try_unwrap_io_result(result)?
             } else {
                 this.handle_not_found()?
@@ -735,7 +929,8 @@ fn read(
         fd: i32,
         buf: Pointer<Option<Provenance>>,
         count: u64,
-    ) -> InterpResult<'tcx, i64> {
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
 
         // Isolation check is done via `FileDescriptor` trait.
@@ -755,34 +950,13 @@ fn read(
         let count = count
             .min(u64::try_from(this.target_isize_max()).unwrap())
             .min(u64::try_from(isize::MAX).unwrap());
-        let communicate = this.machine.communicate();
 
-        if let Some(file_descriptor) = this.machine.file_handler.handles.get_mut(&fd) {
-            trace!("read: FD mapped to {:?}", file_descriptor);
-            // We want to read at most `count` bytes. We are sure that `count` is not negative
-            // because it was a target's `usize`. Also we are sure that its smaller than
-            // `usize::MAX` because it is bounded by the host's `isize`.
-            let mut bytes = vec![0; usize::try_from(count).unwrap()];
-            // `File::read` never returns a value larger than `count`,
-            // so this cannot fail.
-            let result = file_descriptor
-                .read(communicate, &mut bytes, *this.tcx)?
-                .map(|c| i64::try_from(c).unwrap());
-
-            match result {
-                Ok(read_bytes) => {
-                    // If reading to `bytes` did not fail, we write those bytes to the buffer.
-                    this.write_bytes_ptr(buf, bytes)?;
-                    Ok(read_bytes)
-                }
-                Err(e) => {
-                    this.set_last_error_from_io_error(e.kind())?;
-                    Ok(-1)
-                }
+        match this.try_read(fd, buf, count)? {
+            Some(result) => this.write_scalar(Scalar::from_target_isize(result, this), dest),
+            None => {
+                this.block_on_io(BlockedIo { op: IoOp::Read, fd, buf, count, dest: dest.clone() });
+                Ok(())
             }
-        } else {
-            trace!("read: FD not found");
-            this.handle_not_found()
         }
     }
 
@@ -791,7 +965,8 @@ fn write(
         fd: i32,
         buf: Pointer<Option<Provenance>>,
         count: u64,
-    ) -> InterpResult<'tcx, i64> {
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
 
         // Isolation check is done via `FileDescriptor` trait.
@@ -809,16 +984,13 @@ fn write(
         let count = count
             .min(u64::try_from(this.target_isize_max()).unwrap())
             .min(u64::try_from(isize::MAX).unwrap());
-        let communicate = this.machine.communicate();
 
-        if let Some(file_descriptor) = this.machine.file_handler.handles.get(&fd) {
-            let bytes = this.read_bytes_ptr_strip_provenance(buf, Size::from_bytes(count))?;
-            let result = file_descriptor
-                .write(communicate, bytes, *this.tcx)?
-                .map(|c| i64::try_from(c).unwrap());
-            this.try_unwrap_io_result(result)
-        } else {
-            this.handle_not_found()
+        match this.try_write(fd, buf, count)? {
+            Some(result) => this.write_scalar(Scalar::from_target_isize(result, this), dest),
+            None => {
+                this.block_on_io(BlockedIo { op: IoOp::Write, fd, buf, count, dest: dest.clone() });
+                Ok(())
+            }
         }
     }
 
@@ -1,5 +1,6 @@
 use rustc_middle::ty::ScalarInt;
 
+use crate::concurrency::thread::MachineCallback;
 use crate::*;
 use epoll::{Epoll, EpollEvent};
 use event::Event;
@@ -7,7 +8,8 @@
 
 use shims::unix::fs::EvalContextExt as _;
 
-use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
 
 pub mod epoll;
 pub mod event;
@@ -70,42 +72,66 @@ fn epoll_ctl(
         let epoll_ctl_mod = this.eval_libc_i32("EPOLL_CTL_MOD");
         let epoll_ctl_del = this.eval_libc_i32("EPOLL_CTL_DEL");
 
-        if op == epoll_ctl_add || op == epoll_ctl_mod {
+        let Some(epoll) = this.machine.file_handler.handles.get(&epfd) else {
+            return Ok(Scalar::from_i32(this.handle_not_found()?));
+        };
+        let Some(epoll) = epoll.as_any().downcast_ref::<Epoll>() else {
+            let einval = this.eval_libc("EINVAL");
+            this.set_last_error(einval)?;
+            return Ok(Scalar::from_i32(-1));
+        };
+        let file_descriptors = Rc::clone(&epoll.file_descriptors);
+        if fd == epfd {
+            let einval = this.eval_libc("EINVAL");
+            this.set_last_error(einval)?;
+            return Ok(Scalar::from_i32(-1));
+        }
+        let Some(file_descriptor) = this.machine.file_handler.handles.get(&fd) else {
+            return Ok(Scalar::from_i32(this.handle_not_found()?));
+        };
+        if file_descriptor.readiness().is_none() {
+            // Like regular files, the file descriptors whose readiness Miri doesn't know cannot
+            // be watched.
+            let eperm = this.eval_libc("EPERM");
+            this.set_last_error(eperm)?;
+            return Ok(Scalar::from_i32(-1));
+        }
+
+        let errno = if op == epoll_ctl_add || op == epoll_ctl_mod {
             let event = this.deref_operand(event)?;
 
             let events = this.mplace_field(&event, 0)?;
             let events = this.read_scalar(&events.into())?.to_u32()?;
             let data = this.mplace_field(&event, 1)?;
             let data = this.read_scalar(&data.into())?;
-            let event = EpollEvent { events, data };
-
-            if let Some(epfd) = this.machine.file_handler.handles.get_mut(&epfd) {
-                let epfd = epfd
-                    .as_any_mut()
-                    .downcast_mut::<Epoll>()
-                    .ok_or_else(|| err_unsup_format!("non-epoll FD passed to `epoll_ctl`"))?;
-
-                epfd.file_descriptors.insert(fd, event);
-                Ok(Scalar::from_i32(0))
-            } else {
-                Ok(Scalar::from_i32(this.handle_not_found()?))
+            let event = EpollEvent { events, data, reported_generation: None, disabled: false };
+
+            let mut file_descriptors = file_descriptors.borrow_mut();
+            match (op == epoll_ctl_add, file_descriptors.contains_key(&fd)) {
+                (true, true) => Some("EEXIST"),
+                (false, false) => Some("ENOENT"),
+                _ => {
+                    file_descriptors.insert(fd, event);
+                    None
+                }
             }
         } else if op == epoll_ctl_del {
-            if let Some(epfd) = this.machine.file_handler.handles.get_mut(&epfd) {
-                let epfd = epfd
-                    .as_any_mut()
-                    .downcast_mut::<Epoll>()
-                    .ok_or_else(|| err_unsup_format!("non-epoll FD passed to `epoll_ctl`"))?;
-
-                epfd.file_descriptors.remove(&fd);
-                Ok(Scalar::from_i32(0))
-            } else {
-                Ok(Scalar::from_i32(this.handle_not_found()?))
+            match file_descriptors.borrow_mut().remove(&fd) {
+                Some(_) => None,
+                None => Some("ENOENT"),
             }
         } else {
-            let einval = this.eval_libc("EINVAL");
-            this.set_last_error(einval)?;
+            Some("EINVAL")
+        };
+
+        if let Some(errno) = errno {
+            let errno = this.eval_libc(errno);
+            this.set_last_error(errno)?;
             Ok(Scalar::from_i32(-1))
+        } else {
+            // The file descriptor may be ready already, for a thread waiting on the epoll.
+            this.io_readiness_changed();
+            Ok(Scalar::from_i32(0))
         }
     }
 
@@ -115,20 +141,17 @@ fn epoll_ctl(
     /// list about file descriptors in the interest list that have some
     /// events available. Up to `maxevents` are returned by `epoll_wait()`.
     /// The `maxevents` argument must be greater than zero.
-
+    ///
     /// The `timeout` argument specifies the number of milliseconds that
     /// `epoll_wait()` will block. Time is measured against the
     /// CLOCK_MONOTONIC clock.
-
+    ///
     /// A call to `epoll_wait()` will block until either:
     /// • a file descriptor delivers an event;
     /// • the call is interrupted by a signal handler; or
     /// • the timeout expires.
-
-    /// Note that the timeout interval will be rounded up to the system
-    /// clock granularity, and kernel scheduling delays mean that the
-    /// blocking interval may overrun by a small amount. Specifying a
-    /// timeout of -1 causes `epoll_wait()` to block indefinitely, while
+    ///
+    /// Specifying a timeout of -1 causes `epoll_wait()` to block indefinitely, while
     /// specifying a timeout equal to zero cause `epoll_wait()` to return
     /// immediately, even if no events are available.
     ///
@@ -144,25 +167,74 @@ fn epoll_wait(
         events: &OpTy<'tcx, Provenance>,
         maxevents: &OpTy<'tcx, Provenance>,
         timeout: &OpTy<'tcx, Provenance>,
-    ) -> InterpResult<'tcx, Scalar<Provenance>> {
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
         let this = self.eval_context_mut();
 
         let epfd = this.read_scalar(epfd)?.to_i32()?;
-        let _events = this.read_scalar(events)?.to_pointer(this)?;
-        let _maxevents = this.read_scalar(maxevents)?.to_i32()?;
-        let _timeout = this.read_scalar(timeout)?.to_i32()?;
-
-        if let Some(epfd) = this.machine.file_handler.handles.get_mut(&epfd) {
-            let _epfd = epfd
-                .as_any_mut()
-                .downcast_mut::<Epoll>()
-                .ok_or_else(|| err_unsup_format!("non-epoll FD passed to `epoll_wait`"))?;
-
-            // FIXME return number of events ready when scheme for marking events ready exists
-            throw_unsup_format!("returning ready events from epoll_wait is not yet implemented");
-        } else {
-            Ok(Scalar::from_i32(this.handle_not_found()?))
+        let events = this.read_pointer(events)?;
+        let maxevents = this.read_scalar(maxevents)?.to_i32()?;
+        let timeout = this.read_scalar(timeout)?.to_i32()?;
+
+        let Some(epoll) = this.machine.file_handler.handles.get(&epfd) else {
+            let result = this.handle_not_found()?;
+            return this.write_scalar(Scalar::from_i32(result), dest);
+        };
+        let (true, Ok(maxevents)) = (epoll.as_any().is::<Epoll>(), u32::try_from(maxevents))
+        else {
+            let einval = this.eval_libc("EINVAL");
+            this.set_last_error(einval)?;
+            return this.write_scalar(Scalar::from_i32(-1), dest);
+        };
+        if maxevents == 0 {
+            let einval = this.eval_libc("EINVAL");
+            this.set_last_error(einval)?;
+            return this.write_scalar(Scalar::from_i32(-1), dest);
+        }
+
+        let count = this.epoll_report_events(epfd, events, maxevents)?;
+        if count > 0 || timeout == 0 {
+            return this.write_scalar(Scalar::from_i32(count), dest);
         }
+
+        // Block until a file descriptor is ready or the timeout expires.
+        let thread = this.get_active_thread();
+        this.block_thread(thread);
+        this.register_io_callback(
+            thread,
+            Box::new(EpollWaitCallback { epfd, events, maxevents, dest: dest.clone() }),
+        );
+        if let Ok(timeout) = u64::try_from(timeout) {
+            struct Callback<'tcx> {
+                thread: ThreadId,
+                dest: PlaceTy<'tcx, Provenance>,
+            }
+
+            impl<'tcx> VisitTags for Callback<'tcx> {
+                fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
+                    let Callback { thread: _, dest } = self;
+                    dest.visit_tags(visit);
+                }
+            }
+
+            impl<'mir, 'tcx: 'mir> MachineCallback<'mir, 'tcx> for Callback<'tcx> {
+                fn call(&self, this: &mut MiriInterpCx<'mir, 'tcx>) -> InterpResult<'tcx> {
+                    // No file descriptor became ready in time.
+                    this.unblock_thread(self.thread);
+                    this.write_scalar(Scalar::from_i32(0), &self.dest)
+                }
+            }
+
+            let timeout_time = Time::Monotonic(
+                this.machine.clock.now().checked_add(Duration::from_millis(timeout)).unwrap(),
+            );
+            this.register_timeout_callback(
+                thread,
+                timeout_time,
+                Box::new(Callback { thread, dest: dest.clone() }),
+            );
+        }
+        Ok(())
     }
 
     /// This function creates an `Event` that is used as an event wait/notify mechanism by
@@ -174,11 +246,10 @@ fn epoll_wait(
     /// `select`, and `close` operations can be performed on the file descriptor. For more
     /// information on these operations, see the man page linked below.
     ///
-    /// The `flags` are not currently implemented for eventfd.
     /// The `flags` may be bitwise ORed to change the behavior of `eventfd`:
     /// `EFD_CLOEXEC` - Set the close-on-exec (`FD_CLOEXEC`) flag on the new file descriptor.
     /// `EFD_NONBLOCK` - Set the `O_NONBLOCK` file status flag on the new open file description.
-    /// `EFD_SEMAPHORE` - miri does not support semaphore-like semantics.
+    /// `EFD_SEMAPHORE` - Provide semaphore-like semantics for reads from the new file descriptor.
     ///
     /// <https://linux.die.net/man/2/eventfd>
     fn eventfd(
@@ -195,26 +266,23 @@ fn eventfd(
         let efd_nonblock = this.eval_libc_i32("EFD_NONBLOCK");
         let efd_semaphore = this.eval_libc_i32("EFD_SEMAPHORE");
 
-        if flags & (efd_cloexec | efd_nonblock | efd_semaphore) == 0 {
-            throw_unsup_format!("{flags} is unsupported");
-        }
-        // FIXME handle the cloexec and nonblock flags
-        if flags & efd_cloexec == efd_cloexec {}
-        if flags & efd_nonblock == efd_nonblock {}
-        if flags & efd_semaphore == efd_semaphore {
-            throw_unsup_format!("EFD_SEMAPHORE is unsupported");
+        if flags & !(efd_cloexec | efd_nonblock | efd_semaphore) != 0 {
+            let einval = this.eval_libc("EINVAL");
+            this.set_last_error(einval)?;
+            return Ok(Scalar::from_i32(-1));
         }
+        // Miri does not support exec, so `EFD_CLOEXEC` has no effect.
+        let nonblocking = flags & efd_nonblock == efd_nonblock;
+        let semaphore = flags & efd_semaphore == efd_semaphore;
 
         let fh = &mut this.machine.file_handler;
-        let fd = fh.insert_fd(Box::new(Event { val: Cell::new(val.into()) }));
+        let fd = fh.insert_fd(Box::new(Event::new(val.into(), nonblocking, semaphore)));
         Ok(Scalar::from_i32(fd))
     }
 
-    /// Currently this function creates new `SocketPair`s without specifying the domain, type, or
-    /// protocol of the new socket and these are stored in the socket values `sv` argument.
-    ///
     /// This function creates an unnamed pair of connected sockets in the specified domain, of the
-    /// specified type, and using the optionally specified protocol.
+    /// specified type, and using the optionally specified protocol, and stores their file
+    /// descriptors in the socket values `sv` argument.
     ///
     /// The `domain` argument specified a communication domain; this selects the protocol family
     /// used for communication. The socket `type` specifies the communication semantics.
@@ -223,6 +291,9 @@ fn eventfd(
     /// family, in which case `protocol` can be specified as 0. It is possible that many protocols
     /// exist and in that case, a particular protocol must be specified.
     ///
+    /// Only the `AF_UNIX` domain and the `SOCK_STREAM` type are supported, with the
+    /// `SOCK_NONBLOCK` and `SOCK_CLOEXEC` flags.
+    ///
     /// For more information on the arguments see the socket manpage:
     /// <https://linux.die.net/man/2/socket>
     ///
@@ -236,15 +307,32 @@ fn socketpair(
     ) -> InterpResult<'tcx, Scalar<Provenance>> {
         let this = self.eval_context_mut();
 
-        let _domain = this.read_scalar(domain)?.to_i32()?;
-        let _type_ = this.read_scalar(type_)?.to_i32()?;
-        let _protocol = this.read_scalar(protocol)?.to_i32()?;
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut type_ = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
         let sv = this.deref_operand(sv)?;
 
+        let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+        let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+        let nonblocking = type_ & sock_nonblock == sock_nonblock;
+        // Miri does not support exec, so `SOCK_CLOEXEC` has no effect.
+        type_ &= !(sock_nonblock | sock_cloexec);
+
+        if domain != this.eval_libc_i32("AF_UNIX") {
+            throw_unsup_format!("socketpair: domain {domain:#x} is unsupported, only AF_UNIX is");
+        }
+        if type_ != this.eval_libc_i32("SOCK_STREAM") {
+            throw_unsup_format!("socketpair: type {type_:#x} is unsupported, only SOCK_STREAM is");
+        }
+        if protocol != 0 {
+            throw_unsup_format!("socketpair: protocol {protocol} is unsupported, only 0 is");
+        }
+
+        let (end0, end1) = SocketPair::new_pair(nonblocking);
         let fh = &mut this.machine.file_handler;
-        let sv0 = fh.insert_fd(Box::new(SocketPair));
+        let sv0 = fh.insert_fd(Box::new(end0));
         let sv0 = ScalarInt::try_from_int(sv0, sv.layout.size).unwrap();
-        let sv1 = fh.insert_fd(Box::new(SocketPair));
+        let sv1 = fh.insert_fd(Box::new(end1));
         let sv1 = ScalarInt::try_from_int(sv1, sv.layout.size).unwrap();
 
         this.write_scalar(sv0, &sv.into())?;
@@ -253,3 +341,116 @@ fn socketpair(
         Ok(Scalar::from_i32(0))
     }
 }
+
+/// A thread blocked in `epoll_wait` until a file descriptor is ready.
+struct EpollWaitCallback<'tcx> {
+    epfd: i32,
+    events: Pointer<Option<Provenance>>,
+    maxevents: u32,
+    dest: PlaceTy<'tcx, Provenance>,
+}
+
+impl<'tcx> VisitTags for EpollWaitCallback<'tcx> {
+    fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
+        let EpollWaitCallback { epfd: _, events, maxevents: _, dest } = self;
+        events.visit_tags(visit);
+        dest.visit_tags(visit);
+    }
+}
+
+impl<'mir, 'tcx: 'mir> MachineCallback<'mir, 'tcx> for EpollWaitCallback<'tcx> {
+    fn call(&self, this: &mut MiriInterpCx<'mir, 'tcx>) -> InterpResult<'tcx> {
+        let count = this.epoll_report_events(self.epfd, self.events, self.maxevents)?;
+        if count > 0 {
+            this.write_scalar(Scalar::from_i32(count), &self.dest)?;
+            let thread = this.get_active_thread();
+            this.unblock_thread(thread);
+        }
+        Ok(())
+    }
+}
+
+impl<'mir, 'tcx: 'mir> EvalContextExtPrivate<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
+trait EvalContextExtPrivate<'mir, 'tcx: 'mir>: crate::MiriInterpCxExt<'mir, 'tcx> {
+    /// Writes the events of the file descriptors of the interest list of the epoll `epfd` that
+    /// are ready to `events`, an array of `maxevents` `epoll_event`s, and returns their number.
+    fn epoll_report_events(
+        &mut self,
+        epfd: i32,
+        events: Pointer<Option<Provenance>>,
+        maxevents: u32,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let epollin = this.eval_libc_u32("EPOLLIN");
+        let epollout = this.eval_libc_u32("EPOLLOUT");
+        let epollrdhup = this.eval_libc_u32("EPOLLRDHUP");
+        let epollhup = this.eval_libc_u32("EPOLLHUP");
+        let epollerr = this.eval_libc_u32("EPOLLERR");
+        let epollet = this.eval_libc_u32("EPOLLET");
+        let epolloneshot = this.eval_libc_u32("EPOLLONESHOT");
+
+        // The epoll may have been closed while a thread was waiting on it.
+        let Some(epoll) = this
+            .machine
+            .file_handler
+            .handles
+            .get(&epfd)
+            .and_then(|epoll| epoll.as_any().downcast_ref::<Epoll>())
+        else {
+            return Ok(0);
+        };
+        let mut ready = Vec::new();
+        for (fd, event) in epoll.file_descriptors.borrow_mut().iter_mut() {
+            if ready.len() == usize::try_from(maxevents).unwrap() {
+                break;
+            }
+            let Some(readiness) = this
+                .machine
+                .file_handler
+                .handles
+                .get(fd)
+                .and_then(|file_descriptor| file_descriptor.readiness())
+            else {
+                continue;
+            };
+            let mut flags = 0;
+            if readiness.readable {
+                flags |= epollin;
+            }
+            if readiness.writable {
+                flags |= epollout;
+            }
+            if readiness.read_closed {
+                flags |= epollrdhup;
+            }
+            if readiness.hung_up {
+                flags |= epollhup;
+            }
+            // `EPOLLHUP` and `EPOLLERR` are always reported.
+            let flags = flags & (event.events | epollhup | epollerr);
+            if flags == 0
+                || event.disabled
+                || (event.events & epollet != 0
+                    && event.reported_generation == Some(readiness.generation))
+            {
+                continue;
+            }
+            event.reported_generation = Some(readiness.generation);
+            event.disabled = event.events & epolloneshot != 0;
+            ready.push((flags, event.data));
+        }
+
+        let event_layout = this.libc_ty_layout("epoll_event");
+        let events = MPlaceTy::from_aligned_ptr(events, event_layout);
+        for (i, (flags, data)) in ready.iter().enumerate() {
+            let offset = event_layout.size.checked_mul(u64::try_from(i).unwrap(), this).unwrap();
+            let event = events.offset(offset, event_layout, this)?;
+            let events_field = this.mplace_field(&event, 0)?;
+            this.write_scalar(Scalar::from_u32(*flags), &events_field.into())?;
+            let data_field = this.mplace_field(&event, 1)?;
+            this.write_scalar(*data, &data_field.into())?;
+        }
+        Ok(i32::try_from(ready.len()).unwrap())
+    }
+}
@@ -2,18 +2,20 @@
 
 use crate::shims::unix::fs::FileDescriptor;
 
-use rustc_data_structures::fx::FxHashMap;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::io;
+use std::rc::Rc;
 
 /// An `Epoll` file descriptor connects file handles and epoll events
 #[derive(Clone, Debug, Default)]
 pub struct Epoll {
-    /// The file descriptors we are watching, and what we are watching for.
-    pub file_descriptors: FxHashMap<i32, EpollEvent>,
+    /// The file descriptors we are watching, and what we are watching for. The interest list is
+    /// shared by the duplicates of the epoll file descriptor.
+    pub file_descriptors: Rc<RefCell<BTreeMap<i32, EpollEvent>>>,
 }
 
 /// Epoll Events associate events with data.
-/// These fields are currently unused by miri.
 /// This matches the `epoll_event` struct defined
 /// by the epoll_ctl man page. For more information
 /// see the man page:
@@ -25,6 +27,13 @@ pub struct EpollEvent {
     /// `Scalar<Provenance>` is used to represent the
     /// `epoll_data` type union.
     pub data: Scalar<Provenance>,
+    /// The generation of the readiness of the file descriptor when its events were last
+    /// reported, so that edge-triggered (`EPOLLET`) events are only reported again once the
+    /// file descriptor changed.
+    pub reported_generation: Option<u64>,
+    /// Whether the events of the file descriptor are not reported anymore, since they were
+    /// reported once with `EPOLLONESHOT`.
+    pub disabled: bool,
 }
 
 impl FileDescriptor for Epoll {
@@ -42,4 +51,15 @@ fn close<'tcx>(
     ) -> InterpResult<'tcx, io::Result<i32>> {
         Ok(Ok(0))
     }
+
+    fn fd_closed(&self, fd: i32) {
+        // Closing a file descriptor removes it from the interest lists.
+        self.file_descriptors.borrow_mut().remove(&fd);
+    }
+
+    fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
+        for event in self.file_descriptors.borrow().values() {
+            event.data.visit_tags(visit);
+        }
+    }
 }
@@ -1,14 +1,15 @@
-use crate::shims::unix::fs::FileDescriptor;
+use crate::concurrency::vector_clock::VClock;
+use crate::shims::unix::fs::{FileDescriptor, Readiness};
 
 use rustc_const_eval::interpret::InterpResult;
 use rustc_middle::ty::TyCtxt;
 use rustc_target::abi::Endian;
 
-use std::cell::Cell;
+use std::cell::{RefCell, RefMut};
 use std::io;
+use std::rc::Rc;
 
 /// A kind of file descriptor created by `eventfd`.
-/// The `Event` type isn't currently written to by `eventfd`.
 /// The interface is meant to keep track of objects associated
 /// with a file descriptor. For more information see the man
 /// page below:
@@ -16,9 +17,34 @@
 /// <https://man.netbsd.org/eventfd.2>
 #[derive(Debug)]
 pub struct Event {
+    /// The state of the event, shared by the duplicates of its file descriptor.
+    state: Rc<RefCell<EventState>>,
+}
+
+#[derive(Debug)]
+struct EventState {
     /// The object contains an unsigned 64-bit integer (uint64_t) counter that is maintained by the
     /// kernel. This counter is initialized with the value specified in the argument initval.
-    pub val: Cell<u64>,
+    counter: u64,
+    /// Whether reads and writes fail with `EAGAIN` rather than block (`EFD_NONBLOCK`).
+    nonblocking: bool,
+    /// Whether reads decrement the counter by one rather than reset it (`EFD_SEMAPHORE`).
+    semaphore: bool,
+    /// Incremented by every successful read and write, see `Readiness::generation`.
+    generation: u64,
+    /// The vector clock of the writes to the counter.
+    clock: VClock,
+}
+
+impl Event {
+    /// The maximum value of the counter.
+    const MAX: u64 = 0xffff_ffff_ffff_fffe;
+
+    pub fn new(counter: u64, nonblocking: bool, semaphore: bool) -> Self {
+        let state =
+            EventState { counter, nonblocking, semaphore, generation: 0, clock: VClock::default() };
+        Event { state: Rc::new(RefCell::new(state)) }
+    }
 }
 
 impl FileDescriptor for Event {
@@ -27,7 +53,7 @@ fn name(&self) -> &'static str {
     }
 
     fn dup(&mut self) -> io::Result<Box<dyn FileDescriptor>> {
-        Ok(Box::new(Event { val: self.val.clone() }))
+        Ok(Box::new(Event { state: Rc::clone(&self.state) }))
     }
 
     fn close<'tcx>(
@@ -37,6 +63,37 @@ fn close<'tcx>(
         Ok(Ok(0))
     }
 
+    /// A read returns the 8-byte value of the counter (in native endianness) and resets it to
+    /// zero, or with `EFD_SEMAPHORE`, returns one and decrements the counter. If the counter is
+    /// zero, the read either blocks until a write is performed on the file descriptor, or fails
+    /// with the error EAGAIN if the file descriptor has been made nonblocking.
+    ///
+    /// A read fails with the error EINVAL if the size of the supplied buffer is less than 8
+    /// bytes.
+    fn read<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &mut [u8],
+        tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let mut state = self.state.borrow_mut();
+        let Some(bytes) = bytes.get_mut(..8) else {
+            return Ok(Err(io::ErrorKind::InvalidInput.into()));
+        };
+        if state.counter == 0 {
+            return Ok(Err(io::ErrorKind::WouldBlock.into()));
+        }
+        let num = if state.semaphore { 1 } else { state.counter };
+        state.counter = state.counter.checked_sub(num).unwrap();
+        state.generation = state.generation.wrapping_add(1);
+        // Convert from host endianess to target endianess.
+        bytes.copy_from_slice(&match tcx.sess.target.endian {
+            Endian::Little => num.to_le_bytes(),
+            Endian::Big => num.to_be_bytes(),
+        });
+        Ok(Ok(8))
+    }
+
     /// A write call adds the 8-byte integer value supplied in
     /// its buffer (in native endianess) to the counter.  The maximum value that may be
     /// stored in the counter is the largest unsigned 64-bit value
@@ -45,7 +102,7 @@ fn close<'tcx>(
     /// write either blocks until a read is performed on the
     /// file descriptor, or fails with the error EAGAIN if the
     /// file descriptor has been made nonblocking.
-
+    ///
     /// A write fails with the error EINVAL if the size of the
     /// supplied buffer is less than 8 bytes, or if an attempt is
     /// made to write the value 0xffffffffffffffff.
@@ -55,18 +112,49 @@ fn write<'tcx>(
         bytes: &[u8],
         tcx: TyCtxt<'tcx>,
     ) -> InterpResult<'tcx, io::Result<usize>> {
-        let v1 = self.val.get();
-        let bytes: [u8; 8] = bytes.try_into().unwrap(); // FIXME fail gracefully when this has the wrong size
+        let mut state = self.state.borrow_mut();
+        let Some(bytes) = bytes.get(..8) else {
+            return Ok(Err(io::ErrorKind::InvalidInput.into()));
+        };
+        let bytes: [u8; 8] = bytes.try_into().unwrap();
         // Convert from target endianess to host endianess.
         let num = match tcx.sess.target.endian {
             Endian::Little => u64::from_le_bytes(bytes),
             Endian::Big => u64::from_be_bytes(bytes),
         };
-        // FIXME handle blocking when addition results in exceeding the max u64 value
-        // or fail with EAGAIN if the file descriptor is nonblocking.
-        let v2 = v1.checked_add(num).unwrap();
-        self.val.set(v2);
-        assert_eq!(8, bytes.len());
-        Ok(Ok(8))
+        if num == u64::MAX {
+            return Ok(Err(io::ErrorKind::InvalidInput.into()));
+        }
+        match state.counter.checked_add(num) {
+            Some(counter) if counter <= Self::MAX => {
+                state.counter = counter;
+                state.generation = state.generation.wrapping_add(1);
+                Ok(Ok(8))
+            }
+            _ => Ok(Err(io::ErrorKind::WouldBlock.into())),
+        }
+    }
+
+    fn readiness(&self) -> Option<Readiness> {
+        let state = self.state.borrow();
+        Some(Readiness {
+            readable: state.counter > 0,
+            writable: state.counter < Self::MAX,
+            read_closed: false,
+            hung_up: false,
+            generation: state.generation,
+        })
+    }
+
+    fn is_blocking(&self) -> bool {
+        !self.state.borrow().nonblocking
+    }
+
+    fn read_clock(&self) -> Option<RefMut<'_, VClock>> {
+        Some(RefMut::map(self.state.borrow_mut(), |state| &mut state.clock))
+    }
+
+    fn write_clock(&self) -> Option<RefMut<'_, VClock>> {
+        Some(RefMut::map(self.state.borrow_mut(), |state| &mut state.clock))
     }
 }
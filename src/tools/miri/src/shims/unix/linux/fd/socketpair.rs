@@ -1,14 +1,63 @@
+use crate::concurrency::vector_clock::VClock;
+use crate::shims::unix::fs::{FileDescriptor, Readiness};
 use crate::*;
 
-use crate::shims::unix::fs::FileDescriptor;
+use rustc_middle::ty::TyCtxt;
 
+use std::cell::{RefCell, RefMut};
+use std::collections::VecDeque;
 use std::io;
+use std::rc::Rc;
+
+/// The number of bytes that can be written to a socket before the writes block, which is the
+/// default size of the send buffer of sockets on Linux.
+const BUFFER_CAPACITY: usize = 212992;
+
+/// One end of a pair of connected stream sockets, created by `socketpair`.
+#[derive(Debug)]
+pub struct SocketPair {
+    /// The end of the pair, shared by the duplicates of its file descriptor.
+    end: Rc<End>,
+}
 
-/// Pair of connected sockets.
-///
-/// We currently don't allow sending any data through this pair, so this can be just a dummy.
 #[derive(Debug)]
-pub struct SocketPair;
+struct End {
+    /// The data written to the other end and not read yet.
+    readbuf: Rc<RefCell<Buffer>>,
+    /// The data written to this end and not read yet by the other end.
+    writebuf: Rc<RefCell<Buffer>>,
+    /// Whether reads and writes fail with `EAGAIN` rather than block (`SOCK_NONBLOCK`).
+    nonblocking: bool,
+}
+
+/// The data sent in one direction of a socket pair.
+#[derive(Debug, Default)]
+struct Buffer {
+    data: VecDeque<u8>,
+    /// Whether one of the ends of the pair was closed, which closes the pair in both directions.
+    closed: bool,
+    /// Incremented by every read, write and close, see `Readiness::generation`.
+    generation: u64,
+    /// The vector clock of the writes of the data.
+    clock: VClock,
+}
+
+impl Buffer {
+    fn changed(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+impl SocketPair {
+    /// Creates the two ends of a new socket pair.
+    pub fn new_pair(nonblocking: bool) -> (SocketPair, SocketPair) {
+        let buf1 = Rc::new(RefCell::new(Buffer::default()));
+        let buf2 = Rc::new(RefCell::new(Buffer::default()));
+        let end1 = End { readbuf: Rc::clone(&buf1), writebuf: Rc::clone(&buf2), nonblocking };
+        let end2 = End { readbuf: buf2, writebuf: buf1, nonblocking };
+        (SocketPair { end: Rc::new(end1) }, SocketPair { end: Rc::new(end2) })
+    }
+}
 
 impl FileDescriptor for SocketPair {
     fn name(&self) -> &'static str {
@@ -16,13 +65,92 @@ fn name(&self) -> &'static str {
     }
 
     fn dup(&mut self) -> io::Result<Box<dyn FileDescriptor>> {
-        Ok(Box::new(SocketPair))
+        Ok(Box::new(SocketPair { end: Rc::clone(&self.end) }))
     }
 
     fn close<'tcx>(
         self: Box<Self>,
         _communicate_allowed: bool,
     ) -> InterpResult<'tcx, io::Result<i32>> {
+        // The socket is closed once all the file descriptors referring to it are closed.
+        if Rc::strong_count(&self.end) == 1 {
+            for buf in [&self.end.readbuf, &self.end.writebuf] {
+                let mut buf = buf.borrow_mut();
+                buf.closed = true;
+                buf.changed();
+            }
+        }
         Ok(Ok(0))
     }
+
+    /// Reads the data written to the other end. Once the other end is closed and all its data
+    /// was read, reads return 0 bytes (the end of the file).
+    fn read<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &mut [u8],
+        _tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let mut buf = self.end.readbuf.borrow_mut();
+        if bytes.is_empty() || (buf.data.is_empty() && buf.closed) {
+            return Ok(Ok(0));
+        }
+        if buf.data.is_empty() {
+            return Ok(Err(io::ErrorKind::WouldBlock.into()));
+        }
+        let len = bytes.len().min(buf.data.len());
+        for (byte, data) in bytes.iter_mut().zip(buf.data.drain(..len)) {
+            *byte = data;
+        }
+        buf.changed();
+        Ok(Ok(len))
+    }
+
+    /// Writes data for the other end to read, as much as fits in its buffer. Writing after the
+    /// other end was closed fails with `EPIPE`.
+    fn write<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        bytes: &[u8],
+        _tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let mut buf = self.end.writebuf.borrow_mut();
+        if buf.closed {
+            return Ok(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if bytes.is_empty() {
+            return Ok(Ok(0));
+        }
+        let len = bytes.len().min(BUFFER_CAPACITY.saturating_sub(buf.data.len()));
+        if len == 0 {
+            return Ok(Err(io::ErrorKind::WouldBlock.into()));
+        }
+        buf.data.extend(&bytes[..len]);
+        buf.changed();
+        Ok(Ok(len))
+    }
+
+    fn readiness(&self) -> Option<Readiness> {
+        let readbuf = self.end.readbuf.borrow();
+        let writebuf = self.end.writebuf.borrow();
+        Some(Readiness {
+            readable: !readbuf.data.is_empty() || readbuf.closed,
+            writable: writebuf.data.len() < BUFFER_CAPACITY || writebuf.closed,
+            read_closed: readbuf.closed,
+            hung_up: readbuf.closed && writebuf.closed,
+            generation: readbuf.generation.wrapping_add(writebuf.generation),
+        })
+    }
+
+    fn is_blocking(&self) -> bool {
+        !self.end.nonblocking
+    }
+
+    fn read_clock(&self) -> Option<RefMut<'_, VClock>> {
+        Some(RefMut::map(self.end.readbuf.borrow_mut(), |buf| &mut buf.clock))
+    }
+
+    fn write_clock(&self) -> Option<RefMut<'_, VClock>> {
+        Some(RefMut::map(self.end.writebuf.borrow_mut(), |buf| &mut buf.clock))
+    }
 }
@@ -2,6 +2,7 @@
 pub mod foreign_items;
 
 mod fs;
+mod socket;
 mod sync;
 mod thread;
 
@@ -11,6 +12,7 @@
 mod macos;
 
 pub use fs::{DirHandler, FileHandler};
+pub use socket::Network;
 
 // Make up some constants.
 const UID: u32 = 1000;
@@ -0,0 +1,1306 @@
+//! An in-memory loopback network. Sockets can only talk to the other sockets of the interpreted
+//! program: they can only be bound to loopback (or unspecified) addresses, and connecting to or
+//! sending to any other address fails with `ENETUNREACH`. The host network is never touched, so
+//! the network is available even with isolation enabled, and it behaves deterministically.
+
+use std::cell::{Cell, OnceCell, RefCell, RefMut};
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::rc::{Rc, Weak};
+
+use rustc_middle::ty::TyCtxt;
+use rustc_target::abi::{Align, Size};
+
+use crate::concurrency::thread::MachineCallback;
+use crate::concurrency::vector_clock::VClock;
+use crate::shims::unix::fs::{FileDescriptor, Readiness};
+use crate::*;
+use shims::unix::fs::EvalContextExt as _;
+
+/// The first port assigned to the sockets bound to port 0, the start of the dynamic port range.
+const FIRST_EPHEMERAL_PORT: u16 = 49152;
+
+/// The number of bytes that can be written to a stream socket before the writes block, which is
+/// the default size of the send buffer of sockets on Linux.
+const BUFFER_CAPACITY: usize = 212992;
+
+/// The largest payload of a UDP datagram.
+const MAX_DATAGRAM_SIZE: u64 = 65507;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SocketKind {
+    /// `SOCK_STREAM`, that is TCP.
+    Stream,
+    /// `SOCK_DGRAM`, that is UDP.
+    Datagram,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AddressFamily {
+    /// `AF_INET`
+    Inet,
+    /// `AF_INET6`
+    Inet6,
+}
+
+impl AddressFamily {
+    fn of(addr: &SocketAddr) -> Self {
+        match addr {
+            SocketAddr::V4(_) => AddressFamily::Inet,
+            SocketAddr::V6(_) => AddressFamily::Inet6,
+        }
+    }
+
+    fn unspecified(self) -> IpAddr {
+        match self {
+            AddressFamily::Inet => Ipv4Addr::UNSPECIFIED.into(),
+            AddressFamily::Inet6 => Ipv6Addr::UNSPECIFIED.into(),
+        }
+    }
+
+    fn loopback(self) -> IpAddr {
+        match self {
+            AddressFamily::Inet => Ipv4Addr::LOCALHOST.into(),
+            AddressFamily::Inet6 => Ipv6Addr::LOCALHOST.into(),
+        }
+    }
+}
+
+/// The addresses the sockets of the program are bound to.
+#[derive(Debug)]
+pub struct Network {
+    /// The bound sockets. The entries of the sockets that were closed since are removed lazily.
+    sockets: BTreeMap<(SocketKind, SocketAddr), Weak<SocketInner>>,
+    /// The next port to try to assign to a socket bound to port 0.
+    next_port: u16,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Network { sockets: BTreeMap::new(), next_port: FIRST_EPHEMERAL_PORT }
+    }
+}
+
+impl Network {
+    /// Whether binding a socket of the given kind to `addr` would conflict with a bound socket.
+    /// A socket bound to the unspecified address conflicts with all the addresses of its family.
+    fn is_in_use(&self, kind: SocketKind, addr: SocketAddr) -> bool {
+        self.sockets.keys().any(|&(bound_kind, bound)| {
+            bound_kind == kind
+                && bound.port() == addr.port()
+                && AddressFamily::of(&bound) == AddressFamily::of(&addr)
+                && (bound.ip() == addr.ip()
+                    || bound.ip().is_unspecified()
+                    || addr.ip().is_unspecified())
+        })
+    }
+
+    /// Binds `socket` to `addr`, or to a free port of its IP address if its port is 0. Returns
+    /// the address the socket is bound to, or the name of the error.
+    fn bind(
+        &mut self,
+        socket: &Rc<SocketInner>,
+        mut addr: SocketAddr,
+    ) -> Result<SocketAddr, &'static str> {
+        self.sockets.retain(|_, socket| socket.strong_count() > 0);
+        if addr.port() == 0 {
+            let port = (self.next_port..=u16::MAX)
+                .chain(FIRST_EPHEMERAL_PORT..self.next_port)
+                .find(|&port| !self.is_in_use(socket.kind, SocketAddr::new(addr.ip(), port)))
+                .ok_or("EADDRNOTAVAIL")?;
+            self.next_port = port.checked_add(1).unwrap_or(FIRST_EPHEMERAL_PORT);
+            addr.set_port(port);
+        } else if self.is_in_use(socket.kind, addr) {
+            return Err("EADDRINUSE");
+        }
+        self.sockets.insert((socket.kind, addr), Rc::downgrade(socket));
+        Ok(addr)
+    }
+
+    /// Returns the socket of the given kind that receives what is sent to `addr`.
+    fn lookup(&self, kind: SocketKind, addr: SocketAddr) -> Option<Rc<SocketInner>> {
+        let unspecified = SocketAddr::new(AddressFamily::of(&addr).unspecified(), addr.port());
+        [addr, unspecified]
+            .iter()
+            .find_map(|addr| self.sockets.get(&(kind, *addr)).and_then(Weak::upgrade))
+    }
+}
+
+/// A file descriptor created by `socket` or `accept`.
+#[derive(Debug)]
+pub struct Socket {
+    /// The socket, shared by the duplicates of its file descriptor.
+    inner: Rc<SocketInner>,
+}
+
+#[derive(Debug)]
+struct SocketInner {
+    kind: SocketKind,
+    family: AddressFamily,
+    /// Whether the operations that cannot proceed fail with `EAGAIN` rather than block.
+    nonblocking: Cell<bool>,
+    state: RefCell<SocketState>,
+    /// The connection of a connected stream socket.
+    stream: OnceCell<Stream>,
+}
+
+#[derive(Debug, Default)]
+struct SocketState {
+    /// The address the socket is bound to.
+    local: Option<SocketAddr>,
+    /// The address the socket is connected to.
+    peer: Option<SocketAddr>,
+    /// The connections waiting to be accepted, if this is a listening stream socket.
+    backlog: Option<VecDeque<PendingConnection>>,
+    /// The datagrams waiting to be received, if this is a datagram socket.
+    datagrams: VecDeque<Datagram>,
+    /// Incremented by every change of `backlog` and `datagrams`, see `Readiness::generation`.
+    generation: u64,
+}
+
+impl SocketState {
+    fn changed(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+/// A connection made by `connect`, until it is accepted by the listening socket.
+#[derive(Debug)]
+struct PendingConnection {
+    /// The address the connection was made to.
+    local: SocketAddr,
+    /// The address of the connecting socket.
+    peer: SocketAddr,
+    /// The end of the connection of the socket to be returned by `accept`.
+    stream: Stream,
+    /// The vector clock of the connecting thread, so that `connect` happens-before `accept`.
+    clock: VClock,
+}
+
+#[derive(Debug)]
+struct Datagram {
+    /// The address of the sending socket.
+    src: SocketAddr,
+    data: Vec<u8>,
+    /// The vector clock of the sending thread, so that sending happens-before receiving.
+    clock: VClock,
+}
+
+/// One end of a stream connection.
+#[derive(Debug)]
+struct Stream {
+    /// The data sent by the other end and not received yet.
+    readbuf: Rc<RefCell<Buffer>>,
+    /// The data sent by this end and not received yet by the other end.
+    writebuf: Rc<RefCell<Buffer>>,
+}
+
+/// The data sent in one direction of a stream connection.
+#[derive(Debug, Default)]
+struct Buffer {
+    data: VecDeque<u8>,
+    /// Whether the sending end shut down writing or was closed.
+    write_closed: bool,
+    /// Whether the receiving end shut down reading or was closed.
+    read_closed: bool,
+    /// Incremented by every read, write and shutdown, see `Readiness::generation`.
+    generation: u64,
+    /// The vector clock of the writes of the data.
+    clock: VClock,
+}
+
+impl Buffer {
+    fn changed(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+impl Stream {
+    /// Creates the two ends of a new connection.
+    fn new_pair() -> (Stream, Stream) {
+        let buf1 = Rc::new(RefCell::new(Buffer::default()));
+        let buf2 = Rc::new(RefCell::new(Buffer::default()));
+        let end1 = Stream { readbuf: Rc::clone(&buf1), writebuf: Rc::clone(&buf2) };
+        let end2 = Stream { readbuf: buf2, writebuf: buf1 };
+        (end1, end2)
+    }
+}
+
+impl Drop for Stream {
+    /// The connection is closed once its socket is closed, or once the listening socket is
+    /// closed if the connection was not accepted yet.
+    fn drop(&mut self) {
+        let mut readbuf = self.readbuf.borrow_mut();
+        readbuf.read_closed = true;
+        readbuf.changed();
+        let mut writebuf = self.writebuf.borrow_mut();
+        writebuf.write_closed = true;
+        writebuf.changed();
+    }
+}
+
+impl SocketInner {
+    fn new(kind: SocketKind, family: AddressFamily, nonblocking: bool) -> Self {
+        SocketInner {
+            kind,
+            family,
+            nonblocking: Cell::new(nonblocking),
+            state: RefCell::new(SocketState::default()),
+            stream: OnceCell::new(),
+        }
+    }
+}
+
+impl FileDescriptor for Socket {
+    fn name(&self) -> &'static str {
+        "socket"
+    }
+
+    fn dup(&mut self) -> io::Result<Box<dyn FileDescriptor>> {
+        Ok(Box::new(Socket { inner: Rc::clone(&self.inner) }))
+    }
+
+    fn close<'tcx>(
+        self: Box<Self>,
+        _communicate_allowed: bool,
+    ) -> InterpResult<'tcx, io::Result<i32>> {
+        // The socket is closed once all the file descriptors referring to it are closed, when
+        // its connection is dropped.
+        Ok(Ok(0))
+    }
+
+    /// Reads the data sent by the other end of the connection. Once the other end is closed or
+    /// shut down writing and all its data was read, reads return 0 bytes (the end of the file).
+    fn read<'tcx>(
+        &mut self,
+        _communicate_allowed: bool,
+        bytes: &mut [u8],
+        _tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        if self.inner.kind == SocketKind::Datagram {
+            throw_unsup_format!("reading from a datagram socket is not supported, use `recv`");
+        }
+        let Some(stream) = self.inner.stream.get() else {
+            return Ok(Err(io::ErrorKind::NotConnected.into()));
+        };
+        let mut buf = stream.readbuf.borrow_mut();
+        if bytes.is_empty() || (buf.data.is_empty() && (buf.write_closed || buf.read_closed)) {
+            return Ok(Ok(0));
+        }
+        if buf.data.is_empty() {
+            return Ok(Err(io::ErrorKind::WouldBlock.into()));
+        }
+        let len = bytes.len().min(buf.data.len());
+        for (byte, data) in bytes.iter_mut().zip(buf.data.drain(..len)) {
+            *byte = data;
+        }
+        buf.changed();
+        Ok(Ok(len))
+    }
+
+    /// Sends data to the other end of the connection, as much as fits in its buffer. Writing
+    /// after either end was closed or shut down fails with `EPIPE`.
+    fn write<'tcx>(
+        &self,
+        _communicate_allowed: bool,
+        bytes: &[u8],
+        _tcx: TyCtxt<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        if self.inner.kind == SocketKind::Datagram {
+            throw_unsup_format!("writing to a datagram socket is not supported, use `send`");
+        }
+        let Some(stream) = self.inner.stream.get() else {
+            return Ok(Err(io::ErrorKind::NotConnected.into()));
+        };
+        let mut buf = stream.writebuf.borrow_mut();
+        if buf.write_closed || buf.read_closed {
+            return Ok(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if bytes.is_empty() {
+            return Ok(Ok(0));
+        }
+        let len = bytes.len().min(BUFFER_CAPACITY.saturating_sub(buf.data.len()));
+        if len == 0 {
+            return Ok(Err(io::ErrorKind::WouldBlock.into()));
+        }
+        buf.data.extend(&bytes[..len]);
+        buf.changed();
+        Ok(Ok(len))
+    }
+
+    fn readiness(&self) -> Option<Readiness> {
+        if let Some(stream) = self.inner.stream.get() {
+            let readbuf = stream.readbuf.borrow();
+            let writebuf = stream.writebuf.borrow();
+            let read_closed = readbuf.write_closed || readbuf.read_closed;
+            let write_closed = writebuf.write_closed || writebuf.read_closed;
+            return Some(Readiness {
+                readable: !readbuf.data.is_empty() || read_closed,
+                writable: writebuf.data.len() < BUFFER_CAPACITY || write_closed,
+                read_closed: readbuf.write_closed,
+                hung_up: read_closed && write_closed,
+                generation: readbuf.generation.wrapping_add(writebuf.generation),
+            });
+        }
+        let state = self.inner.state.borrow();
+        Some(match (self.inner.kind, &state.backlog) {
+            (SocketKind::Stream, Some(backlog)) =>
+                Readiness {
+                    readable: !backlog.is_empty(),
+                    generation: state.generation,
+                    ..Readiness::default()
+                },
+            // Like on Linux, a stream socket that is neither connected nor listening is hung up.
+            (SocketKind::Stream, None) =>
+                Readiness {
+                    writable: true,
+                    hung_up: true,
+                    generation: state.generation,
+                    ..Readiness::default()
+                },
+            (SocketKind::Datagram, _) =>
+                Readiness {
+                    readable: !state.datagrams.is_empty(),
+                    writable: true,
+                    generation: state.generation,
+                    ..Readiness::default()
+                },
+        })
+    }
+
+    fn is_blocking(&self) -> bool {
+        !self.inner.nonblocking.get()
+    }
+
+    fn read_clock(&self) -> Option<RefMut<'_, VClock>> {
+        let stream = self.inner.stream.get()?;
+        Some(RefMut::map(stream.readbuf.borrow_mut(), |buf| &mut buf.clock))
+    }
+
+    fn write_clock(&self) -> Option<RefMut<'_, VClock>> {
+        let stream = self.inner.stream.get()?;
+        Some(RefMut::map(stream.writebuf.borrow_mut(), |buf| &mut buf.clock))
+    }
+}
+
+/// An `accept` or `recvfrom` of a thread blocked until it can proceed.
+#[derive(Clone, Copy, Debug)]
+enum SocketOp {
+    Accept { nonblocking: bool },
+    RecvFrom { buf: Pointer<Option<Provenance>>, len: u64 },
+}
+
+struct BlockedSocketOp<'tcx> {
+    op: SocketOp,
+    fd: i32,
+    addr: Pointer<Option<Provenance>>,
+    addrlen: Pointer<Option<Provenance>>,
+    dest: PlaceTy<'tcx, Provenance>,
+}
+
+impl<'tcx> VisitTags for BlockedSocketOp<'tcx> {
+    fn visit_tags(&self, visit: &mut dyn FnMut(BorTag)) {
+        let BlockedSocketOp { op, fd: _, addr, addrlen, dest } = self;
+        if let SocketOp::RecvFrom { buf, len: _ } = op {
+            buf.visit_tags(visit);
+        }
+        addr.visit_tags(visit);
+        addrlen.visit_tags(visit);
+        dest.visit_tags(visit);
+    }
+}
+
+impl<'mir, 'tcx: 'mir> MachineCallback<'mir, 'tcx> for BlockedSocketOp<'tcx> {
+    fn call(&self, this: &mut MiriInterpCx<'mir, 'tcx>) -> InterpResult<'tcx> {
+        let result = match self.op {
+            SocketOp::Accept { nonblocking } =>
+                this.try_accept(self.fd, self.addr, self.addrlen, nonblocking)?.map(i64::from),
+            SocketOp::RecvFrom { buf, len } =>
+                this.try_recvfrom(self.fd, buf, len, self.addr, self.addrlen)?,
+        };
+        if let Some(result) = result {
+            this.write_int(result, &self.dest)?;
+            let thread = this.get_active_thread();
+            this.unblock_thread(thread);
+        }
+        Ok(())
+    }
+}
+
+impl<'mir, 'tcx: 'mir> EvalContextExtPrivate<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
+trait EvalContextExtPrivate<'mir, 'tcx: 'mir>: crate::MiriInterpCxExt<'mir, 'tcx> {
+    /// Sets the last OS error to the error `name` and returns `-1`.
+    fn socket_error<T: From<i32>>(&mut self, name: &str) -> InterpResult<'tcx, T> {
+        let this = self.eval_context_mut();
+        let err = this.eval_libc(name);
+        this.set_last_error(err)?;
+        Ok((-1).into())
+    }
+
+    /// Returns the socket of the file descriptor `fd`, or sets the last OS error to `EBADF` or
+    /// `ENOTSOCK` and returns `None`.
+    fn socket_of(&mut self, fd: i32) -> InterpResult<'tcx, Option<Rc<SocketInner>>> {
+        let this = self.eval_context_mut();
+        let Some(file_descriptor) = this.machine.file_handler.handles.get(&fd) else {
+            this.handle_not_found::<i32>()?;
+            return Ok(None);
+        };
+        let Some(socket) = file_descriptor.as_any().downcast_ref::<Socket>() else {
+            this.socket_error::<i32>("ENOTSOCK")?;
+            return Ok(None);
+        };
+        Ok(Some(Rc::clone(&socket.inner)))
+    }
+
+    /// Reads the socket address of `len` bytes at `ptr`. Returns the address, or the name of
+    /// the error.
+    fn read_socket_addr(
+        &self,
+        ptr: Pointer<Option<Provenance>>,
+        len: u64,
+    ) -> InterpResult<'tcx, Result<SocketAddr, &'static str>> {
+        let this = self.eval_context_ref();
+
+        let sockaddr_layout = this.libc_ty_layout("sockaddr");
+        if len < sockaddr_layout.size.bytes() {
+            return Ok(Err("EINVAL"));
+        }
+        let sockaddr = MPlaceTy::from_aligned_ptr(ptr, sockaddr_layout);
+        let family = this.mplace_field_named(&sockaddr, "sa_family")?;
+        let family = this.read_scalar(&family.into())?.to_bits(family.layout.size)?;
+
+        if family == u128::try_from(this.eval_libc_i32("AF_INET")).unwrap() {
+            let sin = MPlaceTy::from_aligned_ptr(ptr, this.libc_ty_layout("sockaddr_in"));
+            if len < sin.layout.size.bytes() {
+                return Ok(Err("EINVAL"));
+            }
+            let port = this.mplace_field_named(&sin, "sin_port")?;
+            let port = this.read_bytes_ptr_strip_provenance(port.ptr, Size::from_bytes(2))?;
+            let ip = this.mplace_field_named(&sin, "sin_addr")?;
+            let ip = this.read_bytes_ptr_strip_provenance(ip.ptr, Size::from_bytes(4))?;
+            let ip = <[u8; 4]>::try_from(ip).unwrap();
+            let port = u16::from_be_bytes(port.try_into().unwrap());
+            Ok(Ok(SocketAddrV4::new(ip.into(), port).into()))
+        } else if family == u128::try_from(this.eval_libc_i32("AF_INET6")).unwrap() {
+            let sin6 = MPlaceTy::from_aligned_ptr(ptr, this.libc_ty_layout("sockaddr_in6"));
+            if len < sin6.layout.size.bytes() {
+                return Ok(Err("EINVAL"));
+            }
+            let port = this.mplace_field_named(&sin6, "sin6_port")?;
+            let port = this.read_bytes_ptr_strip_provenance(port.ptr, Size::from_bytes(2))?;
+            let ip = this.mplace_field_named(&sin6, "sin6_addr")?;
+            let ip = this.read_bytes_ptr_strip_provenance(ip.ptr, Size::from_bytes(16))?;
+            let ip = <[u8; 16]>::try_from(ip).unwrap();
+            let port = u16::from_be_bytes(port.try_into().unwrap());
+            // Scope IDs only matter for link-local addresses, which cannot be used here.
+            Ok(Ok(SocketAddrV6::new(ip.into(), port, 0, 0).into()))
+        } else {
+            Ok(Err("EAFNOSUPPORT"))
+        }
+    }
+
+    /// Writes `addr` to the buffer of `*addrlen` bytes at `ptr`, truncating it if the buffer is
+    /// too small, and sets `*addrlen` to the size of the address. Does nothing if `ptr` is null.
+    fn write_socket_addr(
+        &mut self,
+        addr: SocketAddr,
+        ptr: Pointer<Option<Provenance>>,
+        addrlen: Pointer<Option<Provenance>>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        if this.ptr_is_null(ptr)? {
+            return Ok(());
+        }
+        let addrlen = MPlaceTy::from_aligned_ptr(addrlen, this.libc_ty_layout("socklen_t"));
+        let len = this.read_scalar(&addrlen.into())?.to_u32()?;
+
+        let (layout, family) = match addr {
+            SocketAddr::V4(_) => (this.libc_ty_layout("sockaddr_in"), "AF_INET"),
+            SocketAddr::V6(_) => (this.libc_ty_layout("sockaddr_in6"), "AF_INET6"),
+        };
+        let family = this.eval_libc_i32(family);
+        // Build the address in a temporary allocation, so that it can be truncated.
+        let sockaddr = this.allocate(layout, MiriMemoryKind::Machine.into())?;
+        this.write_bytes_ptr(
+            sockaddr.ptr,
+            std::iter::repeat(0u8).take(usize::try_from(layout.size.bytes()).unwrap()),
+        )?;
+        let (prefix, ip) = match addr.ip() {
+            IpAddr::V4(ip) => ("sin", ip.octets().to_vec()),
+            IpAddr::V6(ip) => ("sin6", ip.octets().to_vec()),
+        };
+        // BSDs store the length of the address in its first byte.
+        if matches!(&*this.tcx.sess.target.os, "macos" | "freebsd") {
+            this.write_int_fields_named(
+                &[(format!("{prefix}_len").as_str(), layout.size.bytes().into())],
+                &sockaddr,
+            )?;
+        }
+        this.write_int_fields_named(
+            &[(format!("{prefix}_family").as_str(), family.into())],
+            &sockaddr,
+        )?;
+        let port = this.mplace_field_named(&sockaddr, &format!("{prefix}_port"))?;
+        this.write_bytes_ptr(port.ptr, addr.port().to_be_bytes())?;
+        let ip_field = this.mplace_field_named(&sockaddr, &format!("{prefix}_addr"))?;
+        this.write_bytes_ptr(ip_field.ptr, ip)?;
+
+        let size = layout.size.bytes().min(u64::from(len));
+        let bytes = this.read_bytes_ptr_strip_provenance(sockaddr.ptr, Size::from_bytes(size))?;
+        let bytes = bytes.to_vec();
+        this.write_bytes_ptr(ptr, bytes)?;
+        this.deallocate_ptr(sockaddr.ptr, None, MiriMemoryKind::Machine.into())?;
+
+        this.write_int(layout.size.bytes(), &addrlen.into())
+    }
+
+    /// Reads the destination address of `connect` or `sendto`. Returns the loopback address to
+    /// use, or the name of the error.
+    fn read_destination_addr(
+        &self,
+        socket: &SocketInner,
+        ptr: Pointer<Option<Provenance>>,
+        len: u64,
+    ) -> InterpResult<'tcx, Result<SocketAddr, &'static str>> {
+        let this = self.eval_context_ref();
+        let mut addr = match this.read_socket_addr(ptr, len)? {
+            Ok(addr) => addr,
+            Err(err) => return Ok(Err(err)),
+        };
+        if AddressFamily::of(&addr) != socket.family {
+            return Ok(Err("EAFNOSUPPORT"));
+        }
+        // Like on Linux, the unspecified address means the local host.
+        if addr.ip().is_unspecified() {
+            addr.set_ip(socket.family.loopback());
+        }
+        if !addr.ip().is_loopback() {
+            return Ok(Err("ENETUNREACH"));
+        }
+        Ok(Ok(addr))
+    }
+
+    /// Returns the address `socket` is bound to, binding it to a free port of the unspecified
+    /// address first if it is not bound yet. Returns the name of the error if there is no free
+    /// port.
+    fn autobind(&mut self, socket: &Rc<SocketInner>) -> Result<SocketAddr, &'static str> {
+        let this = self.eval_context_mut();
+        if let Some(local) = socket.state.borrow().local {
+            return Ok(local);
+        }
+        let addr = SocketAddr::new(socket.family.unspecified(), 0);
+        let local = this.machine.network.bind(socket, addr)?;
+        socket.state.borrow_mut().local = Some(local);
+        Ok(local)
+    }
+
+    /// Accepts a connection on the listening socket `fd`. Returns the file descriptor of the
+    /// connected socket or `-1`, or `None` if `accept` would block the thread.
+    fn try_accept(
+        &mut self,
+        fd: i32,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: Pointer<Option<Provenance>>,
+        nonblocking: bool,
+    ) -> InterpResult<'tcx, Option<i32>> {
+        let this = self.eval_context_mut();
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return Ok(Some(-1));
+        };
+        let pending = {
+            let mut state = socket.state.borrow_mut();
+            match &mut state.backlog {
+                Some(backlog) => {
+                    let pending = backlog.pop_front();
+                    if pending.is_some() {
+                        state.changed();
+                    }
+                    Ok(pending)
+                }
+                None => Err(()),
+            }
+        };
+        let Ok(pending) = pending else {
+            return this.socket_error("EINVAL").map(Some);
+        };
+        let Some(pending) = pending else {
+            if socket.nonblocking.get() {
+                return this.socket_error("EWOULDBLOCK").map(Some);
+            }
+            return Ok(None);
+        };
+
+        if let Some(data_race) = &this.machine.data_race {
+            data_race.validate_lock_acquire(&pending.clock, this.get_active_thread());
+        }
+        this.io_readiness_changed();
+
+        let connected = SocketInner::new(socket.kind, socket.family, nonblocking);
+        {
+            let mut state = connected.state.borrow_mut();
+            state.local = Some(pending.local);
+            state.peer = Some(pending.peer);
+        }
+        connected.stream.set(pending.stream).unwrap();
+        let connected = Socket { inner: Rc::new(connected) };
+        let connected_fd = this.machine.file_handler.insert_fd(Box::new(connected));
+        this.write_socket_addr(pending.peer, addr, addrlen)?;
+        Ok(Some(connected_fd))
+    }
+
+    /// Receives a datagram of at most `len` bytes on the datagram socket `fd`. Returns its size
+    /// or `-1`, or `None` if `recvfrom` would block the thread.
+    fn try_recvfrom(
+        &mut self,
+        fd: i32,
+        buf: Pointer<Option<Provenance>>,
+        len: u64,
+        addr: Pointer<Option<Provenance>>,
+        addrlen: Pointer<Option<Provenance>>,
+    ) -> InterpResult<'tcx, Option<i64>> {
+        let this = self.eval_context_mut();
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return Ok(Some(-1));
+        };
+        let datagram = {
+            let mut state = socket.state.borrow_mut();
+            let datagram = state.datagrams.pop_front();
+            if datagram.is_some() {
+                state.changed();
+            }
+            datagram
+        };
+        let Some(Datagram { src, mut data, clock }) = datagram else {
+            if socket.nonblocking.get() {
+                return this.socket_error("EWOULDBLOCK").map(Some);
+            }
+            return Ok(None);
+        };
+
+        if let Some(data_race) = &this.machine.data_race {
+            data_race.validate_lock_acquire(&clock, this.get_active_thread());
+        }
+        this.io_readiness_changed();
+
+        // The rest of a datagram that does not fit in the buffer is discarded.
+        data.truncate(usize::try_from(len).unwrap());
+        let received = i64::try_from(data.len()).unwrap();
+        this.write_bytes_ptr(buf, data)?;
+        this.write_socket_addr(src, addr, addrlen)?;
+        Ok(Some(received))
+    }
+
+    /// Sends the `len` bytes at `buf` as a datagram from the datagram socket `socket` to `dst`,
+    /// or to the address it is connected to if `dst` is `None`.
+    fn send_datagram(
+        &mut self,
+        socket: &Rc<SocketInner>,
+        buf: Pointer<Option<Provenance>>,
+        len: u64,
+        dst: Option<SocketAddr>,
+    ) -> InterpResult<'tcx, i64> {
+        let this = self.eval_context_mut();
+
+        let Some(dst) = dst.or(socket.state.borrow().peer) else {
+            return this.socket_error("EDESTADDRREQ");
+        };
+        if len > MAX_DATAGRAM_SIZE {
+            return this.socket_error("EMSGSIZE");
+        }
+        let mut src = match this.autobind(socket) {
+            Ok(src) => src,
+            Err(err) => return this.socket_error(err),
+        };
+        if src.ip().is_unspecified() {
+            src.set_ip(socket.family.loopback());
+        }
+        let data = this.read_bytes_ptr_strip_provenance(buf, Size::from_bytes(len))?.to_vec();
+
+        // Like UDP, datagrams sent to addresses no socket is bound to are lost.
+        if let Some(receiver) = this.machine.network.lookup(SocketKind::Datagram, dst) {
+            let mut clock = VClock::default();
+            if let Some(data_race) = &this.machine.data_race {
+                data_race.validate_lock_release(
+                    &mut clock,
+                    this.get_active_thread(),
+                    this.machine.current_span(),
+                );
+            }
+            let mut state = receiver.state.borrow_mut();
+            state.datagrams.push_back(Datagram { src, data, clock });
+            state.changed();
+            drop(state);
+            this.io_readiness_changed();
+        }
+        Ok(i64::try_from(len).unwrap())
+    }
+
+    /// Blocks the active thread until the `accept` or `recvfrom` `op` can proceed.
+    fn block_on_socket(&mut self, op: BlockedSocketOp<'tcx>) {
+        let this = self.eval_context_mut();
+        let thread = this.get_active_thread();
+        this.block_thread(thread);
+        this.register_io_callback(thread, Box::new(op));
+    }
+}
+
+impl<'mir, 'tcx: 'mir> EvalContextExt<'mir, 'tcx> for crate::MiriInterpCx<'mir, 'tcx> {}
+pub trait EvalContextExt<'mir, 'tcx: 'mir>: crate::MiriInterpCxExt<'mir, 'tcx> {
+    /// Creates a socket of the loopback network. Only the `AF_INET` and `AF_INET6` domains are
+    /// supported, with the `SOCK_STREAM` and `SOCK_DGRAM` types.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/socket.2.html>
+    fn socket(
+        &mut self,
+        domain: &OpTy<'tcx, Provenance>,
+        type_: &OpTy<'tcx, Provenance>,
+        protocol: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut type_ = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
+
+        let mut nonblocking = false;
+        if matches!(&*this.tcx.sess.target.os, "linux" | "android" | "freebsd") {
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            nonblocking = type_ & sock_nonblock == sock_nonblock;
+            // Miri does not support exec, so `SOCK_CLOEXEC` has no effect.
+            type_ &= !(sock_nonblock | sock_cloexec);
+        }
+
+        let family = if domain == this.eval_libc_i32("AF_INET") {
+            AddressFamily::Inet
+        } else if domain == this.eval_libc_i32("AF_INET6") {
+            AddressFamily::Inet6
+        } else if domain == this.eval_libc_i32("AF_UNIX") {
+            throw_unsup_format!("socket: the AF_UNIX domain is unsupported, use `socketpair`");
+        } else {
+            return this.socket_error("EAFNOSUPPORT");
+        };
+        let (kind, default_protocol) = if type_ == this.eval_libc_i32("SOCK_STREAM") {
+            (SocketKind::Stream, this.eval_libc_i32("IPPROTO_TCP"))
+        } else if type_ == this.eval_libc_i32("SOCK_DGRAM") {
+            (SocketKind::Datagram, this.eval_libc_i32("IPPROTO_UDP"))
+        } else {
+            throw_unsup_format!("socket: type {type_:#x} is unsupported");
+        };
+        if protocol != 0 && protocol != default_protocol {
+            return this.socket_error("EPROTONOSUPPORT");
+        }
+
+        let socket = Socket { inner: Rc::new(SocketInner::new(kind, family, nonblocking)) };
+        Ok(this.machine.file_handler.insert_fd(Box::new(socket)))
+    }
+
+    /// Binds a socket to a loopback or unspecified address. Binding to port 0 picks a free port.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/bind.2.html>
+    fn bind(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        addr: &OpTy<'tcx, Provenance>,
+        addrlen: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_scalar(addrlen)?.to_u32()?;
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return Ok(-1);
+        };
+        if socket.state.borrow().local.is_some() {
+            return this.socket_error("EINVAL");
+        }
+        let addr = match this.read_socket_addr(addr, addrlen.into())? {
+            Ok(addr) => addr,
+            Err(err) => return this.socket_error(err),
+        };
+        if AddressFamily::of(&addr) != socket.family {
+            return this.socket_error("EAFNOSUPPORT");
+        }
+        // The loopback network has no other interface.
+        if !addr.ip().is_loopback() && !addr.ip().is_unspecified() {
+            return this.socket_error("EADDRNOTAVAIL");
+        }
+        match this.machine.network.bind(&socket, addr) {
+            Ok(local) => {
+                socket.state.borrow_mut().local = Some(local);
+                Ok(0)
+            }
+            Err(err) => this.socket_error(err),
+        }
+    }
+
+    /// Makes a stream socket accept connections. The size of the backlog is ignored: connections
+    /// never block.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/listen.2.html>
+    fn listen(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        backlog: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let _backlog = this.read_scalar(backlog)?.to_i32()?;
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return Ok(-1);
+        };
+        if socket.kind != SocketKind::Stream {
+            return this.socket_error("EOPNOTSUPP");
+        }
+        if socket.stream.get().is_some() {
+            return this.socket_error("EINVAL");
+        }
+        if let Err(err) = this.autobind(&socket) {
+            return this.socket_error(err);
+        }
+        socket.state.borrow_mut().backlog.get_or_insert_with(VecDeque::new);
+        this.io_readiness_changed();
+        Ok(0)
+    }
+
+    /// Connects a socket. Connecting a stream socket to a listening socket of the program
+    /// succeeds right away, even for non-blocking sockets; connecting a datagram socket only sets
+    /// the address its datagrams are sent to.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/connect.2.html>
+    fn connect(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        addr: &OpTy<'tcx, Provenance>,
+        addrlen: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_scalar(addrlen)?.to_u32()?;
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return Ok(-1);
+        };
+        if socket.stream.get().is_some() {
+            return this.socket_error("EISCONN");
+        }
+        if socket.state.borrow().backlog.is_some() {
+            return this.socket_error("EINVAL");
+        }
+        let dst = match this.read_destination_addr(&socket, addr, addrlen.into())? {
+            Ok(dst) => dst,
+            Err(err) => return this.socket_error(err),
+        };
+
+        let listener = match socket.kind {
+            SocketKind::Stream => {
+                let listener = this
+                    .machine
+                    .network
+                    .lookup(SocketKind::Stream, dst)
+                    .filter(|listener| listener.state.borrow().backlog.is_some());
+                let Some(listener) = listener else {
+                    return this.socket_error("ECONNREFUSED");
+                };
+                Some(listener)
+            }
+            SocketKind::Datagram => None,
+        };
+        let mut local = match this.autobind(&socket) {
+            Ok(local) => local,
+            Err(err) => return this.socket_error(err),
+        };
+        if local.ip().is_unspecified() {
+            local.set_ip(socket.family.loopback());
+        }
+
+        if let Some(listener) = listener {
+            let (stream, accepted) = Stream::new_pair();
+            let mut clock = VClock::default();
+            if let Some(data_race) = &this.machine.data_race {
+                data_race.validate_lock_release(
+                    &mut clock,
+                    this.get_active_thread(),
+                    this.machine.current_span(),
+                );
+            }
+            let pending = PendingConnection { local: dst, peer: local, stream: accepted, clock };
+            let mut state = listener.state.borrow_mut();
+            state.backlog.as_mut().unwrap().push_back(pending);
+            state.changed();
+            drop(state);
+            socket.stream.set(stream).unwrap();
+            socket.state.borrow_mut().local = Some(local);
+            this.io_readiness_changed();
+        }
+        socket.state.borrow_mut().peer = Some(dst);
+        Ok(0)
+    }
+
+    /// Accepts a connection on a listening socket, blocking until there is one unless the socket
+    /// is non-blocking. `flags` are the flags of `accept4`.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/accept.2.html>
+    fn accept4(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        addr: &OpTy<'tcx, Provenance>,
+        addrlen: &OpTy<'tcx, Provenance>,
+        flags: Option<&OpTy<'tcx, Provenance>>,
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+        let mut nonblocking = false;
+        if let Some(flags) = flags {
+            let flags = this.read_scalar(flags)?.to_i32()?;
+            let sock_nonblock = this.eval_libc_i32("SOCK_NONBLOCK");
+            let sock_cloexec = this.eval_libc_i32("SOCK_CLOEXEC");
+            if flags & !(sock_nonblock | sock_cloexec) != 0 {
+                let result = this.socket_error::<i32>("EINVAL")?;
+                return this.write_int(result, dest);
+            }
+            // Miri does not support exec, so `SOCK_CLOEXEC` has no effect.
+            nonblocking = flags & sock_nonblock == sock_nonblock;
+        }
+
+        match this.try_accept(fd, addr, addrlen, nonblocking)? {
+            Some(result) => this.write_int(result, dest),
+            None => {
+                let op = SocketOp::Accept { nonblocking };
+                this.block_on_socket(BlockedSocketOp { op, fd, addr, addrlen, dest: dest.clone() });
+                Ok(())
+            }
+        }
+    }
+
+    /// Sends data on a socket. On stream sockets this is `write`, and `MSG_NOSIGNAL` is the only
+    /// supported flag (Miri does not raise `SIGPIPE` anyway).
+    ///
+    /// <https://man7.org/linux/man-pages/man2/send.2.html>
+    fn sendto(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        buf: &OpTy<'tcx, Provenance>,
+        len: &OpTy<'tcx, Provenance>,
+        flags: &OpTy<'tcx, Provenance>,
+        addr: Option<(&OpTy<'tcx, Provenance>, &OpTy<'tcx, Provenance>)>,
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+        let addr = match addr {
+            Some((addr, addrlen)) => {
+                let addr = this.read_pointer(addr)?;
+                let addrlen = this.read_scalar(addrlen)?.to_u32()?;
+                (!this.ptr_is_null(addr)?).then_some((addr, addrlen))
+            }
+            None => None,
+        };
+
+        let target_os = &*this.tcx.sess.target.os;
+        let msg_nosignal = if matches!(target_os, "linux" | "android" | "freebsd") {
+            this.eval_libc_i32("MSG_NOSIGNAL")
+        } else {
+            0
+        };
+        if flags & !msg_nosignal != 0 {
+            throw_unsup_format!("send: flags {flags:#x} are unsupported");
+        }
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return this.write_int(-1, dest);
+        };
+        match socket.kind {
+            SocketKind::Stream => {
+                if addr.is_some() {
+                    let result = this.socket_error::<i32>("EISCONN")?;
+                    return this.write_int(result, dest);
+                }
+                this.write(fd, buf, len, dest)
+            }
+            SocketKind::Datagram => {
+                let dst = match addr {
+                    Some((addr, addrlen)) =>
+                        match this.read_destination_addr(&socket, addr, addrlen.into())? {
+                            Ok(dst) => Some(dst),
+                            Err(err) => {
+                                let result = this.socket_error::<i32>(err)?;
+                                return this.write_int(result, dest);
+                            }
+                        },
+                    None => None,
+                };
+                let result = this.send_datagram(&socket, buf, len, dst)?;
+                this.write_int(result, dest)
+            }
+        }
+    }
+
+    /// Receives data on a socket, blocking until there is some unless the socket is
+    /// non-blocking. On stream sockets this is `read`, and no flag is supported.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/recv.2.html>
+    fn recvfrom(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        buf: &OpTy<'tcx, Provenance>,
+        len: &OpTy<'tcx, Provenance>,
+        flags: &OpTy<'tcx, Provenance>,
+        addr: Option<(&OpTy<'tcx, Provenance>, &OpTy<'tcx, Provenance>)>,
+        dest: &PlaceTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let flags = this.read_scalar(flags)?.to_i32()?;
+        let (addr, addrlen) = match addr {
+            Some((addr, addrlen)) => (this.read_pointer(addr)?, this.read_pointer(addrlen)?),
+            None => (Pointer::null(), Pointer::null()),
+        };
+
+        if flags != 0 {
+            throw_unsup_format!("recv: flags {flags:#x} are unsupported");
+        }
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return this.write_int(-1, dest);
+        };
+        match socket.kind {
+            SocketKind::Stream => this.read(fd, buf, len, dest),
+            SocketKind::Datagram => {
+                // Check that the *entire* buffer is actually valid memory.
+                this.check_ptr_access_align(
+                    buf,
+                    Size::from_bytes(len),
+                    Align::ONE,
+                    CheckInAllocMsg::MemoryAccessTest,
+                )?;
+                match this.try_recvfrom(fd, buf, len, addr, addrlen)? {
+                    Some(result) => this.write_int(result, dest),
+                    None => {
+                        let op = SocketOp::RecvFrom { buf, len };
+                        let dest = dest.clone();
+                        this.block_on_socket(BlockedSocketOp { op, fd, addr, addrlen, dest });
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+
+    /// Shuts down reading, writing or both on a connected stream socket.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/shutdown.2.html>
+    fn shutdown(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        how: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let how = this.read_scalar(how)?.to_i32()?;
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return Ok(-1);
+        };
+        let (read, write) = if how == this.eval_libc_i32("SHUT_RD") {
+            (true, false)
+        } else if how == this.eval_libc_i32("SHUT_WR") {
+            (false, true)
+        } else if how == this.eval_libc_i32("SHUT_RDWR") {
+            (true, true)
+        } else {
+            return this.socket_error("EINVAL");
+        };
+        let Some(stream) = socket.stream.get() else {
+            return this.socket_error("ENOTCONN");
+        };
+        if read {
+            let mut readbuf = stream.readbuf.borrow_mut();
+            readbuf.read_closed = true;
+            readbuf.changed();
+        }
+        if write {
+            let mut writebuf = stream.writebuf.borrow_mut();
+            writebuf.write_closed = true;
+            writebuf.changed();
+        }
+        this.io_readiness_changed();
+        Ok(0)
+    }
+
+    /// Writes the address a socket is bound to (`getsockname`), or the address it is connected
+    /// to (`getpeername`, if `peer` is set).
+    ///
+    /// <https://man7.org/linux/man-pages/man2/getsockname.2.html>
+    fn getsockname(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        addr: &OpTy<'tcx, Provenance>,
+        addrlen: &OpTy<'tcx, Provenance>,
+        peer: bool,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+
+        let Some(socket) = this.socket_of(fd)? else {
+            return Ok(-1);
+        };
+        let state = socket.state.borrow();
+        let name = if peer {
+            state.peer
+        } else {
+            Some(state.local.unwrap_or(SocketAddr::new(socket.family.unspecified(), 0)))
+        };
+        drop(state);
+        let Some(name) = name else {
+            return this.socket_error("ENOTCONN");
+        };
+        this.write_socket_addr(name, addr, addrlen)?;
+        Ok(0)
+    }
+
+    /// Sets a socket option. Only the options that make no difference on the loopback network
+    /// are supported, and they are ignored.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/setsockopt.2.html>
+    fn setsockopt(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        level: &OpTy<'tcx, Provenance>,
+        name: &OpTy<'tcx, Provenance>,
+        value: &OpTy<'tcx, Provenance>,
+        len: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let name = this.read_scalar(name)?.to_i32()?;
+        let _value = this.read_pointer(value)?;
+        let _len = this.read_scalar(len)?.to_u32()?;
+
+        let mut ignored = vec![
+            ("SOL_SOCKET", "SO_REUSEADDR"),
+            ("SOL_SOCKET", "SO_KEEPALIVE"),
+            ("IPPROTO_TCP", "TCP_NODELAY"),
+            ("IPPROTO_IPV6", "IPV6_V6ONLY"),
+        ];
+        if this.tcx.sess.target.os == "macos" {
+            ignored.push(("SOL_SOCKET", "SO_NOSIGPIPE"));
+        }
+        if !ignored.iter().any(|&(ignored_level, ignored_name)| {
+            level == this.eval_libc_i32(ignored_level) && name == this.eval_libc_i32(ignored_name)
+        }) {
+            throw_unsup_format!("setsockopt: option {name:#x} at level {level:#x} is unsupported");
+        }
+
+        if this.socket_of(fd)?.is_none() {
+            return Ok(-1);
+        }
+        Ok(0)
+    }
+
+    /// Gets a socket option. Only `SO_ERROR` is supported, and there never is a pending error.
+    ///
+    /// <https://man7.org/linux/man-pages/man2/getsockopt.2.html>
+    fn getsockopt(
+        &mut self,
+        fd: &OpTy<'tcx, Provenance>,
+        level: &OpTy<'tcx, Provenance>,
+        name: &OpTy<'tcx, Provenance>,
+        value: &OpTy<'tcx, Provenance>,
+        len: &OpTy<'tcx, Provenance>,
+    ) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let name = this.read_scalar(name)?.to_i32()?;
+        let value = this.read_pointer(value)?;
+        let len = this.read_pointer(len)?;
+
+        if level != this.eval_libc_i32("SOL_SOCKET") || name != this.eval_libc_i32("SO_ERROR") {
+            throw_unsup_format!("getsockopt: option {name:#x} at level {level:#x} is unsupported");
+        }
+
+        if this.socket_of(fd)?.is_none() {
+            return Ok(-1);
+        }
+        let len = MPlaceTy::from_aligned_ptr(len, this.libc_ty_layout("socklen_t"));
+        let c_int = this.libc_ty_layout("c_int");
+        if this.read_scalar(&len.into())?.to_u32()? < u32::try_from(c_int.size.bytes()).unwrap() {
+            return this.socket_error("EINVAL");
+        }
+        this.write_int(0, &MPlaceTy::from_aligned_ptr(value, c_int).into())?;
+        this.write_int(c_int.size.bytes(), &len.into())?;
+        Ok(0)
+    }
+
+    /// `ioctl` is only supported with `FIOCLEX`, which has no effect since Miri does not support
+    /// exec, and with `FIONBIO` on sockets, which sets whether they block.
+    fn ioctl(&mut self, args: &[OpTy<'tcx, Provenance>]) -> InterpResult<'tcx, i32> {
+        let this = self.eval_context_mut();
+
+        let [fd, request, ..] = args else {
+            throw_ub_format!(
+                "incorrect number of arguments for ioctl: got {}, expected at least 2",
+                args.len()
+            );
+        };
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let request_size = request.layout.size;
+        let request = this.read_scalar(request)?.to_bits(request_size)?;
+
+        if request == this.eval_libc("FIOCLEX").to_bits(request_size)? {
+            if !this.machine.file_handler.handles.contains_key(&fd) {
+                return this.handle_not_found();
+            }
+            Ok(0)
+        } else if request == this.eval_libc("FIONBIO").to_bits(request_size)? {
+            let Some(arg) = args.get(2) else {
+                throw_ub_format!(
+                    "incorrect number of arguments for ioctl with request=`FIONBIO`: got {}, expected at least 3",
+                    args.len()
+                );
+            };
+            let arg = this.read_pointer(arg)?;
+            if !this.machine.file_handler.handles.contains_key(&fd) {
+                return this.handle_not_found();
+            }
+            let Some(socket) = this.socket_of(fd)? else {
+                throw_unsup_format!("ioctl: `FIONBIO` is only supported on sockets");
+            };
+            let arg = MPlaceTy::from_aligned_ptr(arg, this.libc_ty_layout("c_int"));
+            let nonblocking = this.read_scalar(&arg.into())?.to_i32()? != 0;
+            socket.nonblocking.set(nonblocking);
+            Ok(0)
+        } else {
+            throw_unsup_format!("ioctl: request {request:#x} is unsupported");
+        }
+    }
+}
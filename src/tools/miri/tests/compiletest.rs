@@ -1,8 +1,9 @@
 use colored::*;
 use regex::bytes::Regex;
 use std::path::{Path, PathBuf};
-use std::{env, process::Command};
-use ui_test::{color_eyre::Result, Config, Match, Mode, OutputConflictHandling};
+use std::{env, fs, process::Command};
+use ui_test::color_eyre::{eyre::bail, Result};
+use ui_test::{Config, Match, Mode, OutputConflictHandling};
 
 fn miri_path() -> PathBuf {
     PathBuf::from(option_env!("MIRI").unwrap_or(env!("CARGO_BIN_EXE_miri")))
@@ -206,6 +207,55 @@ fn ui(mode: Mode, path: &str, target: &str, with_dependencies: Dependencies) ->
     run_tests(mode, path, target, with_dependencies)
 }
 
+/// Runs the programs in `tests/coverage` with `-Zmiri-coverage` and compares the lcov file each
+/// of them writes with the `.lcov` file next to it.
+fn coverage(target: &str) -> Result<()> {
+    let msg = format!("## Running coverage tests against miri for {target}");
+    eprintln!("{}", msg.green().bold());
+
+    let bless = env::var_os("MIRI_BLESS").is_some();
+    let out_dir = env::temp_dir().join(format!("miri-coverage-{}", std::process::id()));
+    for entry in fs::read_dir("tests/coverage")? {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "rs") {
+            continue;
+        }
+        eprintln!("{}", path.display());
+
+        // The name of the lcov file includes the process ID, so give every program its own
+        // directory.
+        let _ = fs::remove_dir_all(&out_dir);
+        fs::create_dir_all(&out_dir)?;
+        let mut flag = std::ffi::OsString::from("-Zmiri-coverage=");
+        flag.push(out_dir.join("coverage"));
+        let status = Command::new(miri_path())
+            .arg(&path)
+            .args(["--edition", "2018", "--target", target])
+            .arg(flag)
+            .status()?;
+        if !status.success() {
+            bail!("{} failed: {status}", path.display());
+        }
+
+        let lcov = fs::read_dir(&out_dir)?.next().expect("Miri did not write the coverage")?;
+        // Windows file paths
+        let actual = fs::read_to_string(lcov.path())?.replace('\\', "/");
+        let expected_path = path.with_extension("lcov");
+        if bless {
+            fs::write(&expected_path, &actual)?;
+            continue;
+        }
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if actual != expected {
+            eprintln!("--- expected {}\n{expected}", expected_path.display());
+            eprintln!("--- actual\n{actual}");
+            bail!("the coverage of {} did not match {}", path.display(), expected_path.display());
+        }
+    }
+    let _ = fs::remove_dir_all(&out_dir);
+    Ok(())
+}
+
 fn get_target() -> String {
     env::var("MIRI_TEST_TARGET").ok().unwrap_or_else(get_host)
 }
@@ -232,6 +282,7 @@ fn main() -> Result<()> {
             WithoutDependencies,
         )?;
     }
+    coverage(&target)?;
 
     Ok(())
 }
@@ -0,0 +1,20 @@
+// The `match` that a `for` loop desugars to has an unreachable `otherwise` branch, which must
+// not be reported as a branch that was never taken.
+
+fn main() {
+    let mut sum = 0;
+    for i in 0..3 {
+        if i % 2 == 0 {
+            sum += i;
+        }
+    }
+    assert_eq!(sum, 2);
+}
+
+#[allow(dead_code)]
+fn unused(x: Option<i32>) -> i32 {
+    match x {
+        Some(x) => x,
+        None => 0,
+    }
+}
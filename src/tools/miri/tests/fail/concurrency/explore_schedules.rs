@@ -0,0 +1,23 @@
+//@compile-flags: -Zmiri-explore-schedules=1
+
+// The spawned thread publishes `DATA` with a relaxed store, which does not make the write visible
+// to the main thread. Without preemptions the main thread reads `READY` before the spawned thread
+// runs and never touches `DATA`, so the race only shows up if the main thread is preempted right
+// after spawning the thread.
+
+use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+use std::thread;
+
+static READY: AtomicBool = AtomicBool::new(false);
+static mut DATA: u32 = 0;
+
+fn main() {
+    let handle = thread::spawn(|| {
+        unsafe { DATA = 42 };
+        READY.store(true, Relaxed);
+    });
+    if READY.load(Relaxed) {
+        let _val = unsafe { DATA }; //~ ERROR: Data race detected
+    }
+    handle.join().unwrap();
+}
@@ -0,0 +1,44 @@
+//@ignore-target-windows: No libc on Windows
+//@compile-flags: -Zmiri-replay-schedule=tests/fail/concurrency/minimize_schedule.schedule -Zmiri-minimize-schedule
+
+// Two threads lock two mutexes in opposite order, which deadlocks if the second thread gets
+// preempted between its two locks. The schedule was recorded with `-Zmiri-preemption-rate=0.3`,
+// but only one of its preemptions is needed for the deadlock.
+
+#![feature(start)]
+
+use std::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use std::{mem, ptr};
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+static mut A: libc::pthread_mutex_t = libc::PTHREAD_MUTEX_INITIALIZER;
+static mut B: libc::pthread_mutex_t = libc::PTHREAD_MUTEX_INITIALIZER;
+
+extern "C" fn thread_start(_null: *mut libc::c_void) -> *mut libc::c_void {
+    unsafe {
+        STARTED.store(true, SeqCst);
+        assert_eq!(libc::pthread_mutex_lock(ptr::addr_of_mut!(B)), 0);
+        assert_eq!(libc::pthread_mutex_lock(ptr::addr_of_mut!(A)), 0); //~ ERROR: deadlock
+        assert_eq!(libc::pthread_mutex_unlock(ptr::addr_of_mut!(A)), 0);
+        assert_eq!(libc::pthread_mutex_unlock(ptr::addr_of_mut!(B)), 0);
+    }
+    ptr::null_mut()
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    unsafe {
+        let mut native: libc::pthread_t = mem::zeroed();
+        let attr: libc::pthread_attr_t = mem::zeroed();
+        assert_eq!(libc::pthread_create(&mut native, &attr, thread_start, ptr::null_mut()), 0);
+        while !STARTED.load(SeqCst) {
+            libc::sched_yield();
+        }
+        assert_eq!(libc::pthread_mutex_lock(ptr::addr_of_mut!(A)), 0);
+        assert_eq!(libc::pthread_mutex_lock(ptr::addr_of_mut!(B)), 0);
+        assert_eq!(libc::pthread_mutex_unlock(ptr::addr_of_mut!(B)), 0);
+        assert_eq!(libc::pthread_mutex_unlock(ptr::addr_of_mut!(A)), 0);
+        assert_eq!(libc::pthread_join(native, ptr::null_mut()), 0);
+    }
+    0
+}
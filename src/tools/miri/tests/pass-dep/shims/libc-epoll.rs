@@ -0,0 +1,129 @@
+//@only-target-linux
+
+use std::thread;
+
+fn epoll_ctl(epfd: i32, op: i32, fd: i32, events: i32, data: u64) -> i32 {
+    let mut event = libc::epoll_event { events: events as u32, u64: data };
+    unsafe { libc::epoll_ctl(epfd, op, fd, &mut event) }
+}
+
+fn epoll_wait(epfd: i32, timeout: i32) -> Vec<(u32, u64)> {
+    let mut events = [libc::epoll_event { events: 0, u64: 0 }; 8];
+    let res = unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 8, timeout) };
+    assert!(res >= 0);
+    events[..res as usize].iter().map(|event| (event.events, event.u64)).collect()
+}
+
+fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap()
+}
+
+fn test_ctl() {
+    let epfd = unsafe { libc::epoll_create1(libc::EPOLL_CLOEXEC) };
+    assert!(epfd >= 0);
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, libc::EPOLLIN, 1), 0);
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, libc::EPOLLIN, 1), -1);
+    assert_eq!(errno(), libc::EEXIST);
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, epfd, libc::EPOLLIN, 1), -1);
+    assert_eq!(errno(), libc::EINVAL);
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_MOD, fd, libc::EPOLLOUT, 2), 0);
+    assert_eq!(epoll_wait(epfd, 0), [(libc::EPOLLOUT as u32, 2)]);
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_DEL, fd, 0, 0), 0);
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_DEL, fd, 0, 0), -1);
+    assert_eq!(errno(), libc::ENOENT);
+    assert_eq!(epoll_wait(epfd, 0), []);
+    // Closing a file descriptor removes it from the interest list.
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, libc::EPOLLOUT, 3), 0);
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+    assert_eq!(epoll_wait(epfd, 0), []);
+    assert_eq!(unsafe { libc::close(epfd) }, 0);
+}
+
+fn test_level_and_edge_triggered() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    let mut fds = [-1; 2];
+    let res = unsafe {
+        libc::socketpair(
+            libc::AF_UNIX,
+            libc::SOCK_STREAM | libc::SOCK_NONBLOCK,
+            0,
+            fds.as_mut_ptr(),
+        )
+    };
+    assert_eq!(res, 0);
+    let [fd0, fd1] = fds;
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd0, libc::EPOLLIN, 0), 0);
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd1, libc::EPOLLIN | libc::EPOLLET, 1), 0);
+    assert_eq!(epoll_wait(epfd, 0), []);
+
+    let buf = [1u8; 4];
+    assert_eq!(unsafe { libc::write(fd0, buf.as_ptr().cast(), 4) }, 4);
+    assert_eq!(unsafe { libc::write(fd1, buf.as_ptr().cast(), 4) }, 4);
+    let epollin = libc::EPOLLIN as u32;
+    assert_eq!(epoll_wait(epfd, 0), [(epollin, 0), (epollin, 1)]);
+    // Level-triggered events are reported as long as the file descriptor is ready, edge-triggered
+    // ones only once it changed.
+    assert_eq!(epoll_wait(epfd, 0), [(epollin, 0)]);
+    assert_eq!(unsafe { libc::write(fd0, buf.as_ptr().cast(), 4) }, 4);
+    assert_eq!(epoll_wait(epfd, 0), [(epollin, 0), (epollin, 1)]);
+
+    // Closing one end hangs up the other.
+    assert_eq!(unsafe { libc::close(fd0) }, 0);
+    let hup = (libc::EPOLLIN | libc::EPOLLHUP) as u32;
+    assert_eq!(epoll_wait(epfd, 0), [(hup, 1)]);
+    assert_eq!(unsafe { libc::close(fd1) }, 0);
+    assert_eq!(unsafe { libc::close(epfd) }, 0);
+}
+
+fn test_oneshot() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    let fd = unsafe { libc::eventfd(1, libc::EFD_NONBLOCK) };
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, libc::EPOLLIN | libc::EPOLLONESHOT, 7), 0);
+    assert_eq!(epoll_wait(epfd, 0), [(libc::EPOLLIN as u32, 7)]);
+    assert_eq!(epoll_wait(epfd, 0), []);
+    // Modifying the file descriptor enables it again.
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_MOD, fd, libc::EPOLLIN | libc::EPOLLONESHOT, 8), 0);
+    assert_eq!(epoll_wait(epfd, 0), [(libc::EPOLLIN as u32, 8)]);
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+    assert_eq!(unsafe { libc::close(epfd) }, 0);
+}
+
+fn test_timeout() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, libc::EPOLLIN, 0), 0);
+    assert_eq!(epoll_wait(epfd, 10), []);
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+    assert_eq!(unsafe { libc::close(epfd) }, 0);
+}
+
+fn test_blocking() {
+    static mut DATA: u64 = 0;
+    let epfd = unsafe { libc::epoll_create1(0) };
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    assert_eq!(epoll_ctl(epfd, libc::EPOLL_CTL_ADD, fd, libc::EPOLLIN, 5), 0);
+    let waiter = thread::spawn(move || {
+        // Blocks until the main thread writes to the eventfd.
+        assert_eq!(epoll_wait(epfd, -1), [(libc::EPOLLIN as u32, 5)]);
+        let mut buf = [0u8; 8];
+        assert_eq!(unsafe { libc::read(fd, buf.as_mut_ptr().cast(), 8) }, 8);
+        // The write happens-before the read.
+        assert_eq!(unsafe { DATA }, 42);
+    });
+    thread::yield_now();
+    unsafe { DATA = 42 };
+    let buf = 1u64.to_ne_bytes();
+    assert_eq!(unsafe { libc::write(fd, buf.as_ptr().cast(), 8) }, 8);
+    waiter.join().unwrap();
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+    assert_eq!(unsafe { libc::close(epfd) }, 0);
+}
+
+fn main() {
+    test_ctl();
+    test_level_and_edge_triggered();
+    test_oneshot();
+    test_timeout();
+    test_blocking();
+}
@@ -0,0 +1,85 @@
+//@only-target-linux
+
+use std::thread;
+
+fn read(fd: i32) -> (isize, u64) {
+    let mut buf = [0u8; 8];
+    let res = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), 8) };
+    (res, u64::from_ne_bytes(buf))
+}
+
+fn write(fd: i32, num: u64) -> isize {
+    let buf = num.to_ne_bytes();
+    unsafe { libc::write(fd, buf.as_ptr().cast(), 8) }
+}
+
+fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap()
+}
+
+fn test_counter() {
+    let fd = unsafe { libc::eventfd(3, libc::EFD_NONBLOCK | libc::EFD_CLOEXEC) };
+    assert!(fd >= 0);
+    assert_eq!(write(fd, 4), 8);
+    assert_eq!(read(fd), (8, 7));
+    // The counter is reset by the read.
+    assert_eq!(read(fd).0, -1);
+    assert_eq!(errno(), libc::EAGAIN);
+    // A too small buffer is rejected.
+    let mut buf = [0u8; 4];
+    assert_eq!(unsafe { libc::read(fd, buf.as_mut_ptr().cast(), 4) }, -1);
+    assert_eq!(errno(), libc::EINVAL);
+    // So is the maximum value.
+    assert_eq!(write(fd, u64::MAX), -1);
+    assert_eq!(errno(), libc::EINVAL);
+    // The counter cannot overflow.
+    assert_eq!(write(fd, u64::MAX - 1), 8);
+    assert_eq!(write(fd, 1), -1);
+    assert_eq!(errno(), libc::EAGAIN);
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+}
+
+fn test_semaphore() {
+    let fd = unsafe { libc::eventfd(2, libc::EFD_NONBLOCK | libc::EFD_SEMAPHORE) };
+    assert!(fd >= 0);
+    assert_eq!(read(fd), (8, 1));
+    assert_eq!(read(fd), (8, 1));
+    assert_eq!(read(fd).0, -1);
+    assert_eq!(errno(), libc::EAGAIN);
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+}
+
+fn test_dup() {
+    let fd = unsafe { libc::eventfd(0, libc::EFD_NONBLOCK) };
+    let fd2 = unsafe { libc::fcntl(fd, libc::F_DUPFD, 0) };
+    assert!(fd2 >= 0);
+    // Duplicates share the counter.
+    assert_eq!(write(fd, 5), 8);
+    assert_eq!(read(fd2), (8, 5));
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+    assert_eq!(unsafe { libc::close(fd2) }, 0);
+}
+
+fn test_blocking() {
+    static mut DATA: u64 = 0;
+    let fd = unsafe { libc::eventfd(0, 0) };
+    assert!(fd >= 0);
+    let reader = thread::spawn(move || {
+        // Blocks until the main thread writes to the counter.
+        assert_eq!(read(fd), (8, 1));
+        // The write happens-before the read.
+        assert_eq!(unsafe { DATA }, 42);
+    });
+    thread::yield_now();
+    unsafe { DATA = 42 };
+    assert_eq!(write(fd, 1), 8);
+    reader.join().unwrap();
+    assert_eq!(unsafe { libc::close(fd) }, 0);
+}
+
+fn main() {
+    test_counter();
+    test_semaphore();
+    test_dup();
+    test_blocking();
+}
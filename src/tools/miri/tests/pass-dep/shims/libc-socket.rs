@@ -0,0 +1,72 @@
+//@only-target-linux
+
+use std::mem::{self, MaybeUninit};
+
+fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap()
+}
+
+fn test_errors() {
+    unsafe {
+        assert_eq!(libc::socket(libc::AF_INET, libc::SOCK_STREAM, libc::IPPROTO_UDP), -1);
+        assert_eq!(errno(), libc::EPROTONOSUPPORT);
+        assert_eq!(libc::listen(1, 1), -1);
+        assert_eq!(errno(), libc::ENOTSOCK);
+        assert_eq!(libc::listen(-1, 1), -1);
+        assert_eq!(errno(), libc::EBADF);
+
+        let fd = libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0);
+        assert!(fd >= 0);
+        assert_eq!(libc::listen(fd, 1), -1);
+        assert_eq!(errno(), libc::EOPNOTSUPP);
+        let mut addr: libc::sockaddr_in6 = mem::zeroed();
+        addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+        let len = mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t;
+        assert_eq!(libc::bind(fd, (&addr as *const libc::sockaddr_in6).cast(), len), -1);
+        assert_eq!(errno(), libc::EAFNOSUPPORT);
+        let mut peer = MaybeUninit::<libc::sockaddr_in>::uninit();
+        let mut len = mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+        assert_eq!(libc::getpeername(fd, peer.as_mut_ptr().cast(), &mut len), -1);
+        assert_eq!(errno(), libc::ENOTCONN);
+        assert_eq!(libc::send(fd, [0u8].as_ptr().cast(), 1, 0), -1);
+        assert_eq!(errno(), libc::EDESTADDRREQ);
+        assert_eq!(libc::close(fd), 0);
+    }
+}
+
+fn test_getsockname() {
+    unsafe {
+        let fd = libc::socket(libc::AF_INET, libc::SOCK_STREAM | libc::SOCK_NONBLOCK, 0);
+        let mut addr: libc::sockaddr_in = mem::zeroed();
+        addr.sin_family = libc::AF_INET as libc::sa_family_t;
+        addr.sin_port = 4242u16.to_be();
+        addr.sin_addr.s_addr = u32::from_be_bytes([127, 0, 0, 1]).to_be();
+        let len = mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+        assert_eq!(libc::bind(fd, (&addr as *const libc::sockaddr_in).cast(), len), 0);
+
+        let mut name: libc::sockaddr_in = mem::zeroed();
+        let mut len = mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+        assert_eq!(
+            libc::getsockname(fd, (&mut name as *mut libc::sockaddr_in).cast(), &mut len),
+            0
+        );
+        assert_eq!(len as usize, mem::size_of::<libc::sockaddr_in>());
+        assert_eq!(name.sin_family, libc::AF_INET as libc::sa_family_t);
+        assert_eq!(u16::from_be(name.sin_port), 4242);
+        assert_eq!(name.sin_addr.s_addr, addr.sin_addr.s_addr);
+
+        // A too small buffer gets a truncated address, and the actual size.
+        let mut buf = [0xffu8; 4];
+        let mut len = 4;
+        assert_eq!(libc::getsockname(fd, buf.as_mut_ptr().cast(), &mut len), 0);
+        assert_eq!(len as usize, mem::size_of::<libc::sockaddr_in>());
+        assert_eq!(buf[2..], 4242u16.to_be_bytes());
+
+        assert_eq!(libc::close(fd), 0);
+    }
+}
+
+fn main() {
+    test_errors();
+    test_getsockname();
+}
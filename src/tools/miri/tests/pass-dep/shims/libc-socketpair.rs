@@ -0,0 +1,85 @@
+//@only-target-linux
+
+use std::thread;
+
+fn socketpair(type_: i32) -> [i32; 2] {
+    let mut fds = [-1; 2];
+    let res = unsafe { libc::socketpair(libc::AF_UNIX, type_, 0, fds.as_mut_ptr()) };
+    assert_eq!(res, 0);
+    fds
+}
+
+fn read(fd: i32, buf: &mut [u8]) -> isize {
+    unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) }
+}
+
+fn write(fd: i32, buf: &[u8]) -> isize {
+    unsafe { libc::write(fd, buf.as_ptr().cast(), buf.len()) }
+}
+
+fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap()
+}
+
+fn test_nonblocking() {
+    let [fd0, fd1] = socketpair(libc::SOCK_STREAM | libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC);
+    let mut buf = [0u8; 8];
+    assert_eq!(read(fd0, &mut buf), -1);
+    assert_eq!(errno(), libc::EAGAIN);
+    // Data flows in both directions.
+    assert_eq!(write(fd0, b"abc"), 3);
+    assert_eq!(write(fd1, b"defg"), 4);
+    assert_eq!(read(fd1, &mut buf[..2]), 2);
+    assert_eq!(&buf[..2], b"ab");
+    assert_eq!(read(fd1, &mut buf), 1);
+    assert_eq!(&buf[..1], b"c");
+    assert_eq!(read(fd0, &mut buf), 4);
+    assert_eq!(&buf[..4], b"defg");
+    // Once the other end is closed, the remaining data can be read, followed by the end of file.
+    assert_eq!(write(fd1, b"h"), 1);
+    assert_eq!(unsafe { libc::close(fd1) }, 0);
+    assert_eq!(read(fd0, &mut buf), 1);
+    assert_eq!(read(fd0, &mut buf), 0);
+    assert_eq!(unsafe { libc::close(fd0) }, 0);
+}
+
+fn test_dup() {
+    let [fd0, fd1] = socketpair(libc::SOCK_STREAM | libc::SOCK_NONBLOCK);
+    let fd2 = unsafe { libc::fcntl(fd1, libc::F_DUPFD, 0) };
+    assert!(fd2 >= 0);
+    // The socket stays open while a duplicate of its file descriptor does.
+    assert_eq!(unsafe { libc::close(fd1) }, 0);
+    assert_eq!(write(fd0, b"a"), 1);
+    let mut buf = [0u8; 1];
+    assert_eq!(read(fd2, &mut buf), 1);
+    assert_eq!(unsafe { libc::close(fd2) }, 0);
+    assert_eq!(unsafe { libc::close(fd0) }, 0);
+}
+
+fn test_blocking() {
+    static mut DATA: u64 = 0;
+    let [fd0, fd1] = socketpair(libc::SOCK_STREAM);
+    let reader = thread::spawn(move || {
+        let mut buf = [0u8; 5];
+        // Blocks until the main thread writes.
+        assert_eq!(read(fd1, &mut buf), 5);
+        assert_eq!(&buf, b"hello");
+        // The write happens-before the read.
+        assert_eq!(unsafe { DATA }, 42);
+        // Blocks until the main thread closes its end.
+        assert_eq!(read(fd1, &mut buf), 0);
+    });
+    thread::yield_now();
+    unsafe { DATA = 42 };
+    assert_eq!(write(fd0, b"hello"), 5);
+    thread::yield_now();
+    assert_eq!(unsafe { libc::close(fd0) }, 0);
+    reader.join().unwrap();
+    assert_eq!(unsafe { libc::close(fd1) }, 0);
+}
+
+fn main() {
+    test_nonblocking();
+    test_dup();
+    test_blocking();
+}
@@ -0,0 +1,20 @@
+//@compile-flags: -Zmiri-permissive-provenance
+//@only-target-x86_64-unknown-linux: support for tokio only on linux and x86
+
+use tokio::sync::mpsc;
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+async fn main() {
+    let (tx, mut rx) = mpsc::channel(1);
+    let sender = tokio::spawn(async move {
+        for i in 0..3 {
+            tx.send(i).await.unwrap();
+        }
+    });
+    let mut received = Vec::new();
+    while let Some(i) = rx.recv().await {
+        received.push(i);
+    }
+    sender.await.unwrap();
+    assert_eq!(received, [0, 1, 2]);
+}
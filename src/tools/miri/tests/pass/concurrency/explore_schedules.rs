@@ -0,0 +1,22 @@
+//@compile-flags: -Zmiri-explore-schedules=1
+//@normalize-stderr-test: "within [0-9]+ basic blocks" -> "within $$LIMIT basic blocks"
+
+// The same program as `tests/fail/concurrency/explore_schedules.rs`, but publishing `DATA` with a
+// release store, so no schedule within the preemption bound fails.
+
+use std::sync::atomic::{AtomicBool, Ordering::*};
+use std::thread;
+
+static READY: AtomicBool = AtomicBool::new(false);
+static mut DATA: u32 = 0;
+
+fn main() {
+    let handle = thread::spawn(|| {
+        unsafe { DATA = 42 };
+        READY.store(true, Release);
+    });
+    if READY.load(Acquire) {
+        assert_eq!(unsafe { DATA }, 42);
+    }
+    handle.join().unwrap();
+}
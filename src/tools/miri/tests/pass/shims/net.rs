@@ -0,0 +1,126 @@
+//@ignore-target-windows: Sockets are not implemented yet
+
+#![feature(io_error_more)]
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::thread;
+
+fn main() {
+    test_tcp();
+    test_tcp_ipv6();
+    test_tcp_nonblocking();
+    test_tcp_errors();
+    test_udp();
+    test_udp_connected();
+}
+
+fn test_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert!(addr.ip().is_loopback());
+    assert_ne!(addr.port(), 0);
+
+    let server = thread::spawn(move || {
+        let (mut stream, peer) = listener.accept().unwrap();
+        assert!(peer.ip().is_loopback());
+        assert_eq!(stream.peer_addr().unwrap(), peer);
+        assert_eq!(stream.local_addr().unwrap(), addr);
+        let mut request = String::new();
+        // Blocks until the client shuts down writing.
+        stream.read_to_string(&mut request).unwrap();
+        assert_eq!(request, "ping");
+        stream.write_all(b"pong").unwrap();
+    });
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream.set_nodelay(true).unwrap();
+    assert_eq!(stream.peer_addr().unwrap(), addr);
+    stream.write_all(b"ping").unwrap();
+    stream.shutdown(Shutdown::Write).unwrap();
+    let mut response = String::new();
+    // Blocks until the server closes the connection.
+    stream.read_to_string(&mut response).unwrap();
+    assert_eq!(response, "pong");
+    server.join().unwrap();
+}
+
+fn test_tcp_ipv6() {
+    let listener = TcpListener::bind("[::1]:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut client = TcpStream::connect(addr).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    client.write_all(&[1, 2, 3]).unwrap();
+    let mut buf = [0; 3];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [1, 2, 3]);
+}
+
+fn test_tcp_nonblocking() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    server.set_nonblocking(true).unwrap();
+    let mut buf = [0; 8];
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    // Closing the client ends the stream.
+    drop(client);
+    assert_eq!(server.read(&mut buf).unwrap(), 0);
+    assert_eq!(server.write(b"x").unwrap_err().kind(), ErrorKind::BrokenPipe);
+}
+
+fn test_tcp_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    // The address is in use until the listener is closed.
+    assert_eq!(TcpListener::bind(addr).unwrap_err().kind(), ErrorKind::AddrInUse);
+    drop(listener);
+    assert_eq!(TcpStream::connect(addr).unwrap_err().kind(), ErrorKind::ConnectionRefused);
+    TcpListener::bind(addr).unwrap();
+    // Only the loopback network exists.
+    let remote = SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), 80));
+    assert_eq!(TcpStream::connect(remote).unwrap_err().kind(), ErrorKind::NetworkUnreachable);
+    assert_eq!(TcpListener::bind(remote).unwrap_err().kind(), ErrorKind::AddrNotAvailable);
+}
+
+fn test_udp() {
+    let socket1 = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let socket2 = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let addr1 = socket1.local_addr().unwrap();
+    let port2 = socket2.local_addr().unwrap().port();
+
+    let receiver = thread::spawn(move || {
+        let mut buf = [0; 16];
+        // Blocks until the datagram is sent.
+        let (len, src) = socket2.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"hello");
+        assert_eq!(src, addr1);
+        socket2.send_to(b"world!", src).unwrap();
+    });
+
+    socket1.send_to(b"hello", ("127.0.0.1", port2)).unwrap();
+    // Datagrams that do not fit in the buffer are truncated.
+    let mut buf = [0; 5];
+    let (len, _) = socket1.recv_from(&mut buf).unwrap();
+    assert_eq!(len, 5);
+    assert_eq!(&buf, b"world");
+    receiver.join().unwrap();
+}
+
+fn test_udp_connected() {
+    let socket1 = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let socket2 = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket1.connect(socket2.local_addr().unwrap()).unwrap();
+    socket2.connect(socket1.local_addr().unwrap()).unwrap();
+    socket1.send(b"a").unwrap();
+    socket1.send(b"bc").unwrap();
+    let mut buf = [0; 4];
+    // Datagram boundaries are preserved.
+    assert_eq!(socket2.recv(&mut buf).unwrap(), 1);
+    assert_eq!(socket2.recv(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], b"bc");
+    socket2.set_nonblocking(true).unwrap();
+    assert_eq!(socket2.recv(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
@@ -473,7 +473,7 @@ fn default() -> Config {
                 }
             }
         }
-    )
+    );
 }
 
 pub(crate) fn is_stable_option_and_value<T>(
@@ -61,7 +61,7 @@
 
     // Comments. macros, and strings
     wrap_comments: bool, false, false, "Break comments to fit on the line";
-    format_code_in_doc_comments: bool, false, false, "Format the code snippet in doc comments.";
+    format_code_in_doc_comments: bool, false, true, "Format the code snippet in doc comments.";
     doc_comment_code_block_width: usize, 100, false, "Maximum width for code snippets in doc \
         comments. No effect unless format_code_in_doc_comments = true";
     comment_width: usize, 80, false,
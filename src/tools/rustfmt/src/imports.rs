@@ -1253,10 +1253,8 @@ fn parse_list(&mut self) -> Vec<UseTree> {
 
     macro_rules! parse_use_trees {
         ($($s:expr),* $(,)*) => {
-            vec![
-                $(parse_use_tree($s),)*
-            ]
-        }
+            vec![$(parse_use_tree($s),)*]
+        };
     }
 
     macro_rules! test_merge {
@@ -1268,7 +1266,7 @@ macro_rules! test_merge {
                 ),
                 parse_use_trees!($($output,)*),
             );
-        }
+        };
     }
 
     #[test]
@@ -18,7 +18,8 @@
 use crate::config::{BraceStyle, Config, IndentStyle, Version};
 use crate::expr::{
     is_empty_block, is_simple_block_stmt, rewrite_assign_rhs, rewrite_assign_rhs_with,
-    rewrite_assign_rhs_with_comments, RhsAssignKind, RhsTactics,
+    rewrite_assign_rhs_with_comments, rewrite_else_kw_with_comments, rewrite_let_else_block,
+    RhsAssignKind, RhsTactics,
 };
 use crate::lists::{definitive_tactic, itemize_list, write_list, ListFormatting, Separator};
 use crate::macros::{rewrite_macro, MacroPosition};
@@ -54,7 +55,7 @@ fn rewrite(&self, context: &RewriteContext<'_>, shape: Shape) -> Option<String>
 
         skip_out_of_file_lines_range!(context, self.span);
 
-        if contains_skip(&self.attrs) || matches!(self.kind, ast::LocalKind::InitElse(..)) {
+        if contains_skip(&self.attrs) {
             return None;
         }
 
@@ -74,6 +75,9 @@ fn rewrite(&self, context: &RewriteContext<'_>, shape: Shape) -> Option<String>
                 false,
             )?
         };
+        // Attributes and the comments around them do not count towards the width of the
+        // `let` statement when deciding where to put the `else` block.
+        let let_kw_offset = result.len() - "let ".len();
 
         // 4 = "let ".len()
         let pat_shape = shape.offset_left(4)?;
@@ -112,7 +116,7 @@ fn rewrite(&self, context: &RewriteContext<'_>, shape: Shape) -> Option<String>
 
         result.push_str(&infix);
 
-        if let Some((init, _els)) = self.kind.init_else_opt() {
+        if let Some((init, else_block)) = self.kind.init_else_opt() {
             // 1 = trailing semicolon;
             let nested_shape = shape.sub_width(1)?;
 
@@ -123,7 +127,47 @@ fn rewrite(&self, context: &RewriteContext<'_>, shape: Shape) -> Option<String>
                 &RhsAssignKind::Expr(&init.kind, init.span),
                 nested_shape,
             )?;
-            // todo else
+
+            if let Some(block) = else_block {
+                let else_kw_span = init.span.between(block.span);
+                let force_newline_else = pat_str.contains('\n')
+                    || !same_line_else_kw_and_brace(
+                        &result[let_kw_offset..],
+                        context,
+                        else_kw_span,
+                        nested_shape,
+                    );
+                let else_kw = rewrite_else_kw_with_comments(
+                    force_newline_else,
+                    true,
+                    context,
+                    else_kw_span,
+                    shape,
+                );
+                result.push_str(&else_kw);
+
+                // Now that `let pat = init else` is written, check whether the divergent block
+                // could still fit on the same line.
+                let assign_str_with_else_kw = &result[let_kw_offset..];
+                let max_width = min(shape.width, context.config.single_line_if_else_max_width());
+                let available_space = max_width.saturating_sub(assign_str_with_else_kw.len());
+                let allow_single_line = !force_newline_else
+                    && available_space > 0
+                    && allow_single_line_let_else_block(assign_str_with_else_kw, block);
+
+                let mut rw_else_block =
+                    rewrite_let_else_block(block, allow_single_line, context, shape)?;
+
+                // 1 = trailing semicolon;
+                if allow_single_line
+                    && !rw_else_block.contains('\n')
+                    && rw_else_block.len() + 1 > available_space
+                {
+                    rw_else_block = rewrite_let_else_block(block, false, context, shape)?;
+                }
+
+                result.push_str(&rw_else_block);
+            }
         }
 
         result.push(';');
@@ -131,6 +175,44 @@ fn rewrite(&self, context: &RewriteContext<'_>, shape: Shape) -> Option<String>
     }
 }
 
+/// When the initializer of a `let-else` statement spans several lines, `else {` is put on the
+/// same line as the end of the initializer only if that line:
+///
+/// 1. ends with one or more closing parentheses, square brackets or braces,
+/// 2. contains nothing else after them (e.g. no comment before `else`), and
+/// 3. is not indented beyond the `let` keyword.
+fn same_line_else_kw_and_brace(
+    init_str: &str,
+    context: &RewriteContext<'_>,
+    else_kw_span: Span,
+    init_shape: Shape,
+) -> bool {
+    if !init_str.contains('\n') {
+        // The initializer fits on one line, so `else {` only needs to fit after it.
+        // 7 = ` else {`
+        return init_shape.width.saturating_sub(init_str.len()) >= 7;
+    }
+
+    if !init_str.ends_with([')', ']', '}']) {
+        return false;
+    }
+
+    if context.snippet(else_kw_span).trim() != "else" {
+        return false;
+    }
+
+    let indent = init_shape.indent.to_string(context.config);
+    init_str
+        .lines()
+        .last()
+        .and_then(|line| line.strip_prefix(indent.as_ref()))
+        .map_or(false, |line| !line.starts_with(char::is_whitespace))
+}
+
+fn allow_single_line_let_else_block(result: &str, block: &ast::Block) -> bool {
+    !result.contains('\n') && block.stmts.len() <= 1
+}
+
 // FIXME convert to using rewrite style rather than visitor
 // FIXME format modules in this style
 #[allow(dead_code)]
@@ -25,6 +25,7 @@
     contains_comment, CharClasses, FindUncommented, FullCodeCharKind, LineClasses,
 };
 use crate::config::lists::*;
+use crate::config::Version;
 use crate::expr::{rewrite_array, rewrite_assign_rhs, RhsAssignKind};
 use crate::lists::{itemize_list, write_list, ListFormatting};
 use crate::overflow;
@@ -433,6 +434,13 @@ pub(crate) fn rewrite_macro_def(
             None if context.macro_rewrite_failure.get() => {
                 Some(context.snippet(branch.body).trim().to_string())
             }
+            // keep the branches that could not be formatted as they are, instead of giving up
+            // on the whole macro definition
+            None if context.config.version() == Version::Two => Some(
+                context
+                    .snippet(mk_sp(branch.span.lo(), branch.whole_body.hi()))
+                    .to_owned(),
+            ),
             None => None,
         },
         context.snippet_provider.span_after(span, "{"),
@@ -484,42 +492,228 @@ fn register_metavariable(
 // Replaces `$foo` with `zfoo`. We must check for name overlap to ensure we
 // aren't causing problems.
 // This should also work for escaped `$` variables, where we leave earlier `$`s.
-fn replace_names(input: &str) -> Option<(String, HashMap<String, String>)> {
+//
+// If `repeat_delim` is given, a repetition `$( ... ) sep op` is replaced with an
+// invocation of the placeholder macro `zrepN!( ... )` using `repeat_delim`, so that
+// the code inside of it can be formatted as well. With parentheses, a repetition of
+// statements, i.e. one containing a `;`, is replaced with the labeled block
+// `'zrepN: { ... }` instead. The `sep op` suffix of the `N`th repetition is stored at
+// index `N` of the returned `Vec`; see `restore_repeats`.
+fn replace_names(
+    input: &str,
+    repeat_delim: Option<Delimiter>,
+) -> Option<(String, HashMap<String, String>, Vec<String>)> {
     // Each substitution will require five or six extra bytes.
     let mut result = String::with_capacity(input.len() + 64);
     let mut substs = HashMap::new();
+    let mut repeats = vec![];
+    // The number of open delimiters, and the index, depth and closing delimiter of each open
+    // repetition.
+    let mut depth = 0usize;
+    let mut open_repeats: Vec<(usize, usize, &str)> = vec![];
     let mut dollar_count = 0;
     let mut cur_name = String::new();
 
-    for (kind, c) in CharClasses::new(input.chars()) {
+    let chars: Vec<_> = CharClasses::new(input.chars()).collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let (kind, c) = chars[i];
+        i += 1;
         if kind != FullCodeCharKind::Normal {
             result.push(c);
+            continue;
         } else if c == '$' {
             dollar_count += 1;
-        } else if dollar_count == 0 {
-            result.push(c);
-        } else if !c.is_alphanumeric() && !cur_name.is_empty() {
-            // Terminates a name following one or more dollars.
-            register_metavariable(&mut substs, &mut result, &cur_name, dollar_count);
+            continue;
+        } else if dollar_count != 0 {
+            if !c.is_alphanumeric() && !cur_name.is_empty() {
+                // Terminates a name following one or more dollars.
+                register_metavariable(&mut substs, &mut result, &cur_name, dollar_count);
+
+                dollar_count = 0;
+                cur_name.clear();
+            } else if c == '(' && cur_name.is_empty() {
+                let delim = match repeat_delim {
+                    Some(delim) if dollar_count == 1 => delim,
+                    _ => return None,
+                };
+                let (label, open, close) = match delim {
+                    Delimiter::Parenthesis if repeat_has_statements(&chars[i..]) => {
+                        ("'", ": {", "}")
+                    }
+                    Delimiter::Brace => ("", "!{", "}"),
+                    _ => ("", "!(", ")"),
+                };
+                open_repeats.push((repeats.len(), depth, close));
+                depth += 1;
+                result.push_str(label);
+                result.push_str(REPEAT_MACRO_PREFIX);
+                result.push_str(&repeats.len().to_string());
+                result.push_str(open);
+                repeats.push(String::new());
+                dollar_count = 0;
+                continue;
+            } else {
+                if c.is_alphanumeric() || c == '_' {
+                    cur_name.push(c);
+                }
+                continue;
+            }
+        }
 
-            result.push(c);
-            dollar_count = 0;
-            cur_name.clear();
-        } else if c == '(' && cur_name.is_empty() {
-            // FIXME: Support macro def with repeat.
-            return None;
-        } else if c.is_alphanumeric() || c == '_' {
-            cur_name.push(c);
+        if repeat_delim.is_some() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => {
+                    depth = depth.checked_sub(1)?;
+                    if let Some(&(index, start, close)) = open_repeats.last() {
+                        if start == depth {
+                            open_repeats.pop();
+                            let (suffix, len) = repeat_suffix(&chars[i..])?;
+                            repeats[index] = suffix;
+                            i += len;
+                            result.push_str(close);
+                            continue;
+                        }
+                    }
+                }
+                _ => {}
+            }
         }
+        result.push(c);
     }
 
     if !cur_name.is_empty() {
         register_metavariable(&mut substs, &mut result, &cur_name, dollar_count);
     }
+    if !open_repeats.is_empty() {
+        return None;
+    }
 
-    debug!("replace_names `{}` {:?}", result, substs);
+    debug!("replace_names `{}` {:?} {:?}", result, substs, repeats);
 
-    Some((result, substs))
+    Some((result, substs, repeats))
+}
+
+/// Name prefix of the placeholder macros that stand in for repetitions in macro bodies.
+const REPEAT_MACRO_PREFIX: &str = "zrep";
+
+/// Whether the repetition whose contents start at `chars` contains a `;` outside of any
+/// delimiters, which makes it a repetition of statements.
+fn repeat_has_statements(chars: &[(FullCodeCharKind, char)]) -> bool {
+    let mut depth = 0usize;
+    for &(kind, c) in chars {
+        if kind != FullCodeCharKind::Normal {
+            continue;
+        }
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth == 0 => return false,
+            ')' | ']' | '}' => depth -= 1,
+            ';' if depth == 0 => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Parses the optional separator and the repetition operator following the closing
+/// parenthesis of a repetition. Returns them without whitespace, together with the number
+/// of chars they span.
+fn repeat_suffix(chars: &[(FullCodeCharKind, char)]) -> Option<(String, usize)> {
+    let mut suffix = String::new();
+    for (i, &(kind, c)) in chars.iter().enumerate() {
+        if kind != FullCodeCharKind::Normal {
+            return None;
+        }
+        match c {
+            '*' | '+' | '?' => {
+                suffix.push(c);
+                return Some((suffix, i + 1));
+            }
+            _ if c.is_whitespace() => {}
+            // Only punctuation separators such as `,`, `;` or `=>` are supported.
+            '(' | ')' | '[' | ']' | '{' | '}' | '$' => return None,
+            _ if c.is_alphanumeric() || c == '_' || suffix.len() >= 3 => return None,
+            _ => suffix.push(c),
+        }
+    }
+    None
+}
+
+/// Undoes the replacement of repetitions with placeholder macros done by `replace_names`.
+fn restore_repeats(input: &str, repeats: &[String]) -> Option<String> {
+    let mut result = String::with_capacity(input.len());
+    // The number of open delimiters, and the index and depth of each open repetition.
+    let mut depth = 0usize;
+    let mut open_repeats: Vec<(usize, usize)> = vec![];
+    let mut skip_to = 0;
+
+    for (kind, (idx, c)) in CharClasses::new(input.char_indices()) {
+        if idx < skip_to {
+            continue;
+        }
+        if kind != FullCodeCharKind::Normal {
+            result.push(c);
+            continue;
+        }
+        let is_ident_start = !result
+            .chars()
+            .last()
+            .map_or(false, |prev| prev.is_alphanumeric() || prev == '_');
+        if is_ident_start {
+            if let Some((index, len)) = parse_repeat_placeholder(&input[idx..]) {
+                open_repeats.push((index, depth));
+                depth += 1;
+                result.push_str("$(");
+                skip_to = idx + len;
+                continue;
+            }
+        }
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth = depth.checked_sub(1)?;
+                if let Some(&(index, start)) = open_repeats.last() {
+                    if start == depth {
+                        open_repeats.pop();
+                        result.push(')');
+                        result.push_str(repeats.get(index)?);
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+        result.push(c);
+    }
+
+    if open_repeats.is_empty() {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Parses the beginning of a placeholder macro invocation, e.g. `zrep0!(` or `zrep0! {`, or of
+/// a placeholder labeled block, e.g. `'zrep0: {`, returning the index of the repetition and the
+/// length of the parsed text.
+fn parse_repeat_placeholder(s: &str) -> Option<(usize, usize)> {
+    let (rest, is_block) = match s.strip_prefix('\'') {
+        Some(rest) => (rest, true),
+        None => (s, false),
+    };
+    let rest = rest.strip_prefix(REPEAT_MACRO_PREFIX)?;
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let index = rest[..digits].parse().ok()?;
+    let rest = rest[digits..]
+        .strip_prefix(if is_block { ':' } else { '!' })?
+        .trim_start();
+    if rest.starts_with('{') || !is_block && rest.starts_with('(') {
+        Some((index, s.len() - rest.len() + 1))
+    } else {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1213,7 +1407,9 @@ fn rewrite(
         multi_branch_style: bool,
     ) -> Option<String> {
         // Only attempt to format function-like macros.
-        if self.args_paren_kind != Delimiter::Parenthesis {
+        if self.args_paren_kind != Delimiter::Parenthesis
+            && context.config.version() != Version::Two
+        {
             // FIXME(#1539): implement for non-sugared macros.
             return None;
         }
@@ -1238,73 +1434,36 @@ fn rewrite(
         // variables for new names with the same length first.
 
         let old_body = context.snippet(self.body).trim();
-        let (body_str, substs) = replace_names(old_body)?;
         let has_block_body = old_body.starts_with('{');
 
-        let mut config = context.config.clone();
-        config.set().hide_parse_errors(true);
-
         result += " {";
 
         let body_indent = if has_block_body {
             shape.indent
         } else {
-            shape.indent.block_indent(&config)
+            shape.indent.block_indent(context.config)
         };
-        let new_width = config.max_width() - body_indent.width();
-        config.set().max_width(new_width);
-
-        // First try to format as items, then as statements.
-        let new_body_snippet = match crate::format_snippet(&body_str, &config, true) {
-            Some(new_body) => new_body,
-            None => {
-                let new_width = new_width + config.tab_spaces();
-                config.set().max_width(new_width);
-                match crate::format_code_block(&body_str, &config, true) {
-                    Some(new_body) => new_body,
-                    None => return None,
-                }
-            }
-        };
-
-        if !filtered_str_fits(&new_body_snippet.snippet, config.max_width(), shape) {
-            return None;
-        }
 
-        // Indent the body since it is in a block.
-        let indent_str = body_indent.to_string(&config);
-        let mut new_body = LineClasses::new(new_body_snippet.snippet.trim_end())
-            .enumerate()
-            .fold(
-                (String::new(), true),
-                |(mut s, need_indent), (i, (kind, ref l))| {
-                    if !is_empty_line(l)
-                        && need_indent
-                        && !new_body_snippet.is_line_non_formatted(i + 1)
-                    {
-                        s += &indent_str;
-                    }
-                    (s + l + "\n", indent_next_line(kind, l, &config))
-                },
-            )
-            .0;
-
-        // Undo our replacement of macro variables.
-        // FIXME: this could be *much* more efficient.
-        for (old, new) in &substs {
-            if old_body.contains(new) {
-                debug!("rewrite_macro_def: bailing matching variable: `{}`", new);
-                return None;
-            }
-            new_body = new_body.replace(new, old);
-        }
+        let new_body = if context.config.version() == Version::Two {
+            // Repetitions are first replaced with macro calls using parentheses, which get
+            // formatted like function calls. If that fails, e.g. because the repetition holds
+            // statements, fall back to braces, which keep the repetition as is but still allow
+            // formatting the code around it.
+            [Delimiter::Parenthesis, Delimiter::Brace]
+                .into_iter()
+                .find_map(|delim| {
+                    format_macro_body(context, old_body, body_indent, shape, Some(delim))
+                })
+        } else {
+            format_macro_body(context, old_body, body_indent, shape, None)
+        }?;
 
         if has_block_body {
             result += new_body.trim();
         } else if !new_body.is_empty() {
             result += "\n";
             result += &new_body;
-            result += &shape.indent.to_string(&config);
+            result += &shape.indent.to_string(context.config);
         }
 
         result += "}";
@@ -1313,6 +1472,93 @@ fn rewrite(
     }
 }
 
+/// Formats the body of a macro definition branch, indented with `body_indent`.
+///
+/// See `replace_names` for the meaning of `repeat_delim`.
+fn format_macro_body(
+    context: &RewriteContext<'_>,
+    old_body: &str,
+    body_indent: Indent,
+    shape: Shape,
+    repeat_delim: Option<Delimiter>,
+) -> Option<String> {
+    let (body_str, substs, repeats) = replace_names(old_body, repeat_delim)?;
+    if repeat_delim == Some(Delimiter::Brace) && repeats.is_empty() {
+        // Same as the attempt with parentheses, which failed already.
+        return None;
+    }
+
+    let mut config = context.config.clone();
+    config.set().hide_parse_errors(true);
+
+    let new_width = config.max_width() - body_indent.width();
+    config.set().max_width(new_width);
+
+    // First try to format as items, then as statements.
+    let new_body_snippet = match crate::format_snippet(&body_str, &config, true) {
+        Some(new_body) => new_body,
+        None => {
+            let new_width = new_width + config.tab_spaces();
+            config.set().max_width(new_width);
+            match crate::format_code_block(&body_str, &config, true) {
+                Some(new_body) => new_body,
+                None => return None,
+            }
+        }
+    };
+
+    if !filtered_str_fits(&new_body_snippet.snippet, config.max_width(), shape) {
+        return None;
+    }
+
+    // Indent the body since it is in a block.
+    let indent_str = body_indent.to_string(&config);
+    let mut new_body = LineClasses::new(new_body_snippet.snippet.trim_end())
+        .enumerate()
+        .fold(
+            (String::new(), true),
+            |(mut s, need_indent), (i, (kind, ref l))| {
+                if !is_empty_line(l)
+                    && need_indent
+                    && !new_body_snippet.is_line_non_formatted(i + 1)
+                {
+                    s += &indent_str;
+                }
+                (s + l + "\n", indent_next_line(kind, l, &config))
+            },
+        )
+        .0;
+
+    // Undo our replacement of macro variables.
+    // FIXME: this could be *much* more efficient.
+    for (old, new) in &substs {
+        if old_body.contains(new) {
+            debug!("rewrite_macro_def: bailing matching variable: `{}`", new);
+            return None;
+        }
+        new_body = new_body.replace(new, old);
+    }
+
+    if !repeats.is_empty() {
+        if old_body.contains(REPEAT_MACRO_PREFIX) {
+            debug!("rewrite_macro_def: bailing matching repetition placeholder");
+            return None;
+        }
+        new_body = restore_repeats(&new_body, &repeats)?;
+
+        // Formatting the placeholders must not have changed anything but whitespace, e.g.
+        // added a trailing comma or semicolon inside or after a repetition.
+        let without_whitespace =
+            |s: &str| -> String { s.chars().filter(|c| !c.is_whitespace()).collect() };
+        if without_whitespace(old_body) != without_whitespace(&new_body) {
+            debug!("rewrite_macro_def: bailing changed repetition");
+            return None;
+        }
+    }
+
+    Some(new_body)
+}
+
 /// Format `lazy_static!` from <https://crates.io/crates/lazy_static>.
 ///
 /// # Expected syntax
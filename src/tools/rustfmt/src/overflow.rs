@@ -210,7 +210,7 @@ fn into_overflowable_item(&'a self) -> OverflowableItem<'a> {
                 }
             }
         )*
-    }
+    };
 }
 
 macro_rules! impl_into_overflowable_item_for_rustfmt_types {
@@ -229,7 +229,7 @@ fn into_overflowable_item(&'a self) -> OverflowableItem<'a> {
                 }
             }
         )*
-    }
+    };
 }
 
 impl_into_overflowable_item_for_ast_node!(Expr, GenericParam, NestedMetaItem, FieldDef, Ty, Pat);
@@ -29,7 +29,7 @@ macro_rules! parse_or {
                     return None;
                 }
             }
-        }
+        };
     }
 
     while parser.token.kind != TokenKind::Eof {
@@ -1,3 +1,30 @@
 fn main() {
     let Some(1) = Some(1) else { return };
+
+    let Some(x) = opt else { return; };
+
+    let Some(x) = opt else { panic!("no value") };
+
+    let Some(x) = opt
+    else
+    {
+        return
+    };
+
+    let Some(x) = some_really_long_function_name(argument_one, argument_two) else { return };
+
+    let Some(x) = opt else {
+        let y = 1;
+        return;
+    };
+
+    let Ok(value) = function_with_a_long_name(first_argument, second_argument, third_argument) else {
+        return Err(Error::new("value could not be computed"));
+    };
+
+    let Some(x) = opt // comment
+    else { return };
+
+    #[allow(unused)]
+    let Some(x) = opt else { return };
 }
@@ -0,0 +1,49 @@
+// rustfmt-version: Two
+
+macro_rules! call {
+    ($f:expr, $($arg:expr),*) => {
+        $f(  $(  $arg ),*  )
+    }
+}
+
+macro_rules! array {
+    ($($x:expr),* $(,)?) => { [ $( $x * 2 ),* ] };
+}
+
+macro_rules! nested {
+    ($($name:ident => [$($value:expr),*]);*) => {
+        vec![$(  (stringify!($name), vec![$( $value ),*])  ),*]
+    };
+}
+
+macro_rules! statements {
+    ($($x:expr),*) => {
+        let mut v = Vec::new();
+        $( v.push(  $x ); )*
+        v
+    };
+}
+
+macro_rules! consts {
+    ($($name:ident = $value:expr),*) => {
+        $( const  $name : u32 = $value; )*
+    };
+}
+
+macro_rules! nested_statements {
+    ($($x:ident: [$($y:expr),*]);*) => {
+        $( let $x = [ $( $y ),* ]; println!("{:?}",  $x); )*
+    };
+}
+
+macro_rules! braces {
+    {$x:expr} => { $x  +  1 };
+    ($x:expr) => { foo!(  $x   ) };
+}
+
+macro_rules! unformattable {
+    ($x:expr) => {
+        $x    + +
+    };
+    () => { 1+1 };
+}
@@ -1 +1,9 @@
 pub fn bar() {}
+
+fn generic<T: Default>() -> T {
+    T::default()
+}
+
+pub fn instantiate() -> (u8, u16, u32) {
+    (generic(), generic(), generic())
+}
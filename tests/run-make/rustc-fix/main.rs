@@ -0,0 +1,6 @@
+fn main() {
+    let mut x = 5;
+    if (x > 3) {
+        println!("{}", x);
+    }
+}
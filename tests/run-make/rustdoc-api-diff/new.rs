@@ -0,0 +1,32 @@
+//! A test crate.
+
+use std::rc::Rc;
+
+/// Does many things.
+pub fn frobnicate(x: u64) -> u32 { x as u32 }
+
+pub fn generic<T: Clone + Send>(t: T) -> T { t }
+
+pub enum Exhaustive { A, B, C }
+
+#[non_exhaustive]
+pub enum Open { A, B }
+
+pub struct Point { pub x: i32, pub y: i32, pub z: i32 }
+
+pub struct Opaque { pub a: u8, b: u8, pub c: u8 }
+
+pub struct Handle(pub u32, Rc<()>);
+
+pub struct Config;
+
+pub trait Service {
+    fn call(&self);
+    fn ready(&self) -> bool;
+    fn name(&self) -> &str { "" }
+}
+
+pub mod inner {
+    pub fn helper() {}
+    pub fn added() {}
+}
@@ -0,0 +1,34 @@
+//! A test crate.
+
+/// Does things.
+pub fn frobnicate(x: u32) -> u32 { x }
+
+pub fn removed() {}
+
+pub fn generic<T: Clone>(t: T) -> T { t }
+
+pub enum Exhaustive { A, B }
+
+#[non_exhaustive]
+pub enum Open { A }
+
+pub struct Point { pub x: i32, pub y: i32 }
+
+pub struct Opaque { pub a: u8, b: u8 }
+
+pub struct Handle(pub u32);
+
+pub struct Config;
+
+impl Default for Config {
+    fn default() -> Self { Config }
+}
+
+pub trait Service {
+    fn call(&self);
+}
+
+pub mod inner {
+    pub struct Gone;
+    pub fn helper() {}
+}
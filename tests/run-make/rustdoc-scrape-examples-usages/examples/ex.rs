@@ -0,0 +1,11 @@
+struct Circle;
+impl foobar::Shape for Circle {
+    fn area(&self) -> f64 {
+        3.0
+    }
+}
+
+fn main() {
+    let widget: foobar::Widget = foobar::Widget;
+    foobar::frobnicate(&widget);
+}
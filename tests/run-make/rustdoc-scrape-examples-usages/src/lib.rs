@@ -0,0 +1,17 @@
+// @has foobar/trait.Shape.html '//*[@class="toggle used-by"]//h5' 'Implemented by'
+// @has - '//*[@class="toggle used-by"]//li' 'ex.rs:2'
+// @has foobar/struct.Widget.html '//*[@class="toggle used-by"]//h5' 'Named by'
+// @has - '//*[@class="toggle used-by"]//a/@href' '../src/ex/ex.rs.html#9'
+// @has foobar/fn.frobnicate.html '//*[@class="toggle used-by"]//h5' 'Called by'
+// @has - '//*[@class="toggle used-by"]//code' 'main'
+// @!has foobar/fn.unused.html '//*[@class="toggle used-by"]' ''
+
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Widget;
+
+pub fn frobnicate(_w: &Widget) {}
+
+pub fn unused() {}
@@ -0,0 +1,29 @@
+//! The foo crate.
+//!
+//! # Usage
+//!
+//! Make a [`Widget`] with [`Widget::new`], or see [`Vec`].
+//!
+//! ```
+//! # fn main() {
+//! let w = foo::Widget::new();
+//! # }
+//! ```
+
+/// A widget.
+pub struct Widget {
+    /// The size of the widget.
+    pub size: usize,
+}
+
+impl Widget {
+    /// Makes a new widget.
+    pub fn new() -> Widget {
+        Widget { size: 0 }
+    }
+}
+
+pub mod shapes {
+    /// A shape-producing function.
+    pub fn make() {}
+}
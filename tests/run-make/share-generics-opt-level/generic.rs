@@ -0,0 +1,5 @@
+#![crate_type = "rlib"]
+
+pub fn count_zeros<T: Copy + Default + PartialEq>(items: &[T]) -> usize {
+    items.iter().filter(|&&item| item == T::default()).count()
+}
@@ -0,0 +1,7 @@
+#![crate_type = "rlib"]
+
+extern crate generic;
+
+pub fn zero_bytes(bytes: &[u8]) -> usize {
+    generic::count_zeros(bytes)
+}
@@ -0,0 +1,8 @@
+#![crate_type = "rlib"]
+
+extern crate generic;
+extern crate provider;
+
+pub fn zero_bytes_twice(bytes: &[u8]) -> usize {
+    generic::count_zeros(bytes) + provider::zero_bytes(bytes)
+}
@@ -0,0 +1,69 @@
+const QUERY = [
+    "impl Read -> dyn Iterator<u8>",
+    "impl",
+    "impl:Read",
+];
+
+const PARSED = [
+    {
+        elems: [{
+            name: "read",
+            fullPath: ["read"],
+            pathWithoutLast: [],
+            pathLast: "read",
+            generics: [],
+            typeFilter: -1,
+        }],
+        foundElems: 2,
+        original: "impl Read -> dyn Iterator<u8>",
+        returned: [{
+            name: "iterator",
+            fullPath: ["iterator"],
+            pathWithoutLast: [],
+            pathLast: "iterator",
+            generics: [
+                {
+                    name: "u8",
+                    fullPath: ["u8"],
+                    pathWithoutLast: [],
+                    pathLast: "u8",
+                    generics: [],
+                    typeFilter: -1,
+                },
+            ],
+            typeFilter: -1,
+        }],
+        userQuery: "impl read -> dyn iterator<u8>",
+        error: null,
+    },
+    {
+        elems: [{
+            name: "impl",
+            fullPath: ["impl"],
+            pathWithoutLast: [],
+            pathLast: "impl",
+            generics: [],
+            typeFilter: -1,
+        }],
+        foundElems: 1,
+        original: "impl",
+        returned: [],
+        userQuery: "impl",
+        error: null,
+    },
+    {
+        elems: [{
+            name: "read",
+            fullPath: ["read"],
+            pathWithoutLast: [],
+            pathLast: "read",
+            generics: [],
+            typeFilter: 9,
+        }],
+        foundElems: 1,
+        original: "impl:Read",
+        returned: [],
+        userQuery: "impl:read",
+        error: null,
+    },
+];
@@ -0,0 +1,17 @@
+const QUERY = [
+    'vec<t> -> option<t>',
+    'impl read -> string',
+];
+
+const EXPECTED = [
+    {
+        'others': [
+            { 'path': 'std::vec::Vec', 'name': 'pop' },
+        ],
+    },
+    {
+        'others': [
+            { 'path': 'std::io', 'name': 'read_to_string' },
+        ],
+    },
+];
@@ -0,0 +1,40 @@
+// exact-check
+
+const QUERY = [
+    'cfg:feature=serde',
+    'cfg:feature="std" cfg:feature="serde"',
+    'Serializ cfg:feature=serde',
+    'to_ cfg:feature=std',
+];
+
+const EXPECTED = [
+    {
+        // cfg:feature=serde
+        'others': [
+            { 'path': 'cfg_filter', 'name': 'ser' },
+            { 'path': 'cfg_filter', 'name': 'to_string' },
+            { 'path': 'cfg_filter::ser', 'name': 'to_writer' },
+            { 'path': 'cfg_filter::ser', 'name': 'Serializer' },
+        ],
+    },
+    {
+        // cfg:feature="std" cfg:feature="serde"
+        'others': [
+            { 'path': 'cfg_filter', 'name': 'to_string' },
+            { 'path': 'cfg_filter::ser', 'name': 'to_writer' },
+        ],
+    },
+    {
+        // Serializ cfg:feature=serde
+        'others': [
+            { 'path': 'cfg_filter::ser', 'name': 'Serializer' },
+        ],
+    },
+    {
+        // to_ cfg:feature=std
+        'others': [
+            { 'path': 'cfg_filter', 'name': 'to_string' },
+            { 'path': 'cfg_filter::ser', 'name': 'to_writer' },
+        ],
+    },
+];
@@ -0,0 +1,17 @@
+// compile-flags: -Zunstable-options --auto-cfg --cfg feature="serde" --cfg feature="std"
+
+#[cfg(feature = "serde")]
+pub mod ser {
+    pub struct Serializer;
+
+    #[cfg(feature = "std")]
+    pub fn to_writer() {}
+}
+
+pub struct Serializable;
+
+#[cfg(all(feature = "serde", feature = "std"))]
+pub fn to_string() {}
+
+#[cfg(any(feature = "serde", feature = "std"))]
+pub fn to_bytes() {}
@@ -0,0 +1,68 @@
+// exact-check
+
+const QUERY = [
+    'Stack<T> -> Option<T>',
+    'Stack<T> -> Option<U>',
+    'Stack<u8> -> Option<T>',
+    'T -> T',
+    'Result<A, B> -> Result<B, A>',
+    'Result<T, T> -> Result<T, T>',
+    'impl Read -> String',
+    'dyn Read -> String',
+    'Stack<Read> -> Option<Read>',
+];
+
+const EXPECTED = [
+    {
+        // Stack<T> -> Option<T>
+        'others': [
+            { 'path': 'generics_unification::Stack', 'name': 'pop' },
+            { 'path': 'generics_unification::Stack', 'name': 'first' },
+            { 'path': 'generics_unification', 'name': 'read_first' },
+        ],
+    },
+    {
+        // Stack<T> -> Option<U>
+        'others': [],
+    },
+    {
+        // Stack<u8> -> Option<T>
+        'others': [],
+    },
+    {
+        // T -> T
+        'others': [
+            { 'path': 'generics_unification::Stack', 'name': 'from' },
+            { 'path': 'generics_unification::Other', 'name': 'from' },
+            { 'path': 'generics_unification', 'name': 'identity' },
+        ],
+    },
+    {
+        // Result<A, B> -> Result<B, A>
+        'others': [
+            { 'path': 'generics_unification', 'name': 'swap' },
+        ],
+    },
+    {
+        // Result<T, T> -> Result<T, T>
+        'others': [],
+    },
+    {
+        // impl Read -> String
+        'others': [
+            { 'path': 'generics_unification', 'name': 'read_all' },
+        ],
+    },
+    {
+        // dyn Read -> String
+        'others': [
+            { 'path': 'generics_unification', 'name': 'read_all' },
+        ],
+    },
+    {
+        // Stack<Read> -> Option<Read>
+        'others': [
+            { 'path': 'generics_unification', 'name': 'read_first' },
+        ],
+    },
+];
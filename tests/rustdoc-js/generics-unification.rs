@@ -0,0 +1,46 @@
+use std::io::Read;
+
+pub struct Stack<T>(Vec<T>);
+
+impl<T> Stack<T> {
+    pub fn pop(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+    pub fn first(&self) -> Option<&T> {
+        self.0.first()
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+pub struct Other;
+
+pub fn other(_: Stack<u8>) -> Option<Other> {
+    None
+}
+
+pub fn identity<T>(t: T) -> T {
+    t
+}
+
+pub fn swap<A, B>(pair: Result<A, B>) -> Result<B, A> {
+    match pair {
+        Ok(a) => Err(a),
+        Err(b) => Ok(b),
+    }
+}
+
+pub fn read_all(mut input: impl Read) -> String {
+    let mut out = String::new();
+    input.read_to_string(&mut out).unwrap();
+    out
+}
+
+pub fn read_first<R>(inputs: Stack<R>) -> Option<R>
+where
+    R: Read,
+{
+    let mut inputs = inputs;
+    inputs.pop()
+}
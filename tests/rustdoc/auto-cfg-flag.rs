@@ -0,0 +1,33 @@
+// Checks that `--auto-cfg` infers the `doc(cfg)` of items from their `#[cfg]` attributes and the
+// ones of their parents, without `#![feature(doc_auto_cfg)]`.
+
+// compile-flags: -Zunstable-options --auto-cfg --cfg feature="serde" --cfg feature="std"
+#![crate_name = "foo"]
+
+// @has 'foo/struct.Always.html'
+// @count - '//*[@class="stab portability"]' 0
+pub struct Always;
+
+// @has 'foo/ser/index.html'
+// @matches - '//*[@class="stab portability"]' 'crate feature serde only'
+#[cfg(feature = "serde")]
+pub mod ser {
+    // @has 'foo/ser/struct.Serializer.html'
+    // @count - '//*[@class="stab portability"]' 1
+    // @matches - '//*[@class="stab portability"]' 'crate feature serde only'
+    pub struct Serializer;
+
+    // @has 'foo/ser/fn.to_writer.html'
+    // @count - '//*[@class="stab portability"]' 1
+    // @matches - '//*[@class="stab portability"]' \
+    //     'crate features serde and std only'
+    #[cfg(feature = "std")]
+    pub fn to_writer() {}
+}
+
+// @has 'foo/index.html'
+// @has - '//*[@class="item-name"]/*[@class="stab portability"]' 'std'
+// @has 'foo/fn.to_bytes.html'
+// @matches - '//*[@class="stab portability"]' 'crate feature std only'
+#[cfg(feature = "std")]
+pub fn to_bytes() {}
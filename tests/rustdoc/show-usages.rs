@@ -0,0 +1,43 @@
+// compile-flags: -Zunstable-options --show-usages
+
+#![crate_name = "foo"]
+
+// @has 'foo/struct.Widget.html'
+// @has - '//*[@id="used-by"]//h5' 'Named by'
+// @has - '//*[@id="used-by"]//a[@href="../src/foo/show-usages.rs.html#40"]' 'frobnicate'
+// @has - '//*[@id="used-by-1"]//h5' 'Called by'
+// @has - '//*[@id="used-by-1"]//a[@href="../src/foo/show-usages.rs.html#40"]' 'frobnicate'
+/// A widget.
+///
+/// ```
+/// let widget = foo::Widget::new();
+/// foo::frobnicate(&widget);
+/// ```
+pub struct Widget;
+
+impl Widget {
+    pub fn new() -> Widget {
+        Widget
+    }
+}
+
+// @has 'foo/trait.Shape.html'
+// @has - '//*[@id="used-by"]//h5' 'Implemented by'
+// @has - '//*[@id="used-by"]//a[@href="../src/foo/show-usages.rs.html#31"]' '<Square as Shape>'
+pub trait Shape {}
+
+pub struct Square;
+
+impl Shape for Square {}
+
+// @has 'foo/fn.frobnicate.html'
+// @has - '//*[@id="used-by"]//h5' 'Named in the examples of'
+// @has - '//*[@id="used-by"]//a[@href="../foo/struct.Widget.html"]' 'foo::Widget'
+// @!has - '//*[@id="used-by"]//h5' 'Called by'
+// @has 'foo/fn.unused.html'
+// @!has - '//*[@id="used-by"]' ''
+pub fn frobnicate(_widget: &Widget) -> Widget {
+    Widget::new()
+}
+
+pub fn unused() {}
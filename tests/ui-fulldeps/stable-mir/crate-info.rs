@@ -52,6 +52,12 @@ fn test_stable_mir(tcx: TyCtxt<'_>) {
         stable_mir::mir::Terminator::Return => {}
         other => panic!("{other:?}"),
     }
+    match body.locals[0].kind() {
+        stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::Int(
+            stable_mir::ty::IntTy::I32,
+        )) => {}
+        other => panic!("{other:?}"),
+    }
 
     let foo_bar = get_item(tcx, &items, (DefKind::Fn, "foo_bar")).unwrap();
     let body = foo_bar.body();
@@ -59,13 +65,30 @@ fn test_stable_mir(tcx: TyCtxt<'_>) {
     assert_eq!(body.blocks.len(), 4);
     let block = &body.blocks[0];
     match &block.terminator {
-        stable_mir::mir::Terminator::Call { .. } => {}
+        stable_mir::mir::Terminator::Call {
+            func: stable_mir::mir::Operand::Constant(constant), ..
+        } => match constant.literal.ty.kind() {
+            stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::FnDef(..)) => {}
+            other => panic!("{other:?}"),
+        },
+        other => panic!("{other:?}"),
+    }
+    match body.locals[0].kind() {
+        stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::Int(
+            stable_mir::ty::IntTy::I64,
+        )) => {}
         other => panic!("{other:?}"),
     }
 
     let drop = get_item(tcx, &items, (DefKind::Fn, "drop")).unwrap();
     let body = drop.body();
     assert_eq!(body.blocks.len(), 2);
+    match body.locals[1].kind() {
+        stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::Adt(_, args)) => {
+            assert!(args.0.is_empty())
+        }
+        other => panic!("{other:?}"),
+    }
     let block = &body.blocks[0];
     match &block.terminator {
         stable_mir::mir::Terminator::Drop { .. } => {}
@@ -0,0 +1,190 @@
+// run-pass
+// Test that users are able to use stable mir APIs to query layouts, resolve instances and
+// find trait impls
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_hir;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_smir;
+
+use rustc_driver::{Callbacks, Compilation, RunCompiler};
+use rustc_hir::def::DefKind;
+use rustc_interface::{interface, Queries};
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::stable_mir::mir::mono::{Instance, InstanceKind};
+use rustc_smir::{rustc_internal, stable_mir};
+use std::io::Write;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(tcx: TyCtxt<'_>) {
+    let items = stable_mir::all_local_items();
+
+    // The layout of a primitive type.
+    let bar = get_item(tcx, &items, (DefKind::Fn, "bar")).unwrap();
+    let layout = bar.body().locals[0].layout().unwrap();
+    assert_eq!(layout.size, 4);
+    assert_eq!(layout.abi_align, 4);
+    assert_eq!(layout.fields, stable_mir::abi::FieldsShape::Primitive);
+
+    // Items without generic parameters have a monomorphic type with a layout too.
+    let ty = bar.ty();
+    match ty.kind() {
+        stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::FnDef(..)) => {}
+        other => panic!("{other:?}"),
+    }
+    assert_eq!(ty.layout().unwrap().size, 0);
+
+    // Resolve the trait method call in `call_foo` to the method of the impl.
+    let call_foo = get_item(tcx, &items, (DefKind::Fn, "call_foo")).unwrap();
+    let body = call_foo.body();
+    let (def, args) = match &body.blocks[0].terminator {
+        stable_mir::mir::Terminator::Call {
+            func: stable_mir::mir::Operand::Constant(constant),
+            ..
+        } => match constant.literal.ty.kind() {
+            stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::FnDef(def, args)) => {
+                (def, args)
+            }
+            other => panic!("{other:?}"),
+        },
+        other => panic!("{other:?}"),
+    };
+    let instance = Instance::resolve(def, &args).unwrap();
+    assert_eq!(instance.kind, InstanceKind::Item);
+    assert!(instance.mangled_name().contains("foo"));
+    let body = instance.body().unwrap();
+    assert_eq!(body.locals.len(), 2);
+
+    let fn_abi = instance.fn_abi().unwrap();
+    assert_eq!(fn_abi.args.len(), 1);
+    assert_eq!(fn_abi.args[0].layout.size, 4);
+    assert_eq!(fn_abi.ret.layout.size, 8);
+    assert_eq!(fn_abi.conv, stable_mir::abi::CallConvention::Rust);
+
+    // Foreign functions don't have a body.
+    let call_ext = get_item(tcx, &items, (DefKind::Fn, "call_ext")).unwrap();
+    let (def, args) = match &call_ext.body().blocks[0].terminator {
+        stable_mir::mir::Terminator::Call {
+            func: stable_mir::mir::Operand::Constant(constant),
+            ..
+        } => match constant.literal.ty.kind() {
+            stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::FnDef(def, args)) => {
+                (def, args)
+            }
+            other => panic!("{other:?}"),
+        },
+        other => panic!("{other:?}"),
+    };
+    let instance = Instance::resolve(def, &args).unwrap();
+    assert!(instance.body().is_none());
+
+    // Find the impl of the local trait.
+    let impls = stable_mir::all_trait_impls();
+    assert_eq!(impls.len(), 1);
+    let trait_ref = impls[0].trait_impl().unwrap();
+    assert_eq!(stable_mir::trait_impls(&trait_ref.def_id).len(), 1);
+    match &trait_ref.args.0[..] {
+        [stable_mir::ty::GenericArgKind::Type(self_ty)] => match self_ty.kind() {
+            stable_mir::ty::TyKind::RigidTy(stable_mir::ty::RigidTy::Uint(
+                stable_mir::ty::UintTy::U32,
+            )) => {}
+            other => panic!("{other:?}"),
+        },
+        other => panic!("{other:?}"),
+    }
+}
+
+// Use internal API to find a function in a crate.
+fn get_item<'a>(
+    tcx: TyCtxt,
+    items: &'a stable_mir::CrateItems,
+    item: (DefKind, &str),
+) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| {
+        let def_id = rustc_internal::item_def_id(crate_item);
+        tcx.def_kind(def_id) == item.0 && tcx.def_path_str(def_id) == item.1
+    })
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// It will invoke the compiler using a custom Callback implementation, which will
+/// invoke Stable MIR APIs after the compiler has finished its analysis.
+fn main() {
+    let path = "input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_driver::catch_fatal_errors(|| {
+        RunCompiler::new(&args, &mut SMirCalls {}).run().unwrap();
+    })
+    .unwrap();
+}
+
+struct SMirCalls {}
+
+impl Callbacks for SMirCalls {
+    /// Called after analysis. Return value instructs the compiler whether to
+    /// continue the compilation afterwards (defaults to `Compilation::Continue`)
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        queries.global_ctxt().unwrap().enter(|tcx| {
+            rustc_smir::rustc_internal::run(tcx, || test_stable_mir(tcx));
+        });
+        // No need to keep going.
+        Compilation::Stop
+    }
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub trait Foo {{
+        fn foo(self) -> u64;
+    }}
+
+    impl Foo for u32 {{
+        #[inline(never)]
+        fn foo(self) -> u64 {{
+            self as u64
+        }}
+    }}
+
+    pub fn bar(x: i32) -> i32 {{
+        x
+    }}
+
+    pub fn call_foo(x: u32) -> u64 {{
+        x.foo()
+    }}
+
+    extern "C" {{
+        fn ext() -> i32;
+    }}
+
+    pub fn call_ext() -> i32 {{
+        unsafe {{ ext() }}
+    }}"#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,18 @@
+// build-fail
+// edition:2021
+// compile-flags: -Zfuture-size-limit=10000 --crate-type lib
+
+#![deny(large_futures)]
+
+async fn wait() {}
+
+pub async fn test(arg: [u8; 8192]) {
+    //~^ ERROR this future is 16386 bytes, which is larger than the limit of 10000 bytes
+    wait().await;
+    drop(arg);
+}
+
+pub async fn small(arg: [u8; 16]) {
+    wait().await;
+    drop(arg);
+}
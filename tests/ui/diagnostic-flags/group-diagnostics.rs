@@ -0,0 +1,14 @@
+// compile-flags: -Z group-diagnostics
+
+macro_rules! mismatch {
+    () => {
+        let _: u8 = ""; //~ ERROR mismatched types
+    };
+}
+
+fn main() {
+    mismatch!();
+    mismatch!();
+    mismatch!();
+    let _: u8 = ""; //~ ERROR mismatched types
+}
@@ -0,0 +1,8 @@
+// compile-flags: --error-format=sarif -Zunstable-options
+// Checks the SARIF 2.1.0 log written for a lint with a lint level note and a suggestion.
+
+#![deny(unused_variables)]
+
+fn main() {
+    let x = 1;
+}
@@ -0,0 +1,19 @@
+// compile-flags: -Z print-type-sizes -Z print-type-sizes-format=json --crate-type=lib
+// build-pass
+// ignore-pass
+// ^-- needed because `--pass check` does not emit the output needed.
+
+// This file illustrates the JSON output of `-Z print-type-sizes`, including padding and the
+// location of niches.
+
+#![allow(dead_code)]
+
+struct S {
+    a: bool,
+    g: i32,
+}
+
+enum E {
+    A(S),
+    B,
+}
@@ -0,0 +1,27 @@
+// compile-flags: --test
+
+#![feature(test)]
+
+extern crate test;
+
+use test::TestCases;
+
+#[test]
+fn returns_value(_: &mut TestCases) -> Result<(), ()> {
+//~^ ERROR functions used as parameterized tests must have signature `fn(&mut TestCases)`
+    Ok(())
+}
+
+#[test]
+fn two_arguments(_: &mut TestCases, _: u8) {}
+//~^ ERROR functions used as tests can not have any arguments
+
+#[test]
+fn other_argument(_: u8) {}
+//~^ ERROR functions used as tests can not have any arguments
+
+#[test]
+fn shared_test_cases(_: &TestCases) {}
+//~^ ERROR functions used as tests can not have any arguments
+
+fn main() {}
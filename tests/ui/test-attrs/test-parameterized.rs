@@ -0,0 +1,28 @@
+// no-prefer-dynamic
+// compile-flags: --test
+// run-flags: --test-threads=1
+// run-pass
+// check-run-results
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// ignore-wasm32 no support for `Instant`
+
+// Tests that the cases of a parameterized test are run as tests of their own.
+
+#![feature(test)]
+
+extern crate test;
+
+use test::TestCases;
+
+#[test]
+fn parse(cases: &mut TestCases) {
+    for (input, expected) in [("1", 1), ("-1", -1), ("+1", 1)] {
+        cases.add(input, move || assert_eq!(input.parse::<i32>().unwrap(), expected));
+    }
+}
+
+#[test]
+#[ignore]
+fn ignored(cases: &mut TestCases) {
+    cases.add("case", || {});
+}
@@ -0,0 +1,31 @@
+// no-prefer-dynamic
+// compile-flags: --test -Cpanic=abort -Zpanic_abort_tests
+// run-flags: --test-threads=1 -Zunstable-options --test-timeout=1
+// run-fail
+// check-run-results
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// ignore-wasm no panic or subprocess support
+// ignore-emscripten no panic or subprocess support
+// ignore-sgx no subprocess support
+// ignore-windows needs the `sleep` command
+
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn fast() {}
+
+#[test]
+fn sleeps() {
+    println!("about to sleep");
+    thread::sleep(Duration::from_secs(60));
+}
+
+// The child inherits the pipes that capture the test's output, so they are not closed when
+// the test is killed. The harness must not wait for it.
+#[test]
+fn grandchild_keeps_output_open() {
+    Command::new("sleep").arg("10").spawn().unwrap();
+    thread::sleep(Duration::from_secs(60));
+}